@@ -0,0 +1,63 @@
+//! Vector updates of the iterations of a conjugate gradient solver, `x += alpha * p`,
+//! `r -= alpha * ap` and `p = r + beta * p`, enqueued one operation at a time with
+//! [`ops::axpy`] and [`ops::scal`], and recorded into a single submission per iteration with
+//! [`ops::record_axpy`] and [`ops::record_scal`].
+//!
+//! Arguments: the length of the vectors (65536), the number of iterations (1000) and of runs (3).
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+mod timing;
+
+use gpgpu::{ops, prelude::*};
+
+fn main() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let len = timing::arg(0, 65536u64);
+    let iterations = timing::arg(1, 1000u32);
+    let runs = timing::arg(2, 3u32);
+
+    let (alpha, beta) = (0.5f32, 0.25f32);
+    let values = (0..len).map(|i| (i % 16) as f32).collect::<Vec<_>>();
+
+    let mut x = GpuBuffer::from_slice(&fw, &values);
+    let mut r = GpuBuffer::from_slice(&fw, &values);
+    let mut p = GpuBuffer::from_slice(&fw, &values);
+    let ap = GpuBuffer::from_slice(&fw, &values);
+
+    let unbatched = timing::mean_time(runs, || {
+        for _ in 0..iterations {
+            ops::axpy(&fw, alpha, &p, &mut x)?;
+            ops::axpy(&fw, -alpha, &ap, &mut r)?;
+            ops::scal(&fw, beta, &mut p)?;
+            ops::axpy(&fw, 1.0, &r, &mut p)?;
+        }
+        timing::wait(&fw);
+
+        GpuResult::Ok(())
+    })?;
+
+    let batched = timing::mean_time(runs, || {
+        for _ in 0..iterations {
+            let mut recorder = fw.create_command_recorder();
+            ops::record_axpy(&mut recorder, alpha, &p, &x)?;
+            ops::record_axpy(&mut recorder, -alpha, &ap, &r)?;
+            ops::record_scal(&mut recorder, beta, &p)?;
+            ops::record_axpy(&mut recorder, 1.0, &r, &p)?;
+            recorder.submit();
+        }
+        timing::wait(&fw);
+
+        GpuResult::Ok(())
+    })?;
+
+    println!("iterations of 4 operations over vectors of {} f32s:", len);
+    println!("  unbatched: {:?} per iteration", unbatched / iterations);
+    println!("  batched:   {:?} per iteration", batched / iterations);
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Chains of trivially small kernels, enqueued one by one and recorded into a single
+//! submission with a [`CommandRecorder`](gpgpu::CommandRecorder), comparing the overhead
+//! per dispatch.
+//!
+//! Arguments: the number of kernels per chain (12) and of chains (100).
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+mod timing;
+
+use gpgpu::prelude::*;
+
+/// Increments each element of `values`.
+const INCREMENT_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read_write> values: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&values)) {
+        values[i] = values[i] + 1u;
+    }
+}
+"#;
+
+const LEN: u32 = 64;
+
+fn main() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let chain = timing::arg(0, 12u32);
+    let chains = timing::arg(1, 100u32);
+
+    let shader = Shader::from_wgsl_source(&fw, INCREMENT_SHADER, Some("increment"))?;
+    let values = GpuBuffer::<u32>::with_capacity(&fw, LEN as u64);
+
+    let kernels = (0..chain)
+        .map(|_| {
+            let set = DescriptorSet::default().bind_buffer(&values, GpuBufferUsage::ReadWrite);
+            Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let separate = timing::mean_time(chains, || {
+        for kernel in &kernels {
+            kernel.enqueue(1, 1, 1)?;
+        }
+        timing::wait(&fw);
+
+        GpuResult::Ok(())
+    })?;
+
+    let recorded = timing::mean_time(chains, || {
+        let mut recorder = fw.create_command_recorder();
+        for kernel in &kernels {
+            recorder.enqueue(kernel, 1, 1, 1)?;
+        }
+        recorder.submit().wait();
+
+        GpuResult::Ok(())
+    })?;
+
+    // Every run of both, warm-ups included, added 1 per kernel.
+    let expected = 2 * (chains + 1) * chain;
+    let result = values.read_vec_blocking()?;
+    assert!(result.iter().all(|&value| value == expected));
+
+    println!("chains of {} kernels:", chain);
+    println!("  Kernel::enqueue:  {:?} per dispatch", separate / chain);
+    println!("  CommandRecorder:  {:?} per dispatch", recorded / chain);
+
+    Ok(())
+}
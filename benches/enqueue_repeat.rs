@@ -0,0 +1,113 @@
+//! Iterations of a small Jacobi solver swapping its input and output buffers, each enqueued
+//! with its own submission and all of them recorded into one with [`Kernel::enqueue_repeat_with`].
+//!
+//! Arguments: the length of the vectors (1024), the number of iterations (1000) and of runs (5).
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+mod timing;
+
+use gpgpu::prelude::*;
+
+/// One Jacobi iteration of the 1D Poisson equation -u'' = rhs, with u = 0 at both ends.
+const JACOBI_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> rhs: array<f32>;
+@group(0) @binding(1) var<storage, read> input: array<f32>;
+@group(0) @binding(2) var<storage, read_write> output: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+    let len = arrayLength(&output);
+
+    if (i >= len) {
+        return;
+    }
+
+    var left = 0.0;
+    var right = 0.0;
+
+    if (i > 0u) {
+        left = input[i - 1u];
+    }
+    if (i + 1u < len) {
+        right = input[i + 1u];
+    }
+
+    output[i] = (left + right + rhs[i]) * 0.5;
+}
+"#;
+
+fn main() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let len = timing::arg(0, 1024u32);
+    // Rounded up to an even number, so that the solution ends up in `even`.
+    let iterations = timing::arg(1, 1000u32).next_multiple_of(2);
+    let runs = timing::arg(2, 5u32);
+
+    let shader = Shader::from_wgsl_source(&fw, JACOBI_SHADER, Some("jacobi"))?;
+    let rhs = GpuBuffer::from_slice(&fw, &vec![1.0f32; len as usize]);
+    let even = GpuBuffer::<f32>::with_capacity(&fw, len as u64);
+    let odd = GpuBuffer::<f32>::with_capacity(&fw, len as u64);
+
+    let step = |input, output| {
+        DescriptorSet::default()
+            .bind_buffer(&rhs, GpuBufferUsage::ReadOnly)
+            .bind_buffer(input, GpuBufferUsage::ReadOnly)
+            .bind_buffer(output, GpuBufferUsage::ReadWrite)
+    };
+    let sets = [step(&even, &odd), step(&odd, &even)];
+    let pairs = [[&sets[0]], [&sets[1]]];
+
+    let program = Program::new(&shader, "main").add_descriptor_set(step(&even, &odd));
+    let kernel = Kernel::new(&fw, program)?;
+    let workgroups = len.div_ceil(64);
+
+    let zeros = vec![0.0; len as usize];
+    let reset = || -> GpuResult<()> {
+        even.write(&zeros)?;
+        odd.write(&zeros)?;
+        Ok(())
+    };
+
+    let separate = timing::mean_time(runs, || {
+        reset()?;
+        for iteration in 0..iterations {
+            kernel.enqueue_with_sets(workgroups, 1, 1, pairs[iteration as usize % 2].as_slice())?;
+        }
+        timing::wait(&fw);
+
+        GpuResult::Ok(())
+    })?;
+    let separate_solution = even.read_vec_blocking()?;
+
+    let repeated = timing::mean_time(runs, || {
+        reset()?;
+        kernel
+            .enqueue_repeat_with(iterations, workgroups, 1, 1, |iteration| {
+                &pairs[iteration as usize % 2][..]
+            })?
+            .wait();
+
+        GpuResult::Ok(())
+    })?;
+
+    // Both compute the same iterations.
+    assert_eq!(separate_solution, even.read_vec_blocking()?);
+
+    println!("{} iterations over vectors of {} f32s:", iterations, len);
+    println!(
+        "  Kernel::enqueue_with_sets:   {:?} per iteration",
+        separate / iterations
+    );
+    println!(
+        "  Kernel::enqueue_repeat_with: {:?} per iteration",
+        repeated / iterations
+    );
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Uploads of `f64`s narrowed into `f32`s, collected into a `Vec` given to
+//! [`GpuBuffer::from_slice`] and converted straight into the buffer memory with
+//! [`GpuBuffer::from_slice_map`].
+//!
+//! Arguments: the number of elements (100M) and of runs (3).
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+mod timing;
+
+use gpgpu::prelude::*;
+
+fn main() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let len = timing::arg(0, 100_000_000usize);
+    let runs = timing::arg(1, 3u32);
+
+    let input = (0..len).map(|i| i as f64 * 0.5).collect::<Vec<f64>>();
+
+    let collected = timing::mean_time(runs, || {
+        let narrowed = input.iter().map(|&x| x as f32).collect::<Vec<f32>>();
+        let buf = GpuBuffer::try_from_slice(&fw, &narrowed)?;
+        timing::wait(&fw);
+        drop((buf, narrowed));
+
+        GpuResult::Ok(())
+    })?;
+
+    let mapped = timing::mean_time(runs, || {
+        let buf = GpuBuffer::from_slice_map(&fw, &input, |&x| x as f32);
+        timing::wait(&fw);
+        drop(buf);
+
+        GpuResult::Ok(())
+    })?;
+
+    let bytes = (len * std::mem::size_of::<f64>()) as f64;
+
+    println!("uploads of {} f64s narrowed into f32s:", len);
+    println!(
+        "  collect + GpuBuffer::from_slice: {:?} ({:.2} GB/s of input)",
+        collected,
+        timing::giga_per_second(bytes, collected)
+    );
+    println!(
+        "  GpuBuffer::from_slice_map:       {:?} ({:.2} GB/s of input)",
+        mapped,
+        timing::giga_per_second(bytes, mapped)
+    );
+
+    Ok(())
+}
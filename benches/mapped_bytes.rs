@@ -0,0 +1,81 @@
+//! Transfers of a large region of bytes through a `Vec` copying it as a whole, and in chunks
+//! with [`GpuBuffer::from_mapped_bytes`] and [`GpuBuffer::read_into_mapped_bytes`].
+//!
+//! The region stands for the mapping of a file, e.g. the `&mmap[..]` of a `memmap2::Mmap`:
+//! the `Vec` path doubles the memory it takes on the CPU, while the chunked one only stages
+//! 64 MiB at a time.
+//!
+//! Arguments: the size of the region in MiB (2048) and the number of runs (3).
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+mod timing;
+
+use gpgpu::prelude::*;
+
+fn main() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let mib = timing::arg(0, 2048usize);
+    let runs = timing::arg(1, 3u32);
+
+    let region = (0..mib << 18).map(|i| i as u32).collect::<Vec<u32>>();
+    let bytes: &[u8] = bytemuck::cast_slice(&region);
+
+    let copied_upload = timing::mean_time(runs, || {
+        let copy = region.clone();
+        let buf = GpuBuffer::<u32>::try_from_slice(&fw, &copy)?;
+        timing::wait(&fw);
+        drop((buf, copy));
+
+        GpuResult::Ok(())
+    })?;
+
+    let chunked_upload = timing::mean_time(runs, || {
+        let buf = GpuBuffer::<u32>::from_mapped_bytes(&fw, bytes)?;
+        drop(buf);
+
+        GpuResult::Ok(())
+    })?;
+
+    let buf = GpuBuffer::<u32>::from_mapped_bytes(&fw, bytes)?;
+
+    let copied_download = timing::mean_time(runs, || {
+        let copy = buf.read_vec_blocking()?;
+        assert_eq!(copy.len(), region.len());
+
+        GpuResult::Ok(())
+    })?;
+
+    let mut out = vec![0u8; bytes.len()];
+    let chunked_download = timing::mean_time(runs, || {
+        buf.read_into_mapped_bytes(&mut out)?;
+
+        GpuResult::Ok(())
+    })?;
+    assert!(out == bytes);
+
+    let size = bytes.len() as f64;
+    let report = |name, time| {
+        println!(
+            "  {:<45} {:?} ({:.2} GB/s)",
+            name,
+            time,
+            timing::giga_per_second(size, time)
+        )
+    };
+
+    println!("transfers of {} MiB:", mib);
+    report("upload, Vec + GpuBuffer::from_slice:", copied_upload);
+    report("upload, GpuBuffer::from_mapped_bytes:", chunked_upload);
+    report("download, GpuBuffer::read_vec_blocking:", copied_download);
+    report(
+        "download, GpuBuffer::read_into_mapped_bytes:",
+        chunked_download,
+    );
+
+    Ok(())
+}
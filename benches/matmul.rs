@@ -0,0 +1,72 @@
+//! Products of matrices with [`ops::matmul_with`], square, square with `b` transposed and
+//! tall and skinny, reporting their GFLOP/s.
+//!
+//! Arguments: the size of the square matrices (1024) and the number of runs (5).
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+mod timing;
+
+use gpgpu::{
+    ops::{self, MatmulOptions},
+    prelude::*,
+};
+
+fn main() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let size = timing::arg(0, 1024u32);
+    let runs = timing::arg(1, 5u32);
+
+    // `m`, `n` and `k` of the products, the skinny one of as many operations as the others.
+    let shapes = [
+        ("square", (size, size, size), false),
+        ("square, transposed b", (size, size, size), true),
+        ("skinny", (16 * size, (size / 16).max(1), size), false),
+    ];
+
+    println!("products of {0}x{0} matrices and their reshapes:", size);
+
+    for (name, (m, n, k), transpose_b) in shapes {
+        let a = (0..m * k)
+            .map(|i| (i % 7) as f32 * 0.25)
+            .collect::<Vec<_>>();
+        let b = (0..k * n).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>();
+
+        // The first element of the product, from the first row of `a` and column of `b`.
+        let stride = if transpose_b { 1 } else { n as usize };
+        let expected = (0..k as usize).map(|i| a[i] * b[i * stride]).sum::<f32>();
+
+        let a = GpuBuffer::from_slice(&fw, &a);
+        let b = GpuBuffer::from_slice(&fw, &b);
+        let mut c = GpuBuffer::<f32>::with_capacity(&fw, m as u64 * n as u64);
+        let options = MatmulOptions::default().transpose_b(transpose_b);
+
+        let time = timing::mean_time(runs, || {
+            ops::matmul_with(&fw, &a, &b, &mut c, m, n, k, options)?;
+            timing::wait(&fw);
+
+            GpuResult::Ok(())
+        })?;
+
+        let mut first = [0.0f32];
+        c.read_blocking(&mut first)?;
+        assert!((first[0] - expected).abs() <= 1e-3 * expected.abs().max(1.0));
+
+        let flops = 2.0 * m as f64 * n as f64 * k as f64;
+        println!(
+            "  {:<22} {}x{}x{}: {:?} ({:.2} GFLOP/s)",
+            name,
+            m,
+            n,
+            k,
+            time,
+            timing::giga_per_second(flops, time)
+        );
+    }
+
+    Ok(())
+}
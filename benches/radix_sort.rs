@@ -0,0 +1,94 @@
+//! Sorts of `u32` keys, alone with [`ops::radix_sort`] and with their indices with
+//! [`ops::radix_sort_pairs`], of random, sorted and reversed inputs.
+//!
+//! Arguments: the number of keys (4M) and of runs (5).
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+mod timing;
+
+use gpgpu::{ops, prelude::*};
+
+fn main() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let len = timing::arg(0, 4usize << 20);
+    let runs = timing::arg(1, 5u32);
+
+    let mut state = 0x1234_5678u32;
+    let random = (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        })
+        .collect::<Vec<u32>>();
+    let sorted = (0..len as u32).collect::<Vec<u32>>();
+    let reversed = sorted.iter().rev().copied().collect::<Vec<u32>>();
+
+    let mut buffers = (
+        GpuBuffer::<u32>::with_capacity(&fw, len as u64),
+        GpuBuffer::<u32>::with_capacity(&fw, len as u64),
+    );
+
+    println!("sorts of {} keys:", len);
+
+    for (name, input) in [
+        ("random", &random),
+        ("sorted", &sorted),
+        ("reversed", &reversed),
+    ] {
+        let keys_time = timing::mean_time_with(
+            runs,
+            &mut buffers,
+            |(keys, _)| {
+                keys.write(input)?;
+                timing::wait(&fw);
+                GpuResult::Ok(())
+            },
+            |(keys, _)| {
+                ops::radix_sort(&fw, keys)?;
+                timing::wait(&fw);
+                Ok(())
+            },
+        )?;
+
+        let result = buffers.0.read_vec_blocking()?;
+        assert!(result.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let pairs_time = timing::mean_time_with(
+            runs,
+            &mut buffers,
+            |(keys, values)| {
+                keys.write(input)?;
+                values.write(&sorted)?;
+                timing::wait(&fw);
+                GpuResult::Ok(())
+            },
+            |(keys, values)| {
+                ops::radix_sort_pairs(&fw, keys, values)?;
+                timing::wait(&fw);
+                Ok(())
+            },
+        )?;
+
+        let index = buffers.1.read_vec_blocking()?[0];
+        assert_eq!(input[index as usize], result[0]);
+
+        let throughput = |time| timing::giga_per_second(len as f64, time) * 1e3;
+        println!(
+            "  {:<9} keys: {:?} ({:.1} Mkeys/s), pairs: {:?} ({:.1} Mkeys/s)",
+            name,
+            keys_time,
+            throughput(keys_time),
+            pairs_time,
+            throughput(pairs_time)
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Timing helpers of the benches, which run without the unstable `test` harness.
+//!
+//! The sizes of a bench can be given as its arguments, e.g. `cargo bench --bench matmul -- 256`
+//! for a quick run. Each bench skips itself when no adapter is available, like the tests.
+
+#![allow(dead_code)]
+
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use gpgpu::Framework;
+
+/// Returns the argument at `index`, ignoring the flags `cargo bench` passes, or `default`.
+pub fn arg<T: FromStr>(index: usize, default: T) -> T {
+    std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .nth(index)
+        .map(|arg| {
+            arg.parse()
+                .unwrap_or_else(|_| panic!("argument {} is not a number: `{}`", index, arg))
+        })
+        .unwrap_or(default)
+}
+
+/// Runs `f` once to warm the caches of the framework up, then `runs` times, returning the
+/// mean time of these runs. `f` must [`wait`] for the work it enqueues to be done.
+pub fn mean_time<E>(runs: u32, mut f: impl FnMut() -> Result<(), E>) -> Result<Duration, E> {
+    f()?;
+
+    let start = Instant::now();
+    for _ in 0..runs {
+        f()?;
+    }
+
+    Ok(start.elapsed() / runs.max(1))
+}
+
+/// Like [`mean_time`], calling `setup` before each run of `f` without timing it, e.g. to
+/// restore the input of an operation working in place. Both are given `state` to work on.
+pub fn mean_time_with<S, E>(
+    runs: u32,
+    state: &mut S,
+    mut setup: impl FnMut(&mut S) -> Result<(), E>,
+    mut f: impl FnMut(&mut S) -> Result<(), E>,
+) -> Result<Duration, E> {
+    let mut total = Duration::ZERO;
+
+    // The first run warms up.
+    for run in 0..=runs {
+        setup(state)?;
+
+        let start = Instant::now();
+        f(state)?;
+        if run > 0 {
+            total += start.elapsed();
+        }
+    }
+
+    Ok(total / runs.max(1))
+}
+
+/// Blocks until the GPU is done with everything submitted to it.
+pub fn wait(fw: &Framework) {
+    fw.as_gpu_device().poll(wgpu::Maintain::Wait);
+}
+
+/// Returns the billions of units per second of processing `units` in `time`.
+pub fn giga_per_second(units: f64, time: Duration) -> f64 {
+    units / time.as_secs_f64() / 1e9
+}
@@ -0,0 +1,97 @@
+//! Transpositions of matrices of `f32`s with [`ops::transpose`] and [`ops::transpose_in_place`],
+//! next to a naive kernel reading by rows and writing by columns, reporting their bandwidth.
+//!
+//! Arguments: the size of the square matrix (4096), rounded up to a multiple of 16, and the
+//! number of runs (5).
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+mod timing;
+
+use gpgpu::{ops, prelude::*};
+
+/// Transposes a square matrix, the size of which is the number of invocations per row.
+const NAIVE_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> input: array<f32>;
+@group(0) @binding(1) var<storage, read_write> output: array<f32>;
+
+@compute @workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let size = groups.x * 16u;
+    output[id.x * size + id.y] = input[id.y * size + id.x];
+}
+"#;
+
+fn main() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let size = timing::arg(0, 4096u32).next_multiple_of(16);
+    let runs = timing::arg(1, 5u32);
+
+    let len = size as u64 * size as u64;
+    let matrix = (0..len).map(|i| i as f32).collect::<Vec<_>>();
+    let input = GpuBuffer::from_slice(&fw, &matrix);
+    let mut output = GpuBuffer::<f32>::with_capacity(&fw, len);
+
+    let shader = Shader::from_wgsl_source(&fw, NAIVE_SHADER, Some("naive transpose"))?;
+    let set = DescriptorSet::default()
+        .bind_buffer(&input, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&output, GpuBufferUsage::ReadWrite);
+    let naive = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    let naive_time = timing::mean_time(runs, || {
+        naive.enqueue(size / 16, size / 16, 1)?;
+        timing::wait(&fw);
+
+        GpuResult::Ok(())
+    })?;
+
+    let tiled_time = timing::mean_time(runs, || {
+        ops::transpose(&fw, &input, &mut output, size, size)?;
+        timing::wait(&fw);
+
+        GpuResult::Ok(())
+    })?;
+
+    // The element at row 0 and column 1 comes from row 1 and column 0.
+    let mut first = [0.0f32; 2];
+    output.read_blocking(&mut first)?;
+    assert_eq!(first, [0.0, size as f32]);
+
+    // Half as wide and twice as long, of as many elements.
+    let skinny_time = timing::mean_time(runs, || {
+        ops::transpose(&fw, &input, &mut output, 2 * size, size / 2)?;
+        timing::wait(&fw);
+
+        GpuResult::Ok(())
+    })?;
+
+    let in_place_time = timing::mean_time(runs, || {
+        ops::transpose_in_place(&fw, &mut output, size)?;
+        timing::wait(&fw);
+
+        GpuResult::Ok(())
+    })?;
+
+    // Each element is read and written once.
+    let bytes = 2.0 * (len * 4) as f64;
+    let report = |name, time| {
+        println!(
+            "  {:<28} {:?} ({:.2} GB/s)",
+            name,
+            time,
+            timing::giga_per_second(bytes, time)
+        )
+    };
+
+    println!("transpositions of {0}x{0} f32s:", size);
+    report("naive kernel:", naive_time);
+    report("ops::transpose:", tiled_time);
+    report("ops::transpose, 2n x n/2:", skinny_time);
+    report("ops::transpose_in_place:", in_place_time);
+
+    Ok(())
+}
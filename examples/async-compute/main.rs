@@ -0,0 +1,45 @@
+use gpgpu::prelude::*;
+
+// `simple-compute` example without any blocking call: the futures of gpgpu are driven by the
+// polling thread of the framework, so any executor runs them, here the one of `futures`.
+fn main() -> GpuResult<()> {
+    futures::executor::block_on(run())
+}
+
+async fn run() -> GpuResult<()> {
+    let fw = Framework::try_default_async().await?;
+
+    let shader = Shader::from_wgsl_file(&fw, "examples/simple-compute/shader.wgsl")?;
+
+    let size = 10000;
+
+    let data_a = (0..size).collect::<Vec<u32>>();
+    let data_b = (0..size).rev().collect::<Vec<u32>>();
+
+    let gpu_vec_a = GpuBuffer::<u32>::with_capacity(&fw, size as u64);
+    let gpu_vec_b = GpuBuffer::<u32>::with_capacity(&fw, size as u64);
+    let gpu_vec_c = GpuBuffer::<u32>::with_capacity(&fw, size as u64);
+
+    // Resolves once the GPU has received the data.
+    gpu_vec_a.write_async(&data_a).await?;
+    gpu_vec_b.write_async(&data_b).await?;
+
+    let bindings = DescriptorSet::default()
+        .bind_buffer(&gpu_vec_a, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_vec_b, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_vec_c, GpuBufferUsage::ReadWrite);
+
+    let program = Program::new(&shader, "main").add_descriptor_set(bindings);
+    let kernel = Kernel::new_async(&fw, program).await?;
+
+    // Resolves once the GPU has finished the dispatch.
+    kernel.enqueue_async(size, 1, 1).await?;
+
+    let gpu_result = gpu_vec_c.read_vec().await?;
+
+    for (a, (b, c)) in data_a.into_iter().zip(data_b.into_iter().zip(gpu_result)) {
+        assert_eq!(a * b, c);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use gpgpu::{ops, BufOps};
+
+// Example that times the vector updates of the iterations of a conjugate gradient solver,
+// `x += alpha * p`, `r -= alpha * ap` and `p = r + beta * p`, enqueued one operation at a time
+// with `ops::axpy` and `ops::scal`, and recorded into a single submission per iteration with
+// `ops::record_axpy` and `ops::record_scal`, after a first run warming the caches of the
+// framework up.
+//
+// The length of the vectors, 65536 by default, and the number of iterations, 1000 by default,
+// can be given as the first and second arguments.
+
+/// Runs `f` twice, returning the time of the second run.
+fn time(mut f: impl FnMut()) -> Duration {
+    f();
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let mut args = std::env::args().skip(1);
+    let len = args
+        .next()
+        .map(|len| len.parse().expect("The length must be a number"))
+        .unwrap_or(65536u64);
+    let iterations = args
+        .next()
+        .map(|n| {
+            n.parse()
+                .expect("The number of iterations must be a number")
+        })
+        .unwrap_or(1000u32);
+
+    let (alpha, beta) = (0.5f32, 0.25f32);
+    let values = (0..len).map(|i| (i % 16) as f32).collect::<Vec<_>>();
+
+    let mut x = gpgpu::GpuBuffer::from_slice(&fw, &values);
+    let mut r = gpgpu::GpuBuffer::from_slice(&fw, &values);
+    let mut p = gpgpu::GpuBuffer::from_slice(&fw, &values);
+    let ap = gpgpu::GpuBuffer::from_slice(&fw, &values);
+    let mut first = [0.0f32];
+
+    let unbatched = time(|| {
+        for _ in 0..iterations {
+            ops::axpy(&fw, alpha, &p, &mut x).unwrap();
+            ops::axpy(&fw, -alpha, &ap, &mut r).unwrap();
+            ops::scal(&fw, beta, &mut p).unwrap();
+            ops::axpy(&fw, 1.0, &r, &mut p).unwrap();
+        }
+        x.read_blocking(&mut first).unwrap(); // Waits for the iterations to be done
+    });
+
+    let batched = time(|| {
+        for _ in 0..iterations {
+            let mut recorder = fw.create_command_recorder();
+            ops::record_axpy(&mut recorder, alpha, &p, &x).unwrap();
+            ops::record_axpy(&mut recorder, -alpha, &ap, &r).unwrap();
+            ops::record_scal(&mut recorder, beta, &p).unwrap();
+            ops::record_axpy(&mut recorder, 1.0, &r, &p).unwrap();
+            recorder.submit();
+        }
+        x.read_blocking(&mut first).unwrap();
+    });
+
+    let per_iteration = |time: Duration| time / iterations;
+
+    println!(
+        "{} iterations of 4 operations over vectors of {} f32s:",
+        iterations, len
+    );
+    println!(
+        "  unbatched: {:?} ({:?} per iteration)",
+        unbatched,
+        per_iteration(unbatched)
+    );
+    println!(
+        "  batched:   {:?} ({:?} per iteration)",
+        batched,
+        per_iteration(batched)
+    );
+}
@@ -0,0 +1,74 @@
+use gpgpu::{BufOps, ImgOps};
+use minifb::{Key, Window, WindowOptions};
+
+const WIDTH: usize = 256;
+const HEIGHT: usize = 256;
+
+// Example evolving Conway's Game of Life on the GPU, presenting each generation
+// to a window. The generations swap their input and output buffers, like `jacobi`.
+fn main() -> gpgpu::GpuResult<()> {
+    let mut window = Window::new(
+        "gpgpu cellular automaton (ESC to exit)",
+        WIDTH * 3,
+        HEIGHT * 3,
+        WindowOptions {
+            resize: true,
+            ..Default::default()
+        },
+    )
+    .expect("The window could not be created");
+    window.limit_update_rate(Some(std::time::Duration::from_millis(33)));
+
+    let (width, height) = window.get_size();
+
+    // SAFETY: `window` outlives `surface`, dropped before it.
+    let (fw, mut surface) =
+        unsafe { gpgpu::Framework::try_with_window(&window, width as u32, height as u32)? };
+
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/cellular-automaton/shader.wgsl")?;
+
+    // Pseudo-random initial generation.
+    let mut seed = 0x2545_f491u32;
+    let cells = (0..WIDTH * HEIGHT)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            (seed % 4 == 0) as u32
+        })
+        .collect::<Vec<u32>>();
+
+    let even = gpgpu::GpuBuffer::from_slice(&fw, &cells);
+    let odd = gpgpu::GpuBuffer::<u32>::with_capacity(&fw, cells.len() as u64);
+    let image = gpgpu::GpuImage::<gpgpu::primitives::pixels::Rgba8UintNorm>::new(
+        &fw,
+        WIDTH as u32,
+        HEIGHT as u32,
+    );
+
+    let generation = |cells, next| {
+        gpgpu::DescriptorSet::default()
+            .bind_buffer(cells, gpgpu::GpuBufferUsage::ReadOnly)
+            .bind_buffer(next, gpgpu::GpuBufferUsage::ReadWrite)
+            .bind_image(&image)
+    };
+    let sets = [generation(&even, &odd), generation(&odd, &even)];
+
+    let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(generation(&even, &odd));
+    let kernel = gpgpu::Kernel::new(&fw, program)?;
+
+    let mut step = 0;
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        kernel.enqueue_with_sets(WIDTH as u32 / 8, HEIGHT as u32 / 8, 1, &[&sets[step % 2]])?;
+        step += 1;
+
+        let (width, height) = window.get_size();
+        surface.resize(width as u32, height as u32);
+
+        image.present_to(&mut surface)?;
+
+        window.update();
+    }
+
+    Ok(())
+}
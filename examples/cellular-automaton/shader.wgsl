@@ -0,0 +1,38 @@
+// Conway's Game of Life on a torus, drawing the cells of the new generation.
+
+let WIDTH: u32 = 256u;
+let HEIGHT: u32 = 256u;
+
+@group(0) @binding(0) var<storage, read> cells: array<u32>;
+@group(0) @binding(1) var<storage, read_write> next: array<u32>;
+@group(0) @binding(2) var image: texture_storage_2d<rgba8unorm, write>;
+
+fn cell(x: u32, y: u32) -> u32 {
+    return cells[(y % HEIGHT) * WIDTH + x % WIDTH];
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let x = global_id.x;
+    let y = global_id.y;
+
+    if (x >= WIDTH || y >= HEIGHT) {
+        return;
+    }
+
+    // Neighbours, the borders wrapping around.
+    let left = x + WIDTH - 1u;
+    let up = y + HEIGHT - 1u;
+    let neighbours = cell(left, up) + cell(x, up) + cell(x + 1u, up)
+        + cell(left, y) + cell(x + 1u, y)
+        + cell(left, y + 1u) + cell(x, y + 1u) + cell(x + 1u, y + 1u);
+
+    let alive = cell(x, y);
+    var state = 0u;
+    if (neighbours == 3u || (alive == 1u && neighbours == 2u)) {
+        state = 1u;
+    }
+
+    next[y * WIDTH + x] = state;
+    textureStore(image, vec2<i32>(i32(x), i32(y)), vec4<f32>(0.1, f32(state), f32(state) * 0.6, 1.0));
+}
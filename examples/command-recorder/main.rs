@@ -0,0 +1,64 @@
+use std::time::{Duration, Instant};
+
+use gpgpu::BufOps;
+
+// Example that runs a chain of small kernels, enqueueing them one by one and then
+// recording them into a single submission, comparing the time spent per dispatch.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let shader =
+        gpgpu::Shader::from_wgsl_file(&fw, "examples/command-recorder/shader.wgsl").unwrap();
+
+    let size = 64; // Size of the vector
+    let chain = 12; // Kernels run back to back per frame
+    let frames = 100;
+
+    let values = gpgpu::GpuBuffer::<u32>::with_capacity(&fw, size as u64);
+
+    let kernels = (0..chain)
+        .map(|_| {
+            let desc = gpgpu::DescriptorSet::default()
+                .bind_buffer(&values, gpgpu::GpuBufferUsage::ReadWrite);
+            let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(desc);
+
+            gpgpu::Kernel::new(&fw, program).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let separate = time_frames(frames, &values, || {
+        for kernel in &kernels {
+            kernel.enqueue(size, 1, 1).unwrap();
+        }
+    });
+
+    let recorded = time_frames(frames, &values, || {
+        let mut recorder = fw.create_command_recorder();
+
+        for kernel in &kernels {
+            recorder.enqueue(kernel, size, 1, 1).unwrap();
+        }
+
+        recorder.submit();
+    });
+
+    // Every frame adds `chain` to each value, in both runs.
+    let result = values.read_vec_blocking().unwrap();
+    assert!(result.iter().all(|&value| value == 2 * frames * chain));
+
+    let dispatches = frames * chain;
+    println!("Kernel::enqueue: {:?} per dispatch", separate / dispatches);
+    println!("CommandRecorder: {:?} per dispatch", recorded / dispatches);
+}
+
+// Runs `frame` `frames` times, then waits for the GPU to finish all of them.
+fn time_frames(frames: u32, values: &gpgpu::GpuBuffer<u32>, mut frame: impl FnMut()) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..frames {
+        frame();
+    }
+    values.read_vec_blocking().unwrap();
+
+    start.elapsed()
+}
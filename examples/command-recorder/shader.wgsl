@@ -0,0 +1,12 @@
+struct Vector {
+    data: array<u32>,
+};
+
+@group(0) @binding(0) var<storage, read_write> values: Vector;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+
+    values.data[idx] = values.data[idx] + 1u;
+}
@@ -0,0 +1,71 @@
+use gpgpu::{
+    ops::{self, MatmulOptions},
+    BufOps,
+};
+
+// Example that solves the linear system of a 1D Poisson equation with the conjugate gradient
+// method, using only the operations of `gpgpu::ops`: the products of the matrix are computed
+// with `ops::matmul`, the dot products with `ops::dot`, and the vector updates `y += alpha * x`
+// with `ops::axpy`.
+//
+// The size of the system, 256 by default, can be given as the first argument.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let n = std::env::args()
+        .nth(1)
+        .map(|n| n.parse().expect("The size must be a number"))
+        .unwrap_or(256u32);
+    let len = n as usize;
+
+    // The tridiagonal matrix of the second derivative, symmetric positive definite.
+    let mut matrix = vec![0.0f32; len * len];
+    for i in 0..len {
+        matrix[i * len + i] = 2.0;
+        if i > 0 {
+            matrix[i * len + i - 1] = -1.0;
+            matrix[(i - 1) * len + i] = -1.0;
+        }
+    }
+    let rhs = vec![1.0f32; len];
+
+    let a = gpgpu::GpuBuffer::from_slice(&fw, &matrix);
+    let b = gpgpu::GpuBuffer::from_slice(&fw, &rhs);
+
+    let mut x = gpgpu::GpuBuffer::from_slice(&fw, &vec![0.0f32; len]);
+    let mut r = gpgpu::GpuBuffer::from_slice(&fw, &rhs);
+    let mut p = gpgpu::GpuBuffer::from_slice(&fw, &rhs);
+    let mut ap = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, len as u64);
+
+    let tolerance = 1e-5 * ops::norm2(&fw, &b).unwrap();
+    let mut rs = ops::dot(&fw, &r, &r).unwrap();
+    let mut iterations = 0;
+
+    while rs.sqrt() > tolerance && iterations < 4 * n {
+        ops::matmul(&fw, &a, &p, &mut ap, n, 1, n).unwrap();
+        let alpha = rs / ops::dot(&fw, &p, &ap).unwrap();
+
+        ops::axpy(&fw, alpha, &p, &mut x).unwrap();
+        ops::axpy(&fw, -alpha, &ap, &mut r).unwrap();
+
+        let next_rs = ops::dot(&fw, &r, &r).unwrap();
+        // `p = r + beta * p`.
+        ops::scal(&fw, next_rs / rs, &mut p).unwrap();
+        ops::axpy(&fw, 1.0, &r, &mut p).unwrap();
+
+        rs = next_rs;
+        iterations += 1;
+    }
+
+    // The residual `b - A * x` computed from scratch, without the rounding of the iterations.
+    let mut residual = gpgpu::GpuBuffer::from_slice(&fw, &rhs);
+    let options = MatmulOptions::default().alpha(-1.0).beta(1.0);
+    ops::matmul_with(&fw, &a, &x, &mut residual, n, 1, n, options).unwrap();
+    let residual = ops::norm2(&fw, &residual).unwrap() / ops::norm2(&fw, &b).unwrap();
+
+    println!(
+        "Solved a {0}x{0} Poisson system in {1} iterations, relative residual {2:e}",
+        n, iterations, residual
+    );
+    assert!(residual < 1e-3);
+}
@@ -0,0 +1,40 @@
+#![allow(deprecated)]
+
+use gpgpu::BufOps;
+
+// `simple-compute` example written with the former names of the API only,
+// checking that they keep forwarding to their replacements.
+fn main() -> gpgpu::GpuResult<()> {
+    let fw = gpgpu::Framework::try_default()?;
+
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/simple-compute/shader.wgsl")?;
+
+    let size = 10000;
+
+    let data_a = (0..size).collect::<Vec<u32>>();
+    let data_b = (0..size).rev().collect::<Vec<u32>>();
+
+    let gpu_vec_a = gpgpu::GpuBuffer::from_slice(&fw, &data_a);
+    let gpu_vec_b = gpgpu::GpuBuffer::from_slice(&fw, &data_b);
+    let gpu_vec_c = gpgpu::GpuBuffer::with_capacity(&fw, size as u64);
+
+    let bindings = gpgpu::DescriptorSet::default()
+        .bind_storage_buffer(&gpu_vec_a, gpgpu::AccessMode::ReadOnly)
+        .bind_storage_buffer(&gpu_vec_b, gpgpu::AccessMode::ReadOnly)
+        .bind_storage_buffer(&gpu_vec_c, gpgpu::AccessMode::ReadWrite);
+
+    let builder: gpgpu::KernelBuilder = fw
+        .create_kernel_builder(&shader, "main")
+        .add_descriptor_set(bindings);
+    let kernel = builder.build(&fw)?;
+
+    kernel.enqueue(size, 1, 1)?;
+
+    let gpu_result = gpu_vec_c.read_vec_blocking()?;
+
+    for (a, (b, c)) in data_a.into_iter().zip(data_b.into_iter().zip(gpu_result)) {
+        assert_eq!(a * b, c);
+    }
+
+    Ok(())
+}
@@ -20,7 +20,10 @@ fn main() {
         .bind_image(&output_img);
     let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(desc);
 
-    gpgpu::Kernel::new(&fw, program).enqueue(width / 32, height / 32, 1); // Since the kernel workgroup size is (32,32,1) dims are divided
+    gpgpu::Kernel::new(&fw, program)
+        .unwrap()
+        .enqueue(width / 32, height / 32, 1) // Since the kernel workgroup size is (32,32,1) dims are divided
+        .unwrap();
 
     let output = output_img.read_to_image_buffer_blocking().unwrap();
     output
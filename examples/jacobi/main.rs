@@ -0,0 +1,72 @@
+use std::time::Instant;
+
+use gpgpu::BufOps;
+
+// Example that runs the iterations of a Jacobi solver, swapping its input and output
+// buffers on each of them. The iterations are enqueued one by one and then in a
+// single submission, comparing the time spent per iteration.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/jacobi/shader.wgsl").unwrap();
+
+    let size = 1024; // Size of the vectors
+    let iterations = 1000; // Even, so that the solution ends up in `even`
+
+    let rhs = gpgpu::GpuBuffer::from_slice(&fw, &vec![1.0f32; size]);
+    let even = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, size as u64);
+    let odd = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, size as u64);
+
+    let step = |input, output| {
+        gpgpu::DescriptorSet::default()
+            .bind_buffer(&rhs, gpgpu::GpuBufferUsage::ReadOnly)
+            .bind_buffer(input, gpgpu::GpuBufferUsage::ReadOnly)
+            .bind_buffer(output, gpgpu::GpuBufferUsage::ReadWrite)
+    };
+    let sets = [step(&even, &odd), step(&odd, &even)];
+
+    let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(step(&even, &odd));
+    let kernel = gpgpu::Kernel::new(&fw, program).unwrap();
+
+    let workgroups = (size as u32).div_ceil(64);
+
+    let start = Instant::now();
+    for iteration in 0..iterations {
+        kernel
+            .enqueue_with_sets(workgroups, 1, 1, &[&sets[iteration as usize % 2]])
+            .unwrap();
+    }
+    let separate = read(&even);
+    let separate_time = start.elapsed();
+
+    even.write(&vec![0.0; size]).unwrap();
+    odd.write(&vec![0.0; size]).unwrap();
+
+    let pairs = [[&sets[0]], [&sets[1]]];
+
+    let start = Instant::now();
+    kernel
+        .enqueue_repeat_with(iterations, workgroups, 1, 1, |iteration| {
+            &pairs[iteration as usize % 2][..]
+        })
+        .unwrap()
+        .wait();
+    let repeated = read(&even);
+    let repeated_time = start.elapsed();
+
+    // Both runs compute the same iterations.
+    assert_eq!(separate, repeated);
+
+    println!(
+        "Kernel::enqueue_with_sets: {:?} per iteration",
+        separate_time / iterations
+    );
+    println!(
+        "Kernel::enqueue_repeat_with: {:?} per iteration",
+        repeated_time / iterations
+    );
+}
+
+fn read(buffer: &gpgpu::GpuBuffer<f32>) -> Vec<f32> {
+    buffer.read_vec_blocking().unwrap()
+}
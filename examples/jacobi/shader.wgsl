@@ -0,0 +1,30 @@
+struct Vector {
+    data: array<f32>,
+};
+
+@group(0) @binding(0) var<storage, read> rhs: Vector;
+@group(0) @binding(1) var<storage, read> input: Vector;
+@group(0) @binding(2) var<storage, read_write> output: Vector;
+
+// One Jacobi iteration of the 1D Poisson equation -u'' = rhs, with u = 0 at both ends.
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    let len = arrayLength(&output.data);
+
+    if (idx >= len) {
+        return;
+    }
+
+    var left = 0.0;
+    var right = 0.0;
+
+    if (idx > 0u) {
+        left = input.data[idx - 1u];
+    }
+    if (idx + 1u < len) {
+        right = input.data[idx + 1u];
+    }
+
+    output.data[idx] = (left + right + rhs.data[idx]) * 0.5;
+}
@@ -0,0 +1,48 @@
+use std::time::Instant;
+
+use gpgpu::BufOps;
+
+// Example that multiplies two square matrices with `ops::matmul`, printing the GFLOP/s
+// of the product after a first one warming the caches of the framework up.
+//
+// The size of the matrices, 1024 by default, can be given as the first argument.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let size = std::env::args()
+        .nth(1)
+        .map(|size| size.parse().expect("The size must be a number"))
+        .unwrap_or(1024u32);
+    let len = (size * size) as usize;
+
+    let a = (0..len)
+        .map(|i| (i % 7) as f32 * 0.25)
+        .collect::<Vec<f32>>();
+    let b = (0..len).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<f32>>();
+
+    let gpu_a = gpgpu::GpuBuffer::from_slice(&fw, &a);
+    let gpu_b = gpgpu::GpuBuffer::from_slice(&fw, &b);
+    let mut gpu_c = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, len as u64);
+
+    gpgpu::ops::matmul(&fw, &gpu_a, &gpu_b, &mut gpu_c, size, size, size).unwrap();
+    let mut first = [0.0f32];
+    gpu_c.read_blocking(&mut first).unwrap();
+
+    let start = Instant::now();
+    gpgpu::ops::matmul(&fw, &gpu_a, &gpu_b, &mut gpu_c, size, size, size).unwrap();
+    gpu_c.read_blocking(&mut first).unwrap(); // Waits for the product to be done
+    let time = start.elapsed();
+
+    let expected = (0..size as usize)
+        .map(|i| a[i] * b[i * size as usize])
+        .sum::<f32>();
+    assert!((first[0] - expected).abs() <= 1e-3 * expected.abs().max(1.0));
+
+    let flops = 2.0 * (size as f64).powi(3);
+    println!(
+        "Product of {0}x{0} matrices: {1:?} ({2:.2} GFLOP/s)",
+        size,
+        time,
+        flops / time.as_secs_f64() / 1e9
+    );
+}
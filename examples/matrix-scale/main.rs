@@ -0,0 +1,43 @@
+use gpgpu::BufOps;
+
+gpgpu::gpu_struct! {
+    // `Params` of the shader. Uniform structs are a multiple of 16 bytes.
+    uniform struct Params {
+        factor: f32,
+        _padding: [f32; 3],
+    }
+}
+
+// Scales a matrix uploaded from a transposed `ndarray` view, which is copied into
+// row-major order, and reads the result back as an `Array2<f32>`.
+fn main() -> gpgpu::GpuResult<()> {
+    let fw = gpgpu::Framework::try_default()?;
+
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/matrix-scale/shader.wgsl")?;
+
+    let matrix = ndarray::Array2::from_shape_fn((300, 200), |(row, col)| (row * 200 + col) as f32);
+    let transposed = matrix.t(); // 200 x 300, not in standard layout
+    let (rows, cols) = transposed.dim();
+
+    let params = Params {
+        factor: 0.5,
+        _padding: [0.0; 3],
+    };
+    let params = gpgpu::GpuUniformBuffer::from_slice(&fw, &[params]);
+    let input = gpgpu::GpuBuffer::from_array2(&fw, &transposed);
+    let output = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, (rows * cols) as u64);
+
+    let bindings = gpgpu::DescriptorSet::default()
+        .bind_uniform_buffer(&params)
+        .bind_buffer(&input, gpgpu::GpuBufferUsage::ReadOnly)
+        .bind_buffer(&output, gpgpu::GpuBufferUsage::ReadWrite);
+
+    let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(bindings);
+    gpgpu::Kernel::new(&fw, program)?.enqueue_for_extent(((rows * cols) as u32, 1, 1))?;
+
+    let scaled: ndarray::Array2<f32> = output.read_to_array2_blocking(rows, cols)?;
+
+    assert_eq!(scaled, &transposed * 0.5);
+
+    Ok(())
+}
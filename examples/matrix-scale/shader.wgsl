@@ -0,0 +1,16 @@
+struct Params {
+    factor: f32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> input: array<f32>;
+@group(0) @binding(2) var<storage, read_write> output: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&output)) {
+        output[i] = input[i] * params.factor;
+    }
+}
@@ -1,5 +1,14 @@
 use gpgpu::BufOps;
 
+gpgpu::gpu_struct! {
+    // `Dims` of the shader. Uniform structs are a multiple of 16 bytes.
+    uniform struct Dims {
+        x: u32,
+        y: u32,
+        _padding: [u32; 2],
+    }
+}
+
 // Simple compute example that multiplies 2 square arrays (matrixes)  A and B, storing the result in another array C using ndarray.
 fn main() {
     let fw = gpgpu::Framework::default();
@@ -11,7 +20,12 @@ fn main() {
     let array_src = ndarray::Array::<i32, _>::ones(dims) * 2;
     let src_view = array_src.view();
 
-    let gpu_arrays_len = gpgpu::GpuUniformBuffer::from_slice(&fw, &[dims.0, dims.1]); // Send the ndarray dimensions
+    let gpu_dims = Dims {
+        x: dims.0 as u32,
+        y: dims.1 as u32,
+        _padding: [0; 2],
+    };
+    let gpu_arrays_len = gpgpu::GpuUniformBuffer::from_slice(&fw, &[gpu_dims]); // Send the ndarray dimensions
 
     let gpu_array_a = gpgpu::GpuArray::from_array(&fw, src_view).unwrap(); // Array A
     let gpu_array_b = gpgpu::GpuArray::from_array(&fw, src_view).unwrap(); // Array B
@@ -29,8 +43,10 @@ fn main() {
         .add_descriptor_set(desc_1);
 
     gpgpu::Kernel::new(&fw, program)
-        // .enqueue((dims.0 * dims.1) as u32, 1, 1); // Kernel main_fn 1. Enqueuing in a single dimension
-        .enqueue(dims.0 as u32 / 32, dims.1 as u32 / 32, 1); // Kernel main_fn 2. Enqueuing in x and y dimensions (array dimensions are needed)
+        .unwrap()
+        // .enqueue((dims.0 * dims.1) as u32, 1, 1) // Kernel main_fn 1. Enqueuing in a single dimension
+        .enqueue(dims.0 as u32 / 32, dims.1 as u32 / 32, 1) // Kernel main_fn 2. Enqueuing in x and y dimensions (array dimensions are needed)
+        .unwrap();
 
     let array_output = gpu_array_c.read_blocking().unwrap();
 
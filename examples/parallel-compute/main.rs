@@ -37,7 +37,10 @@ fn main() {
                 .bind_buffer(&local_output_buffer, gpgpu::GpuBufferUsage::ReadWrite);
             let program = gpgpu::Program::new(&local_shader, "main").add_descriptor_set(desc);
 
-            gpgpu::Kernel::new(&FW, program).enqueue(size / 32, 1, 1);
+            gpgpu::Kernel::new(&FW, program)
+                .unwrap()
+                .enqueue(size / 32, 1, 1)
+                .unwrap();
 
             local_output_buffer.read_vec_blocking().unwrap()
         });
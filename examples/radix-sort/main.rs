@@ -0,0 +1,56 @@
+use std::time::Instant;
+
+use gpgpu::BufOps;
+
+// Example that sorts random `u32` keys on the GPU with `ops::radix_sort`, then pairs of keys
+// and indices with `ops::radix_sort_pairs`, printing the throughput of each after a first
+// sort warming the caches of the framework up.
+//
+// The number of keys, 4M by default, can be given as the first argument.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let len = std::env::args()
+        .nth(1)
+        .map(|len| len.parse().expect("The number of keys must be a number"))
+        .unwrap_or(4 << 20usize);
+
+    let mut state = 0x1234_5678u32;
+    let keys = (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        })
+        .collect::<Vec<u32>>();
+    let indices = (0..len as u32).collect::<Vec<u32>>();
+
+    let mut gpu_keys = gpgpu::GpuBuffer::from_slice(&fw, &keys);
+    gpgpu::ops::radix_sort(&fw, &mut gpu_keys).unwrap();
+
+    gpu_keys.write(&keys).unwrap();
+    let start = Instant::now();
+    gpgpu::ops::radix_sort(&fw, &mut gpu_keys).unwrap();
+    let sorted = gpu_keys.read_vec_blocking().unwrap();
+    let keys_time = start.elapsed();
+    assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    gpu_keys.write(&keys).unwrap();
+    let mut gpu_values = gpgpu::GpuBuffer::from_slice(&fw, &indices);
+    let start = Instant::now();
+    gpgpu::ops::radix_sort_pairs(&fw, &mut gpu_keys, &mut gpu_values).unwrap();
+    let values = gpu_values.read_vec_blocking().unwrap();
+    let pairs_time = start.elapsed();
+    assert_eq!(keys[values[0] as usize], sorted[0]);
+
+    let throughput = |time: std::time::Duration| len as f64 / time.as_secs_f64() / 1e6;
+    println!(
+        "Sort of {} keys: {:?} ({:.1} Mkeys/s), {:?} with their indices ({:.1} Mkeys/s)",
+        len,
+        keys_time,
+        throughput(keys_time),
+        pairs_time,
+        throughput(pairs_time)
+    );
+}
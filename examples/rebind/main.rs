@@ -0,0 +1,43 @@
+use gpgpu::BufOps;
+
+// Example that processes several input buffers with a single kernel, only swapping its descriptor set.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/rebind/shader.wgsl").unwrap();
+
+    let size = 1000; // Size of the vectors
+
+    let inputs = (0..10)
+        .map(|i| {
+            let data = (0..size).map(|x| x + i).collect::<Vec<u32>>();
+            gpgpu::GpuBuffer::from_slice(&fw, &data)
+        })
+        .collect::<Vec<_>>(); // 10 different input vectors
+    let output = gpgpu::GpuBuffer::<u32>::with_capacity(&fw, size as u64); // Single output vector
+
+    // The kernel is created only once, using the first input.
+    let desc = gpgpu::DescriptorSet::default()
+        .bind_buffer(&inputs[0], gpgpu::GpuBufferUsage::ReadOnly)
+        .bind_buffer(&output, gpgpu::GpuBufferUsage::ReadWrite);
+    let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(desc);
+
+    let mut kernel = gpgpu::Kernel::new(&fw, program).unwrap();
+
+    for (i, input) in inputs.iter().enumerate() {
+        // Same shape as the original descriptor set, so the pipeline is reused.
+        let desc = gpgpu::DescriptorSet::default()
+            .bind_buffer(input, gpgpu::GpuBufferUsage::ReadOnly)
+            .bind_buffer(&output, gpgpu::GpuBufferUsage::ReadWrite);
+        kernel.set_descriptor_set(0, desc).unwrap();
+
+        kernel.enqueue(size, 1, 1).unwrap();
+
+        let result = output.read_vec_blocking().unwrap();
+        for (x, value) in result.into_iter().enumerate() {
+            assert_eq!((x as u32 + i as u32) * 2, value);
+        }
+    }
+
+    println!("{:?}", fw.layout_cache_stats());
+}
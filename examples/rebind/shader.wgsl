@@ -0,0 +1,13 @@
+struct Vector {
+    data: array<u32>,
+};
+
+@group(0) @binding(0) var<storage, read> input: Vector;
+@group(0) @binding(1) var<storage, read_write> output: Vector;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+
+    output.data[idx] = input.data[idx] * 2u;
+}
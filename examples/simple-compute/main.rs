@@ -28,11 +28,11 @@ fn main() {
     let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(bindings);
 
     // Creation of a kernel. This represents the `program` function and its `enqueuing` parameters,
-    let kernel = gpgpu::Kernel::new(&fw, program);
+    let kernel = gpgpu::Kernel::new(&fw, program).unwrap();
 
     // Execution of the kernel. It needs 3 dimmensions, x y and z.
     // Since we are using single-dim vectors, only x is required.
-    kernel.enqueue(size as u32, 1, 1);
+    kernel.enqueue(size as u32, 1, 1).unwrap();
 
     // After the kernel execution, we can read the results from the GPU.
     let gpu_result = gpu_vec_c.read_vec_blocking().unwrap();
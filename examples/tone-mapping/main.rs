@@ -0,0 +1,56 @@
+use gpgpu::{
+    primitives::pixels::{Rgba32Float, Rgba8UintNorm},
+    ImgOps,
+};
+
+const WIDTH: u32 = 512;
+const HEIGHT: u32 = 256;
+
+// This example tone-maps an HDR image into an 8 bit PNG.
+//
+// The image is read from the OpenEXR file given as argument, which requires the `openexr`
+// feature of the `image` crate. Without argument, an HDR gradient is generated instead.
+fn main() {
+    let fw = gpgpu::Framework::default();
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/tone-mapping/shader.wgsl").unwrap();
+
+    let input_img = match std::env::args().nth(1) {
+        Some(path) => gpgpu::GpuImage::<Rgba32Float>::from_exr_file(&fw, path).unwrap(),
+        None => gpgpu::GpuImage::from_image_buffer(&fw, &hdr_gradient()),
+    };
+    let (width, height) = input_img.dimensions();
+
+    // The kernel samples the radiances through a constant image
+    let radiance = gpgpu::GpuConstImage::<Rgba32Float>::new(&fw, width, height);
+    radiance
+        .write(&input_img.read_vec_blocking().unwrap())
+        .unwrap();
+
+    let output_img = gpgpu::GpuImage::<Rgba8UintNorm>::new(&fw, width, height);
+
+    let desc = gpgpu::DescriptorSet::default()
+        .bind_const_image(&radiance)
+        .bind_image(&output_img);
+    let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(desc);
+
+    gpgpu::Kernel::new(&fw, program)
+        .unwrap()
+        .enqueue(width.div_ceil(8), height.div_ceil(8), 1) // The kernel workgroup size is (8, 8, 1)
+        .unwrap();
+
+    output_img
+        .read_to_image_buffer_blocking()
+        .unwrap()
+        .save("examples/tone-mapping/tone-mapped.png")
+        .unwrap();
+}
+
+/// Radiances growing exponentially from left to right, up to 2^12, tinted from top to bottom.
+fn hdr_gradient() -> image::ImageBuffer<image::Rgba<f32>, Vec<f32>> {
+    image::ImageBuffer::from_fn(WIDTH, HEIGHT, |x, y| {
+        let radiance = 2f32.powf(x as f32 / WIDTH as f32 * 24.0 - 12.0);
+        let tint = y as f32 / HEIGHT as f32;
+
+        image::Rgba([radiance, radiance * (1.0 - tint), radiance * tint, 1.0])
+    })
+}
@@ -0,0 +1,31 @@
+@group(0) @binding(0) var input: texture_2d<f32>;
+@group(0) @binding(1) var output: texture_storage_2d<rgba8unorm, write>;
+
+let EXPOSURE: f32 = 1.0;
+let GAMMA: f32 = 2.2;
+
+// Reinhard operator, mapping [0, inf] radiances to [0, 1].
+fn reinhard(radiance: vec3<f32>) -> vec3<f32> {
+    let exposed = max(radiance * EXPOSURE, vec3<f32>(0.0));
+    return exposed / (exposed + vec3<f32>(1.0));
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let coord = vec2<i32>(global_id.xy);
+    let dims = textureDimensions(input);
+
+    if (coord.x >= dims.x || coord.y >= dims.y) {
+        return;
+    }
+
+    let hdr = textureLoad(input, coord, 0);
+
+    // NaNs fail every comparison: they are mapped to black.
+    let finite = hdr.rgb == hdr.rgb;
+    let radiance = select(vec3<f32>(0.0), hdr.rgb, finite);
+
+    let ldr = pow(reinhard(radiance), vec3<f32>(1.0 / GAMMA));
+
+    textureStore(output, coord, vec4<f32>(ldr, 1.0));
+}
@@ -0,0 +1,71 @@
+use gpgpu::BufOps;
+
+// Logger printing the events of gpgpu, enabled by the `tracing` feature, to stderr.
+// Events are emitted with the `log` crate: any logger works, and `tracing` subscribers
+// receive them through `tracing_log::LogTracer`.
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target().starts_with("gpgpu")
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "{:>5} {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+// `simple-compute` example, printing the events of gpgpu:
+//
+// DEBUG gpgpu: created `GpuBuffer::from_slice` of 40000 bytes
+// DEBUG gpgpu: created `GpuBuffer::from_slice` of 40000 bytes
+// DEBUG gpgpu: created `GpuBuffer::with_capacity` of 40000 bytes
+// DEBUG gpgpu: built kernel `main` from the entry point `main` of the shader `examples/simple-compute/shader.wgsl` in 1.559979ms
+// DEBUG gpgpu: enqueued kernel `main` as `main` with 10000x1x1 workgroups
+// DEBUG gpgpu: read 40000 bytes from a GpuBuffer in 1.107137ms
+fn main() -> gpgpu::GpuResult<()> {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let fw = gpgpu::Framework::try_default()?;
+
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/simple-compute/shader.wgsl")?;
+
+    let size = 10000;
+
+    let data_a = (0..size).collect::<Vec<u32>>();
+    let data_b = (0..size).rev().collect::<Vec<u32>>();
+
+    let gpu_vec_a = gpgpu::GpuBuffer::from_slice(&fw, &data_a);
+    let gpu_vec_b = gpgpu::GpuBuffer::from_slice(&fw, &data_b);
+    let gpu_vec_c = gpgpu::GpuBuffer::with_capacity(&fw, size as u64);
+
+    let bindings = gpgpu::DescriptorSet::default()
+        .bind_buffer(&gpu_vec_a, gpgpu::GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_vec_b, gpgpu::GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_vec_c, gpgpu::GpuBufferUsage::ReadWrite);
+
+    let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(bindings);
+    let kernel = gpgpu::Kernel::new(&fw, program)?;
+
+    kernel.enqueue(size, 1, 1)?;
+
+    let gpu_result = gpu_vec_c.read_vec_blocking()?;
+
+    for (a, (b, c)) in data_a.into_iter().zip(data_b.into_iter().zip(gpu_result)) {
+        assert_eq!(a * b, c);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+use gpgpu::{BufOps, DescriptorSet, GpuBufferUsage, Kernel, Program, Shader};
+
+// Example that transposes a square matrix with `ops::transpose` and `ops::transpose_in_place`,
+// printing the bandwidth of each next to the one of a naive kernel reading by rows
+// and writing by columns, after a first run warming the caches of the framework up.
+//
+// The size of the matrix, 4096 by default, can be given as the first argument.
+
+const NAIVE: &str = r#"
+@group(0) @binding(0) var<storage, read> input: array<f32>;
+@group(0) @binding(1) var<storage, read_write> output: array<f32>;
+
+@compute @workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let size = groups.x * 16u;
+    output[id.x * size + id.y] = input[id.y * size + id.x];
+}
+"#;
+
+/// Runs `f` twice, returning the time of the second run.
+fn time(mut f: impl FnMut()) -> Duration {
+    f();
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let size = std::env::args()
+        .nth(1)
+        .map(|size| size.parse().expect("The size must be a number"))
+        .unwrap_or(4096u32);
+    let len = size as usize * size as usize;
+
+    let matrix = (0..len).map(|i| i as f32).collect::<Vec<_>>();
+    let input = gpgpu::GpuBuffer::from_slice(&fw, &matrix);
+    let mut output = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, len as u64);
+    let mut first = [0.0f32; 2];
+
+    let tiled = time(|| {
+        gpgpu::ops::transpose(&fw, &input, &mut output, size, size).unwrap();
+        output.read_blocking(&mut first).unwrap(); // Waits for the transpose to be done
+    });
+    assert_eq!(first, [0.0, size as f32]);
+
+    let in_place = time(|| {
+        gpgpu::ops::transpose_in_place(&fw, &mut output, size).unwrap();
+        output.read_blocking(&mut first).unwrap();
+    });
+    assert_eq!(first, [0.0, size as f32]); // Transposed twice, back to the transpose
+
+    let naive = match size % 16 {
+        0 => {
+            let shader = Shader::from_wgsl_source(&fw, NAIVE, Some("naive")).unwrap();
+            let set = DescriptorSet::default()
+                .bind_buffer(&input, GpuBufferUsage::ReadOnly)
+                .bind_buffer(&output, GpuBufferUsage::ReadWrite);
+            let kernel =
+                Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set)).unwrap();
+
+            Some(time(|| {
+                kernel.enqueue(size / 16, size / 16, 1).unwrap();
+                output.read_blocking(&mut first).unwrap();
+            }))
+        }
+        _ => None,
+    };
+
+    // Each element is read and written once.
+    let bandwidth = |time: Duration| 2.0 * (len * 4) as f64 / time.as_secs_f64() / 1e9;
+
+    println!("Transposes of a {0}x{0} matrix of f32s:", size);
+    println!("  tiled:    {:?} ({:.2} GB/s)", tiled, bandwidth(tiled));
+    println!(
+        "  in place: {:?} ({:.2} GB/s)",
+        in_place,
+        bandwidth(in_place)
+    );
+    if let Some(naive) = naive {
+        println!("  naive:    {:?} ({:.2} GB/s)", naive, bandwidth(naive));
+    }
+}
@@ -0,0 +1,42 @@
+use std::time::Instant;
+
+use gpgpu::BufOps;
+
+// Example that uploads `f64`s narrowed into `f32`s, first collecting them into a `Vec`
+// given to `GpuBuffer::from_slice`, then converting them straight into the buffer
+// memory with `GpuBuffer::from_slice_map`, comparing the time spent by each.
+//
+// The number of elements, 100M by default, can be given as the first argument.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let size = std::env::args()
+        .nth(1)
+        .map(|size| size.parse().expect("The size must be a number"))
+        .unwrap_or(100_000_000usize);
+    let input = (0..size).map(|i| i as f64 * 0.5).collect::<Vec<f64>>();
+
+    let start = Instant::now();
+    let narrowed = input.iter().map(|&x| x as f32).collect::<Vec<f32>>();
+    let naive = gpgpu::GpuBuffer::from_slice(&fw, &narrowed);
+    read_first(&naive);
+    let naive_time = start.elapsed();
+    drop((naive, narrowed));
+
+    let start = Instant::now();
+    let mapped = gpgpu::GpuBuffer::from_slice_map(&fw, &input, |&x| x as f32);
+    read_first(&mapped);
+    let mapped_time = start.elapsed();
+
+    println!(
+        "Upload of {} elements: {:?} with `from_slice`, {:?} with `from_slice_map`",
+        size, naive_time, mapped_time
+    );
+}
+
+// Reads back the first element, waiting for the upload to be done.
+fn read_first(buf: &gpgpu::GpuBuffer<f32>) {
+    let mut first = [0.0f32];
+    buf.read_blocking(&mut first).unwrap();
+    assert_eq!(first[0], 0.0);
+}
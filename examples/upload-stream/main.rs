@@ -0,0 +1,48 @@
+use std::time::Instant;
+
+use gpgpu::BufOps;
+
+// Example that uploads a large region of bytes, e.g. a memory-mapped file, first with
+// `GpuBuffer::from_slice`, which stages the whole region at once, then in 64 MiB chunks
+// with `GpuBuffer::from_mapped_bytes`, comparing the time spent by each.
+//
+// With `memmap2`, the region would be the `&mmap[..]` of the mapping of the file, which
+// `from_mapped_bytes` never copies as a whole: only one chunk is staged at a time.
+//
+// The size of the region in MiB, 2048 by default, can be given as the first argument.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let mib = std::env::args()
+        .nth(1)
+        .map(|mib| mib.parse().expect("The size must be a number"))
+        .unwrap_or(2048usize);
+    let region = (0..mib << 18).map(|i| i as u32).collect::<Vec<u32>>();
+    let bytes: &[u8] = bytemuck::cast_slice(&region);
+
+    let start = Instant::now();
+    let staged = gpgpu::GpuBuffer::<u32>::try_from_slice(&fw, &region).unwrap();
+    read_last(&staged, &region);
+    let staged_time = start.elapsed();
+    drop(staged);
+
+    let start = Instant::now();
+    let streamed = gpgpu::GpuBuffer::<u32>::from_mapped_bytes(&fw, bytes).unwrap();
+    read_last(&streamed, &region);
+    let streamed_time = start.elapsed();
+
+    println!(
+        "Upload of {} MiB: {:?} with `from_slice`, {:?} with `from_mapped_bytes`",
+        mib, staged_time, streamed_time
+    );
+}
+
+// Reads back the chunk holding the last element, waiting for the upload to be done.
+fn read_last(buf: &gpgpu::GpuBuffer<u32>, region: &[u32]) {
+    let mut out = vec![0u8; buf.size() as usize];
+    buf.read_into_mapped_bytes(&mut out).unwrap();
+    assert_eq!(
+        &out[out.len() - 4..],
+        region[region.len() - 1].to_ne_bytes()
+    );
+}
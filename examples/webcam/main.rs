@@ -56,7 +56,7 @@ fn main() {
         .bind_uniform_buffer(&buf_time);
     let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(desc);
 
-    let kernel = gpgpu::Kernel::new(&fw, program);
+    let kernel = gpgpu::Kernel::new(&fw, program).unwrap();
 
     let time = std::time::Instant::now();
 
@@ -72,7 +72,9 @@ fn main() {
         gpu_input.write_image_buffer(&cam_buf.convert()).unwrap(); // Upload cam frame into the cam frame texture
         buf_time.write(&[time.elapsed().as_secs_f32()]).unwrap(); // Upload elapsed time into elapsed time buffer
 
-        kernel.enqueue(WIDTH as u32 / 32, HEIGHT as u32 / 31, 1);
+        kernel
+            .enqueue(WIDTH as u32 / 32, HEIGHT as u32 / 31, 1)
+            .unwrap();
 
         gpu_output
             .read_blocking(bytemuck::cast_slice_mut(&mut frame_buffer))
@@ -1,15 +1,15 @@
 struct Time {
-    time: f32;
+    time: f32,
 };
 
-[[group(0), binding(0)]] var input: texture_2d<f32>;
-[[group(0), binding(1)]] var output: texture_storage_2d<rgba8unorm, write>;
-[[group(0), binding(2)]] var<uniform> time: Time; 
+@group(0) @binding(0) var input: texture_2d<f32>;
+@group(0) @binding(1) var output: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(2) var<uniform> time: Time; 
 
 let pi: f32 = 3.14159;
 
-[[stage(compute), workgroup_size(32, 32, 1)]]
-fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {
+@compute @workgroup_size(32, 32, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let coord = vec2<i32>(global_id.xy);
     let pixel = textureLoad(input, coord, 0);
 
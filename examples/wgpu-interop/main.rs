@@ -0,0 +1,136 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use gpgpu::{primitives::pixels::Rgba8UintNorm, ImgOps};
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 32;
+
+// Example where another library owns the device and its textures, e.g. a video decoder:
+// gpgpu runs on that device, inverts the colors of a frame the library created and copies
+// the result into another of its textures, all in a single submission.
+fn main() -> gpgpu::GpuResult<()> {
+    // The "library": its device, queue and textures.
+    let instance =
+        wgpu::Instance::new(wgpu::util::backend_bits_from_env().unwrap_or(wgpu::Backends::PRIMARY));
+    let adapter = futures::executor::block_on(
+        instance.request_adapter(&wgpu::RequestAdapterOptions::default()),
+    )
+    .expect("No adapter found");
+    let (device, queue) = futures::executor::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: None,
+            features: wgpu::Features::empty(),
+            limits: adapter.limits(),
+        },
+        None,
+    ))
+    .expect("The device could not be created");
+    let (device, queue) = (Arc::new(device), Arc::new(queue));
+
+    let frame_desc = texture_desc(
+        "decoded frame",
+        wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+    );
+    let frame = device.create_texture(&frame_desc);
+    let pixels = (0..WIDTH * HEIGHT)
+        .flat_map(|i| [(i % 256) as u8, 0, 255, 255])
+        .collect::<Vec<u8>>();
+    queue.write_texture(
+        frame.as_image_copy(),
+        &pixels,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(WIDTH * 4),
+            rows_per_image: None,
+        },
+        frame_desc.size,
+    );
+
+    let display = device.create_texture(&texture_desc(
+        "display",
+        wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+    ));
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("readback"),
+        size: (WIDTH * HEIGHT * 4) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    // gpgpu, on the same device.
+    let fw = gpgpu::Framework::from_wgpu(
+        Arc::clone(&device),
+        Arc::clone(&queue),
+        adapter.get_info().backend,
+        Duration::from_millis(10),
+    );
+
+    let frame = gpgpu::GpuConstImage::<Rgba8UintNorm>::from_wgpu_texture(&fw, frame, &frame_desc)?;
+    let inverted = gpgpu::GpuImage::<Rgba8UintNorm>::new(&fw, WIDTH, HEIGHT);
+
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/wgpu-interop/shader.wgsl")?;
+    let bindings = gpgpu::DescriptorSet::default()
+        .bind_const_image(&frame)
+        .bind_image(&inverted);
+    let program = gpgpu::Program::new(&shader, "main").add_descriptor_set(bindings);
+    let kernel = gpgpu::Kernel::new(&fw, program)?;
+
+    let mut recorder = fw.create_command_recorder();
+    recorder.enqueue(&kernel, WIDTH / 8, HEIGHT / 8, 1)?;
+    recorder.with_encoder(|encoder| {
+        encoder.copy_texture_to_texture(
+            inverted.as_gpu_texture().as_image_copy(),
+            display.as_image_copy(),
+            inverted.get_wgpu_extent3d(),
+        );
+        // The library reads its texture back in the same submission.
+        encoder.copy_texture_to_buffer(
+            display.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(WIDTH * 4),
+                    rows_per_image: None,
+                },
+            },
+            frame_desc.size,
+        );
+    });
+    recorder.submit().wait();
+
+    // The frame goes back to the library, which keeps decoding into it.
+    let (_frame, _) = frame.into_gpu_parts();
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+    device.poll(wgpu::Maintain::Wait);
+
+    for (pixel, inverted) in pixels.chunks(4).zip(slice.get_mapped_range().chunks(4)) {
+        assert_eq!(inverted, [255 - pixel[0], 255, 0, 255]);
+    }
+
+    println!(
+        "Inverted a {}x{} frame of another wgpu library",
+        WIDTH, HEIGHT
+    );
+
+    Ok(())
+}
+
+fn texture_desc(label: &str, usage: wgpu::TextureUsages) -> wgpu::TextureDescriptor<'_> {
+    wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width: WIDTH,
+            height: HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage,
+    }
+}
@@ -0,0 +1,16 @@
+// Inverts the colors of a frame.
+
+@group(0) @binding(0) var frame: texture_2d<f32>;
+@group(0) @binding(1) var inverted: texture_storage_2d<rgba8unorm, write>;
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let coords = vec2<i32>(global_id.xy);
+
+    if (any(coords >= textureDimensions(frame))) {
+        return;
+    }
+
+    let color = textureLoad(frame, coords, 0);
+    textureStore(inverted, coords, vec4<f32>(1.0 - color.rgb, color.a));
+}
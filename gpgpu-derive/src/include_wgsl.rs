@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::LitStr;
+
+pub(crate) fn expand(path: LitStr) -> syn::Result<TokenStream2> {
+    let name = path.value();
+
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").map_or_else(PathBuf::new, PathBuf::from);
+    let full_path = root.join(&name);
+
+    let source = std::fs::read_to_string(&full_path).map_err(|err| {
+        syn::Error::new(
+            path.span(),
+            format!("cannot read `{}`: {}", full_path.display(), err),
+        )
+    })?;
+
+    validate(&name, &source).map_err(|message| syn::Error::new(path.span(), message))?;
+
+    let full_path = full_path
+        .to_str()
+        .ok_or_else(|| syn::Error::new(path.span(), "the path of the shader is not valid UTF-8"))?;
+
+    // `include_str!` makes cargo build again when the shader is modified.
+    Ok(quote! {
+        ::gpgpu::EmbeddedShader {
+            name: #name,
+            source: include_str!(#full_path),
+        }
+    })
+}
+
+/// Parses and validates `source` with `naga` like `gpgpu` does at runtime,
+/// returning the diagnostics of the first error.
+fn validate(name: &str, source: &str) -> Result<(), String> {
+    let module = naga::front::wgsl::parse_str(source).map_err(|err| {
+        // `naga` names the file `wgsl` in its diagnostics,
+        // and rustc already prefixes them with `error: `.
+        let message = err.emit_to_string(source);
+        let message = message.trim_end();
+
+        message.strip_prefix("error: ").unwrap_or(message).replacen(
+            "wgsl:",
+            &format!("{}:", name),
+            1,
+        )
+    })?;
+
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|err| {
+        let mut message = err.to_string();
+        let mut cause = std::error::Error::source(&err);
+
+        while let Some(err) = cause {
+            message.push_str(": ");
+            message.push_str(&err.to_string());
+            cause = err.source();
+        }
+
+        // The last span is the innermost one, e.g. the expression in a function.
+        match err
+            .spans()
+            .filter(|(span, _)| span.is_defined())
+            .last()
+            .map(|(span, _)| span.location(source))
+        {
+            Some(location) => format!(
+                "{}:{}:{}: {}",
+                name, location.line_number, location.line_position, message
+            ),
+            None => format!("{}: {}", name, message),
+        }
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+//! Procedural macros of [`gpgpu`](https://docs.rs/gpgpu), available through its `derive`
+//! and `include-wgsl` features.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::ParseStream, parse_macro_input, spanned::Spanned, Data, DeriveInput, Field, Fields,
+    Ident, LitInt, LitStr, Token, Type,
+};
+
+mod include_wgsl;
+
+/// Derives `gpgpu::GpuBindings` for a struct of references to `gpgpu` resources.
+///
+/// Every field needs a `#[binding(index)]` attribute with its binding index in the
+/// descriptor set. Storage buffers are read-only unless declared with
+/// `#[binding(index, access = "read_write")]`.
+///
+/// The binding indices are checked to be unique at compile time. The generated
+/// `descriptor_set` still fails where the `bind_*_at` methods of `gpgpu::DescriptorSet` do,
+/// e.g. for an image built without the storage usage.
+///
+/// ```ignore
+/// #[derive(GpuBindings)]
+/// struct BlurBindings<'a> {
+///     #[binding(0)]
+///     input: &'a GpuBuffer<'a, f32>,
+///     #[binding(1, access = "read_write")]
+///     output: &'a GpuBuffer<'a, f32>,
+///     #[binding(2)]
+///     params: &'a GpuUniformBuffer<'a, Params>,
+/// }
+/// ```
+#[proc_macro_derive(GpuBindings, attributes(binding))]
+pub fn derive_gpu_bindings(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Embeds a `WGSL` shader as a `gpgpu::EmbeddedShader`, to be loaded with
+/// `gpgpu::Shader::from_embedded`.
+///
+/// The path is relative to the root of the crate, i.e. the directory of its `Cargo.toml`.
+/// The shader is parsed and validated at compile time: its errors are compile errors.
+///
+/// ```ignore
+/// const BLUR: EmbeddedShader = include_wgsl!("shaders/blur.wgsl");
+///
+/// let shader = Shader::from_embedded(&fw, BLUR)?;
+/// ```
+#[proc_macro]
+pub fn include_wgsl(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+
+    include_wgsl::expand(path)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Kind of `gpgpu` resource of a field.
+enum Resource {
+    Buffer,
+    UniformBuffer,
+    Image,
+    ConstImage,
+}
+
+/// Parsed `#[binding(..)]` attribute.
+struct Binding {
+    index: LitInt,
+    read_write: Option<bool>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "`GpuBindings` can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`GpuBindings` can only be derived for structs",
+            ))
+        }
+    };
+
+    let lifetime = input
+        .generics
+        .lifetimes()
+        .next()
+        .map(|param| param.lifetime.clone())
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                &input.ident,
+                "`GpuBindings` requires a lifetime parameter for the bound resources",
+            )
+        })?;
+
+    let mut used = Vec::new();
+    let mut binds = Vec::new();
+
+    for field in fields {
+        let binding = parse_binding(field)?;
+        let index = binding.index.base10_parse::<u32>()?;
+
+        if used.contains(&index) {
+            return Err(syn::Error::new_spanned(
+                &binding.index,
+                format!("duplicate binding index {}", index),
+            ));
+        }
+        used.push(index);
+
+        binds.push(bind_call(field, &binding)?);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::gpgpu::GpuBindings<#lifetime> for #name #ty_generics #where_clause {
+            fn descriptor_set(
+                &self,
+            ) -> ::gpgpu::kernel::DescriptorSetResult<::gpgpu::DescriptorSet<#lifetime>> {
+                let desc = ::gpgpu::DescriptorSet::default();
+                #(
+                    let desc = desc #binds?;
+                )*
+                ::std::result::Result::Ok(desc)
+            }
+        }
+    })
+}
+
+fn parse_binding(field: &Field) -> syn::Result<Binding> {
+    let attr = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("binding"))
+        .ok_or_else(|| syn::Error::new_spanned(field, "missing `#[binding(..)]` attribute"))?;
+
+    attr.parse_args_with(|input: ParseStream| {
+        let index = input.parse::<LitInt>()?;
+        let mut read_write = None;
+
+        if input.parse::<Option<Token![,]>>()?.is_some() {
+            let key = input.parse::<Ident>()?;
+            if key != "access" {
+                return Err(syn::Error::new_spanned(key, "expected `access`"));
+            }
+
+            input.parse::<Token![=]>()?;
+            let access = input.parse::<LitStr>()?;
+
+            read_write = match access.value().as_str() {
+                "read" | "read_only" => Some(false),
+                "read_write" => Some(true),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        access,
+                        "expected `\"read\"`, `\"read_only\"` or `\"read_write\"`",
+                    ))
+                }
+            };
+        }
+
+        Ok(Binding { index, read_write })
+    })
+}
+
+/// Returns the `DescriptorSet` method call binding `field`.
+fn bind_call(field: &Field, binding: &Binding) -> syn::Result<TokenStream2> {
+    let ident = field.ident.as_ref().expect("Fields are named.");
+    let index = &binding.index;
+
+    let resource = resource_kind(&field.ty).ok_or_else(|| {
+        syn::Error::new_spanned(
+            &field.ty,
+            "expected a reference to a `GpuBuffer`, `GpuUniformBuffer`, `GpuImage` or `GpuConstImage`",
+        )
+    })?;
+
+    if binding.read_write.is_some() && !matches!(resource, Resource::Buffer) {
+        return Err(syn::Error::new_spanned(
+            index,
+            "`access` is only supported for `GpuBuffer` bindings",
+        ));
+    }
+
+    let span = field.span();
+
+    Ok(match resource {
+        Resource::Buffer => {
+            let usage = match binding.read_write {
+                Some(true) => quote!(ReadWrite),
+                _ => quote!(ReadOnly),
+            };
+
+            quote_spanned!(span=> .bind_buffer_at(#index, self.#ident, ::gpgpu::GpuBufferUsage::#usage))
+        }
+        Resource::UniformBuffer => {
+            quote_spanned!(span=> .bind_uniform_buffer_at(#index, self.#ident))
+        }
+        Resource::Image => quote_spanned!(span=> .bind_image_at(#index, self.#ident)),
+        Resource::ConstImage => quote_spanned!(span=> .bind_const_image_at(#index, self.#ident)),
+    })
+}
+
+/// Kind of resource referenced by `ty`, from the name of the referenced type.
+fn resource_kind(ty: &Type) -> Option<Resource> {
+    let path = match ty {
+        Type::Reference(reference) => match reference.elem.as_ref() {
+            Type::Path(path) => path,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    match path.path.segments.last()?.ident.to_string().as_str() {
+        "GpuBuffer" => Some(Resource::Buffer),
+        "GpuUniformBuffer" => Some(Resource::UniformBuffer),
+        "GpuImage" => Some(Resource::Image),
+        "GpuConstImage" => Some(Resource::ConstImage),
+        _ => None,
+    }
+}
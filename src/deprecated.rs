@@ -0,0 +1,42 @@
+//! Former names of renamed items, forwarding to their replacements.
+//! They are kept for at least one minor release after their rename.
+
+use crate::{
+    kernel::KernelResult, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, Kernel, Program,
+    Shader,
+};
+
+impl Framework {
+    /// Creates a [`Program`] running the `entry_point` of a `shader`.
+    #[deprecated(since = "0.2.0", note = "use `Program::new` instead")]
+    pub fn create_kernel_builder<'sha, 'res>(
+        &self,
+        shader: &'sha Shader,
+        entry_point: impl Into<String>,
+    ) -> Program<'sha, 'res> {
+        Program::new(shader, entry_point)
+    }
+}
+
+impl<'sha, 'res> Program<'sha, 'res> {
+    /// Creates a [`Kernel`] from this [`Program`].
+    #[deprecated(since = "0.2.0", note = "use `Kernel::new` instead")]
+    pub fn build<'fw>(self, fw: &'fw Framework) -> KernelResult<Kernel<'fw>> {
+        Kernel::new(fw, self)
+    }
+}
+
+impl<'res> DescriptorSet<'res> {
+    /// Binds a [`GpuBuffer`] as a storage buffer in the shader with a specific `usage`.
+    #[deprecated(since = "0.2.0", note = "use `DescriptorSet::bind_buffer` instead")]
+    pub fn bind_storage_buffer<T>(
+        self,
+        storage_buf: &'res GpuBuffer<T>,
+        usage: GpuBufferUsage,
+    ) -> Self
+    where
+        T: bytemuck::Pod,
+    {
+        self.bind_buffer(storage_buf, usage)
+    }
+}
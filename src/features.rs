@@ -1,8 +1,32 @@
 //! This modules controls the enablement of all the features
 //! of the `gpgpu` crate.
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "hot-reload")]
+pub mod hot_reload;
+
+#[cfg(feature = "num-complex")]
+pub mod integrate_complex;
+
 #[cfg(feature = "integrate-image")]
 pub mod integrate_image;
 
 #[cfg(feature = "integrate-ndarray")]
 pub mod integrate_ndarray;
+
+#[cfg(feature = "rust-gpu")]
+pub mod rust_gpu;
+
+#[cfg(feature = "shader-cache")]
+pub mod shader_cache;
+
+#[cfg(feature = "spirv-passthrough")]
+pub mod spirv_passthrough;
+
+#[cfg(feature = "video")]
+pub mod video;
+
+#[cfg(feature = "viewer")]
+pub mod viewer;
@@ -0,0 +1,91 @@
+//! Entry points for bindings embedding `gpgpu` behind a foreign function interface,
+//! e.g. a Python module built with PyO3.
+//!
+//! The safe API remains the one to use from Rust: these functions only exist for the
+//! callers that hold raw memory or need the native objects of the backend.
+
+use crate::{primitives::buffers::BufferResult, Framework, GpuBuffer};
+
+pub use wgpu_core::hub::HalApi;
+pub use wgpu_hal as hal;
+
+/// Objects of the device of a [`Framework`], returned by [`Framework::as_hal_handles`].
+#[derive(Clone, Copy)]
+pub struct HalHandles<'fw> {
+    /// Device all the resources of the [`Framework`] are created on.
+    pub device: &'fw wgpu::Device,
+    /// Queue all the work of the [`Framework`] is submitted to.
+    pub queue: &'fw wgpu::Queue,
+    /// Backend of the device, telling which [`hal::api`] it can be accessed through.
+    pub backend: wgpu::Backend,
+}
+
+impl HalHandles<'_> {
+    /// Calls `f` with the native device of the backend `A`, e.g. [`hal::api::Vulkan`],
+    /// or `None` if the device is not of this backend.
+    ///
+    /// # Safety
+    /// The native device must not be destroyed, see [`wgpu::Device::as_hal`].
+    pub unsafe fn device_as_hal<A: HalApi, R>(&self, f: impl FnOnce(Option<&A::Device>) -> R) -> R {
+        self.device.as_hal::<A, _, R>(f)
+    }
+}
+
+impl Framework {
+    /// Gets the device, queue and backend of this [`Framework`], for the bindings that
+    /// create their own objects on its device or reach its native handles.
+    pub fn as_hal_handles(&self) -> HalHandles<'_> {
+        HalHandles {
+            device: &self.device,
+            queue: &self.queue,
+            backend: self.backend,
+        }
+    }
+}
+
+impl<'fw, T> GpuBuffer<'fw, T>
+where
+    T: bytemuck::Pod,
+{
+    /// Pulls the first bytes of the [`GpuBuffer`] into the `len` bytes at `ptr`, blocking,
+    /// returning how many bytes were read, like [`GpuBuffer::read_blocking`].
+    ///
+    /// `ptr` does not need to be aligned for `T`: `len` can be any number of bytes,
+    /// e.g. the size of a Python buffer.
+    ///
+    /// # Safety
+    /// Unless `len` is 0, in which case `ptr` can be null:
+    /// - `ptr` must be valid for writes of `len` initialized bytes, within a single allocation.
+    /// - These bytes must not be accessed by anything else until this function returns.
+    /// - `len` must not exceed `isize::MAX`.
+    pub unsafe fn read_into_raw(&self, ptr: *mut u8, len: usize) -> BufferResult<usize> {
+        if len == 0 {
+            return Ok(0);
+        }
+
+        let buf = std::slice::from_raw_parts_mut(ptr, len);
+
+        futures::executor::block_on(self.read_bytes(buf)).map(|read| read as usize)
+    }
+
+    /// Writes the `len` bytes at `ptr` at the start of this [`GpuBuffer`], returning how many
+    /// bytes were written, like [`GpuBuffer::write`].
+    ///
+    /// The bytes are copied before this function returns. `ptr` does not need to be
+    /// aligned for `T`.
+    ///
+    /// # Safety
+    /// Unless `len` is 0, in which case `ptr` can be null:
+    /// - `ptr` must be valid for reads of `len` initialized bytes, within a single allocation.
+    /// - These bytes must not be written by anything else until this function returns.
+    /// - `len` must not exceed `isize::MAX`.
+    pub unsafe fn write_from_raw(&self, ptr: *const u8, len: usize) -> BufferResult<usize> {
+        if len == 0 {
+            return Ok(0);
+        }
+
+        let bytes = std::slice::from_raw_parts(ptr, len);
+
+        self.write_bytes(bytes).map(|written| written as usize)
+    }
+}
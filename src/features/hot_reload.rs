@@ -0,0 +1,87 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use thiserror::Error;
+
+use crate::{kernel::KernelError, Framework, Kernel, Shader};
+
+#[derive(Error, Debug)]
+pub enum HotReloadError {
+    #[error("Shader `{path}` is not valid: {reason}")]
+    InvalidShader { path: PathBuf, reason: String },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    KernelError(#[from] KernelError),
+}
+
+pub type HotReloadResult<T> = Result<T, HotReloadError>;
+
+/// Watches a `WGSL` file, compiling it again each time it is modified.
+///
+/// It is polled from the application loop, e.g. once per frame, so no thread is involved.
+///
+/// ```ignore
+/// let mut watcher = WgslWatcher::new("shader.wgsl")?;
+///
+/// loop {
+///     if let Err(err) = watcher.reload(&fw, &mut kernel) {
+///         eprintln!("{}", err); // The kernel keeps its previous shader.
+///     }
+///
+///     kernel.enqueue(x, y, z)?;
+/// }
+/// ```
+pub struct WgslWatcher {
+    path: PathBuf,
+    modified: SystemTime,
+}
+
+impl WgslWatcher {
+    /// Starts watching the `WGSL` file at `path`. Only its modifications from now on are reported.
+    pub fn new(path: impl AsRef<Path>) -> HotReloadResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let modified = std::fs::metadata(&path)?.modified()?;
+
+        Ok(Self { path, modified })
+    }
+
+    /// Returns the [`Shader`] compiled from the watched file if it was modified since the last call.
+    ///
+    /// A shader that `wgpu` rejects is reported as [`HotReloadError::InvalidShader`]
+    /// instead of aborting the application. It is not tried again until the file is modified again.
+    pub fn poll(&mut self, fw: &Framework) -> HotReloadResult<Option<Shader>> {
+        let modified = std::fs::metadata(&self.path)?.modified()?;
+
+        if modified == self.modified {
+            return Ok(None);
+        }
+        self.modified = modified;
+
+        let source = std::fs::read_to_string(&self.path)?;
+
+        match Shader::from_wgsl_source(fw, &source, self.path.to_str()) {
+            Ok(shader) => Ok(Some(shader)),
+            Err(err) => Err(HotReloadError::InvalidShader {
+                path: self.path.clone(),
+                reason: err.to_string(),
+            }),
+        }
+    }
+
+    /// Rebuilds the pipeline of `kernel` with [`Kernel::reload_shader`] if the watched file
+    /// was modified since the last call, returning whether it was.
+    ///
+    /// On failure `kernel` keeps its previous pipeline.
+    pub fn reload(&mut self, fw: &Framework, kernel: &mut Kernel) -> HotReloadResult<bool> {
+        match self.poll(fw)? {
+            Some(shader) => {
+                kernel.reload_shader(&shader)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use num_complex::Complex;
+
+use crate::{primitives::buffers::BufferResult, BufOps, GpuBuffer};
+
+/// Complex number of `f32`s, laid out like a `vec2<f32>` in `WGSL`:
+/// the real part in `x` and the imaginary part in `y`.
+///
+/// [`num_complex::Complex<f32>`] has the same layout but does not implement [`bytemuck::Pod`],
+/// so it is converted to and from [`GpuComplex32`] when the buffers are uploaded and read.
+/// A buffer of them is declared as `array<vec2<f32>>` in the shader, e.g. to multiply them:
+///
+/// ```ignore
+/// let product = vec2<f32>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+/// ```
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GpuComplex32 {
+    pub re: f32,
+    pub im: f32,
+}
+
+// Safety: two `f32`s without padding, valid for any bit pattern.
+unsafe impl bytemuck::Zeroable for GpuComplex32 {}
+unsafe impl bytemuck::Pod for GpuComplex32 {}
+
+impl From<Complex<f32>> for GpuComplex32 {
+    fn from(complex: Complex<f32>) -> Self {
+        Self {
+            re: complex.re,
+            im: complex.im,
+        }
+    }
+}
+
+impl From<GpuComplex32> for Complex<f32> {
+    fn from(complex: GpuComplex32) -> Self {
+        Complex::new(complex.re, complex.im)
+    }
+}
+
+impl<'fw> GpuBuffer<'fw, GpuComplex32> {
+    /// Constructs a new [`GpuBuffer`] from a slice of [`Complex<f32>`]s.
+    pub fn from_complex_slice(fw: &'fw crate::Framework, slice: &[Complex<f32>]) -> Self {
+        let complexes = slice
+            .iter()
+            .copied()
+            .map(GpuComplex32::from)
+            .collect::<Vec<_>>();
+
+        Self::from_slice(fw, &complexes)
+    }
+
+    /// Pulls all the elements from the [`GpuBuffer`] into a [`Vec`] of [`Complex<f32>`]s.
+    pub async fn read_complex_vec(&self) -> BufferResult<Vec<Complex<f32>>> {
+        Ok(self
+            .read_vec()
+            .await?
+            .into_iter()
+            .map(Complex::from)
+            .collect())
+    }
+
+    /// Blocking version of `GpuBuffer::read_complex_vec()`.
+    pub fn read_complex_vec_blocking(&self) -> BufferResult<Vec<Complex<f32>>> {
+        futures::executor::block_on(self.read_complex_vec())
+    }
+}
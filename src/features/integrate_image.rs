@@ -1,6 +1,10 @@
+use std::path::Path;
+
+use thiserror::Error;
+
 use crate::{
     primitives::{
-        images::{ImageInputError, ImageOutputError},
+        images::{ImageError, ImageInputError, ImageOutputError},
         pixels, ImgOps, PixelInfo,
     },
     GpuConstImage, GpuImage,
@@ -8,6 +12,18 @@ use crate::{
 
 use image::ImageBuffer;
 
+pub type ImageFileResult<T> = Result<T, ImageFileError>;
+
+#[derive(Error, Debug)]
+pub enum ImageFileError {
+    #[error(transparent)]
+    Image(#[from] ImageError),
+    #[error(transparent)]
+    ImageOutput(#[from] ImageOutputError),
+    #[error(transparent)]
+    Codec(#[from] image::ImageError),
+}
+
 /// Contains information about the `image::ImageBuffer` -> `gpgpu::GpuImage` or `gpgpu::GpuConstImage` images conversion.
 pub trait ImageToGpgpu {
     type GpgpuPixel: PixelInfo + GpgpuToImage;
@@ -44,13 +60,15 @@ macro_rules! gpgpu_to_image_impl {
 
 gpgpu_to_image_impl! {
     ::image::Rgba<u8>, pixels::Rgba8Uint, pixels::Rgba8UintNorm;
-    ::image::Rgba<i8>, pixels::Rgba8Sint, pixels::Rgba8SintNorm
+    ::image::Rgba<i8>, pixels::Rgba8Sint, pixels::Rgba8SintNorm;
+    ::image::Rgba<f32>, pixels::Rgba32Float
     // ::image::Luma<u8>, pixels::Luma8, pixels::Luma8Norm
 }
 
 image_to_gpgpu_impl! {
     ::image::Rgba<u8>, pixels::Rgba8Uint, pixels::Rgba8UintNorm;
-    ::image::Rgba<i8>, pixels::Rgba8Sint, pixels::Rgba8SintNorm
+    ::image::Rgba<i8>, pixels::Rgba8Sint, pixels::Rgba8SintNorm;
+    ::image::Rgba<f32>, pixels::Rgba32Float, pixels::Rgba32Float
     // ::image::Luma<u8>, pixels::Luma8, pixels::Luma8Norm
 }
 
@@ -70,12 +88,7 @@ where
         Container: std::ops::Deref<Target = [Pixel::Subpixel]>,
     {
         let (width, height) = img.dimensions();
-        GpuImage::from_bytes(
-            fw,
-            bytemuck::cast_slice(img),
-            width * Pixel::GpgpuPixel::byte_size() as u32,
-            height,
-        )
+        GpuImage::from_bytes(fw, bytemuck::cast_slice(img), width, height)
     }
 
     /// Constructs a new normalised [`GpuImage`] from a [`image::ImageBuffer`].
@@ -87,12 +100,7 @@ where
         Container: std::ops::Deref<Target = [Pixel::Subpixel]>,
     {
         let (width, height) = img.dimensions();
-        GpuImage::from_bytes(
-            fw,
-            bytemuck::cast_slice(img),
-            width * Pixel::GpgpuPixel::byte_size() as u32,
-            height,
-        )
+        GpuImage::from_bytes(fw, bytemuck::cast_slice(img), width, height)
     }
 }
 
@@ -110,12 +118,7 @@ where
         Container: std::ops::Deref<Target = [Pixel::Subpixel]>,
     {
         let (width, height) = img.dimensions();
-        GpuConstImage::from_bytes(
-            fw,
-            bytemuck::cast_slice(img),
-            width * Pixel::GpgpuPixel::byte_size() as u32,
-            height,
-        )
+        GpuConstImage::from_bytes(fw, bytemuck::cast_slice(img), width, height)
     }
 
     /// Constructs a new normalised [`GpuConstImage`] from a [`image::ImageBuffer`].
@@ -127,12 +130,7 @@ where
         Container: std::ops::Deref<Target = [Pixel::Subpixel]>,
     {
         let (width, height) = img.dimensions();
-        GpuConstImage::from_bytes(
-            fw,
-            bytemuck::cast_slice(img),
-            width * Pixel::GpgpuPixel::byte_size() as u32,
-            height,
-        )
+        GpuConstImage::from_bytes(fw, bytemuck::cast_slice(img), width, height)
     }
 }
 
@@ -226,6 +224,44 @@ where
     }
 }
 
+impl<'fw> GpuImage<'fw, pixels::Rgba32Float> {
+    /// Constructs a new [`GpuImage`] from the OpenEXR file at `path`.
+    ///
+    /// The channels are uploaded as they are stored, NaN and infinite values included:
+    /// nothing is clamped nor normalised. A file without alpha channel gets an alpha of 1.
+    ///
+    /// Decoding OpenEXR requires the `openexr` feature of the `image` crate, which
+    /// `gpgpu` does not enable: fails with [`ImageFileError::Codec`] without it.
+    pub fn from_exr_file(
+        fw: &'fw crate::Framework,
+        path: impl AsRef<Path>,
+    ) -> ImageFileResult<Self> {
+        let mut reader = image::io::Reader::open(path).map_err(image::ImageError::IoError)?;
+        reader.set_format(image::ImageFormat::OpenExr);
+
+        let img = reader.decode()?.into_rgba32f();
+
+        let (width, height) = img.dimensions();
+
+        Ok(Self::try_from_bytes(
+            fw,
+            bytemuck::cast_slice(&img),
+            width,
+            height,
+        )?)
+    }
+
+    /// Saves the pixels of this [`GpuImage`] to an OpenEXR file at `path`, blocking.
+    ///
+    /// The channels are saved as they are read back, NaN and infinite values included.
+    /// Like [`GpuImage::from_exr_file`], requires the `openexr` feature of the `image` crate.
+    pub fn save_exr(&self, path: impl AsRef<Path>) -> ImageFileResult<()> {
+        let img = self.read_to_image_buffer_blocking()?;
+
+        Ok(img.save_with_format(path, image::ImageFormat::OpenExr)?)
+    }
+}
+
 pub(crate) fn bytes_to_primitive_vec<P>(mut bytes: Vec<u8>) -> Vec<P::Subpixel>
 where
     P: image::Pixel,
@@ -1,6 +1,12 @@
 use thiserror::Error;
 
-use crate::{primitives::buffers::BufferError, BufOps, DescriptorSet, GpuBuffer, GpuBufferUsage};
+use std::borrow::Cow;
+
+use crate::{
+    kernel::DescriptorSetResult,
+    primitives::buffers::{BufferError, BufferResult},
+    BufOps, DescriptorSet, GpuBuffer, GpuBufferUsage,
+};
 
 #[derive(Error, Debug)]
 pub enum NdarrayError {
@@ -65,34 +71,113 @@ where
 }
 
 impl<'res> DescriptorSet<'res> {
-    pub fn bind_array<T, D>(mut self, array: &'res GpuArray<T, D>, access: GpuBufferUsage) -> Self
+    pub fn bind_array<T, D>(self, array: &'res GpuArray<T, D>, access: GpuBufferUsage) -> Self
+    where
+        T: bytemuck::Pod,
+        D: ndarray::Dimension,
+    {
+        let bind_id = self.next_binding();
+
+        self.bind_array_at(bind_id, array, access)
+            .expect("Cannot fail with a free binding.")
+    }
+
+    /// Binds a [`GpuArray`] as a storage buffer in the shader at the `binding` index.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_array_at<T, D>(
+        self,
+        binding: u32,
+        array: &'res GpuArray<T, D>,
+        access: GpuBufferUsage,
+    ) -> DescriptorSetResult<Self>
     where
         T: bytemuck::Pod,
         D: ndarray::Dimension,
     {
-        let bind_id = self.set_layout.len() as u32;
-
-        let bind_entry = wgpu::BindGroupLayoutEntry {
-            binding: bind_id,
-            visibility: wgpu::ShaderStages::COMPUTE,
-            ty: wgpu::BindingType::Buffer {
-                has_dynamic_offset: false,
-                min_binding_size: None,
-                ty: wgpu::BufferBindingType::Storage {
-                    read_only: access == GpuBufferUsage::ReadOnly,
-                },
-            },
-            count: None,
-        };
-
-        let bind = wgpu::BindGroupEntry {
-            binding: bind_id,
-            resource: array.0.as_binding_resource(),
-        };
-
-        self.set_layout.push(bind_entry);
-        self.binds.push(bind);
-
-        self
+        self.push_storage_buffer(binding, &array.0, access, None)
+    }
+}
+
+/// Elements of `array` in logical order, borrowed if it is in standard layout
+/// and copied otherwise.
+fn standard_order<S, D>(array: &ndarray::ArrayBase<S, D>) -> Cow<'_, [S::Elem]>
+where
+    S: ndarray::Data,
+    S::Elem: Clone,
+    D: ndarray::Dimension,
+{
+    match array.as_slice() {
+        Some(slice) => Cow::Borrowed(slice),
+        None => Cow::Owned(array.iter().cloned().collect()),
+    }
+}
+
+impl<'fw, T> GpuBuffer<'fw, T>
+where
+    T: bytemuck::Pod,
+{
+    /// Constructs a new [`GpuBuffer`] from the elements of a 1-dimensional `array`.
+    ///
+    /// An array that is not contiguous, e.g. a slice with a step or reversed, is copied
+    /// into a contiguous one first.
+    pub fn from_array<S>(
+        fw: &'fw crate::Framework,
+        array: &ndarray::ArrayBase<S, ndarray::Ix1>,
+    ) -> Self
+    where
+        S: ndarray::Data<Elem = T>,
+    {
+        Self::from_slice(fw, &standard_order(array))
+    }
+
+    /// Constructs a new [`GpuBuffer`] from the elements of a 2-dimensional `array`,
+    /// in row-major order.
+    ///
+    /// An array that is not in standard layout, e.g. a transposed view or
+    /// a column-major array, is copied into row-major order first.
+    pub fn from_array2<S>(
+        fw: &'fw crate::Framework,
+        array: &ndarray::ArrayBase<S, ndarray::Ix2>,
+    ) -> Self
+    where
+        S: ndarray::Data<Elem = T>,
+    {
+        Self::from_slice(fw, &standard_order(array))
+    }
+
+    /// Pulls all the elements from the [`GpuBuffer`] into an [`ndarray::Array1`].
+    pub async fn read_to_array1(&self) -> BufferResult<ndarray::Array1<T>> {
+        Ok(ndarray::Array1::from(self.read_vec().await?))
+    }
+
+    /// Blocking version of `GpuBuffer::read_to_array1()`.
+    pub fn read_to_array1_blocking(&self) -> BufferResult<ndarray::Array1<T>> {
+        futures::executor::block_on(self.read_to_array1())
+    }
+
+    /// Pulls all the elements from the [`GpuBuffer`] into a `rows` x `cols` [`ndarray::Array2`],
+    /// in row-major order.
+    ///
+    /// Fails with [`NdarrayError::InvalidShape`] if the [`GpuBuffer`] does not hold
+    /// exactly `rows * cols` elements.
+    pub async fn read_to_array2(
+        &self,
+        rows: usize,
+        cols: usize,
+    ) -> ArrayResult<ndarray::Array2<T>> {
+        let v = self.read_vec().await?;
+        ndarray::Array2::from_shape_vec((rows, cols), v)
+            .map_err(NdarrayError::InvalidShape)
+            .map_err(ArrayError::NdarrayError)
+    }
+
+    /// Blocking version of `GpuBuffer::read_to_array2()`.
+    pub fn read_to_array2_blocking(
+        &self,
+        rows: usize,
+        cols: usize,
+    ) -> ArrayResult<ndarray::Array2<T>> {
+        futures::executor::block_on(self.read_to_array2(rows, cols))
     }
 }
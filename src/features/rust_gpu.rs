@@ -0,0 +1,61 @@
+//! Loading of compute shaders written in Rust with [rust-gpu](https://github.com/EmbarkStudios/rust-gpu).
+//!
+//! Shaders are compiled to SPIR-V by `spirv-builder` in the `build.rs` of the crate using them:
+//!
+//! ```ignore
+//! SpirvBuilder::new("shaders", "spirv-unknown-vulkan1.1")
+//!     .print_metadata(MetadataPrintout::Full)
+//!     .build()?;
+//! ```
+//!
+//! and embedded with `include_bytes!(env!("shaders.spv"))`. The SPIR-V is translated
+//! by `naga`, which only supports some SPIR-V capabilities: only request the capabilities
+//! the shader needs from `spirv-builder`, e.g. `Int8` for `u8` values. With `multimodule`
+//! enabled, each entry point is in its own module, loaded as a separate [`Shader`].
+
+use crate::{
+    kernel::{KernelError, KernelResult, ShaderResult},
+    Framework, Program, Shader,
+};
+
+impl Shader {
+    /// Initialises a [`Shader`] from the SPIR-V module built by `spirv-builder`.
+    ///
+    /// Fails like [`Shader::from_spirv_bytes`] if the module is not valid.
+    pub fn from_rust_gpu_module(fw: &Framework, bytes: &[u8]) -> ShaderResult<Self> {
+        Self::from_spirv_bytes(fw, bytes, Some("rust-gpu module"))
+    }
+
+    /// Returns the name of the compute entry point of this [`Shader`] defined
+    /// by the Rust function at `path`, e.g. `kernels::blur::main_cs`.
+    ///
+    /// rust-gpu names the entry points after their function, with or without the path
+    /// of its module depending on its version, so the entry point named like the
+    /// whole `path` is preferred, then the one named like its longest suffix.
+    pub fn rust_gpu_entry_point(&self, path: &str) -> Option<String> {
+        let entry_points = self.reflection.as_ref()?.compute_entry_points();
+
+        let segments = path.split("::").collect::<Vec<_>>();
+
+        (0..segments.len())
+            .map(|start| segments[start..].join("::"))
+            .find_map(|suffix| entry_points.iter().find(|name| **name == suffix).cloned())
+    }
+}
+
+impl<'sha, 'res> Program<'sha, 'res> {
+    /// Creates a new [`Program`] using the entry point of a rust-gpu `shader`
+    /// defined by the Rust function at `path`, see [`Shader::rust_gpu_entry_point`].
+    ///
+    /// Fails with [`KernelError::EntryPointNotFound`] if `shader` has no such entry point.
+    pub fn from_rust_gpu(shader: &'sha Shader, path: &str) -> KernelResult<Self> {
+        match shader.rust_gpu_entry_point(path) {
+            Some(entry_point) => Ok(Self::new(shader, entry_point)),
+            None => Err(KernelError::EntryPointNotFound {
+                shader: shader.label.clone(),
+                entry_point: path.to_string(),
+                available: shader.entry_points().unwrap_or_default(),
+            }),
+        }
+    }
+}
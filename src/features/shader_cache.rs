@@ -0,0 +1,186 @@
+use std::{
+    convert::TryInto,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    kernel::{check_legacy_wgsl, ShaderReflection, ShaderResult, SourceMap},
+    Framework, Shader,
+};
+
+/// Version of the cache entries, changed with their format or the `naga` version
+/// compiling them. Entries of other versions are never read.
+const CACHE_VERSION: &str = "gpgpu-0.2-naga-0.9";
+
+/// Extension of the cache entries.
+const ENTRY_EXTENSION: &str = "spv";
+
+/// Statistics of a [`CachedLoader`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CachedLoaderStats {
+    /// Number of shaders loaded from the cache.
+    pub hits: u64,
+    /// Number of shaders compiled because they were not in the cache.
+    pub misses: u64,
+    /// Number of shaders compiled again because their cache entry was corrupted.
+    pub corrupted: u64,
+}
+
+/// Loads `WGSL` shaders through an on-disk cache of their validated SPIR-V,
+/// skipping the `WGSL` frontend on the next launches.
+///
+/// Entries are named after a hash of the source and of the options changing its compilation,
+/// i.e. [`Framework::set_migrate_legacy_wgsl`], so a modified shader is compiled again.
+/// Each entry starts with a checksum of its SPIR-V: a corrupted one is compiled again
+/// and replaced. `wgpu` 0.13 has no pipeline cache, so the compute pipelines
+/// themselves are still compiled by the driver on every launch.
+///
+/// ```ignore
+/// let mut loader = CachedLoader::new("target/shader-cache")?;
+///
+/// let blur = loader.load_wgsl_file(&fw, "shaders/blur.wgsl")?;
+/// let sum = loader.load_wgsl_file(&fw, "shaders/sum.wgsl")?;
+/// ```
+pub struct CachedLoader {
+    dir: PathBuf,
+    stats: CachedLoaderStats,
+}
+
+impl CachedLoader {
+    /// Creates a [`CachedLoader`] storing its entries in `cache_dir`, created if it does not exist.
+    pub fn new(cache_dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let dir = cache_dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir,
+            stats: CachedLoaderStats::default(),
+        })
+    }
+
+    /// Loads the `WGSL` shader at `path` from the cache, compiling it on a miss.
+    pub fn load_wgsl_file(
+        &mut self,
+        fw: &Framework,
+        path: impl AsRef<Path>,
+    ) -> ShaderResult<Shader> {
+        let source = std::fs::read_to_string(&path)?;
+
+        self.load_wgsl_source(fw, &source, path.as_ref().to_str())
+    }
+
+    /// Loads the `WGSL` `source` with an optional `name` from the cache, compiling it on a miss.
+    ///
+    /// Fails like [`Shader::from_wgsl_source`] if the shader is not valid, or if the entry
+    /// cannot be written.
+    pub fn load_wgsl_source(
+        &mut self,
+        fw: &Framework,
+        source: &str,
+        name: Option<&str>,
+    ) -> ShaderResult<Shader> {
+        let migrate = fw.migrate_legacy_wgsl();
+        let entry = self.dir.join(format!(
+            "{:016x}.{}",
+            entry_hash(source, migrate),
+            ENTRY_EXTENSION
+        ));
+
+        match std::fs::read(&entry) {
+            Ok(bytes) => match decode_entry(&bytes) {
+                Some(words) => {
+                    if let Ok(shader) = Shader::from_spirv_words(fw, &words, name) {
+                        self.stats.hits += 1;
+                        return Ok(shader);
+                    }
+                    self.stats.corrupted += 1;
+                }
+                None => self.stats.corrupted += 1,
+            },
+            Err(_) => self.stats.misses += 1,
+        }
+
+        let migrated = check_legacy_wgsl(source, name.unwrap_or("wgsl"), migrate)?;
+        let source = migrated.as_deref().unwrap_or(source);
+
+        let words = ShaderReflection::from_wgsl(source, &SourceMap::new(name.unwrap_or("wgsl")))?
+            .to_spirv()?;
+        let shader = Shader::from_spirv_words(fw, &words, name)?;
+
+        std::fs::write(&entry, encode_entry(&words))?;
+
+        Ok(shader)
+    }
+
+    /// Returns the statistics of this [`CachedLoader`].
+    pub fn stats(&self) -> CachedLoaderStats {
+        self.stats
+    }
+
+    /// Removes every entry of the cache and resets its statistics.
+    pub fn clear(&mut self) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+
+            if path.extension() == Some(ENTRY_EXTENSION.as_ref()) {
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        self.stats = CachedLoaderStats::default();
+
+        Ok(())
+    }
+}
+
+/// Hash naming the entry of `source`, compiled with the legacy syntax migrated if `migrate`.
+fn entry_hash(source: &str, migrate: bool) -> u64 {
+    let options = [migrate as u8];
+
+    fnv1a(
+        CACHE_VERSION
+            .bytes()
+            .chain(options.iter().copied())
+            .chain(source.bytes()),
+    )
+}
+
+/// 64-bit FNV-1a hash of `bytes`, stable across platforms and Rust versions.
+fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Serializes `words` as an entry: their checksum followed by them, in little endian.
+fn encode_entry(words: &[u32]) -> Vec<u8> {
+    let payload = words
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect::<Vec<_>>();
+
+    let mut entry = fnv1a(payload.iter().copied()).to_le_bytes().to_vec();
+    entry.extend(payload);
+    entry
+}
+
+/// Reads the words of an entry, or `None` if its checksum does not match.
+fn decode_entry(entry: &[u8]) -> Option<Vec<u32>> {
+    if entry.len() < 8 || !(entry.len() - 8).is_multiple_of(4) {
+        return None;
+    }
+
+    let (checksum, payload) = entry.split_at(8);
+    let checksum = u64::from_le_bytes(checksum.try_into().ok()?);
+
+    if checksum != fnv1a(payload.iter().copied()) {
+        return None;
+    }
+
+    Some(
+        payload
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect(),
+    )
+}
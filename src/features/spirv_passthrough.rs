@@ -0,0 +1,76 @@
+//! Loading of SPIR-V shaders passed as is to the driver, skipping their translation and
+//! validation by `naga`, e.g. to use SPIR-V capabilities `naga` does not support.
+//!
+//! This is only supported by the Vulkan backend, when the adapter supports
+//! [`wgpu::Features::SPIRV_SHADER_PASSTHROUGH`], see [`Framework::capabilities`].
+
+use std::{borrow::Cow, path::Path};
+
+use crate::{
+    kernel::{
+        spirv_words, ShaderError, ShaderId, ShaderReflection, ShaderResult, SPIRV_MAGIC_NUMBER,
+    },
+    Framework, Shader,
+};
+
+impl Shader {
+    /// Initialises a [`Shader`] from a SPIR-V file passed as is to the driver,
+    /// see [`Shader::from_spirv_bytes_unchecked`].
+    ///
+    /// # Safety
+    ///
+    /// The shader must be valid SPIR-V for the device.
+    pub unsafe fn from_spirv_file_unchecked(
+        fw: &Framework,
+        path: impl AsRef<Path>,
+    ) -> ShaderResult<Self> {
+        let bytes = std::fs::read(&path)?;
+        let shader_name = path.as_ref().to_str();
+
+        Self::from_spirv_bytes_unchecked(fw, &bytes, shader_name)
+    }
+
+    /// Initialises a [`Shader`] from SPIR-V bytes with an optional `name`,
+    /// passed as is to the driver.
+    ///
+    /// Only the length of `bytes` and the magic number are checked. When `naga` cannot
+    /// reflect the shader, the bindings of its [`Kernel`](crate::Kernel)s are not checked either.
+    ///
+    /// Fails with [`ShaderError::PassthroughUnsupported`] if the device does not support
+    /// the passthrough, instead of translating the shader anyway.
+    ///
+    /// # Safety
+    ///
+    /// The shader must be valid SPIR-V for the device: an invalid one is undefined behaviour
+    /// of the driver, e.g. a crash or a device loss.
+    pub unsafe fn from_spirv_bytes_unchecked(
+        fw: &Framework,
+        bytes: &[u8],
+        name: Option<&str>,
+    ) -> ShaderResult<Self> {
+        let capabilities = fw.capabilities();
+        if !capabilities.spirv_passthrough {
+            return Err(ShaderError::PassthroughUnsupported(capabilities.backend));
+        }
+
+        let words = spirv_words(bytes)?;
+        let magic = words.first().copied().unwrap_or_default();
+        if magic != SPIRV_MAGIC_NUMBER {
+            return Err(ShaderError::InvalidMagicNumber(magic));
+        }
+
+        let module = fw
+            .device
+            .create_shader_module_spirv(&wgpu::ShaderModuleDescriptorSpirV {
+                label: name,
+                source: Cow::Borrowed(&words),
+            });
+
+        Ok(Self {
+            id: ShaderId::new(),
+            module,
+            label: name.map(str::to_string),
+            reflection: ShaderReflection::from_spirv(&words).ok(),
+        })
+    }
+}
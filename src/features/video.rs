@@ -0,0 +1,141 @@
+use thiserror::Error;
+
+use crate::{
+    kernel::KernelError,
+    primitives::{pixels::Rgba8Uint, PixelInfo},
+    BufOps, DescriptorSet, GpuConstImage, GpuImage, GpuUniformBuffer, ImgOps, Kernel, Program,
+    Shader,
+};
+
+const WORKGROUP_SIZE: u32 = 8;
+
+#[derive(Error, Debug)]
+pub enum VideoInputError {
+    #[error("Frame dimensions must be non-zero (got {width}x{height}).")]
+    EmptyFrame { width: u32, height: u32 },
+    #[error("Luma plane has {current} bytes, {required} bytes required.")]
+    InvalidLumaPlane { required: usize, current: usize },
+    #[error("Chroma plane has {current} bytes, {required} bytes required.")]
+    InvalidChromaPlane { required: usize, current: usize },
+    #[error(transparent)]
+    KernelError(#[from] KernelError),
+}
+
+/// YUV to RGB conversion matrix used when decoding a video frame.
+///
+/// Both matrices assume limited (studio swing) range input, which is what
+/// cameras and video decoders output in the vast majority of cases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// ITU-R BT.601. Standard definition video.
+    Bt601,
+    /// ITU-R BT.709. High definition video.
+    Bt709,
+}
+
+/// Single 8 bit channel. Used for the luma plane.
+struct R8UintNorm;
+
+impl PixelInfo for R8UintNorm {
+    fn byte_size() -> usize {
+        1
+    }
+
+    fn wgpu_format() -> wgpu::TextureFormat {
+        wgpu::TextureFormat::R8Unorm
+    }
+
+    fn wgpu_texture_sample() -> wgpu::TextureSampleType {
+        wgpu::TextureSampleType::Float { filterable: false }
+    }
+}
+
+/// Two 8 bit channels. Used for the interleaved UV chroma plane.
+struct Rg8UintNorm;
+
+impl PixelInfo for Rg8UintNorm {
+    fn byte_size() -> usize {
+        2
+    }
+
+    fn wgpu_format() -> wgpu::TextureFormat {
+        wgpu::TextureFormat::Rg8Unorm
+    }
+
+    fn wgpu_texture_sample() -> wgpu::TextureSampleType {
+        wgpu::TextureSampleType::Float { filterable: false }
+    }
+}
+
+impl<'fw> GpuImage<'fw, Rgba8Uint> {
+    /// Constructs a new [`GpuImage`] from a NV12 frame, doing the color conversion on the GPU.
+    ///
+    /// `y_plane` must contain `width * height` bytes and `uv_plane` the interleaved
+    /// U and V samples at half resolution, this is, `2 * ceil(width / 2) * ceil(height / 2)` bytes.
+    /// Each pixel `(x, y)` uses the chroma sample `(x / 2, y / 2)`: when `width` or `height` are
+    /// odd, the chroma plane ends with a column or row of samples covering only the last column
+    /// or row of pixels, which use these trailing samples rather than the ones of their neighbors.
+    ///
+    /// The alpha channel of the resulting image is always 255.
+    pub fn from_nv12(
+        fw: &'fw crate::Framework,
+        y_plane: &[u8],
+        uv_plane: &[u8],
+        width: u32,
+        height: u32,
+        color_space: ColorSpace,
+    ) -> Result<Self, VideoInputError> {
+        if width == 0 || height == 0 {
+            return Err(VideoInputError::EmptyFrame { width, height });
+        }
+
+        let luma_bytes = width as usize * height as usize;
+        if y_plane.len() != luma_bytes {
+            return Err(VideoInputError::InvalidLumaPlane {
+                required: luma_bytes,
+                current: y_plane.len(),
+            });
+        }
+
+        let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+        let chroma_bytes =
+            chroma_width as usize * chroma_height as usize * Rg8UintNorm::byte_size();
+        if uv_plane.len() != chroma_bytes {
+            return Err(VideoInputError::InvalidChromaPlane {
+                required: chroma_bytes,
+                current: uv_plane.len(),
+            });
+        }
+
+        let luma = GpuConstImage::<R8UintNorm>::from_bytes(fw, y_plane, width, height);
+        let chroma =
+            GpuConstImage::<Rg8UintNorm>::from_bytes(fw, uv_plane, chroma_width, chroma_height);
+        let output = GpuImage::<Rgba8Uint>::new(fw, width, height);
+
+        let color_space = match color_space {
+            ColorSpace::Bt601 => 0,
+            ColorSpace::Bt709 => 1,
+        };
+        let params = GpuUniformBuffer::from_slice(fw, &[width, height, color_space, 0u32]);
+
+        let shader =
+            Shader::from_wgsl_source(fw, include_str!("video.wgsl"), Some("GpuImage::from_nv12"))
+                .expect("The built-in NV12 shader is valid.");
+
+        let desc = DescriptorSet::default()
+            .bind_const_image(&luma)
+            .bind_const_image(&chroma)
+            .bind_image(&output)
+            .bind_uniform_buffer(&params);
+        let program = Program::new(&shader, "nv12_to_rgba").add_descriptor_set(desc);
+
+        // Built-in shader: its bindings are known to match.
+        Kernel::new_unchecked(fw, program).enqueue(
+            width.div_ceil(WORKGROUP_SIZE),
+            height.div_ceil(WORKGROUP_SIZE),
+            1,
+        )?;
+
+        Ok(output)
+    }
+}
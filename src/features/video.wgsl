@@ -0,0 +1,45 @@
+struct Params {
+    width: u32,
+    height: u32,
+    // 0 = BT.601, 1 = BT.709
+    color_space: u32,
+    _pad: u32,
+};
+
+@group(0) @binding(0) var luma: texture_2d<f32>;
+@group(0) @binding(1) var chroma: texture_2d<f32>;
+@group(0) @binding(2) var output: texture_storage_2d<rgba8uint, write>;
+@group(0) @binding(3) var<uniform> params: Params;
+
+@compute @workgroup_size(8, 8, 1)
+fn nv12_to_rgba(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if (global_id.x >= params.width || global_id.y >= params.height) {
+        return;
+    }
+
+    let coord = vec2<i32>(global_id.xy);
+
+    // Chroma is subsampled 2x2. For odd dimensions the last column / row
+    // has a trailing chroma sample of its own, at the end of the rounded up plane.
+    let y = textureLoad(luma, coord, 0).r * 255.0 - 16.0;
+    let uv = textureLoad(chroma, coord / 2, 0).rg * 255.0 - vec2<f32>(128.0, 128.0);
+
+    var rgb: vec3<f32>;
+    if (params.color_space == 0u) {
+        rgb = vec3<f32>(
+            1.164 * y + 1.596 * uv.y,
+            1.164 * y - 0.392 * uv.x - 0.813 * uv.y,
+            1.164 * y + 2.017 * uv.x
+        );
+    } else {
+        rgb = vec3<f32>(
+            1.164 * y + 1.793 * uv.y,
+            1.164 * y - 0.213 * uv.x - 0.533 * uv.y,
+            1.164 * y + 2.112 * uv.x
+        );
+    }
+
+    let pixel = vec3<u32>(clamp(round(rgb), vec3<f32>(0.0), vec3<f32>(255.0)));
+
+    textureStore(output, coord, vec4<u32>(pixel, 255u));
+}
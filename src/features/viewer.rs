@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use raw_window_handle::HasRawWindowHandle;
+use thiserror::Error;
+
+use crate::{framework::FrameworkError, primitives::PixelInfo, Framework, GpuImage, GpuResult};
+
+pub type ViewerResult<T> = Result<T, ViewerError>;
+
+#[derive(Error, Debug)]
+pub enum ViewerError {
+    #[error("The surface is not compatible with the adapter.")]
+    UnsupportedSurface,
+    #[error(transparent)]
+    Surface(#[from] wgpu::SurfaceError),
+}
+
+/// Sample type of the images the blit pipeline reads.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum TexelKind {
+    Float,
+    Uint,
+    Sint,
+}
+
+impl TexelKind {
+    fn of<P: PixelInfo>() -> Self {
+        match P::wgpu_texture_sample() {
+            wgpu::TextureSampleType::Uint => Self::Uint,
+            wgpu::TextureSampleType::Sint => Self::Sint,
+            _ => Self::Float,
+        }
+    }
+
+    /// Source of the blit shader for this kind of texels.
+    fn shader_source(self) -> String {
+        let (texel, to_color) = match self {
+            Self::Float => ("f32", "texel"),
+            Self::Uint => ("u32", "vec4<f32>(texel) / 255.0"),
+            Self::Sint => ("i32", "max(vec4<f32>(texel) / 127.0, vec4<f32>(0.0))"),
+        };
+
+        format!(
+            "fn to_color(texel: vec4<{}>) -> vec4<f32> {{\n    return {};\n}}\n\n{}",
+            texel,
+            to_color,
+            include_str!("viewer.wgsl").replace("TEXEL", texel)
+        )
+    }
+}
+
+/// Render pipeline drawing the images of a [`TexelKind`] over the surface.
+struct Blit {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+}
+
+/// Sampled copy of the images that cannot be bound to the blit pipeline themselves.
+struct Staging {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    format: wgpu::TextureFormat,
+    size: wgpu::Extent3d,
+}
+
+/// [`wgpu::Surface`] of a window, where [`GpuImage`]s are presented by
+/// [`GpuImage::present_to`].
+///
+/// The surface is reconfigured by [`SurfaceContext::resize`] when the window is resized,
+/// and when it is outdated or lost.
+pub struct SurfaceContext {
+    device: Arc<wgpu::Device>,
+    surface: wgpu::Surface,
+    config: wgpu::SurfaceConfiguration,
+    blits: HashMap<TexelKind, Blit>,
+    staging: Option<Staging>,
+}
+
+impl Framework {
+    /// Creates a [`Framework`] like [`Framework::try_default`], on an adapter able to
+    /// present to `window`, and the [`SurfaceContext`] of its `width` x `height` pixels.
+    ///
+    /// # Safety
+    /// `window` must outlive the [`SurfaceContext`], see [`wgpu::Instance::create_surface`].
+    pub unsafe fn try_with_window<W: HasRawWindowHandle>(
+        window: &W,
+        width: u32,
+        height: u32,
+    ) -> GpuResult<(Self, SurfaceContext)> {
+        let backend = wgpu::util::backend_bits_from_env().unwrap_or(wgpu::Backends::PRIMARY);
+        let power_preference = wgpu::util::power_preference_from_env()
+            .unwrap_or(wgpu::PowerPreference::HighPerformance);
+        let instance = wgpu::Instance::new(backend);
+        let surface = instance.create_surface(window);
+
+        let adapter =
+            futures::executor::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference,
+                compatible_surface: Some(&surface),
+                ..Default::default()
+            }))
+            .ok_or(FrameworkError::NoAdapter)?;
+
+        let formats = surface.get_supported_formats(&adapter);
+        let format = formats
+            .iter()
+            .copied()
+            .find(|format| !format.describe().srgb)
+            .or_else(|| formats.first().copied())
+            .ok_or(ViewerError::UnsupportedSurface)?;
+
+        let fw = futures::executor::block_on(Self::try_new(adapter, Duration::from_millis(10)))?;
+        let ctx = SurfaceContext::from_surface(&fw, surface, format, width, height);
+
+        Ok((fw, ctx))
+    }
+}
+
+impl SurfaceContext {
+    /// Creates the [`SurfaceContext`] of a `surface` of `width` x `height` pixels,
+    /// configured with `format`.
+    ///
+    /// The adapter of `fw` must be compatible with `surface`, and `format` one of
+    /// the [`wgpu::Surface::get_supported_formats`] of the adapter.
+    pub fn from_surface(
+        fw: &Framework,
+        surface: wgpu::Surface,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::Fifo,
+        };
+
+        let ctx = Self {
+            device: Arc::clone(&fw.device),
+            surface,
+            config,
+            blits: HashMap::new(),
+            staging: None,
+        };
+        ctx.configure();
+
+        ctx
+    }
+
+    /// Adds the `extra` usages to the ones of the surface, e.g.
+    /// [`wgpu::TextureUsages::COPY_DST`] to present the images of its format and size
+    /// with a copy instead of drawing them. The surface must support them.
+    pub fn usage(mut self, extra: wgpu::TextureUsages) -> Self {
+        self.config.usage |= extra;
+        self.configure();
+
+        self
+    }
+
+    /// Sets the presentation mode of the surface, [`wgpu::PresentMode::Fifo`] by default.
+    pub fn present_mode(mut self, mode: wgpu::PresentMode) -> Self {
+        self.config.present_mode = mode;
+        self.configure();
+
+        self
+    }
+
+    /// Reconfigures the surface to `width` x `height` pixels, after its window has been resized.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if (width, height) != (self.config.width, self.config.height) {
+            self.config.width = width;
+            self.config.height = height;
+            self.configure();
+        }
+    }
+
+    /// Gets the width and height of the surface.
+    pub fn size(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
+
+    /// Gets the texture format of the surface.
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.config.format
+    }
+
+    /// Gets the inner [`wgpu::Surface`].
+    pub fn as_surface(&self) -> &wgpu::Surface {
+        &self.surface
+    }
+
+    fn configure(&self) {
+        // An empty surface, e.g. of a minimized window, cannot be configured.
+        if self.config.width > 0 && self.config.height > 0 {
+            self.surface.configure(&self.device, &self.config);
+        }
+    }
+
+    /// Gets the next texture of the surface, reconfiguring it once if it is outdated or lost.
+    /// `None` if the surface timed out, skipping the frame.
+    fn next_texture(&self) -> ViewerResult<Option<wgpu::SurfaceTexture>> {
+        match self.surface.get_current_texture() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(wgpu::SurfaceError::Timeout) => Ok(None),
+            Err(wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost) => {
+                self.configure();
+
+                match self.surface.get_current_texture() {
+                    Ok(frame) => Ok(Some(frame)),
+                    Err(wgpu::SurfaceError::Timeout) => Ok(None),
+                    Err(err) => Err(err.into()),
+                }
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Creates the blit pipeline of `kind` unless it exists.
+    fn prepare_blit(&mut self, kind: TexelKind) {
+        let device = &self.device;
+        let format = self.config.format;
+
+        self.blits.entry(kind).or_insert_with(|| {
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("SurfaceContext::blit"),
+                source: wgpu::ShaderSource::Wgsl(kind.shader_source().into()),
+            });
+
+            let sample_type = match kind {
+                TexelKind::Float => wgpu::TextureSampleType::Float { filterable: false },
+                TexelKind::Uint => wgpu::TextureSampleType::Uint,
+                TexelKind::Sint => wgpu::TextureSampleType::Sint,
+            };
+
+            let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("SurfaceContext::blit"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                }],
+            });
+
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("SurfaceContext::blit"),
+                bind_group_layouts: &[&layout],
+                push_constant_ranges: &[],
+            });
+
+            let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("SurfaceContext::blit"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: "fs_main",
+                    targets: &[Some(format.into())],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            });
+
+            Blit { layout, pipeline }
+        });
+    }
+
+    /// Creates the sampled texture of `format` and `size` where the images that cannot be
+    /// bound to the blit pipeline are copied, unless it exists.
+    fn prepare_staging(&mut self, format: wgpu::TextureFormat, size: wgpu::Extent3d) {
+        let device = &self.device;
+
+        match &mut self.staging {
+            Some(staging) if staging.format == format && staging.size == size => {}
+            staging => {
+                let texture = device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("SurfaceContext::staging"),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                });
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+                *staging = Some(Staging {
+                    texture,
+                    view,
+                    format,
+                    size,
+                });
+            }
+        }
+    }
+}
+
+impl<'fw, P: PixelInfo> GpuImage<'fw, P> {
+    /// Presents the image to the window of `surface_ctx`.
+    ///
+    /// The image is copied to the surface when it has its format and size and the
+    /// [`wgpu::TextureUsages::COPY_DST`] usage (see [`SurfaceContext::usage`]).
+    /// Otherwise, it is drawn over the whole surface, nearest-neighbour scaled, unsigned and
+    /// signed integer texels mapped from `[0, 255]` and `[0, 127]` to `[0, 1]`.
+    ///
+    /// Nothing is presented while the surface is empty, e.g. its window minimized.
+    pub fn present_to(&self, surface_ctx: &mut SurfaceContext) -> GpuResult<()> {
+        let (width, height) = surface_ctx.size();
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        let frame = match surface_ctx.next_texture()? {
+            Some(frame) => frame,
+            None => return Ok(()),
+        };
+
+        let mut encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuImage::present_to"),
+            });
+
+        let copyable = P::wgpu_format() == surface_ctx.config.format
+            && (self.size.width, self.size.height) == (width, height)
+            && surface_ctx
+                .config
+                .usage
+                .contains(wgpu::TextureUsages::COPY_DST);
+
+        if copyable {
+            encoder.copy_texture_to_texture(
+                self.texture.as_image_copy(),
+                frame.texture.as_image_copy(),
+                self.size,
+            );
+        } else {
+            let kind = TexelKind::of::<P>();
+            surface_ctx.prepare_blit(kind);
+
+            let image_view = if self.usage.contains(wgpu::TextureUsages::TEXTURE_BINDING) {
+                &self.full_view
+            } else {
+                surface_ctx.prepare_staging(P::wgpu_format(), self.size);
+
+                let staging = surface_ctx.staging.as_ref().unwrap();
+                encoder.copy_texture_to_texture(
+                    self.texture.as_image_copy(),
+                    staging.texture.as_image_copy(),
+                    self.size,
+                );
+
+                &staging.view
+            };
+
+            let surface_view = frame
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+
+            let blit = &surface_ctx.blits[&kind];
+            let bind_group = self
+                .fw
+                .device
+                .create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("GpuImage::present_to"),
+                    layout: &blit.layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(image_view),
+                    }],
+                });
+
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("GpuImage::present_to"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &surface_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+            pass.set_pipeline(&blit.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+
+        self.fw.queue.submit(Some(encoder.finish()));
+        frame.present();
+
+        Ok(())
+    }
+}
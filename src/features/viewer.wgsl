@@ -0,0 +1,28 @@
+// Draws a GpuImage over a whole surface with a fullscreen triangle.
+// `TEXEL` is replaced by the sample type of the image, `to_color`, prepended, converting its texels.
+
+@group(0) @binding(0) var image: texture_2d<TEXEL>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let size = textureDimensions(image);
+    let texel = clamp(vec2<i32>(in.uv * vec2<f32>(size)), vec2<i32>(0), size - vec2<i32>(1));
+
+    return to_color(textureLoad(image, texel, 0));
+}
@@ -1,53 +1,378 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use futures::FutureExt;
+use thiserror::Error;
 
 use crate::Framework;
 
+pub(crate) use self::cache::{LayoutCache, PipelineCache};
+pub use self::cache::{LayoutCacheStats, PipelineCacheStats};
+pub use self::desc::{
+    BindingDesc, BufferDesc, ElementDesc, InstantiatedPipeline, KernelDesc, PipelineDesc,
+    PipelineDescError, PipelineDescResult, ShaderDesc,
+};
+pub use self::memory::MemoryStats;
+pub(crate) use self::memory::{Allocation, MemoryTracker};
+pub(crate) use self::placeholders::{PlaceholderImage, PlaceholderPool};
+pub(crate) use self::poller::Poller;
+#[cfg(feature = "profiler")]
+pub(crate) use self::profiler::Profiler;
+#[cfg(feature = "profiler")]
+pub use self::profiler::{ProfilerError, ProfilerResult};
+pub(crate) use self::scratch::ScratchPool;
+pub(crate) use self::shaders::ShaderCache;
+
+mod cache;
+mod desc;
+mod memory;
+mod placeholders;
+mod poller;
+#[cfg(feature = "profiler")]
+mod profiler;
+mod scratch;
+mod shaders;
+
+/// Features enabled when the adapter supports them.
+const OPTIONAL_FEATURES: wgpu::Features = wgpu::Features::from_bits_truncate(
+    wgpu::Features::BUFFER_BINDING_ARRAY.bits()
+        | wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY.bits()
+        | wgpu::Features::TEXTURE_BINDING_ARRAY.bits()
+        | wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING.bits()
+        | wgpu::Features::PIPELINE_STATISTICS_QUERY.bits(),
+);
+
+/// Features the profiler needs, enabled when the adapter supports them.
+#[cfg(feature = "profiler")]
+const PROFILER_FEATURES: wgpu::Features = wgpu::Features::TIMESTAMP_QUERY;
+#[cfg(not(feature = "profiler"))]
+const PROFILER_FEATURES: wgpu::Features = wgpu::Features::empty();
+
+/// Features the unchecked SPIR-V shaders need, enabled when the adapter supports them.
+#[cfg(feature = "spirv-passthrough")]
+const PASSTHROUGH_FEATURES: wgpu::Features = wgpu::Features::SPIRV_SHADER_PASSTHROUGH;
+#[cfg(not(feature = "spirv-passthrough"))]
+const PASSTHROUGH_FEATURES: wgpu::Features = wgpu::Features::empty();
+
+/// Optional capabilities of the device of a [`Framework`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameworkCapabilities {
+    /// Backend of the device.
+    pub backend: wgpu::Backend,
+    /// Whether SPIR-V shaders can be passed to the driver without being translated,
+    /// see the `spirv-passthrough` feature.
+    pub spirv_passthrough: bool,
+    /// Whether [`Kernel::enqueue_with_stats`](crate::Kernel::enqueue_with_stats) is supported.
+    pub pipeline_statistics: bool,
+    /// Whether the dispatches can be timed by the profiler.
+    pub timestamp_queries: bool,
+}
+
+pub type FrameworkResult<T> = Result<T, FrameworkError>;
+
+#[derive(Error, Debug)]
+pub enum FrameworkError {
+    #[error("No adapter of the requested backends was found.")]
+    NoAdapter,
+    #[error(transparent)]
+    RequestDevice(#[from] wgpu::RequestDeviceError),
+}
+
 impl Default for Framework {
+    /// Creates a [`Framework`] like [`Framework::try_default`].
+    ///
+    /// # Panics
+    /// If there is no adapter or its device cannot be created.
     fn default() -> Self {
+        Self::try_default().expect("A GPU device could not be created")
+    }
+}
+
+impl Framework {
+    /// Creates a [`Framework`] on the adapter of the backends and power preference
+    /// the `WGPU_BACKEND` and `WGPU_POWER_PREF` environment variables select,
+    /// the primary backends and the high-performance adapter by default.
+    pub fn try_default() -> FrameworkResult<Self> {
+        futures::executor::block_on(Self::try_default_async())
+    }
+
+    /// Creates a [`Framework`] like [`Framework::try_default`], without blocking.
+    pub async fn try_default_async() -> FrameworkResult<Self> {
         let backend = wgpu::util::backend_bits_from_env().unwrap_or(wgpu::Backends::PRIMARY);
         let power_preference = wgpu::util::power_preference_from_env()
             .unwrap_or(wgpu::PowerPreference::HighPerformance);
         let instance = wgpu::Instance::new(backend);
 
-        futures::executor::block_on(async {
-            let adapter = instance
-                .request_adapter(&wgpu::RequestAdapterOptions {
-                    power_preference,
-                    ..Default::default()
-                })
-                .await
-                .unwrap();
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference,
+                ..Default::default()
+            })
+            .await
+            .ok_or(FrameworkError::NoAdapter)?;
 
-            Self::new(adapter, Duration::from_millis(10)).await
-        })
+        Self::try_new(adapter, Duration::from_millis(10)).await
     }
-}
 
-impl Framework {
     /// Creates a new [`Framework`] instance from a [`wgpu::Adapter`] and a `polling_time`.
     ///
+    /// A thread polls the device every `polling_time` until the [`Framework`] is dropped,
+    /// completing the pending reads and reclaiming the memory of the dropped resources.
+    ///
     /// Use this method when there are multiple GPUs in use or when a [`wgpu::Surface`] is required.
+    ///
+    /// # Panics
+    /// If the device cannot be created, see [`Framework::try_new`].
     pub async fn new(adapter: wgpu::Adapter, polling_time: Duration) -> Self {
+        Self::try_new(adapter, polling_time)
+            .await
+            .expect("The GPU device could not be created")
+    }
+
+    /// Creates a new [`Framework`] instance from a [`wgpu::Adapter`] and a `polling_time`
+    /// like [`Framework::new`], failing if the device cannot be created.
+    pub async fn try_new(adapter: wgpu::Adapter, polling_time: Duration) -> FrameworkResult<Self> {
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
                     label: None,
-                    features: wgpu::Features::empty(),
+                    features: adapter.features()
+                        & (OPTIONAL_FEATURES | PROFILER_FEATURES | PASSTHROUGH_FEATURES),
                     limits: adapter.limits(), // Bye WebGL2 support :(
                 },
                 None,
             )
-            .await
-            .unwrap();
+            .await?;
+
+        Ok(Self::from_wgpu(
+            Arc::new(device),
+            Arc::new(queue),
+            adapter.get_info().backend,
+            polling_time,
+        ))
+    }
+
+    /// Creates a [`Framework`] on the `device` and `queue` of another library, e.g. one
+    /// decoding video into [`wgpu::Texture`]s, to run kernels on its resources.
+    /// See [`GpuImage::from_wgpu_texture`](crate::GpuImage::from_wgpu_texture).
+    ///
+    /// `backend` is the one of the adapter of `device`. The optional features of `gpgpu`,
+    /// e.g. the pipeline statistics, are only available if `device` was requested with them.
+    ///
+    /// A thread polls `device` every `polling_time` like for [`Framework::new`], until the
+    /// [`Framework`] is dropped. The other library can keep using `device` and `queue` meanwhile.
+    pub fn from_wgpu(
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+        backend: wgpu::Backend,
+        polling_time: Duration,
+    ) -> Self {
+        let _poller = Poller::spawn(&device, polling_time);
+
+        Self {
+            device,
+            _poller,
+            queue,
+            backend,
+            layout_cache: Mutex::new(LayoutCache::default()),
+            pipeline_cache: PipelineCache::default(),
+            error_scopes: Mutex::new(()),
+            placeholders: Mutex::new(PlaceholderPool::default()),
+            scratch: Mutex::new(ScratchPool::default()),
+            op_shaders: Mutex::new(ShaderCache::default()),
+            memory: MemoryTracker::default(),
+            debug_markers: AtomicBool::new(cfg!(debug_assertions)),
+            paranoid_checks: AtomicBool::new(cfg!(debug_assertions)),
+            migrate_legacy_wgsl: AtomicBool::new(false),
+            #[cfg(feature = "profiler")]
+            profiler: Mutex::new(None),
+        }
+    }
 
-        let device = Arc::new(device);
-        let polling_device = Arc::clone(&device);
+    /// Runs `f`, returning the first error the device reports meanwhile, e.g. out of memory,
+    /// instead of panicking when the objects `f` created are used.
+    pub(crate) fn capture_errors<T>(&self, f: impl FnOnce() -> T) -> Result<T, wgpu::Error> {
+        let _scopes = self.error_scopes.lock().unwrap();
 
-        std::thread::spawn(move || loop {
-            polling_device.poll(wgpu::Maintain::Poll);
-            std::thread::sleep(polling_time);
+        self.device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let value = f();
+
+        let validation = self.pop_error_scope();
+        let out_of_memory = self.pop_error_scope();
+
+        match validation.or(out_of_memory) {
+            Some(err) => {
+                event!(warn, "wgpu reported an error: {}", err);
+                Err(err)
+            }
+            None => Ok(value),
+        }
+    }
+
+    /// Runs `f`, returning the validation error the device reports meanwhile if any.
+    ///
+    /// `wgpu` keeps a single stack of error scopes per device, shared by all threads,
+    /// so the scopes of the [`Framework`] are locked until `f` returns: the error of one thread
+    /// is never reported to another. `f` must not capture errors itself.
+    pub(crate) fn capture_validation<T>(&self, f: impl FnOnce() -> T) -> (T, Option<wgpu::Error>) {
+        let _scopes = self.error_scopes.lock().unwrap();
+
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let value = f();
+
+        (value, self.pop_error_scope())
+    }
+
+    /// Pops the innermost error scope of the device, returning its error if any.
+    ///
+    /// `wgpu` resolves it immediately on native backends, so it is not blocked on
+    /// and can be called from async code running on another executor.
+    pub(crate) fn pop_error_scope(&self) -> Option<wgpu::Error> {
+        let mut error = Box::pin(self.device.pop_error_scope());
+
+        match error.as_mut().now_or_never() {
+            Some(error) => error,
+            None => futures::executor::block_on(error),
+        }
+    }
+
+    /// Returns a future that resolves once the GPU has finished the work submitted so far,
+    /// from the polling thread.
+    pub(crate) fn work_done(&self) -> impl std::future::Future<Output = ()> {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+
+        self.queue.on_submitted_work_done(move || {
+            sender.send(()).ok();
         });
 
-        Self { device, queue }
+        async move {
+            receiver.await.ok();
+        }
+    }
+
+    /// Enables or disables the labels of the compute passes and the debug groups around
+    /// the [`Kernel`](crate::Kernel) dispatches, shown by GPU debuggers like RenderDoc.
+    ///
+    /// They are enabled by default in debug builds only.
+    pub fn set_debug_markers(&self, enabled: bool) {
+        self.debug_markers.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns whether the dispatches are labeled for GPU debuggers.
+    pub(crate) fn debug_markers(&self) -> bool {
+        self.debug_markers.load(Ordering::Relaxed)
+    }
+
+    /// Enables or disables the sanity checks catching the elements of a buffer read
+    /// as another type than the one the shader writes:
+    /// - creating a [`Kernel`](crate::Kernel) fails with
+    ///   [`KernelError::ElementTypeMismatch`](crate::kernel::KernelError::ElementTypeMismatch)
+    ///   if a bound buffer holds elements of another size, or another scalar or vector type,
+    ///   than the array the shader declares for it.
+    /// - reads fail with a `StagingLength` error if `wgpu` downloads another number of bytes
+    ///   than requested, or if the unpadded rows of an image do not add up to its size.
+    ///
+    /// They are enabled by default in debug builds only, and cost nothing when disabled.
+    pub fn set_paranoid_checks(&self, enabled: bool) {
+        self.paranoid_checks.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns whether the sanity checks of the paranoid mode are enabled.
+    pub(crate) fn paranoid_checks(&self) -> bool {
+        self.paranoid_checks.load(Ordering::Relaxed)
+    }
+
+    /// Enables or disables the migration of the `WGSL` shaders using the legacy `[[attribute]]`
+    /// syntax when they are loaded, like [`migrate_legacy_wgsl`](crate::kernel::migrate_legacy_wgsl),
+    /// logging a warning for each of them.
+    ///
+    /// It is disabled by default: loading such a shader fails with
+    /// [`ShaderError::LegacyWgsl`](crate::kernel::ShaderError::LegacyWgsl).
+    pub fn set_migrate_legacy_wgsl(&self, enabled: bool) {
+        self.migrate_legacy_wgsl.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns whether the shaders using the legacy `WGSL` syntax are migrated.
+    pub(crate) fn migrate_legacy_wgsl(&self) -> bool {
+        self.migrate_legacy_wgsl.load(Ordering::Relaxed)
+    }
+
+    /// Gets the inner [`wgpu::Device`], e.g. to create render pipelines drawing
+    /// the resources of `gpgpu`.
+    pub fn as_gpu_device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    /// Gets the inner [`wgpu::Queue`].
+    pub fn as_gpu_queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    /// Returns the limits of the device, e.g. the maximum number of workgroups per dispatch.
+    pub fn limits(&self) -> wgpu::Limits {
+        self.device.limits()
+    }
+
+    /// Returns the optional capabilities of the device.
+    pub fn capabilities(&self) -> FrameworkCapabilities {
+        let features = self.device.features();
+
+        FrameworkCapabilities {
+            backend: self.backend,
+            spirv_passthrough: features.contains(wgpu::Features::SPIRV_SHADER_PASSTHROUGH),
+            pipeline_statistics: features.contains(wgpu::Features::PIPELINE_STATISTICS_QUERY),
+            timestamp_queries: features.contains(wgpu::Features::TIMESTAMP_QUERY),
+        }
+    }
+
+    /// Returns the GPU memory held by the buffers and images of this [`Framework`] still alive.
+    ///
+    /// A resource stops being counted when it is dropped or destroyed, e.g. with
+    /// [`BufOps::destroy`](crate::BufOps::destroy). Its memory is reclaimed by the polling
+    /// thread of the [`Framework`] once the GPU no longer uses it.
+    ///
+    /// A dropped resource that a [`Kernel`](crate::Kernel) still binds is not counted either,
+    /// although its memory is only reclaimed once no [`Kernel`](crate::Kernel) binds it.
+    pub fn memory_stats(&self) -> MemoryStats {
+        self.memory.stats()
+    }
+
+    /// Returns the statistics of the bind group and pipeline layouts cache.
+    ///
+    /// [`Kernel`](crate::Kernel)s whose [`DescriptorSet`](crate::DescriptorSet)s have the same shape
+    /// share their layouts, which are freed once no [`Kernel`](crate::Kernel) uses them.
+    pub fn layout_cache_stats(&self) -> LayoutCacheStats {
+        self.layout_cache.lock().unwrap().stats()
+    }
+
+    /// Forgets all the cached layouts and resets the cache statistics.
+    ///
+    /// Layouts still used by existing [`Kernel`](crate::Kernel)s are not freed.
+    pub fn clear_layout_cache(&self) {
+        self.layout_cache.lock().unwrap().clear();
+    }
+
+    /// Returns the statistics of the compute pipelines cache.
+    ///
+    /// [`Kernel`](crate::Kernel)s created from the same entry point of a [`Shader`](crate::Shader)
+    /// with [`DescriptorSet`](crate::DescriptorSet)s of the same shape share their compute pipeline,
+    /// which is freed once no [`Kernel`](crate::Kernel) uses it.
+    /// The cache only lives in memory, pipelines are compiled again on every launch.
+    pub fn pipeline_cache_stats(&self) -> PipelineCacheStats {
+        self.pipeline_cache.stats()
+    }
+
+    /// Forgets all the cached compute pipelines and resets the cache statistics.
+    ///
+    /// Pipelines still used by existing [`Kernel`](crate::Kernel)s are not freed.
+    pub fn clear_pipeline_cache(&self) {
+        self.pipeline_cache.clear();
     }
 }
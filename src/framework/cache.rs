@@ -0,0 +1,228 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
+
+use crate::{kernel::ShaderId, Framework, Shader};
+
+type LayoutKey = Vec<wgpu::BindGroupLayoutEntry>;
+type PipelineKey = (ShaderId, String, Vec<LayoutKey>);
+
+/// Statistics of the bind group and pipeline layouts cache of a [`Framework`](crate::Framework).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LayoutCacheStats {
+    /// Number of layouts reused from the cache.
+    pub hits: u64,
+    /// Number of layouts created because they were not in the cache.
+    pub misses: u64,
+    /// Number of bind group layouts alive in the cache.
+    pub bind_group_layouts: usize,
+    /// Number of pipeline layouts alive in the cache.
+    pub pipeline_layouts: usize,
+}
+
+/// Statistics of the compute pipelines cache of a [`Framework`](crate::Framework).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PipelineCacheStats {
+    /// Number of pipelines reused from the cache.
+    pub hits: u64,
+    /// Number of pipelines created because they were not in the cache.
+    pub misses: u64,
+    /// Number of pipelines alive in the cache.
+    pub pipelines: usize,
+}
+
+/// Deduplicates [`wgpu::BindGroupLayout`]s and [`wgpu::PipelineLayout`]s with the same shape.
+///
+/// Only weak references are kept: a layout is freed as soon as the last
+/// [`Kernel`](crate::Kernel) using it is dropped.
+#[derive(Default)]
+pub(crate) struct LayoutCache {
+    bind_group_layouts: HashMap<LayoutKey, Weak<wgpu::BindGroupLayout>>,
+    pipeline_layouts: HashMap<Vec<LayoutKey>, Weak<wgpu::PipelineLayout>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl LayoutCache {
+    /// Returns a [`wgpu::BindGroupLayout`] with the `entries` shape, creating it if needed.
+    pub(crate) fn bind_group_layout(
+        &mut self,
+        device: &wgpu::Device,
+        entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> Arc<wgpu::BindGroupLayout> {
+        let key = layout_key(entries);
+
+        if let Some(layout) = self.bind_group_layouts.get(&key).and_then(Weak::upgrade) {
+            self.hits += 1;
+            return layout;
+        }
+
+        self.misses += 1;
+        self.purge();
+
+        let layout = Arc::new(
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &key,
+            }),
+        );
+        self.bind_group_layouts.insert(key, Arc::downgrade(&layout));
+
+        layout
+    }
+
+    /// Returns a [`wgpu::PipelineLayout`] made of `layouts`, creating it if needed.
+    ///
+    /// `sets` are the entries each of the `layouts` was created with.
+    pub(crate) fn pipeline_layout(
+        &mut self,
+        device: &wgpu::Device,
+        sets: &[&[wgpu::BindGroupLayoutEntry]],
+        layouts: &[&wgpu::BindGroupLayout],
+    ) -> Arc<wgpu::PipelineLayout> {
+        let key = sets.iter().map(|set| layout_key(set)).collect::<Vec<_>>();
+
+        if let Some(layout) = self.pipeline_layouts.get(&key).and_then(Weak::upgrade) {
+            self.hits += 1;
+            return layout;
+        }
+
+        self.misses += 1;
+        self.purge();
+
+        let layout = Arc::new(
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: layouts,
+                push_constant_ranges: &[],
+            }),
+        );
+        self.pipeline_layouts.insert(key, Arc::downgrade(&layout));
+
+        layout
+    }
+
+    pub(crate) fn stats(&mut self) -> LayoutCacheStats {
+        self.purge();
+
+        LayoutCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            bind_group_layouts: self.bind_group_layouts.len(),
+            pipeline_layouts: self.pipeline_layouts.len(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Removes the entries of the layouts already freed.
+    fn purge(&mut self) {
+        self.bind_group_layouts
+            .retain(|_, layout| layout.strong_count() > 0);
+        self.pipeline_layouts
+            .retain(|_, layout| layout.strong_count() > 0);
+    }
+}
+
+/// Deduplicates [`wgpu::ComputePipeline`]s of the same shader entry point and layout.
+///
+/// Like [`LayoutCache`], only weak references are kept.
+/// Each pipeline has its own slot, locked while it is created: concurrent requests of the same
+/// pipeline wait for it. A pipeline `wgpu` rejects is not kept, so each of them reports the error.
+#[derive(Default)]
+pub(crate) struct PipelineCache {
+    slots: Mutex<HashMap<PipelineKey, PipelineSlot>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+type PipelineSlot = Arc<Mutex<Weak<wgpu::ComputePipeline>>>;
+
+impl PipelineCache {
+    /// Returns the [`wgpu::ComputePipeline`] of the `entry_point` of `shader` with `layout`,
+    /// creating it if needed.
+    ///
+    /// `sets` are the entries of the bind group layouts `layout` was created with.
+    /// Fails with the validation error `wgpu` reports while creating the pipeline.
+    pub(crate) fn pipeline(
+        &self,
+        fw: &Framework,
+        shader: &Shader,
+        entry_point: &str,
+        sets: &[&[wgpu::BindGroupLayoutEntry]],
+        layout: &wgpu::PipelineLayout,
+    ) -> Result<Arc<wgpu::ComputePipeline>, wgpu::Error> {
+        let key = (
+            shader.id,
+            entry_point.to_string(),
+            sets.iter().map(|set| layout_key(set)).collect(),
+        );
+
+        let slot = {
+            let mut slots = self.slots.lock().unwrap();
+            purge(&mut slots);
+            Arc::clone(slots.entry(key).or_default())
+        };
+        let mut slot = slot.lock().unwrap();
+
+        if let Some(pipeline) = slot.upgrade() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(pipeline);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let (pipeline, err) = fw.capture_validation(|| {
+            fw.device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    module: &shader.module,
+                    entry_point,
+                    layout: Some(layout),
+                })
+        });
+        if let Some(err) = err {
+            return Err(err);
+        }
+
+        let pipeline = Arc::new(pipeline);
+        *slot = Arc::downgrade(&pipeline);
+
+        Ok(pipeline)
+    }
+
+    pub(crate) fn stats(&self) -> PipelineCacheStats {
+        let mut slots = self.slots.lock().unwrap();
+        purge(&mut slots);
+
+        PipelineCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            pipelines: slots.len(),
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        self.slots.lock().unwrap().clear();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Removes the slots of the pipelines already freed, unless they are being created.
+fn purge(slots: &mut HashMap<PipelineKey, PipelineSlot>) {
+    slots.retain(|_, slot| Arc::strong_count(slot) > 1 || slot.lock().unwrap().strong_count() > 0);
+}
+
+/// Layouts with the same entries in different order are the same layout.
+fn layout_key(entries: &[wgpu::BindGroupLayoutEntry]) -> LayoutKey {
+    let mut key = entries.to_vec();
+    key.sort_by_key(|entry| entry.binding);
+    key
+}
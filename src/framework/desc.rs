@@ -0,0 +1,431 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use thiserror::Error;
+
+use crate::{
+    primitives::{buffers::file::read_elements, BufOps},
+    DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuError, GpuResult, GpuUniformBuffer,
+    Kernel, Program, Shader,
+};
+
+/// Compute pipeline described as plain data, e.g. loaded from a TOML or JSON file
+/// with the `serde` feature, and created with [`Framework::instantiate`].
+///
+/// Fields not described here are rejected when deserializing, with the location
+/// the format reports them at.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct PipelineDesc {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shaders: Vec<ShaderDesc>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub buffers: Vec<BufferDesc>,
+    /// Kernels, enqueued in this order by [`InstantiatedPipeline::run`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kernels: Vec<KernelDesc>,
+}
+
+/// `WGSL` shader of a [`PipelineDesc`], loaded from either a `path` or its `source`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct ShaderDesc {
+    /// Name the kernels refer to the shader by.
+    pub name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub path: Option<PathBuf>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source: Option<String>,
+}
+
+/// Buffer of `len` elements of a [`PipelineDesc`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct BufferDesc {
+    /// Name the kernels and [`InstantiatedPipeline`] refer to the buffer by.
+    pub name: String,
+    pub element: ElementDesc,
+    pub len: u64,
+    /// Whether the buffer is a [`GpuUniformBuffer`] instead of a [`GpuBuffer`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub uniform: bool,
+    /// File the elements are loaded from, saved by [`GpuBuffer::save_to_file`].
+    /// The buffer is zeroed if there is none.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub init: Option<PathBuf>,
+}
+
+/// Type of the elements of a [`BufferDesc`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ElementDesc {
+    U32,
+    I32,
+    F32,
+}
+
+/// Kernel of a [`PipelineDesc`], running the `entry_point` of a shader over `dispatch` workgroups.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct KernelDesc {
+    /// Name the [`InstantiatedPipeline`] refers to the kernel by.
+    pub name: String,
+    /// Name of the [`ShaderDesc`] of the kernel.
+    pub shader: String,
+    pub entry_point: String,
+    /// Buffers bound in the bind group 0 of the shader.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bindings: Vec<BindingDesc>,
+    pub dispatch: [u32; 3],
+}
+
+/// Buffer bound by a [`KernelDesc`], at the `binding` index or after the previous one.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct BindingDesc {
+    /// Name of the [`BufferDesc`] bound.
+    pub buffer: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub binding: Option<u32>,
+    /// Whether the shader only reads a storage buffer. Ignored for uniform buffers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub read_only: bool,
+}
+
+pub type PipelineDescResult<T> = Result<T, PipelineDescError>;
+
+/// Error of [`Framework::instantiate`], located by the path of the offending field
+/// in the [`PipelineDesc`], e.g. `kernels[1].bindings[0].buffer`.
+#[derive(Error, Debug)]
+pub enum PipelineDescError {
+    #[error("{path}: no {kind} is named `{name}`.")]
+    UnknownReference {
+        path: String,
+        kind: &'static str,
+        name: String,
+    },
+    #[error("{path}: the name `{name}` is already used.")]
+    DuplicateName { path: String, name: String },
+    #[error("{path}: {reason}")]
+    Invalid { path: String, reason: String },
+    #[error("{path}: {source}")]
+    Gpu {
+        path: String,
+        #[source]
+        source: Box<GpuError>,
+    },
+}
+
+/// Buffer of an [`InstantiatedPipeline`], of the type of its [`ElementDesc`].
+enum NamedBuffer<'fw> {
+    U32(GpuBuffer<'fw, u32>),
+    I32(GpuBuffer<'fw, i32>),
+    F32(GpuBuffer<'fw, f32>),
+    UniformU32(GpuUniformBuffer<'fw, u32>),
+    UniformI32(GpuUniformBuffer<'fw, i32>),
+    UniformF32(GpuUniformBuffer<'fw, f32>),
+}
+
+/// Runs `$body` with `$buf` bound to the buffer of any variant of a [`NamedBuffer`].
+macro_rules! with_buffer {
+    ($named:expr, $buf:ident => $body:expr) => {
+        match $named {
+            NamedBuffer::U32($buf) => $body,
+            NamedBuffer::I32($buf) => $body,
+            NamedBuffer::F32($buf) => $body,
+            NamedBuffer::UniformU32($buf) => $body,
+            NamedBuffer::UniformI32($buf) => $body,
+            NamedBuffer::UniformF32($buf) => $body,
+        }
+    };
+}
+
+impl<'fw> NamedBuffer<'fw> {
+    fn new(fw: &'fw Framework, desc: &BufferDesc, path: &str) -> PipelineDescResult<Self> {
+        fn create<'fw, T, B>(
+            fw: &'fw Framework,
+            desc: &BufferDesc,
+            path: &str,
+        ) -> PipelineDescResult<B>
+        where
+            T: crate::primitives::buffers::ScalarElement,
+            B: BufOps<'fw, T>,
+        {
+            let created = match &desc.init {
+                Some(init) => {
+                    let elements =
+                        read_elements::<T>(init).map_err(|err| gpu(path, "init", err))?;
+
+                    if elements.len() as u64 != desc.len {
+                        return Err(PipelineDescError::Invalid {
+                            path: format!("{}.init", path),
+                            reason: format!(
+                                "the file holds {} elements, not {}.",
+                                elements.len(),
+                                desc.len
+                            ),
+                        });
+                    }
+
+                    B::try_from_slice(fw, &elements)
+                }
+                None => B::try_with_capacity(fw, desc.len),
+            };
+
+            created.map_err(|err| gpu(path, "len", err))
+        }
+
+        Ok(match (desc.element, desc.uniform) {
+            (ElementDesc::U32, false) => Self::U32(create(fw, desc, path)?),
+            (ElementDesc::I32, false) => Self::I32(create(fw, desc, path)?),
+            (ElementDesc::F32, false) => Self::F32(create(fw, desc, path)?),
+            (ElementDesc::U32, true) => Self::UniformU32(create(fw, desc, path)?),
+            (ElementDesc::I32, true) => Self::UniformI32(create(fw, desc, path)?),
+            (ElementDesc::F32, true) => Self::UniformF32(create(fw, desc, path)?),
+        })
+    }
+
+    fn bind<'res>(
+        &'res self,
+        set: DescriptorSet<'res>,
+        desc: &BindingDesc,
+    ) -> crate::kernel::DescriptorSetResult<DescriptorSet<'res>> {
+        let usage = if desc.read_only {
+            GpuBufferUsage::ReadOnly
+        } else {
+            GpuBufferUsage::ReadWrite
+        };
+
+        macro_rules! bind_storage {
+            ($buf:expr) => {
+                match desc.binding {
+                    Some(binding) => set.bind_buffer_at(binding, $buf, usage),
+                    None => Ok(set.bind_buffer($buf, usage)),
+                }
+            };
+        }
+
+        macro_rules! bind_uniform {
+            ($buf:expr) => {
+                match desc.binding {
+                    Some(binding) => set.bind_uniform_buffer_at(binding, $buf),
+                    None => Ok(set.bind_uniform_buffer($buf)),
+                }
+            };
+        }
+
+        match self {
+            Self::U32(buf) => bind_storage!(buf),
+            Self::I32(buf) => bind_storage!(buf),
+            Self::F32(buf) => bind_storage!(buf),
+            Self::UniformU32(buf) => bind_uniform!(buf),
+            Self::UniformI32(buf) => bind_uniform!(buf),
+            Self::UniformF32(buf) => bind_uniform!(buf),
+        }
+    }
+}
+
+/// Wraps an error of the field `field` of the item at `path`.
+fn gpu(path: &str, field: &str, err: impl Into<GpuError>) -> PipelineDescError {
+    PipelineDescError::Gpu {
+        path: format!("{}.{}", path, field),
+        source: Box::new(err.into()),
+    }
+}
+
+/// Buffers and kernels created from a [`PipelineDesc`] by [`Framework::instantiate`].
+pub struct InstantiatedPipeline<'fw> {
+    buffers: HashMap<String, NamedBuffer<'fw>>,
+    kernels: Vec<(String, Kernel<'fw>, [u32; 3])>,
+}
+
+impl<'fw> InstantiatedPipeline<'fw> {
+    /// Returns the names of the buffers, in no particular order.
+    pub fn buffer_names(&self) -> impl Iterator<Item = &str> {
+        self.buffers.keys().map(String::as_str)
+    }
+
+    /// Returns the kernel named `name`.
+    pub fn kernel(&self, name: &str) -> Option<&Kernel<'fw>> {
+        self.kernels
+            .iter()
+            .find(|(kernel_name, _, _)| kernel_name == name)
+            .map(|(_, kernel, _)| kernel)
+    }
+
+    fn buffer(&self, name: &str) -> PipelineDescResult<&NamedBuffer<'fw>> {
+        self.buffers
+            .get(name)
+            .ok_or_else(|| PipelineDescError::UnknownReference {
+                path: "buffers".to_string(),
+                kind: "buffer",
+                name: name.to_string(),
+            })
+    }
+
+    /// Writes `data` into the buffer named `name`, returning how many bytes were written.
+    ///
+    /// `T` can be any type whose size is a multiple of the size of the elements of the buffer,
+    /// e.g. `[f32; 4]` for a buffer of `f32`s.
+    pub fn write<T: bytemuck::Pod>(&self, name: &str, data: &[T]) -> GpuResult<u64> {
+        let named = self.buffer(name)?;
+
+        with_buffer!(named, buf => {
+            let elements = bytemuck::try_cast_slice(data).map_err(|err| PipelineDescError::Invalid {
+                path: format!("buffers.{}", name),
+                reason: format!("cannot write elements of `{}`: {}", std::any::type_name::<T>(), err),
+            })?;
+
+            Ok(buf.write(elements)?)
+        })
+    }
+
+    /// Pulls all the elements from the storage buffer named `name` as elements of `T`.
+    ///
+    /// Fails with [`PipelineDescError::Invalid`] for uniform buffers, which cannot be read back.
+    pub fn read<T: bytemuck::Pod>(&self, name: &str) -> GpuResult<Vec<T>> {
+        let bytes = match self.buffer(name)? {
+            NamedBuffer::U32(buf) => bytemuck::cast_slice(&buf.read_vec_blocking()?).to_vec(),
+            NamedBuffer::I32(buf) => bytemuck::cast_slice(&buf.read_vec_blocking()?).to_vec(),
+            NamedBuffer::F32(buf) => bytemuck::cast_slice(&buf.read_vec_blocking()?).to_vec(),
+            _ => {
+                return Err(PipelineDescError::Invalid {
+                    path: format!("buffers.{}", name),
+                    reason: "uniform buffers cannot be read back.".to_string(),
+                }
+                .into())
+            }
+        };
+
+        let size = std::mem::size_of::<T>();
+        if size == 0 || bytes.len() % size != 0 {
+            return Err(PipelineDescError::Invalid {
+                path: format!("buffers.{}", name),
+                reason: format!(
+                    "{} bytes are not made of elements of `{}`.",
+                    bytes.len(),
+                    std::any::type_name::<T>()
+                ),
+            }
+            .into());
+        }
+
+        let mut elements = vec![T::zeroed(); bytes.len() / size];
+        bytemuck::cast_slice_mut::<T, u8>(&mut elements).copy_from_slice(&bytes);
+
+        Ok(elements)
+    }
+
+    /// Enqueues the kernels in the order of the [`PipelineDesc`], with their `dispatch` workgroups.
+    pub fn run(&self) -> GpuResult<()> {
+        for (_, kernel, [x, y, z]) in &self.kernels {
+            kernel.enqueue(*x, *y, *z)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Framework {
+    /// Creates the shaders, buffers and kernels of a [`PipelineDesc`].
+    ///
+    /// The buffers are zeroed unless they are loaded from a file, and the kernels bind them
+    /// by name. The shaders are only kept for as long as the kernels are created.
+    ///
+    /// Fails with the [`PipelineDescError`] of the first invalid field, e.g. a kernel referring
+    /// to a buffer that is not described, or a shader that does not compile.
+    pub fn instantiate(&self, desc: &PipelineDesc) -> GpuResult<InstantiatedPipeline<'_>> {
+        let mut shaders = HashMap::new();
+
+        for (i, shader) in desc.shaders.iter().enumerate() {
+            let path = format!("shaders[{}]", i);
+
+            let created = match (&shader.path, &shader.source) {
+                (Some(file), None) => Shader::from_wgsl_file(self, file),
+                (None, Some(source)) => Shader::from_wgsl_source(self, source, Some(&shader.name)),
+                _ => {
+                    return Err(PipelineDescError::Invalid {
+                        path,
+                        reason: "a shader needs either a `path` or a `source`.".to_string(),
+                    }
+                    .into())
+                }
+            };
+            let created = created.map_err(|err| gpu(&path, "source", err))?;
+
+            if shaders.insert(shader.name.as_str(), created).is_some() {
+                return Err(duplicate(&path, &shader.name).into());
+            }
+        }
+
+        let mut buffers = HashMap::new();
+
+        for (i, buffer) in desc.buffers.iter().enumerate() {
+            let path = format!("buffers[{}]", i);
+            let created = NamedBuffer::new(self, buffer, &path)?;
+
+            if buffers.insert(buffer.name.clone(), created).is_some() {
+                return Err(duplicate(&path, &buffer.name).into());
+            }
+        }
+
+        let mut kernels = Vec::with_capacity(desc.kernels.len());
+
+        for (i, kernel) in desc.kernels.iter().enumerate() {
+            let path = format!("kernels[{}]", i);
+
+            if kernels.iter().any(|(name, _, _)| name == &kernel.name) {
+                return Err(duplicate(&path, &kernel.name).into());
+            }
+
+            let shader = shaders.get(kernel.shader.as_str()).ok_or_else(|| {
+                PipelineDescError::UnknownReference {
+                    path: format!("{}.shader", path),
+                    kind: "shader",
+                    name: kernel.shader.clone(),
+                }
+            })?;
+
+            let mut set = DescriptorSet::default();
+
+            for (j, binding) in kernel.bindings.iter().enumerate() {
+                let binding_path = format!("{}.bindings[{}]", path, j);
+
+                let buffer = buffers.get(&binding.buffer).ok_or_else(|| {
+                    PipelineDescError::UnknownReference {
+                        path: format!("{}.buffer", binding_path),
+                        kind: "buffer",
+                        name: binding.buffer.clone(),
+                    }
+                })?;
+
+                set = buffer
+                    .bind(set, binding)
+                    .map_err(|err| gpu(&binding_path, "binding", err))?;
+            }
+
+            let set = set
+                .into_owned(self)
+                .map_err(|err| gpu(&path, "bindings", err))?;
+            let program = Program::new(shader, kernel.entry_point.as_str()).add_descriptor_set(set);
+            let created =
+                Kernel::new(self, program).map_err(|err| gpu(&path, "entry_point", err))?;
+
+            kernels.push((kernel.name.clone(), created, kernel.dispatch));
+        }
+
+        Ok(InstantiatedPipeline { buffers, kernels })
+    }
+}
+
+fn duplicate(path: &str, name: &str) -> PipelineDescError {
+    PipelineDescError::DuplicateName {
+        path: format!("{}.name", path),
+        name: name.to_string(),
+    }
+}
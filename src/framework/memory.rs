@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// GPU memory held by the buffers and images of a [`Framework`](crate::Framework) still alive.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of [`GpuBuffer`](crate::GpuBuffer)s and [`GpuUniformBuffer`](crate::GpuUniformBuffer)s alive.
+    pub buffers: u64,
+    /// Bytes of the buffers alive.
+    pub buffer_bytes: u64,
+    /// Number of [`GpuImage`](crate::GpuImage)s and [`GpuConstImage`](crate::GpuConstImage)s alive.
+    pub images: u64,
+    /// Bytes of the pixels of the images alive, mip levels included.
+    pub image_bytes: u64,
+}
+
+/// Counters of the memory held by the resources of a [`Framework`](crate::Framework).
+#[derive(Default)]
+pub(crate) struct MemoryTracker {
+    buffers: AtomicU64,
+    buffer_bytes: AtomicU64,
+    images: AtomicU64,
+    image_bytes: AtomicU64,
+}
+
+impl MemoryTracker {
+    /// Counts a new buffer of `bytes` until the returned [`Allocation`] is dropped.
+    pub(crate) fn buffer(&self, bytes: u64) -> Allocation<'_> {
+        self.buffers.fetch_add(1, Ordering::Relaxed);
+        self.buffer_bytes.fetch_add(bytes, Ordering::Relaxed);
+
+        Allocation {
+            count: &self.buffers,
+            total: &self.buffer_bytes,
+            bytes,
+        }
+    }
+
+    /// Counts a new image of `bytes` until the returned [`Allocation`] is dropped.
+    pub(crate) fn image(&self, bytes: u64) -> Allocation<'_> {
+        self.images.fetch_add(1, Ordering::Relaxed);
+        self.image_bytes.fetch_add(bytes, Ordering::Relaxed);
+
+        Allocation {
+            count: &self.images,
+            total: &self.image_bytes,
+            bytes,
+        }
+    }
+
+    pub(crate) fn stats(&self) -> MemoryStats {
+        MemoryStats {
+            buffers: self.buffers.load(Ordering::Relaxed),
+            buffer_bytes: self.buffer_bytes.load(Ordering::Relaxed),
+            images: self.images.load(Ordering::Relaxed),
+            image_bytes: self.image_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Memory of a resource, counted by its [`MemoryTracker`] while it is alive.
+pub(crate) struct Allocation<'fw> {
+    count: &'fw AtomicU64,
+    total: &'fw AtomicU64,
+    bytes: u64,
+}
+
+impl Drop for Allocation<'_> {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::Relaxed);
+        self.total.fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
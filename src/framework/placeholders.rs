@@ -0,0 +1,79 @@
+use std::{collections::HashMap, sync::Arc};
+
+/// Zeroed resources bound in place of the ones a shader does not actually use.
+///
+/// They are shared by every [`DescriptorSet`](crate::DescriptorSet) of a [`Framework`](crate::Framework)
+/// and only freed with it.
+#[derive(Default)]
+pub(crate) struct PlaceholderPool {
+    buffers: HashMap<(u64, bool), Arc<wgpu::Buffer>>,
+    images: HashMap<(wgpu::TextureFormat, bool), Arc<PlaceholderImage>>,
+}
+
+/// 1x1 texture of a placeholder image, kept alive alongside its view.
+pub(crate) struct PlaceholderImage {
+    _texture: wgpu::Texture,
+    pub(crate) view: wgpu::TextureView,
+}
+
+impl PlaceholderPool {
+    /// Returns a zeroed buffer of `size` bytes, usable as a storage buffer.
+    ///
+    /// Read-only and read-write placeholders are different buffers, so both can be used in the same dispatch.
+    pub(crate) fn buffer(
+        &mut self,
+        device: &wgpu::Device,
+        size: u64,
+        read_only: bool,
+    ) -> Arc<wgpu::Buffer> {
+        let buffer = self.buffers.entry((size, read_only)).or_insert_with(|| {
+            Arc::new(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("DescriptorSet::bind_placeholder_buffer"),
+                size,
+                usage: wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            }))
+        });
+
+        Arc::clone(buffer)
+    }
+
+    /// Returns a zeroed 1x1 image of `format`, usable as a storage image if `storage`
+    /// or as a texture otherwise.
+    pub(crate) fn image(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        storage: bool,
+    ) -> Arc<PlaceholderImage> {
+        let image = self.images.entry((format, storage)).or_insert_with(|| {
+            let usage = if storage {
+                wgpu::TextureUsages::STORAGE_BINDING
+            } else {
+                wgpu::TextureUsages::TEXTURE_BINDING
+            };
+
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("DescriptorSet::bind_placeholder_image"),
+                size: wgpu::Extent3d {
+                    width: 1,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+            Arc::new(PlaceholderImage {
+                _texture: texture,
+                view,
+            })
+        });
+
+        Arc::clone(image)
+    }
+}
@@ -0,0 +1,42 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Thread polling the device of a [`Framework`](crate::Framework), stopped when it is dropped.
+///
+/// The thread only holds a weak reference to the device between its polls,
+/// so that the device is freed with the [`Framework`](crate::Framework).
+pub(crate) struct Poller {
+    stop: Arc<AtomicBool>,
+}
+
+impl Poller {
+    /// Spawns a thread polling `device` every `polling_time`.
+    pub(crate) fn spawn(device: &Arc<wgpu::Device>, polling_time: Duration) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let device = Arc::downgrade(device);
+
+        std::thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                match device.upgrade() {
+                    Some(device) => device.poll(wgpu::Maintain::Poll),
+                    None => break,
+                };
+                std::thread::sleep(polling_time);
+            }
+        });
+
+        Self { stop }
+    }
+}
+
+impl Drop for Poller {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
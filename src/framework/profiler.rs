@@ -0,0 +1,235 @@
+use std::{fmt::Write as _, path::Path};
+
+use thiserror::Error;
+use wgpu::util::DownloadBuffer;
+
+use crate::Framework;
+
+/// Maximum number of scopes recorded per frame. Further scopes are not recorded.
+const MAX_SCOPES: u32 = 1024;
+
+pub type ProfilerResult<T> = Result<T, ProfilerError>;
+
+#[derive(Error, Debug)]
+pub enum ProfilerError {
+    #[error("The device does not support timestamp queries")]
+    Unsupported,
+    #[error("The profiler is not enabled")]
+    NotEnabled,
+    #[error(transparent)]
+    AsyncMapError(#[from] wgpu::BufferAsyncError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Records the GPU time spent in the scopes of a frame with timestamp queries.
+///
+/// Scope `n` writes its start and end timestamps into the queries `2n` and `2n + 1`.
+pub(crate) struct Profiler {
+    query_set: wgpu::QuerySet,
+    scopes: Vec<String>,
+}
+
+impl Profiler {
+    fn new(device: &wgpu::Device) -> Self {
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Profiler"),
+            ty: wgpu::QueryType::Timestamp,
+            count: MAX_SCOPES * 2,
+        });
+
+        Self {
+            query_set,
+            scopes: Vec::new(),
+        }
+    }
+}
+
+/// Escapes `text` to be written inside a JSON string: quotes, backslashes and control
+/// characters are escaped, and other characters, non-ASCII ones included, are kept as is.
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32)
+                .expect("Writing into a String does not fail."),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+impl Framework {
+    /// Starts recording the GPU time spent by every [`Kernel`](crate::Kernel) dispatch,
+    /// in scopes named after their entry points.
+    ///
+    /// Dispatches submitted together by a [`CommandRecorder`](crate::CommandRecorder)
+    /// are nested in a scope of the whole submission. While the profiler is enabled,
+    /// each of them runs in its own compute pass to be timed separately.
+    ///
+    /// Fails with [`ProfilerError::Unsupported`] if the device does not support
+    /// [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub fn enable_profiler(&self) -> ProfilerResult<()> {
+        if !self
+            .device
+            .features()
+            .contains(wgpu::Features::TIMESTAMP_QUERY)
+        {
+            return Err(ProfilerError::Unsupported);
+        }
+
+        let mut profiler = self.profiler.lock().unwrap();
+
+        if profiler.is_none() {
+            *profiler = Some(Profiler::new(&self.device));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the scopes recorded since the profiler was enabled or the previous call
+    /// into `path`, in the Chrome trace event format of `chrome://tracing`, and starts a new frame.
+    ///
+    /// Blocks until the GPU has finished the work of the recorded scopes.
+    /// At most 1024 scopes are recorded per frame.
+    pub fn end_frame_profile(&self, path: impl AsRef<Path>) -> ProfilerResult<()> {
+        let scopes = self.take_profile_scopes()?;
+
+        let mut trace = String::from("{\"traceEvents\":[");
+
+        for (index, (name, start, duration)) in scopes.iter().enumerate() {
+            if index > 0 {
+                trace.push(',');
+            }
+
+            write!(
+                trace,
+                "{{\"name\":\"{}\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":0,\"tid\":0}}",
+                json_escape(name),
+                start,
+                duration
+            )
+            .expect("Writing into a String does not fail.");
+        }
+
+        trace.push_str("]}");
+        std::fs::write(path, trace)?;
+
+        Ok(())
+    }
+
+    /// Begins a scope named `name` in `encoder`, returning its index
+    /// if the profiler is enabled and the frame has room for it.
+    pub(crate) fn begin_profile_scope(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        name: &str,
+    ) -> Option<u32> {
+        let mut profiler = self.profiler.lock().unwrap();
+        let profiler = profiler.as_mut()?;
+
+        let scope = profiler.scopes.len() as u32;
+        if scope == MAX_SCOPES {
+            return None;
+        }
+
+        encoder.write_timestamp(&profiler.query_set, scope * 2);
+        profiler.scopes.push(name.to_string());
+
+        Some(scope)
+    }
+
+    /// Ends the `scope` returned by [`Framework::begin_profile_scope`] in `encoder`.
+    pub(crate) fn end_profile_scope(&self, encoder: &mut wgpu::CommandEncoder, scope: Option<u32>) {
+        if let (Some(scope), Some(profiler)) = (scope, self.profiler.lock().unwrap().as_ref()) {
+            encoder.write_timestamp(&profiler.query_set, scope * 2 + 1);
+        }
+    }
+
+    /// Reads the recorded scopes as their names, start and duration in microseconds,
+    /// relative to the earliest start, and clears them.
+    fn take_profile_scopes(&self) -> ProfilerResult<Vec<(String, f64, f64)>> {
+        let (names, resolve) = {
+            let mut profiler = self.profiler.lock().unwrap();
+            let profiler = profiler.as_mut().ok_or(ProfilerError::NotEnabled)?;
+
+            let names = std::mem::take(&mut profiler.scopes);
+            if names.is_empty() {
+                return Ok(Vec::new());
+            }
+            let queries = names.len() as u32 * 2;
+
+            let resolve = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Profiler"),
+                size: queries as u64 * std::mem::size_of::<u64>() as u64,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+
+            let mut encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Framework::end_frame_profile"),
+                });
+            encoder.resolve_query_set(&profiler.query_set, 0..queries, &resolve, 0);
+            self.queue.submit(Some(encoder.finish()));
+
+            (names, resolve)
+        };
+
+        let (sender, receiver) = futures::channel::oneshot::channel();
+
+        DownloadBuffer::read_buffer(&self.device, &self.queue, &resolve.slice(..), |arg| {
+            sender.send(arg).ok();
+        });
+
+        // The callback is only dropped without being called if the device is lost.
+        let download =
+            futures::executor::block_on(receiver).unwrap_or(Err(wgpu::BufferAsyncError))?;
+        let timestamps: &[u64] = bytemuck::cast_slice(&download);
+
+        let period = self.queue.get_timestamp_period() as f64 / 1000.0;
+        let base = timestamps
+            .iter()
+            .step_by(2)
+            .min()
+            .copied()
+            .unwrap_or_default();
+
+        Ok(names
+            .into_iter()
+            .zip(timestamps.chunks_exact(2))
+            .map(|(name, times)| {
+                let start = times[0].saturating_sub(base) as f64 * period;
+                let duration = times[1].saturating_sub(times[0]) as f64 * period;
+
+                (name, start, duration)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_escape_keeps_apostrophes_and_non_ascii_text() {
+        assert_eq!(json_escape("l'échelle ×2 — 拡大"), "l'échelle ×2 — 拡大");
+    }
+
+    #[test]
+    fn json_escape_escapes_quotes_backslashes_and_control_characters() {
+        assert_eq!(
+            json_escape("\"blur\"\\pass\n\t\u{1}end"),
+            "\\\"blur\\\"\\\\pass\\n\\t\\u0001end"
+        );
+    }
+}
@@ -0,0 +1,38 @@
+/// Most buffers a [`ScratchPool`] keeps: the least recently returned ones are freed first.
+const MAX_BUFFERS: usize = 32;
+
+/// Storage buffers the [`ops`](crate::ops) use for their intermediate results, kept by the
+/// [`Framework`](crate::Framework) between operations instead of being allocated each time.
+///
+/// Buffers are only reused for the same size, which repeated operations on a buffer ask for.
+#[derive(Default)]
+pub(crate) struct ScratchPool {
+    buffers: Vec<(u64, wgpu::Buffer)>,
+}
+
+impl ScratchPool {
+    /// Takes a buffer of `size` bytes, usable as a storage buffer and copied from and to.
+    /// Its contents are unspecified.
+    pub(crate) fn take(&mut self, device: &wgpu::Device, size: u64) -> wgpu::Buffer {
+        match self.buffers.iter().rposition(|(len, _)| *len == size) {
+            Some(i) => self.buffers.remove(i).1,
+            None => device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("ops::scratch"),
+                size,
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_SRC
+                    | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+        }
+    }
+
+    /// Returns a buffer of `size` bytes taken from this pool, for the next operations to reuse.
+    pub(crate) fn give(&mut self, buffer: wgpu::Buffer, size: u64) {
+        if self.buffers.len() == MAX_BUFFERS {
+            self.buffers.remove(0);
+        }
+
+        self.buffers.push((size, buffer));
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use crate::{kernel::ShaderResult, Shader};
+
+/// Most shaders a [`ShaderCache`] keeps: the least recently used ones are freed first.
+const MAX_SHADERS: usize = 64;
+
+/// Shaders the [`ops`](crate::ops) generate from templates, kept by the
+/// [`Framework`](crate::Framework) so that repeating an operation compiles its shader once.
+///
+/// Shaders are looked up by a key naming their template and substitutions.
+#[derive(Default)]
+pub(crate) struct ShaderCache {
+    shaders: Vec<(String, Arc<Shader>)>,
+}
+
+impl ShaderCache {
+    /// Returns the shader of `key`, compiling it with `compile` if it is not in the cache.
+    /// Shaders that fail to compile are not kept.
+    pub(crate) fn get_or_compile(
+        &mut self,
+        key: &str,
+        compile: impl FnOnce() -> ShaderResult<Shader>,
+    ) -> ShaderResult<Arc<Shader>> {
+        let shader = match self.shaders.iter().position(|(k, _)| k == key) {
+            Some(i) => self.shaders.remove(i).1,
+            None => Arc::new(compile()?),
+        };
+
+        if self.shaders.len() == MAX_SHADERS {
+            self.shaders.remove(0);
+        }
+        self.shaders.push((key.to_string(), Arc::clone(&shader)));
+
+        Ok(shader)
+    }
+}
@@ -1,23 +1,525 @@
-use std::{borrow::Cow, path::Path};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+    num::NonZeroU32,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use thiserror::Error;
 
 use crate::{
-    primitives::{BufOps, ImgOps, PixelInfo},
-    DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuConstImage, GpuImage, GpuUniformBuffer,
-    Kernel, Program, Shader,
+    framework::PlaceholderImage,
+    primitives::{samplers::SamplerKind, BufOps, ImgOps, PixelInfo},
+    AnyDescriptorSet, DescriptorLayout, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage,
+    GpuConstImage, GpuId, GpuImage, GpuSampler, GpuUniformBuffer, Kernel, OwnedDescriptorSet,
+    Program, Shader, Submission,
 };
 
+pub use self::dispatch::Bindable;
+pub(crate) use self::pipeline::PipelineResource;
+pub(crate) use self::preprocess::{check_legacy_wgsl, preprocess_file, SourceMap};
+pub use self::preprocess::{migrate_legacy_wgsl, preprocess_wgsl, substitute_wgsl};
+pub(crate) use self::recorder::RecordedCommand;
+pub(crate) use self::reflection::ShaderReflection;
+pub use self::reflection::{BindingKind, EntryPointInfo, SampleKind, ShaderBinding, ShaderInfo};
+
+mod dispatch;
+mod layout;
+mod library;
+mod pipeline;
+mod preprocess;
+mod recorder;
+mod reflection;
+
+pub type DescriptorSetResult<T> = Result<T, DescriptorSetError>;
+
+#[derive(Error, Debug)]
+pub enum DescriptorSetError {
+    #[error("Binding {0} is already in use in this descriptor set.")]
+    DuplicateBinding(u32),
+    #[error("Binding arrays cannot be empty.")]
+    EmptyBindingArray,
+    #[error("Binding arrays require the {0:?} features, not supported by the device.")]
+    MissingFeatures(wgpu::Features),
+    #[error(
+        "Too many {kind}s in a single shader stage ({count} bound, the device limit is {limit})."
+    )]
+    TooManyBindings {
+        kind: &'static str,
+        count: u32,
+        limit: u32,
+    },
+    #[error("The {kind} at binding {binding} holds `{}` ({} bytes each) and is {size} bytes, over the device limit of {limit} bytes.", .element.name, .element.size)]
+    BufferTooLarge {
+        binding: u32,
+        kind: BindingKind,
+        element: ElementType,
+        size: u64,
+        limit: u32,
+    },
+    #[error("The storage buffer at binding {binding} holds {capacity} elements of `{}`, but the shader requires at least {required}.", .element.name)]
+    BufferTooSmall {
+        binding: u32,
+        element: ElementType,
+        capacity: u64,
+        required: u64,
+    },
+    #[error("A {sampler:?} sampler cannot sample textures of {sample:?} type.")]
+    IncompatibleSampler {
+        sampler: SamplerKind,
+        sample: wgpu::TextureSampleType,
+    },
+    #[error("The image was created without the {0:?} usage required to bind it.")]
+    MissingImageUsage(wgpu::TextureUsages),
+    #[error("The descriptor layout has no slot named `{0}`.")]
+    UnknownSlot(String),
+    #[error("Slot `{slot}` expects {expected:?}, but the resource bound is {bound:?}.")]
+    SlotMismatch {
+        slot: String,
+        expected: wgpu::BindingType,
+        bound: wgpu::BindingType,
+    },
+    #[error("Slot `{0}` of the descriptor layout was not bound.")]
+    MissingSlot(String),
+}
+
+pub type ShaderResult<T> = Result<T, ShaderError>;
+
+#[derive(Error, Debug)]
+pub enum ShaderError {
+    #[error("The WGSL shader could not be parsed:\n{0}")]
+    InvalidWgsl(String),
+    #[error("The SPIR-V shader could not be parsed: {0}")]
+    InvalidSpirv(String),
+    #[error("The SPIR-V shader is {0} bytes long, which is not a multiple of 4.")]
+    MisalignedSpirv(usize),
+    #[error("The SPIR-V shader starts with {0:#010x} instead of the magic number 0x07230203.")]
+    InvalidMagicNumber(u32),
+    #[error("The SPIR-V shader (version {version}, generator {generator:#010x}) uses {capability}, which is not supported. {hint}")]
+    UnsupportedShader {
+        capability: String,
+        hint: &'static str,
+        version: String,
+        generator: u32,
+    },
+    #[error("The shader is not valid: {0}")]
+    InvalidShader(String),
+    #[error("The shader could not be compiled:\n{0}")]
+    Compilation(ShaderDiagnostic),
+    #[error("Malformed include directive in `{file}` at line {line}, expected `//!include \"file.wgsl\"`.")]
+    MalformedInclude { file: String, line: usize },
+    #[error("File `{include}` included by `{file}` not found.")]
+    IncludeNotFound { include: String, file: String },
+    #[error("Include cycle: {}.", .0.join(" -> "))]
+    IncludeCycle(Vec<String>),
+    #[error("Malformed or unmatched conditional directive in `{file}` at line {line}.")]
+    MalformedConditional { file: String, line: usize },
+    #[error("Conditional block opened in `{file}` at line {line} is not closed by `//#endif`.")]
+    UnterminatedConditional { file: String, line: usize },
+    #[error("Placeholder `{placeholder}` at line {line} has no substitution.")]
+    MissingSubstitution { placeholder: String, line: usize },
+    #[error("`{0}` uses the legacy `[[attribute]]` syntax `naga` no longer supports: convert it with `migrate_legacy_wgsl`, or enable `Framework::set_migrate_legacy_wgsl`.")]
+    LegacyWgsl(String),
+    #[error("Legacy attributes at line {line} are not closed by `]]`.")]
+    UnterminatedAttributes { line: usize },
+    #[error("Placeholder at line {line} is not closed by `}}}}`.")]
+    UnterminatedPlaceholder { line: usize },
+    #[error("Constant `{0}` is already declared in the shader.")]
+    ConstantCollision(String),
+    #[error("Constant `{name}` cannot be declared: {reason}.")]
+    InvalidConstant { name: String, reason: &'static str },
+    #[error("SPIR-V passthrough is not supported by the {0:?} backend.")]
+    PassthroughUnsupported(wgpu::Backend),
+    #[error("{} shaders of the library could not be loaded:\n{}", .0.len(), describe_failures(.0))]
+    Library(Vec<(String, ShaderError)>),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Lists the shaders of a [`ShaderError::Library`] error with their errors.
+fn describe_failures(failures: &[(String, ShaderError)]) -> String {
+    failures
+        .iter()
+        .map(|(name, err)| format!("{}: {}", name, err))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub type KernelResult<T> = Result<T, KernelError>;
+
+#[derive(Error, Debug)]
+pub enum KernelError {
+    #[error("Entry point `{entry_point}` not found in {} (available compute entry points: {available:?}).", describe_shader(.shader))]
+    EntryPointNotFound {
+        shader: Option<String>,
+        entry_point: String,
+        available: Vec<String>,
+    },
+    #[error("The compute entry points of the shader are unknown, as it could not be reflected.")]
+    UnknownEntryPoints,
+    #[error("The compute pipeline of kernel `{kernel}` (entry point `{entry_point}` of {}) could not be created: {reason}", describe_shader(.shader))]
+    InvalidPipeline {
+        kernel: String,
+        entry_point: String,
+        shader: Option<String>,
+        reason: String,
+    },
+    #[error("The {0:?} features are required, not supported by the device.")]
+    MissingFeatures(wgpu::Features),
+    #[error("group {group} binding {binding}: shader expects {expected}, but nothing was bound. Group {group} bindings: {}.", describe_bindings(.group_bindings))]
+    MissingBinding {
+        group: u32,
+        binding: u32,
+        expected: BindingKind,
+        group_bindings: Vec<BindingInfo>,
+    },
+    #[error("group {group} binding {binding}: shader expects {expected}, you bound a {bound}. Group {group} bindings: {}.", describe_bindings(.group_bindings))]
+    BindingMismatch {
+        group: u32,
+        binding: u32,
+        expected: BindingKind,
+        bound: BindingKind,
+        group_bindings: Vec<BindingInfo>,
+    },
+    #[error("group {group} binding {binding}: shader expects at least {required} bytes, but the {kind} bound is {size} bytes of `{}` ({} bytes each). {}", .element.name, .element.size, padding_hint(*.kind))]
+    BufferTooSmall {
+        group: u32,
+        binding: u32,
+        kind: BindingKind,
+        element: ElementType,
+        size: u64,
+        required: u64,
+    },
+    #[error("group {group} binding {binding}: shader declares elements of `{shader_element}` ({stride} bytes each), but the {kind} bound holds `{}` ({} bytes each).", .element.name, .element.size)]
+    ElementTypeMismatch {
+        group: u32,
+        binding: u32,
+        kind: BindingKind,
+        shader_element: String,
+        stride: u32,
+        element: ElementType,
+    },
+    #[error(transparent)]
+    DescriptorSetError(#[from] DescriptorSetError),
+    #[error("The same buffer is bound read-only at group {} binding {} and read-write at group {} binding {}. Use `Program::allow_aliasing` if this is intended.", .read_only.0, .read_only.1, .read_write.0, .read_write.1)]
+    ConflictingBufferAccess {
+        read_only: (usize, u32),
+        read_write: (usize, u32),
+    },
+    #[error("The program uses {count} bind groups, but the device limit is {limit}.")]
+    TooManyBindGroups { count: usize, limit: u32 },
+    #[error("Dispatch of {}x{}x{} workgroups exceeds the device limit of {limit} workgroups per dimension. Split it over more dimensions or increase the `workgroup_size` of the shader.", .workgroups.0, .workgroups.1, .workgroups.2)]
+    TooManyWorkgroups {
+        workgroups: (u32, u32, u32),
+        limit: u32,
+    },
+    #[error(
+        "The workgroup size of entry point `{0}` is unknown, as its shader could not be reflected."
+    )]
+    UnknownWorkgroupSize(String),
+    #[error("The workgroup size of entry point `{entry_point}` is {}x{}x{}, but one invocation per element needs a workgroup size of the form `(x, 1, 1)`.", .workgroup_size.0, .workgroup_size.1, .workgroup_size.2)]
+    MultidimensionalWorkgroupSize {
+        entry_point: String,
+        workgroup_size: (u32, u32, u32),
+    },
+    #[error("Descriptor set {group} has {expected} bindings with a dynamic offset, but {found} offsets were given.")]
+    DynamicOffsetCountMismatch {
+        group: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Dynamic offset {offset} of group {group} binding {binding} is not a multiple of the {alignment} bytes alignment required by the device.")]
+    MisalignedDynamicOffset {
+        group: usize,
+        binding: u32,
+        offset: u32,
+        alignment: u32,
+    },
+    #[error("Descriptor set {0} of the kernel has no resources bound.")]
+    DescriptorSetNotBound(usize),
+    #[error("Kernel has no descriptor set {0}.")]
+    DescriptorSetNotFound(usize),
+    #[error(
+        "Descriptor set {0} does not have the same shape as the one the kernel was created with."
+    )]
+    DescriptorSetShapeMismatch(usize),
+    #[error("Kernel was created with {expected} descriptor sets, but {found} were provided.")]
+    DescriptorSetCountMismatch { expected: usize, found: usize },
+    #[error("The GPU never reported the end of the dispatch, e.g. because the device was lost.")]
+    DispatchNotCompleted,
+    #[error("The statistics of the dispatch could not be read back: {0}")]
+    StatsUnavailable(wgpu::BufferAsyncError),
+}
+
+/// Hint for [`KernelError::BufferTooSmall`] errors.
+fn padding_hint(kind: BindingKind) -> &'static str {
+    match kind {
+        BindingKind::UniformBuffer => "WGSL pads uniform structs to their alignment, up to 16 bytes: add padding fields to the Rust type.",
+        _ => "Check that the Rust type matches the WGSL layout.",
+    }
+}
+
+/// Names the shader labeled `label` for error messages and events.
+fn describe_shader(label: &Option<String>) -> String {
+    match label {
+        Some(label) => format!("the shader `{}`", label),
+        None => "the unnamed shader".to_string(),
+    }
+}
+
+/// Joins the descriptions of `bindings` for error messages.
+fn describe_bindings(bindings: &[BindingInfo]) -> String {
+    if bindings.is_empty() {
+        return "none".to_string();
+    }
+
+    bindings
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Access of the shader to a bound resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindingAccess {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+/// Size of a resource bound in a [`DescriptorSet`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceSize {
+    /// Size in bytes of a buffer.
+    Bytes(u64),
+    /// Dimensions in pixels of an image.
+    Dimensions { width: u32, height: u32 },
+    /// Size of a raw resource or of a binding array of resources of different sizes.
+    Unknown,
+}
+
+impl ResourceSize {
+    fn of_image((width, height): (u32, u32)) -> Self {
+        Self::Dimensions { width, height }
+    }
+
+    /// Size shared by all the elements of a binding array, if any.
+    fn of_array(mut sizes: impl Iterator<Item = Self>) -> Self {
+        let first = sizes.next().unwrap_or(Self::Unknown);
+
+        if sizes.all(|size| size == first) {
+            first
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+impl fmt::Display for ResourceSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bytes(bytes) => write!(f, "{} bytes", bytes),
+            Self::Dimensions { width, height } => write!(f, "{}x{}", width, height),
+            Self::Unknown => write!(f, "unknown size"),
+        }
+    }
+}
+
+/// Rust type of the elements of a bound buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElementType {
+    /// Name of the type, as given by [`std::any::type_name`].
+    pub name: &'static str,
+    /// Size in bytes of the type.
+    pub size: usize,
+}
+
+impl ElementType {
+    pub(crate) fn of<T>() -> Self {
+        Self {
+            name: std::any::type_name::<T>(),
+            size: std::mem::size_of::<T>(),
+        }
+    }
+}
+
+/// Error located in the source of a `WGSL` shader, reported by `naga`.
+///
+/// It is displayed like the `rustc` diagnostics, with the offending line underlined.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderDiagnostic {
+    /// File the error is in, or the name of the shader if it was not loaded from a file.
+    pub file: String,
+    /// 1-based line of the error in `file`.
+    pub line: usize,
+    /// 1-based column of the error in `line`, in characters.
+    pub column: usize,
+    /// Number of characters of `line` the error spans from `column`.
+    pub length: usize,
+    /// Text of the offending line.
+    pub source_line: String,
+    /// Description of the error by `naga`.
+    pub message: String,
+}
+
+impl fmt::Display for ShaderDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        // Tabs are kept so the carets line up with the offending line.
+        let indent = self
+            .source_line
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(
+            f,
+            "{}--> {}:{}:{}",
+            gutter, self.file, self.line, self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{} | {}{}", gutter, indent, "^".repeat(self.length))
+    }
+}
+
+/// Statistics the GPU reports for a dispatch, returned by [`Kernel::enqueue_with_stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DispatchStats {
+    /// Number of times the compute shader was invoked, i.e. the number of workgroups
+    /// times the `workgroup_size` of the shader.
+    pub compute_shader_invocations: u64,
+}
+
+/// Metadata of a resource bound in a [`DescriptorSet`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BindingInfo {
+    /// Binding index in the [`DescriptorSet`].
+    pub binding: u32,
+    pub kind: BindingKind,
+    pub access: BindingAccess,
+    /// Number of elements of a binding array, `None` for single resources.
+    pub count: Option<NonZeroU32>,
+    /// Size of the resource. For binding arrays, the size of each element.
+    pub size: ResourceSize,
+    /// Rust type of the elements of the buffer, `None` for images.
+    /// Raw buffers are made of [`u8`]s.
+    pub element: Option<ElementType>,
+}
+
+impl BindingInfo {
+    fn new(
+        entry: &wgpu::BindGroupLayoutEntry,
+        size: ResourceSize,
+        element: Option<ElementType>,
+    ) -> Self {
+        let access = match entry.ty {
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                ..
+            } => BindingAccess::ReadWrite,
+            wgpu::BindingType::StorageTexture { access, .. } => match access {
+                wgpu::StorageTextureAccess::ReadOnly => BindingAccess::ReadOnly,
+                wgpu::StorageTextureAccess::WriteOnly => BindingAccess::WriteOnly,
+                wgpu::StorageTextureAccess::ReadWrite => BindingAccess::ReadWrite,
+            },
+            _ => BindingAccess::ReadOnly,
+        };
+
+        Self {
+            binding: entry.binding,
+            kind: BindingKind::from_layout(&entry.ty),
+            access,
+            count: entry.count,
+            size,
+            element,
+        }
+    }
+}
+
+impl BindingInfo {
+    /// Element type of a bound buffer.
+    pub(crate) fn buffer_element(&self) -> ElementType {
+        self.element.unwrap_or_else(ElementType::of::<u8>)
+    }
+}
+
+impl fmt::Display for BindingInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "binding {}: ", self.binding)?;
+
+        match (self.kind, self.access) {
+            (BindingKind::StorageTexture, BindingAccess::ReadOnly) => write!(f, "read-only ")?,
+            (BindingKind::StorageTexture, BindingAccess::WriteOnly) => write!(f, "write-only ")?,
+            (BindingKind::StorageTexture, BindingAccess::ReadWrite) => write!(f, "read_write ")?,
+            _ => (),
+        }
+
+        match (self.count, self.kind) {
+            (Some(count), BindingKind::Sampler { .. }) => {
+                write!(f, "array of {} {}s", count, self.kind)
+            }
+            (Some(count), _) => write!(f, "array of {} {}s of {}", count, self.kind, self.size),
+            (None, BindingKind::Sampler { .. }) => write!(f, "{}", self.kind),
+            (None, _) => write!(f, "{} of {}", self.kind, self.size),
+        }
+    }
+}
+
+/// Group of resources with fixed binding indices, bound together as a [`DescriptorSet`].
+///
+/// With the `derive` feature, it can be derived for structs of resource references
+/// whose fields declare their binding index:
+///
+/// ```ignore
+/// #[derive(GpuBindings)]
+/// struct BlurBindings<'a> {
+///     #[binding(0)]
+///     input: &'a GpuBuffer<'a, f32>,
+///     #[binding(1, access = "read_write")]
+///     output: &'a GpuBuffer<'a, f32>,
+///     #[binding(2)]
+///     params: &'a GpuUniformBuffer<'a, Params>,
+/// }
+///
+/// let program = Program::new(&shader, "main").add_descriptor_set(bindings.descriptor_set()?);
+/// ```
+///
+/// Duplicate binding indices are rejected at compile time.
+pub trait GpuBindings<'res> {
+    /// Returns a [`DescriptorSet`] with every resource bound at its binding index.
+    ///
+    /// Fails where the `bind_*_at` methods do, e.g. with [`DescriptorSetError::MissingImageUsage`]
+    /// for an image built without [`wgpu::TextureUsages::STORAGE_BINDING`].
+    fn descriptor_set(&self) -> DescriptorSetResult<DescriptorSet<'res>>;
+}
+
 impl<'res> DescriptorSet<'res> {
     /// Binds a [`GpuUniformBuffer`] as a uniform buffer in the shader.
     ///
+    /// Its size is checked when creating the [`Kernel`] against the device limits and
+    /// the type the shader declares, which WGSL pads to a multiple of 16 bytes for structs
+    /// containing `vec3` or `vec4` members.
+    ///
     /// ### Example WGSL syntax:
     /// ```ignore
     /// struct UniformStruct {
-    ///     a: vec3<u32>;
-    ///     b: vec3<u32>;
-    ///     c: vec3<u32>;
+    ///     a: vec3<u32>,
+    ///     b: vec3<u32>,
+    ///     c: vec3<u32>,
     /// };
     ///
-    /// [[group(0), binding(0)]]
+    /// @group(0) @binding(0)
     /// var<uniform> myUniformBuffer: UniformStruct;
     /// ```
     ///
@@ -30,43 +532,57 @@ impl<'res> DescriptorSet<'res> {
     ///     uvec3 c;
     /// };
     /// ```
-    pub fn bind_uniform_buffer<T>(mut self, uniform_buf: &'res GpuUniformBuffer<T>) -> Self
+    pub fn bind_uniform_buffer<T>(self, uniform_buf: &'res GpuUniformBuffer<T>) -> Self
     where
         T: bytemuck::Pod,
     {
-        let bind_id = self.set_layout.len() as u32;
+        let bind_id = self.next_binding();
 
-        let bind_entry = wgpu::BindGroupLayoutEntry {
-            binding: bind_id,
-            visibility: wgpu::ShaderStages::COMPUTE,
-            ty: wgpu::BindingType::Buffer {
-                has_dynamic_offset: false,
-                min_binding_size: None,
-                ty: wgpu::BufferBindingType::Uniform,
-            },
-            count: None,
-        };
+        self.bind_uniform_buffer_at(bind_id, uniform_buf)
+            .expect("Cannot fail with a free binding.")
+    }
 
-        let bind = wgpu::BindGroupEntry {
-            binding: bind_id,
-            resource: uniform_buf.as_binding_resource(),
+    /// Binds a [`GpuUniformBuffer`] as a uniform buffer in the shader at the `binding` index.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_uniform_buffer_at<T>(
+        self,
+        binding: u32,
+        uniform_buf: &'res GpuUniformBuffer<T>,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        let ty = wgpu::BindingType::Buffer {
+            has_dynamic_offset: false,
+            min_binding_size: None,
+            ty: wgpu::BufferBindingType::Uniform,
         };
 
-        self.set_layout.push(bind_entry);
-        self.binds.push(bind);
-
-        self
+        self.push_binding(
+            binding,
+            ty,
+            uniform_buf.as_binding_resource(),
+            ResourceSize::Bytes(uniform_buf.size()),
+            Some(ElementType::of::<T>()),
+        )
+        .map(|desc| desc.track_buffer(binding, uniform_buf.id, true))
     }
 
     /// Binds a [`GpuBuffer`] as a storage buffer in the shader with a specific `usage`.
     ///
+    /// No minimum binding size is declared, not even the size of a `T`: it is part of the layout,
+    /// which then would not match the sets of buffers of other types, nor the ones made from a
+    /// [`DescriptorLayout`]. The bound size is still checked against the shader when creating
+    /// the [`Kernel`]. Use [`DescriptorSet::bind_buffer_with_min_size`] to declare one.
+    ///
     /// ### Example WGSL syntax:
     /// ```ignore
     /// struct StorageStruct {
-    ///     data: [[stride(4)]] array<i32>;
+    ///     data: array<i32>,
     /// };
     ///
-    /// [[group(0), binding(0)]]
+    /// @group(0) @binding(0)
     /// var<storage, read_write> myStorageBuffer: StorageStruct;
     /// ```
     ///
@@ -76,41 +592,182 @@ impl<'res> DescriptorSet<'res> {
     ///     int data[];
     /// };
     /// ```
-    pub fn bind_buffer<T>(mut self, storage_buf: &'res GpuBuffer<T>, usage: GpuBufferUsage) -> Self
+    pub fn bind_buffer<T>(self, storage_buf: &'res GpuBuffer<T>, usage: GpuBufferUsage) -> Self
     where
         T: bytemuck::Pod,
     {
-        let bind_id = self.set_layout.len() as u32;
+        let bind_id = self.next_binding();
 
-        let bind_entry = wgpu::BindGroupLayoutEntry {
-            binding: bind_id,
-            visibility: wgpu::ShaderStages::COMPUTE,
-            ty: wgpu::BindingType::Buffer {
-                has_dynamic_offset: false,
-                min_binding_size: None,
-                ty: wgpu::BufferBindingType::Storage {
-                    read_only: usage == GpuBufferUsage::ReadOnly,
-                },
-            },
-            count: None,
-        };
+        self.bind_buffer_at(bind_id, storage_buf, usage)
+            .expect("Cannot fail with a free binding.")
+    }
 
-        let bind = wgpu::BindGroupEntry {
-            binding: bind_id,
-            resource: storage_buf.as_binding_resource(),
-        };
+    /// Binds a [`GpuBuffer`] as a storage buffer in the shader with a specific `usage`
+    /// at the `binding` index.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_buffer_at<T>(
+        self,
+        binding: u32,
+        storage_buf: &'res GpuBuffer<T>,
+        usage: GpuBufferUsage,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        self.push_storage_buffer(binding, storage_buf, usage, None)
+    }
 
-        self.set_layout.push(bind_entry);
-        self.binds.push(bind);
+    /// Binds a [`GpuBuffer`] as a storage buffer in the shader with a specific `usage`,
+    /// declaring that the shader requires at least `min_elems` elements.
+    ///
+    /// The size is part of the binding layout, so `wgpu` validates it when creating the bind group
+    /// instead of at dispatch time, and every buffer bound in its place must also be large enough.
+    /// Meant for runtime-sized arrays: the fixed part of the shader types is already
+    /// checked when creating the [`Kernel`].
+    ///
+    /// Fails if `storage_buf` holds less than `min_elems` elements.
+    pub fn bind_buffer_with_min_size<T>(
+        self,
+        storage_buf: &'res GpuBuffer<T>,
+        usage: GpuBufferUsage,
+        min_elems: u64,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        let bind_id = self.next_binding();
 
-        self
+        self.bind_buffer_with_min_size_at(bind_id, storage_buf, usage, min_elems)
+    }
+
+    /// Binds a [`GpuBuffer`] as a storage buffer in the shader with a specific `usage`
+    /// at the `binding` index, declaring that the shader requires at least `min_elems` elements.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`] or if `storage_buf`
+    /// holds less than `min_elems` elements.
+    /// See [`DescriptorSet::bind_buffer_with_min_size`] for more information.
+    pub fn bind_buffer_with_min_size_at<T>(
+        self,
+        binding: u32,
+        storage_buf: &'res GpuBuffer<T>,
+        usage: GpuBufferUsage,
+        min_elems: u64,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        if storage_buf.capacity() < min_elems {
+            return Err(DescriptorSetError::BufferTooSmall {
+                binding,
+                element: ElementType::of::<T>(),
+                capacity: storage_buf.capacity(),
+                required: min_elems,
+            });
+        }
+
+        let min_size = wgpu::BufferSize::new(min_elems * std::mem::size_of::<T>() as u64);
+
+        self.push_storage_buffer(binding, storage_buf, usage, min_size)
+    }
+
+    /// Binds a window of `len` elements of a [`GpuBuffer`] as a storage buffer in the shader
+    /// with a specific `usage`, starting at the dynamic offset given to each dispatch
+    /// by [`Kernel::enqueue_with_offsets`].
+    ///
+    /// Meant for buffers holding the data of many dispatches, each of which reads its own
+    /// window without a bind group of its own. The offsets are in bytes, multiples of the
+    /// `min_storage_buffer_offset_alignment` of the device, and the window must fit in the buffer
+    /// from each of them.
+    ///
+    /// Only the window at offset 0 counts as written by the dispatches for `wgpu`, which clears
+    /// the parts of the buffer that were never written the first time they are read. The writes of
+    /// the dispatches at other offsets are then lost, unless the buffer was created with
+    /// [`GpuBuffer::from_slice`] or written before.
+    ///
+    /// Fails if `len` is 0 or if `storage_buf` holds less than `len` elements.
+    pub fn bind_buffer_dynamic<T>(
+        self,
+        storage_buf: &'res GpuBuffer<T>,
+        usage: GpuBufferUsage,
+        len: u64,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        let bind_id = self.next_binding();
+
+        self.bind_buffer_dynamic_at(bind_id, storage_buf, usage, len)
+    }
+
+    /// Binds a window of `len` elements of a [`GpuBuffer`] as a storage buffer in the shader
+    /// with a specific `usage` at the `binding` index, starting at a dynamic offset.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`], if `len` is 0 or if
+    /// `storage_buf` holds less than `len` elements.
+    /// See [`DescriptorSet::bind_buffer_dynamic`] for more information.
+    pub fn bind_buffer_dynamic_at<T>(
+        self,
+        binding: u32,
+        storage_buf: &'res GpuBuffer<T>,
+        usage: GpuBufferUsage,
+        len: u64,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        let read_only = usage == GpuBufferUsage::ReadOnly;
+        let ty = wgpu::BufferBindingType::Storage { read_only };
+
+        self.push_dynamic_buffer(binding, storage_buf, ty, len)
+            .map(|desc| desc.track_buffer(binding, storage_buf.id, read_only))
+    }
+
+    /// Binds a window of `len` elements of a [`GpuUniformBuffer`] as a uniform buffer in the
+    /// shader, starting at the dynamic offset given to each dispatch by
+    /// [`Kernel::enqueue_with_offsets`], e.g. to select the parameters of each dispatch.
+    ///
+    /// The offsets are multiples of the `min_uniform_buffer_offset_alignment` of the device,
+    /// 256 bytes by default, so the parameters of each dispatch must be padded to it.
+    ///
+    /// Fails if `len` is 0 or if `uniform_buf` holds less than `len` elements.
+    pub fn bind_uniform_buffer_dynamic<T>(
+        self,
+        uniform_buf: &'res GpuUniformBuffer<T>,
+        len: u64,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        let bind_id = self.next_binding();
+
+        self.bind_uniform_buffer_dynamic_at(bind_id, uniform_buf, len)
+    }
+
+    /// Binds a window of `len` elements of a [`GpuUniformBuffer`] as a uniform buffer in the
+    /// shader at the `binding` index, starting at a dynamic offset.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`], if `len` is 0 or if
+    /// `uniform_buf` holds less than `len` elements.
+    /// See [`DescriptorSet::bind_uniform_buffer_dynamic`] for more information.
+    pub fn bind_uniform_buffer_dynamic_at<T>(
+        self,
+        binding: u32,
+        uniform_buf: &'res GpuUniformBuffer<T>,
+        len: u64,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        self.push_dynamic_buffer(binding, uniform_buf, wgpu::BufferBindingType::Uniform, len)
+            .map(|desc| desc.track_buffer(binding, uniform_buf.id, true))
     }
 
     /// Binds a [`GpuImage`] as a storage image in the shader.
     /// This image is write-only.
     /// ### Example WGSL syntax:
     /// ```ignore
-    /// [[group(0), binding(0)]]
+    /// @group(0) @binding(0)
     /// var myStorageImg: texture_storage_2d<rgba8uint, write>;
     /// ```
     ///
@@ -118,36 +775,55 @@ impl<'res> DescriptorSet<'res> {
     /// ```glsl
     /// layout (set=0, binding=0, rgba8uint) uimage2D myStorageImg;
     /// ```
-    pub fn bind_image<P: PixelInfo>(mut self, img: &'res GpuImage<P>) -> Self {
-        let bind_id = self.set_layout.len() as u32;
+    ///
+    /// # Panics
+    /// If the image was built without [`wgpu::TextureUsages::STORAGE_BINDING`].
+    pub fn bind_image<P: PixelInfo>(self, img: &'res GpuImage<P>) -> Self {
+        let bind_id = self.next_binding();
 
-        let bind_entry = wgpu::BindGroupLayoutEntry {
-            binding: bind_id,
-            visibility: wgpu::ShaderStages::COMPUTE,
-            ty: wgpu::BindingType::StorageTexture {
-                access: wgpu::StorageTextureAccess::WriteOnly,
-                format: P::wgpu_format(),
-                view_dimension: wgpu::TextureViewDimension::D2,
-            },
-            count: None,
-        };
+        match self.bind_image_at(bind_id, img) {
+            Ok(desc) => desc,
+            Err(err) => panic!("{}", err),
+        }
+    }
 
-        let bind = wgpu::BindGroupEntry {
-            binding: bind_id,
-            resource: img.as_binding_resource(),
-        };
+    /// Binds a [`GpuImage`] as a storage image in the shader at the `binding` index.
+    /// This image is write-only.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`], or with
+    /// [`DescriptorSetError::MissingImageUsage`] if the image was built without
+    /// [`wgpu::TextureUsages::STORAGE_BINDING`].
+    pub fn bind_image_at<P: PixelInfo>(
+        self,
+        binding: u32,
+        img: &'res GpuImage<P>,
+    ) -> DescriptorSetResult<Self> {
+        if !img.usage().contains(wgpu::TextureUsages::STORAGE_BINDING) {
+            return Err(DescriptorSetError::MissingImageUsage(
+                wgpu::TextureUsages::STORAGE_BINDING,
+            ));
+        }
 
-        self.set_layout.push(bind_entry);
-        self.binds.push(bind);
+        let ty = wgpu::BindingType::StorageTexture {
+            access: wgpu::StorageTextureAccess::WriteOnly,
+            format: P::wgpu_format(),
+            view_dimension: wgpu::TextureViewDimension::D2,
+        };
 
-        self
+        self.push_binding(
+            binding,
+            ty,
+            img.as_binding_resource(),
+            ResourceSize::of_image(img.dimensions()),
+            None,
+        )
     }
 
     /// Binds a [`GpuConstImage`] as a texture in the shader.
     /// This image is read-only.
     /// ### Example WGSL syntax:
     /// ```ignore
-    /// [[group(0), binding(0)]]
+    /// @group(0) @binding(0)
     /// var myTexture: texture_2d<u32>;
     /// ```
     ///
@@ -155,167 +831,2313 @@ impl<'res> DescriptorSet<'res> {
     /// ```glsl
     /// layout (set=0, binding=0) utexture2D myTexture;
     /// ```
-    pub fn bind_const_image<P>(mut self, img: &'res GpuConstImage<P>) -> Self
+    pub fn bind_const_image<P>(self, img: &'res GpuConstImage<P>) -> Self
     where
         P: PixelInfo,
     {
-        let bind_id = self.set_layout.len() as u32;
+        let bind_id = self.next_binding();
 
-        let bind_entry = wgpu::BindGroupLayoutEntry {
-            binding: bind_id,
-            visibility: wgpu::ShaderStages::COMPUTE,
-            ty: wgpu::BindingType::Texture {
-                sample_type: P::wgpu_texture_sample(),
-                multisampled: false,
-                view_dimension: wgpu::TextureViewDimension::D2,
-            },
-            count: None,
-        };
+        self.bind_const_image_at(bind_id, img)
+            .expect("Cannot fail with a free binding.")
+    }
 
-        let bind = wgpu::BindGroupEntry {
-            binding: bind_id,
-            resource: img.as_binding_resource(),
+    /// Binds a [`GpuConstImage`] as a texture in the shader at the `binding` index.
+    /// This image is read-only.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_const_image_at<P>(
+        self,
+        binding: u32,
+        img: &'res GpuConstImage<P>,
+    ) -> DescriptorSetResult<Self>
+    where
+        P: PixelInfo,
+    {
+        let ty = wgpu::BindingType::Texture {
+            sample_type: P::wgpu_texture_sample(),
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
         };
 
-        self.set_layout.push(bind_entry);
-        self.binds.push(bind);
-
-        self
+        self.push_binding(
+            binding,
+            ty,
+            img.as_binding_resource(),
+            ResourceSize::of_image(img.dimensions()),
+            None,
+        )
     }
-}
 
-impl Shader {
-    /// Initialises a [`Shader`] from a SPIR-V file.
-    pub fn from_spirv_file(fw: &Framework, path: impl AsRef<Path>) -> std::io::Result<Self> {
-        let bytes = std::fs::read(&path)?;
-        let shader_name = path.as_ref().to_str();
+    /// Binds a [`GpuSampler`] as a sampler in the shader, of its [`SamplerKind`](crate::primitives::samplers::SamplerKind).
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var mySampler: sampler;
+    /// ```
+    pub fn bind_sampler(self, sampler: &'res GpuSampler) -> Self {
+        let bind_id = self.next_binding();
 
-        Ok(Self::from_spirv_bytes(fw, &bytes, shader_name))
+        self.bind_sampler_at(bind_id, sampler)
+            .expect("Cannot fail with a free binding.")
     }
 
-    /// Initialises a [`Shader`] from SPIR-V bytes with an optional `name`.
-    pub fn from_spirv_bytes(fw: &Framework, bytes: &[u8], name: Option<&str>) -> Self {
-        let source = wgpu::util::make_spirv(bytes);
+    /// Binds a [`GpuSampler`] as a sampler in the shader at the `binding` index.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_sampler_at(
+        self,
+        binding: u32,
+        sampler: &'res GpuSampler,
+    ) -> DescriptorSetResult<Self> {
+        self.push_binding(
+            binding,
+            sampler.binding_type(),
+            sampler.as_binding_resource(),
+            ResourceSize::Unknown,
+            None,
+        )
+    }
 
-        let shader = fw
-            .device
-            .create_shader_module(wgpu::ShaderModuleDescriptor {
-                label: name,
-                source,
-            });
+    /// Binds a [`GpuConstImage`] as a texture and a [`GpuSampler`] to sample it, in this order.
+    ///
+    /// The sample type of the texture is picked to match the [`SamplerKind`](crate::primitives::samplers::SamplerKind)
+    /// of `sampler`, failing if the pixels of `img` cannot be sampled by it
+    /// (e.g. integer pixels with a filtering sampler).
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var myTexture: texture_2d<f32>;
+    /// @group(0) @binding(1)
+    /// var mySampler: sampler;
+    /// ```
+    pub fn bind_sampled_image<P>(
+        self,
+        img: &'res GpuConstImage<P>,
+        sampler: &'res GpuSampler,
+    ) -> DescriptorSetResult<Self>
+    where
+        P: PixelInfo,
+    {
+        let texture_binding = self.next_binding();
+        let sampler_binding = (texture_binding + 1..)
+            .find(|id| self.set_layout.iter().all(|entry| entry.binding != *id))
+            .expect("Cannot run out of binding indices.");
 
-        Self(shader)
+        self.bind_sampled_image_at(texture_binding, sampler_binding, img, sampler)
     }
 
-    /// Initialises a [`Shader`] from a `WGSL` file.
-    pub fn from_wgsl_file(fw: &Framework, path: impl AsRef<Path>) -> std::io::Result<Self> {
-        let source_string = std::fs::read_to_string(&path)?;
-        let shader_name = path.as_ref().to_str();
-
-        Ok(Self(fw.device.create_shader_module(
-            wgpu::ShaderModuleDescriptor {
-                label: shader_name,
-                source: wgpu::ShaderSource::Wgsl(Cow::Owned(source_string)),
+    /// Binds a [`GpuConstImage`] as a texture at the `texture_binding` index and a [`GpuSampler`]
+    /// to sample it at the `sampler_binding` index.
+    ///
+    /// Fails if any binding is already used in this [`DescriptorSet`] or if the pixels
+    /// of `img` cannot be sampled by `sampler`.
+    pub fn bind_sampled_image_at<P>(
+        self,
+        texture_binding: u32,
+        sampler_binding: u32,
+        img: &'res GpuConstImage<P>,
+        sampler: &'res GpuSampler,
+    ) -> DescriptorSetResult<Self>
+    where
+        P: PixelInfo,
+    {
+        let sample_type = sampler.texture_sample(P::wgpu_texture_sample()).ok_or(
+            DescriptorSetError::IncompatibleSampler {
+                sampler: sampler.kind(),
+                sample: P::wgpu_texture_sample(),
             },
-        )))
-    }
-}
+        )?;
 
-impl<'sha, 'res> Program<'sha, 'res> {
-    /// Creates a new [`Program`] using a `shader` and an `entry_point`.
-    pub fn new(shader: &'sha Shader, entry_point: impl Into<String>) -> Self {
-        Self {
-            shader,
-            entry_point: entry_point.into(),
-            descriptors: Vec::new(),
-        }
-    }
+        let ty = wgpu::BindingType::Texture {
+            sample_type,
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
+        };
 
-    /// Adds a [`DescriptorSet`] to this [`Program`] layout.
-    pub fn add_descriptor_set(mut self, desc: DescriptorSet<'res>) -> Self {
-        self.descriptors.push(desc);
-        self
+        self.push_binding(
+            texture_binding,
+            ty,
+            img.as_binding_resource(),
+            ResourceSize::of_image(img.dimensions()),
+            None,
+        )?
+        .bind_sampler_at(sampler_binding, sampler)
     }
-}
-
-impl<'fw> Kernel<'fw> {
-    /// Creates a [`Kernel`] from a [`Program`].
-    pub fn new<'sha, 'res>(fw: &'fw Framework, program: Program<'sha, 'res>) -> Self {
-        let mut layouts = Vec::new();
-        let mut sets = Vec::new();
 
-        // Unwraping of descriptors from program
-        for desc in &program.descriptors {
-            let set_layout = fw
-                .device
-                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                    label: None,
-                    entries: &desc.set_layout,
-                });
+    /// Binds a zeroed placeholder storage buffer of at least `size` bytes with a specific `usage`
+    /// at the `binding` index.
+    ///
+    /// Meant for bindings the shader does not actually use, so the layout stays the same
+    /// across shader variants. Placeholders are pooled on the [`Framework`] and shared by every
+    /// [`DescriptorSet`]: reading them yields zeros unless a shader wrote to a read-write one.
+    /// `size` is rounded up to a multiple of 4 bytes.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_placeholder_buffer(
+        self,
+        fw: &Framework,
+        binding: u32,
+        size: u64,
+        usage: GpuBufferUsage,
+    ) -> DescriptorSetResult<Self> {
+        let size = size.max(1).div_ceil(4) * 4;
+        let read_only = usage == GpuBufferUsage::ReadOnly;
 
-            let set = fw.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: None,
-                layout: &set_layout,
-                entries: &desc.binds,
-            });
+        let ty = wgpu::BindingType::Buffer {
+            has_dynamic_offset: false,
+            min_binding_size: None,
+            ty: wgpu::BufferBindingType::Storage { read_only },
+        };
 
-            layouts.push(set_layout);
-            sets.push(set);
-        }
+        let buffer = fw
+            .placeholders
+            .lock()
+            .unwrap()
+            .buffer(&fw.device, size, read_only);
 
-        // Compute pipeline bindings
-        let group_layouts = layouts.iter().collect::<Vec<_>>();
+        self.push_entry(
+            binding,
+            ty,
+            None,
+            BindResource::PlaceholderBuffer(buffer),
+            ResourceSize::Bytes(size),
+            Some(ElementType::of::<u8>()),
+        )
+    }
 
-        let pipeline_layout = fw
-            .device
-            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: None,
-                bind_group_layouts: &group_layouts,
-                push_constant_ranges: &[],
-            });
+    /// Binds a 1x1 placeholder storage image of `P` pixels at the `binding` index.
+    /// This image is write-only.
+    ///
+    /// Placeholders are pooled on the [`Framework`], see [`DescriptorSet::bind_placeholder_buffer`].
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_placeholder_image<P: PixelInfo>(
+        self,
+        fw: &Framework,
+        binding: u32,
+    ) -> DescriptorSetResult<Self> {
+        let ty = wgpu::BindingType::StorageTexture {
+            access: wgpu::StorageTextureAccess::WriteOnly,
+            format: P::wgpu_format(),
+            view_dimension: wgpu::TextureViewDimension::D2,
+        };
 
-        let pipeline = fw
-            .device
-            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-                label: None,
-                module: &program.shader.0,
-                entry_point: &program.entry_point,
-                layout: Some(&pipeline_layout),
-            });
+        let image = fw
+            .placeholders
+            .lock()
+            .unwrap()
+            .image(&fw.device, P::wgpu_format(), true);
 
-        Self {
-            fw,
-            pipeline,
-            sets,
-            entry_point: program.entry_point,
-        }
+        self.push_entry(
+            binding,
+            ty,
+            None,
+            BindResource::PlaceholderImage(image),
+            ResourceSize::of_image((1, 1)),
+            None,
+        )
     }
 
-    /// Enqueues the execution of this [`Kernel`] onto the GPU.
+    /// Binds a zeroed 1x1 placeholder texture of `P` pixels at the `binding` index.
+    /// This image is read-only.
+    ///
+    /// Placeholders are pooled on the [`Framework`], see [`DescriptorSet::bind_placeholder_buffer`].
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_placeholder_const_image<P: PixelInfo>(
+        self,
+        fw: &Framework,
+        binding: u32,
+    ) -> DescriptorSetResult<Self> {
+        let ty = wgpu::BindingType::Texture {
+            sample_type: P::wgpu_texture_sample(),
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
+        };
+
+        let image = fw
+            .placeholders
+            .lock()
+            .unwrap()
+            .image(&fw.device, P::wgpu_format(), false);
+
+        self.push_entry(
+            binding,
+            ty,
+            None,
+            BindResource::PlaceholderImage(image),
+            ResourceSize::of_image((1, 1)),
+            None,
+        )
+    }
+
+    /// Binds a raw [`wgpu::Buffer`] as a storage buffer in the shader with a specific `usage`.
+    ///
+    /// Only `size` bytes starting at `offset` are bound, or until the end of the buffer if `size` is `None`.
+    ///
+    /// Since `gpgpu` cannot introspect raw resources, it is not validated that `buffer` was created
+    /// with the [`wgpu::BufferUsages::STORAGE`] usage nor that `offset` and `size` fit in it.
+    pub fn bind_raw_buffer(
+        self,
+        buffer: &'res wgpu::Buffer,
+        usage: GpuBufferUsage,
+        offset: wgpu::BufferAddress,
+        size: Option<wgpu::BufferSize>,
+    ) -> Self {
+        let bind_id = self.next_binding();
+
+        self.bind_raw_buffer_at(bind_id, buffer, usage, offset, size)
+            .expect("Cannot fail with a free binding.")
+    }
+
+    /// Binds a raw [`wgpu::Buffer`] as a storage buffer in the shader with a specific `usage`
+    /// at the `binding` index.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    /// See [`DescriptorSet::bind_raw_buffer`] for the meaning of the other parameters.
+    pub fn bind_raw_buffer_at(
+        self,
+        binding: u32,
+        buffer: &'res wgpu::Buffer,
+        usage: GpuBufferUsage,
+        offset: wgpu::BufferAddress,
+        size: Option<wgpu::BufferSize>,
+    ) -> DescriptorSetResult<Self> {
+        let ty = wgpu::BindingType::Buffer {
+            has_dynamic_offset: false,
+            min_binding_size: None,
+            ty: wgpu::BufferBindingType::Storage {
+                read_only: usage == GpuBufferUsage::ReadOnly,
+            },
+        };
+
+        let resource = wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer,
+            offset,
+            size,
+        });
+        let size = size.map_or(ResourceSize::Unknown, |size| {
+            ResourceSize::Bytes(size.get())
+        });
+
+        self.push_binding(binding, ty, resource, size, Some(ElementType::of::<u8>()))
+    }
+
+    /// Binds a raw 2D [`wgpu::TextureView`] as a storage image in the shader with
+    /// a specific `access` and texel `format`.
+    ///
+    /// Since `gpgpu` cannot introspect raw resources, it is not validated that the texture of `view`
+    /// was created with the [`wgpu::TextureUsages::STORAGE_BINDING`] usage nor that `format` is its format.
+    pub fn bind_raw_texture_view(
+        self,
+        view: &'res wgpu::TextureView,
+        access: wgpu::StorageTextureAccess,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        let bind_id = self.next_binding();
+
+        self.bind_raw_texture_view_at(bind_id, view, access, format)
+            .expect("Cannot fail with a free binding.")
+    }
+
+    /// Binds a raw 2D [`wgpu::TextureView`] as a storage image in the shader with
+    /// a specific `access` and texel `format` at the `binding` index.
+    ///
+    /// Fails if `binding` is already used in this [`DescriptorSet`].
+    /// See [`DescriptorSet::bind_raw_texture_view`] for the meaning of the other parameters.
+    pub fn bind_raw_texture_view_at(
+        self,
+        binding: u32,
+        view: &'res wgpu::TextureView,
+        access: wgpu::StorageTextureAccess,
+        format: wgpu::TextureFormat,
+    ) -> DescriptorSetResult<Self> {
+        let ty = wgpu::BindingType::StorageTexture {
+            access,
+            format,
+            view_dimension: wgpu::TextureViewDimension::D2,
+        };
+
+        self.push_binding(
+            binding,
+            ty,
+            wgpu::BindingResource::TextureView(view),
+            ResourceSize::Unknown,
+            None,
+        )
+    }
+
+    /// Binds an array of [`GpuBuffer`]s as a binding array of storage buffers in the shader
+    /// with a specific `usage`.
+    ///
+    /// Requires the [`wgpu::Features::BUFFER_BINDING_ARRAY`] and [`wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY`]
+    /// features, checked when the [`Kernel`] is created. Indexing the array with non-uniform values
+    /// also requires [`wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING`].
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// struct StorageStruct {
+    ///     data: array<f32>,
+    /// };
+    ///
+    /// @group(0) @binding(0)
+    /// var<storage, read> myStorageBuffers: binding_array<StorageStruct, 4>;
+    /// ```
+    pub fn bind_buffer_array<T>(
+        self,
+        storage_bufs: &[&'res GpuBuffer<T>],
+        usage: GpuBufferUsage,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        let bind_id = self.next_binding();
+
+        self.bind_buffer_array_at(bind_id, storage_bufs, usage)
+    }
+
+    /// Binds an array of [`GpuBuffer`]s as a binding array of storage buffers in the shader
+    /// with a specific `usage` at the `binding` index.
+    ///
+    /// Fails if `storage_bufs` is empty or `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_buffer_array_at<T>(
+        self,
+        binding: u32,
+        storage_bufs: &[&'res GpuBuffer<T>],
+        usage: GpuBufferUsage,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        let count = NonZeroU32::new(storage_bufs.len() as u32)
+            .ok_or(DescriptorSetError::EmptyBindingArray)?;
+
+        let ty = wgpu::BindingType::Buffer {
+            has_dynamic_offset: false,
+            min_binding_size: None,
+            ty: wgpu::BufferBindingType::Storage {
+                read_only: usage == GpuBufferUsage::ReadOnly,
+            },
+        };
+
+        let buffers = storage_bufs
+            .iter()
+            .map(|buf| buf.as_gpu_buffer().as_entire_buffer_binding())
+            .collect();
+        let size = ResourceSize::of_array(
+            storage_bufs
+                .iter()
+                .map(|buf| ResourceSize::Bytes(buf.size())),
+        );
+
+        let read_only = usage == GpuBufferUsage::ReadOnly;
+
+        self.push_entry(
+            binding,
+            ty,
+            Some(count),
+            BindResource::BufferArray(buffers),
+            size,
+            Some(ElementType::of::<T>()),
+        )
+        .map(|desc| {
+            storage_bufs.iter().fold(desc, |desc, buf| {
+                desc.track_buffer(binding, buf.id, read_only)
+            })
+        })
+    }
+
+    /// Binds an array of [`GpuConstImage`]s as a binding array of textures in the shader.
+    ///
+    /// Requires the [`wgpu::Features::TEXTURE_BINDING_ARRAY`] feature, checked when the [`Kernel`]
+    /// is created. Indexing the array with non-uniform values also requires
+    /// [`wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING`].
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var myTextures: binding_array<texture_2d<u32>, 4>;
+    /// ```
+    pub fn bind_const_image_array<P>(
+        self,
+        imgs: &[&'res GpuConstImage<P>],
+    ) -> DescriptorSetResult<Self>
+    where
+        P: PixelInfo,
+    {
+        let bind_id = self.next_binding();
+
+        self.bind_const_image_array_at(bind_id, imgs)
+    }
+
+    /// Binds an array of [`GpuConstImage`]s as a binding array of textures in the shader
+    /// at the `binding` index.
+    ///
+    /// Fails if `imgs` is empty or `binding` is already used in this [`DescriptorSet`].
+    pub fn bind_const_image_array_at<P>(
+        self,
+        binding: u32,
+        imgs: &[&'res GpuConstImage<P>],
+    ) -> DescriptorSetResult<Self>
+    where
+        P: PixelInfo,
+    {
+        let count =
+            NonZeroU32::new(imgs.len() as u32).ok_or(DescriptorSetError::EmptyBindingArray)?;
+
+        let ty = wgpu::BindingType::Texture {
+            sample_type: P::wgpu_texture_sample(),
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
+        };
+
+        let views = imgs.iter().map(|img| &img.full_view).collect();
+        let size = ResourceSize::of_array(
+            imgs.iter()
+                .map(|img| ResourceSize::of_image(img.dimensions())),
+        );
+
+        self.push_entry(
+            binding,
+            ty,
+            Some(count),
+            BindResource::TextureViewArray(views),
+            size,
+            None,
+        )
+    }
+
+    /// Appends the bindings of `other` to this [`DescriptorSet`].
+    ///
+    /// The bindings of `other` are renumbered to follow the highest binding index of `self`,
+    /// keeping their relative order and gaps: an `other` binding at index `i` ends at
+    /// `i + 1 + max(self)`, or `i` if `self` is empty. This allows sharing a common
+    /// [`DescriptorSet`] between kernels by cloning it and extending it with each kernel's extras.
+    pub fn extend(mut self, other: DescriptorSet<'res>) -> Self {
+        let offset = self
+            .set_layout
+            .iter()
+            .map(|entry| entry.binding + 1)
+            .max()
+            .unwrap_or(0);
+
+        self.set_layout
+            .extend(other.set_layout.into_iter().map(|mut entry| {
+                entry.binding += offset;
+                entry
+            }));
+        self.bindings
+            .extend(other.bindings.into_iter().map(|mut info| {
+                info.binding += offset;
+                info
+            }));
+        self.binds.extend(
+            other
+                .binds
+                .into_iter()
+                .map(|(binding, resource)| (binding + offset, resource)),
+        );
+        self.buffers
+            .extend(other.buffers.into_iter().map(|mut buffer| {
+                buffer.binding += offset;
+                buffer
+            }));
+
+        self
+    }
+
+    /// Returns the metadata of the resources bound in this [`DescriptorSet`], in the order they were bound.
+    pub fn bindings(&self) -> &[BindingInfo] {
+        &self.bindings
+    }
+
+    /// Converts this [`DescriptorSet`] into an [`OwnedDescriptorSet`], which does not borrow
+    /// the bound resources.
+    ///
+    /// Fails if the device does not support the bindings of this [`DescriptorSet`].
+    pub fn into_owned(self, fw: &Framework) -> DescriptorSetResult<OwnedDescriptorSet> {
+        validate_device_support(fw, &[&self.bindings])?;
+
+        let layout = fw
+            .layout_cache
+            .lock()
+            .unwrap()
+            .bind_group_layout(&fw.device, &self.set_layout);
+
+        let bind_group = fw.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &layout,
+            entries: &self.bind_group_entries(),
+        });
+
+        let mut set_layout = self.set_layout;
+        set_layout.sort_by_key(|entry| entry.binding);
+
+        let mut bindings = self.bindings;
+        bindings.sort_by_key(|info| info.binding);
+
+        Ok(OwnedDescriptorSet {
+            set_layout,
+            bindings,
+            buffers: self.buffers,
+            layout,
+            bind_group: Arc::new(bind_group),
+        })
+    }
+
+    /// Returns the lowest binding index not used yet in this [`DescriptorSet`].
+    pub(crate) fn next_binding(&self) -> u32 {
+        (0..)
+            .find(|id| self.set_layout.iter().all(|entry| entry.binding != *id))
+            .expect("Cannot run out of binding indices.")
+    }
+
+    pub(crate) fn push_storage_buffer<T>(
+        self,
+        binding: u32,
+        storage_buf: &'res GpuBuffer<T>,
+        usage: GpuBufferUsage,
+        min_binding_size: Option<wgpu::BufferSize>,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        let ty = wgpu::BindingType::Buffer {
+            has_dynamic_offset: false,
+            min_binding_size,
+            ty: wgpu::BufferBindingType::Storage {
+                read_only: usage == GpuBufferUsage::ReadOnly,
+            },
+        };
+
+        let read_only = usage == GpuBufferUsage::ReadOnly;
+
+        self.push_binding(
+            binding,
+            ty,
+            storage_buf.as_binding_resource(),
+            ResourceSize::Bytes(storage_buf.size()),
+            Some(ElementType::of::<T>()),
+        )
+        .map(|desc| desc.track_buffer(binding, storage_buf.id, read_only))
+    }
+
+    /// Adds a binding of the first `len` elements of `buf` with a dynamic offset.
+    fn push_dynamic_buffer<'fw, B, T>(
+        self,
+        binding: u32,
+        buf: &'res B,
+        ty: wgpu::BufferBindingType,
+        len: u64,
+    ) -> DescriptorSetResult<Self>
+    where
+        B: BufOps<'fw, T>,
+        T: bytemuck::Pod,
+    {
+        let size = len * std::mem::size_of::<T>() as u64;
+
+        if len == 0 || buf.capacity() < len {
+            return Err(DescriptorSetError::BufferTooSmall {
+                binding,
+                element: ElementType::of::<T>(),
+                capacity: buf.capacity(),
+                required: len.max(1),
+            });
+        }
+
+        let ty = wgpu::BindingType::Buffer {
+            has_dynamic_offset: true,
+            min_binding_size: None,
+            ty,
+        };
+        let resource = wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer: buf.as_gpu_buffer(),
+            offset: 0,
+            size: wgpu::BufferSize::new(size),
+        });
+
+        self.push_binding(
+            binding,
+            ty,
+            resource,
+            ResourceSize::Bytes(size),
+            Some(ElementType::of::<T>()),
+        )
+    }
+
+    /// Records that the buffer `id` is bound at `binding`, to detect conflicting accesses.
+    fn track_buffer(mut self, binding: u32, id: GpuId, read_only: bool) -> Self {
+        self.buffers.push(BoundBuffer {
+            binding,
+            id,
+            read_only,
+        });
+        self
+    }
+
+    /// Adds a binding of type `ty` at the `binding` index, of a resource of `size`
+    /// made of `element`s.
+    pub(crate) fn push_binding(
+        self,
+        binding: u32,
+        ty: wgpu::BindingType,
+        resource: wgpu::BindingResource<'res>,
+        size: ResourceSize,
+        element: Option<ElementType>,
+    ) -> DescriptorSetResult<Self> {
+        self.push_entry(
+            binding,
+            ty,
+            None,
+            BindResource::Single(resource),
+            size,
+            element,
+        )
+    }
+
+    fn push_entry(
+        mut self,
+        binding: u32,
+        ty: wgpu::BindingType,
+        count: Option<NonZeroU32>,
+        resource: BindResource<'res>,
+        size: ResourceSize,
+        element: Option<ElementType>,
+    ) -> DescriptorSetResult<Self> {
+        if self.set_layout.iter().any(|entry| entry.binding == binding) {
+            return Err(DescriptorSetError::DuplicateBinding(binding));
+        }
+
+        let bind_entry = wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty,
+            count,
+        };
+
+        self.bindings
+            .push(BindingInfo::new(&bind_entry, size, element));
+        self.set_layout.push(bind_entry);
+        self.binds.push((binding, resource));
+
+        Ok(self)
+    }
+
+    /// Returns the [`wgpu::BindGroupEntry`]s of this [`DescriptorSet`].
+    pub(crate) fn bind_group_entries(&self) -> Vec<wgpu::BindGroupEntry<'_>> {
+        self.binds
+            .iter()
+            .map(|(binding, resource)| wgpu::BindGroupEntry {
+                binding: *binding,
+                resource: match resource {
+                    BindResource::Single(resource) => resource.clone(),
+                    BindResource::BufferArray(buffers) => {
+                        wgpu::BindingResource::BufferArray(buffers)
+                    }
+                    BindResource::TextureViewArray(views) => {
+                        wgpu::BindingResource::TextureViewArray(views)
+                    }
+                    BindResource::PlaceholderBuffer(buffer) => buffer.as_entire_binding(),
+                    BindResource::PlaceholderImage(image) => {
+                        wgpu::BindingResource::TextureView(&image.view)
+                    }
+                },
+            })
+            .collect()
+    }
+}
+
+/// Resource bound in a [`DescriptorSet`].
+///
+/// Binding arrays own the slice [`wgpu::BindingResource`] borrows, so the
+/// actual [`wgpu::BindGroupEntry`]s are only built when creating the bind group.
+#[derive(Clone)]
+pub(crate) enum BindResource<'res> {
+    Single(wgpu::BindingResource<'res>),
+    BufferArray(Vec<wgpu::BufferBinding<'res>>),
+    TextureViewArray(Vec<&'res wgpu::TextureView>),
+    PlaceholderBuffer(Arc<wgpu::Buffer>),
+    PlaceholderImage(Arc<PlaceholderImage>),
+}
+
+impl GpuId {
+    pub(crate) fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Returns the value of this [`GpuId`].
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for GpuId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Identity of a [`Shader`], used to share the compute pipelines of its entry points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ShaderId(u64);
+
+impl ShaderId {
+    pub(crate) fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Buffer bound in a descriptor set, with the access of the shader to it.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct BoundBuffer {
+    binding: u32,
+    id: GpuId,
+    read_only: bool,
+}
+
+/// Checks that no buffer is bound both read-only and read-write in `sets`.
+fn validate_buffer_aliasing(sets: &[&[BoundBuffer]]) -> KernelResult<()> {
+    let mut accesses = HashMap::new();
+
+    for (group, set) in sets.iter().enumerate() {
+        for buffer in set.iter() {
+            let (first_group, first) = *accesses.entry(buffer.id).or_insert((group, *buffer));
+
+            if first.read_only != buffer.read_only {
+                let (read_only, read_write) = if first.read_only {
+                    ((first_group, first.binding), (group, buffer.binding))
+                } else {
+                    ((group, buffer.binding), (first_group, first.binding))
+                };
+
+                return Err(KernelError::ConflictingBufferAccess {
+                    read_only,
+                    read_write,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the device supports the bindings of `sets`: their binding arrays,
+/// number of bindings per shader stage and buffer sizes.
+pub(crate) fn validate_device_support(
+    fw: &Framework,
+    sets: &[&[BindingInfo]],
+) -> DescriptorSetResult<()> {
+    let limits = fw.device.limits();
+    let features = fw.device.features();
+
+    let mut required = wgpu::Features::empty();
+    let (mut uniforms, mut storages, mut textures, mut storage_textures) = (0, 0, 0, 0);
+
+    for info in sets.iter().flat_map(|set| set.iter()) {
+        let count = info.count.map_or(1, NonZeroU32::get);
+        let is_array = info.count.is_some();
+
+        match info.kind {
+            BindingKind::UniformBuffer => {
+                uniforms += count;
+                if is_array {
+                    required |= wgpu::Features::BUFFER_BINDING_ARRAY;
+                }
+            }
+            BindingKind::StorageBuffer { .. } => {
+                storages += count;
+                if is_array {
+                    required |= wgpu::Features::BUFFER_BINDING_ARRAY
+                        | wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY;
+                }
+            }
+            BindingKind::Texture { .. } => {
+                textures += count;
+                if is_array {
+                    required |= wgpu::Features::TEXTURE_BINDING_ARRAY;
+                }
+            }
+            BindingKind::StorageTexture => {
+                storage_textures += count;
+                if is_array {
+                    required |= wgpu::Features::TEXTURE_BINDING_ARRAY
+                        | wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY;
+                }
+            }
+            BindingKind::Sampler { .. } => {
+                if is_array {
+                    required |= wgpu::Features::TEXTURE_BINDING_ARRAY;
+                }
+            }
+        }
+
+        validate_buffer_size(&limits, info)?;
+    }
+
+    if !features.contains(required) {
+        return Err(DescriptorSetError::MissingFeatures(required - features));
+    }
+
+    for (kind, count, limit) in [
+        (
+            "uniform buffer",
+            uniforms,
+            limits.max_uniform_buffers_per_shader_stage,
+        ),
+        (
+            "storage buffer",
+            storages,
+            limits.max_storage_buffers_per_shader_stage,
+        ),
+        (
+            "texture",
+            textures,
+            limits.max_sampled_textures_per_shader_stage,
+        ),
+        (
+            "storage texture",
+            storage_textures,
+            limits.max_storage_textures_per_shader_stage,
+        ),
+    ] {
+        if count > limit {
+            return Err(DescriptorSetError::TooManyBindings { kind, count, limit });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the size of a bound buffer against the device `limits`.
+fn validate_buffer_size(limits: &wgpu::Limits, info: &BindingInfo) -> DescriptorSetResult<()> {
+    let size = match info.size {
+        ResourceSize::Bytes(size) => size,
+        _ => return Ok(()),
+    };
+
+    let limit = match info.kind {
+        BindingKind::UniformBuffer => limits.max_uniform_buffer_binding_size,
+        BindingKind::StorageBuffer { .. } => limits.max_storage_buffer_binding_size,
+        _ => return Ok(()),
+    };
+
+    if size > limit as u64 {
+        return Err(DescriptorSetError::BufferTooLarge {
+            binding: info.binding,
+            kind: info.kind,
+            element: info.buffer_element(),
+            size,
+            limit,
+        });
+    }
+
+    Ok(())
+}
+
+impl fmt::Debug for DescriptorSet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut bindings = self.bindings.iter().collect::<Vec<_>>();
+        bindings.sort_by_key(|info| info.binding);
+
+        f.debug_list().entries(bindings).finish()
+    }
+}
+
+impl OwnedDescriptorSet {
+    /// Returns the metadata of the resources bound in this [`OwnedDescriptorSet`], in binding order.
+    pub fn bindings(&self) -> &[BindingInfo] {
+        &self.bindings
+    }
+}
+
+impl fmt::Debug for OwnedDescriptorSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.bindings.iter()).finish()
+    }
+}
+
+impl<'res> AnyDescriptorSet<'res> {
+    pub(crate) fn set_layout(&self) -> &[wgpu::BindGroupLayoutEntry] {
+        match self {
+            Self::Borrowed(desc) => &desc.set_layout,
+            Self::Owned(desc) => &desc.set_layout,
+            Self::Unbound(layout) => layout.set_layout(),
+        }
+    }
+
+    pub(crate) fn bindings(&self) -> &[BindingInfo] {
+        match self {
+            Self::Borrowed(desc) => desc.bindings(),
+            Self::Owned(desc) => desc.bindings(),
+            Self::Unbound(layout) => layout.bindings(),
+        }
+    }
+
+    pub(crate) fn buffers(&self) -> &[BoundBuffer] {
+        match self {
+            Self::Borrowed(desc) => &desc.buffers,
+            Self::Owned(desc) => &desc.buffers,
+            Self::Unbound(_) => &[],
+        }
+    }
+}
+
+impl<'res> From<DescriptorSet<'res>> for AnyDescriptorSet<'res> {
+    fn from(desc: DescriptorSet<'res>) -> Self {
+        Self::Borrowed(desc)
+    }
+}
+
+impl<'res> From<OwnedDescriptorSet> for AnyDescriptorSet<'res> {
+    fn from(desc: OwnedDescriptorSet) -> Self {
+        Self::Owned(desc)
+    }
+}
+
+/// First word of every SPIR-V binary.
+pub(crate) const SPIRV_MAGIC_NUMBER: u32 = 0x0723_0203;
+
+/// Reads SPIR-V `bytes` as words in native byte order.
+pub(crate) fn spirv_words(bytes: &[u8]) -> ShaderResult<Vec<u32>> {
+    if !bytes.len().is_multiple_of(4) {
+        return Err(ShaderError::MisalignedSpirv(bytes.len()));
+    }
+
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|word| u32::from_ne_bytes([word[0], word[1], word[2], word[3]]))
+        .collect())
+}
+
+impl Shader {
+    /// Describes the compute entry points of this [`Shader`] and the resources they use.
+    ///
+    /// Returns `None` if the shader could not be reflected.
+    pub fn info(&self) -> Option<ShaderInfo> {
+        self.reflection.as_ref().map(ShaderReflection::info)
+    }
+
+    /// Returns the label of this [`Shader`], the `name` or path it was created with,
+    /// also shown by GPU debuggers and the profiler.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Returns the names of the compute entry points of this [`Shader`], in the order
+    /// it declares them, or `None` if the shader could not be reflected.
+    ///
+    /// Those of a SPIR-V binary can be listed before loading it
+    /// with [`ShaderInfo::from_spirv`].
+    pub fn entry_points(&self) -> Option<Vec<String>> {
+        self.reflection
+            .as_ref()
+            .map(ShaderReflection::compute_entry_points)
+    }
+
+    /// Initialises a [`Shader`] from a SPIR-V file.
+    pub fn from_spirv_file(fw: &Framework, path: impl AsRef<Path>) -> ShaderResult<Self> {
+        let bytes = std::fs::read(&path)?;
+        let shader_name = path.as_ref().to_str();
+
+        Self::from_spirv_bytes(fw, &bytes, shader_name)
+    }
+
+    /// Initialises a [`Shader`] from SPIR-V bytes with an optional `name`,
+    /// e.g. embedded with [`include_bytes!`].
+    ///
+    /// The words are read in native byte order. Fails if the length of `bytes`
+    /// is not a multiple of 4, if they do not start with the SPIR-V magic number,
+    /// or if the shader is not valid.
+    pub fn from_spirv_bytes(
+        fw: &Framework,
+        bytes: &[u8],
+        name: Option<&str>,
+    ) -> ShaderResult<Self> {
+        Self::from_spirv_words(fw, &spirv_words(bytes)?, name)
+    }
+
+    /// Initialises a [`Shader`] from SPIR-V words with an optional `name`.
+    ///
+    /// Fails if the words do not start with the SPIR-V magic number or if the shader is not valid.
+    pub fn from_spirv_words(
+        fw: &Framework,
+        words: &[u32],
+        name: Option<&str>,
+    ) -> ShaderResult<Self> {
+        let reflection = ShaderReflection::from_spirv(words)?;
+
+        Self::create(
+            fw,
+            wgpu::ShaderSource::SpirV(Cow::Borrowed(words)),
+            name,
+            reflection,
+        )
+    }
+
+    /// Initialises a [`Shader`] from a `WGSL` file.
+    pub fn from_wgsl_file(fw: &Framework, path: impl AsRef<Path>) -> ShaderResult<Self> {
+        let source_string = std::fs::read_to_string(&path)?;
+        let shader_name = path.as_ref().to_str();
+
+        Self::from_wgsl_source(fw, &source_string, shader_name)
+    }
+
+    /// Initialises a [`Shader`] from a `WGSL` source with an optional `name`,
+    /// e.g. embedded with [`include_str!`] or generated at runtime.
+    ///
+    /// Fails with a [`ShaderError::Compilation`] pointing at the error if the shader is not valid,
+    /// or with a [`ShaderError::LegacyWgsl`] if it uses the legacy `[[attribute]]` syntax, unless
+    /// [`Framework::set_migrate_legacy_wgsl`] is enabled.
+    pub fn from_wgsl_source(
+        fw: &Framework,
+        source: &str,
+        name: Option<&str>,
+    ) -> ShaderResult<Self> {
+        Self::from_wgsl_mapped(fw, source, name, &SourceMap::new(name.unwrap_or("wgsl")))
+    }
+
+    /// Initialises a [`Shader`] from a `WGSL` source embedded with [`include_wgsl!`](crate::include_wgsl).
+    #[cfg(feature = "include-wgsl")]
+    pub fn from_embedded(fw: &Framework, shader: crate::EmbeddedShader) -> ShaderResult<Self> {
+        Self::from_wgsl_source(fw, shader.source, Some(shader.name))
+    }
+
+    /// Initialises a [`Shader`] from a preprocessed `WGSL` source,
+    /// reporting errors against the files `map` gives.
+    fn from_wgsl_mapped(
+        fw: &Framework,
+        source: &str,
+        name: Option<&str>,
+        map: &SourceMap,
+    ) -> ShaderResult<Self> {
+        let migrated = check_legacy_wgsl(source, name.unwrap_or("wgsl"), fw.migrate_legacy_wgsl())?;
+        let source = migrated.as_deref().unwrap_or(source);
+
+        let reflection = ShaderReflection::from_wgsl(source, map)?;
+
+        Self::create(
+            fw,
+            wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
+            name,
+            reflection,
+        )
+    }
+
+    /// Creates the module of an already reflected shader, reporting what `wgpu`
+    /// still rejects, e.g. a feature the device does not support, as an error.
+    fn create(
+        fw: &Framework,
+        source: wgpu::ShaderSource,
+        name: Option<&str>,
+        reflection: ShaderReflection,
+    ) -> ShaderResult<Self> {
+        let (module, err) = fw.capture_validation(|| {
+            fw.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: name,
+                    source,
+                })
+        });
+
+        if let Some(err) = err {
+            return Err(ShaderError::InvalidShader(err.to_string()));
+        }
+
+        Ok(Self {
+            id: ShaderId::new(),
+            module,
+            label: name.map(str::to_string),
+            reflection: Some(reflection),
+        })
+    }
+}
+
+impl<'sha, 'res> Program<'sha, 'res> {
+    /// Creates a new [`Program`] using a `shader` and an `entry_point`.
+    pub fn new(shader: &'sha Shader, entry_point: impl Into<String>) -> Self {
+        Self {
+            shader,
+            entry_point: entry_point.into(),
+            label: None,
+            descriptors: Vec::new(),
+            allow_aliasing: false,
+        }
+    }
+
+    /// Sets the label of the [`Kernel`] created from this [`Program`], instead of its entry point.
+    ///
+    /// It names the dispatches of the [`Kernel`] in GPU debuggers (see [`Framework::set_debug_markers`]).
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Adds a [`DescriptorSet`] or an [`OwnedDescriptorSet`] to this [`Program`] layout,
+    /// in the bind group following the last one.
+    pub fn add_descriptor_set(mut self, desc: impl Into<AnyDescriptorSet<'res>>) -> Self {
+        self.descriptors.push(desc.into());
+        self
+    }
+
+    /// Sets the [`DescriptorSet`] or [`OwnedDescriptorSet`] of the bind `group` of this [`Program`] layout,
+    /// replacing the one already there if any.
+    ///
+    /// Skipped groups are filled with empty [`DescriptorSet`]s, so a shader can use
+    /// `@group(2)` without declaring groups 0 and 1. The same indices are used by
+    /// [`Kernel::set_descriptor_set`] and [`Kernel::enqueue_with_sets`].
+    pub fn add_descriptor_set_at(
+        mut self,
+        group: u32,
+        desc: impl Into<AnyDescriptorSet<'res>>,
+    ) -> Self {
+        let group = group as usize;
+
+        if group >= self.descriptors.len() {
+            self.descriptors.resize_with(group + 1, || {
+                AnyDescriptorSet::Borrowed(DescriptorSet::default())
+            });
+        }
+        self.descriptors[group] = desc.into();
+
+        self
+    }
+
+    /// Allows binding the same buffer both read-only and read-write in this [`Program`].
+    ///
+    /// By default [`Kernel::new`] rejects it, since the shader reading a buffer it also writes
+    /// through another binding is usually a bug.
+    ///
+    /// This only skips the check of `gpgpu`: `wgpu` validates the buffer usages of each dispatch too
+    /// and rejects read-only and read-write storage usages of the same buffer.
+    pub fn allow_aliasing(mut self) -> Self {
+        self.allow_aliasing = true;
+        self
+    }
+
+    /// Adds the shape of a descriptor set to this [`Program`] layout, without any resource bound.
+    ///
+    /// The resources must be provided to the [`Kernel`] with [`Kernel::set_descriptor_set`]
+    /// or [`Kernel::enqueue_with_sets`] before dispatching it.
+    pub fn add_descriptor_layout(mut self, layout: &DescriptorLayout) -> Self {
+        self.descriptors
+            .push(AnyDescriptorSet::Unbound(layout.clone()));
+        self
+    }
+}
+
+/// The layouts and bind groups of a [`Kernel`], see [`Kernel::build_layouts`].
+struct KernelLayouts {
+    layouts: Vec<(Vec<wgpu::BindGroupLayoutEntry>, Arc<wgpu::BindGroupLayout>)>,
+    sets: Vec<Option<Arc<wgpu::BindGroup>>>,
+    slots: Vec<Option<DescriptorLayout>>,
+    pipeline_layout: Arc<wgpu::PipelineLayout>,
+}
+
+impl<'fw> Kernel<'fw> {
+    /// Creates a [`Kernel`] from a [`Program`].
+    ///
+    /// The [`DescriptorSet`]s of the `program` are checked against the bindings
+    /// its entry point uses, so a missing binding or a binding of the wrong kind
+    /// (e.g. a uniform buffer where the shader declares a storage buffer) is reported here
+    /// instead of by `wgpu` at dispatch time.
+    ///
+    /// Fails with [`KernelError::EntryPointNotFound`] if the shader has no compute entry point
+    /// named like the one of the `program`, and with [`KernelError::InvalidPipeline`]
+    /// if `wgpu` rejects the compute pipeline.
+    pub fn new<'sha, 'res>(fw: &'fw Framework, program: Program<'sha, 'res>) -> KernelResult<Self> {
+        futures::executor::block_on(Self::new_async(fw, program))
+    }
+
+    /// Creates a [`Kernel`] from a [`Program`] like [`Kernel::new`], as a [`Send`] future.
+    ///
+    /// `wgpu` compiles the compute pipeline when the future is first polled, on the polling thread.
+    /// The futures can be polled from different threads, e.g. with a multithreaded executor,
+    /// but `wgpu` reports errors per device: the creations of their pipelines are serialized
+    /// so each of them only reports its own errors.
+    /// Concurrent creations of the same pipeline compile it only once.
+    pub async fn new_async<'sha, 'res>(
+        fw: &'fw Framework,
+        program: Program<'sha, 'res>,
+    ) -> KernelResult<Self> {
+        let max_bind_groups = fw.device.limits().max_bind_groups;
+        if program.descriptors.len() > max_bind_groups as usize {
+            return Err(KernelError::TooManyBindGroups {
+                count: program.descriptors.len(),
+                limit: max_bind_groups,
+            });
+        }
+
+        if let Some(reflection) = &program.shader.reflection {
+            let sets = program
+                .descriptors
+                .iter()
+                .map(|desc| desc.bindings())
+                .collect::<Vec<_>>();
+
+            reflection.validate_bindings(
+                program.shader.label(),
+                &program.entry_point,
+                &sets,
+                fw.paranoid_checks(),
+            )?;
+        }
+
+        let sets = program
+            .descriptors
+            .iter()
+            .map(|desc| desc.bindings())
+            .collect::<Vec<_>>();
+        validate_device_support(fw, &sets)?;
+
+        if !program.allow_aliasing {
+            let sets = program
+                .descriptors
+                .iter()
+                .map(|desc| desc.buffers())
+                .collect::<Vec<_>>();
+            validate_buffer_aliasing(&sets)?;
+        }
+
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        match Self::build(fw, program) {
+            Err(err) => {
+                event!(warn, "{}", err);
+                Err(err)
+            }
+            Ok(kernel) => {
+                event!(
+                    debug,
+                    "built kernel `{}` from the entry point `{}` of {} in {:?}",
+                    kernel.label,
+                    kernel.entry_point,
+                    describe_shader(&kernel.shader),
+                    start.elapsed()
+                );
+                Ok(kernel)
+            }
+        }
+    }
+
+    /// Creates a [`Kernel`] from a [`Program`] without checking its bindings against the shader.
+    ///
+    /// # Panics
+    /// If `wgpu` rejects the compute pipeline, e.g. because the entry point does not exist.
+    pub fn new_unchecked<'sha, 'res>(fw: &'fw Framework, program: Program<'sha, 'res>) -> Self {
+        Self::build(fw, program).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a [`Kernel`] from a [`Program`] without checking its bindings against the shader,
+    /// failing with [`KernelError::InvalidPipeline`] for the errors `wgpu` reports.
+    fn build<'sha, 'res>(fw: &'fw Framework, program: Program<'sha, 'res>) -> KernelResult<Self> {
+        let (
+            KernelLayouts {
+                layouts,
+                sets,
+                slots,
+                pipeline_layout,
+            },
+            err,
+        ) = fw.capture_validation(|| Self::build_layouts(fw, &program));
+
+        let shader = program.shader;
+        let entry_point = program.entry_point;
+        let label = program.label.unwrap_or_else(|| entry_point.clone());
+        let invalid_pipeline = |err: wgpu::Error| KernelError::InvalidPipeline {
+            kernel: label.clone(),
+            entry_point: entry_point.clone(),
+            shader: shader.label.clone(),
+            reason: err.to_string(),
+        };
+
+        if let Some(err) = err {
+            return Err(invalid_pipeline(err));
+        }
+
+        let group_entries = layouts
+            .iter()
+            .map(|(entries, _)| entries.as_slice())
+            .collect::<Vec<_>>();
+        let pipeline = fw
+            .pipeline_cache
+            .pipeline(fw, shader, &entry_point, &group_entries, &pipeline_layout)
+            .map_err(invalid_pipeline)?;
+
+        let workgroup_size = shader
+            .reflection
+            .as_ref()
+            .and_then(|reflection| reflection.workgroup_size(&entry_point));
+
+        Ok(Self {
+            fw,
+            id: GpuId::new(),
+            pipeline,
+            layouts,
+            pipeline_layout,
+            sets,
+            label,
+            entry_point,
+            shader: shader.label.clone(),
+            workgroup_size,
+            slots,
+        })
+    }
+
+    /// Creates (or reuses from the cache) the bind group layouts, bind groups
+    /// and pipeline layout of a [`Program`].
+    fn build_layouts(fw: &Framework, program: &Program) -> KernelLayouts {
+        let mut cache = fw.layout_cache.lock().unwrap();
+
+        let mut layouts = Vec::new();
+        let mut sets = Vec::new();
+        let mut slots = Vec::new();
+
+        // Unwraping of descriptors from program
+        for desc in &program.descriptors {
+            let (set_layout, set) = match desc {
+                AnyDescriptorSet::Borrowed(desc) => {
+                    let set_layout = cache.bind_group_layout(&fw.device, &desc.set_layout);
+
+                    let set = fw.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                        label: None,
+                        layout: &set_layout,
+                        entries: &desc.bind_group_entries(),
+                    });
+
+                    (set_layout, Some(Arc::new(set)))
+                }
+                AnyDescriptorSet::Owned(desc) => {
+                    (Arc::clone(&desc.layout), Some(Arc::clone(&desc.bind_group)))
+                }
+                AnyDescriptorSet::Unbound(layout) => (
+                    cache.bind_group_layout(&fw.device, layout.set_layout()),
+                    None,
+                ),
+            };
+
+            let mut entries = desc.set_layout().to_vec();
+            entries.sort_by_key(|entry| entry.binding);
+
+            layouts.push((entries, set_layout));
+            sets.push(set);
+            slots.push(match desc {
+                AnyDescriptorSet::Unbound(layout) => Some(layout.clone()),
+                _ => None,
+            });
+        }
+
+        // Compute pipeline bindings
+        let group_entries = program
+            .descriptors
+            .iter()
+            .map(|desc| desc.set_layout())
+            .collect::<Vec<_>>();
+        let group_layouts = layouts
+            .iter()
+            .map(|(_, layout)| layout.as_ref())
+            .collect::<Vec<_>>();
+
+        let pipeline_layout = cache.pipeline_layout(&fw.device, &group_entries, &group_layouts);
+
+        KernelLayouts {
+            layouts,
+            sets,
+            slots,
+            pipeline_layout,
+        }
+    }
+
+    /// Creates a [`Kernel`] for each of the `entry_points` of the shader of a [`Program`],
+    /// instead of its own entry point.
+    ///
+    /// The kernels share the bind group layouts, bind groups and pipeline layout of `program`,
+    /// only creating a compute pipeline per entry point. They can be enqueued independently,
+    /// and their descriptor sets can be replaced independently too.
+    /// Each of them is labeled with its entry point.
+    ///
+    /// Fails if any of the entry points is not a compute entry point of the shader or does not
+    /// accept the [`DescriptorSet`]s of `program`, as well as for the reasons of [`Kernel::new`].
+    pub fn new_entry_points<'sha, 'res>(
+        fw: &'fw Framework,
+        mut program: Program<'sha, 'res>,
+        entry_points: &[&str],
+    ) -> KernelResult<Vec<Self>> {
+        let (first, rest) = match entry_points.split_first() {
+            Some(entry_points) => entry_points,
+            None => return Ok(Vec::new()),
+        };
+
+        let shader = program.shader;
+
+        if let Some(reflection) = &shader.reflection {
+            let sets = program
+                .descriptors
+                .iter()
+                .map(|desc| desc.bindings())
+                .collect::<Vec<_>>();
+
+            for entry_point in rest {
+                reflection.validate_bindings(
+                    shader.label(),
+                    entry_point,
+                    &sets,
+                    fw.paranoid_checks(),
+                )?;
+            }
+        }
+
+        program.entry_point = first.to_string();
+        program.label = None;
+        let kernel = Self::new(fw, program)?;
+
+        let mut kernels = vec![kernel];
+
+        for entry_point in rest {
+            let sibling = kernels[0].with_entry_point(shader, entry_point)?;
+            kernels.push(sibling);
+        }
+
+        Ok(kernels)
+    }
+
+    /// Creates a [`Kernel`] for every compute entry point of the shader of a [`Program`]
+    /// like [`Kernel::new_entry_points`], in the order the shader declares them.
+    ///
+    /// Fails with [`KernelError::UnknownEntryPoints`] if the shader could not be reflected.
+    pub fn new_all<'sha, 'res>(
+        fw: &'fw Framework,
+        program: Program<'sha, 'res>,
+    ) -> KernelResult<Vec<Self>> {
+        let entry_points = program
+            .shader
+            .reflection
+            .as_ref()
+            .ok_or(KernelError::UnknownEntryPoints)?
+            .compute_entry_points();
+        let entry_points = entry_points.iter().map(String::as_str).collect::<Vec<_>>();
+
+        Self::new_entry_points(fw, program, &entry_points)
+    }
+
+    /// Returns a copy of this [`Kernel`] running the `entry_point` of `shader`,
+    /// which must be the shader this [`Kernel`] was created from.
+    fn with_entry_point(&self, shader: &Shader, entry_point: &str) -> KernelResult<Self> {
+        let pipeline = self.create_pipeline(shader, entry_point).map_err(|err| {
+            KernelError::InvalidPipeline {
+                kernel: entry_point.to_string(),
+                entry_point: entry_point.to_string(),
+                shader: shader.label.clone(),
+                reason: err.to_string(),
+            }
+        })?;
+
+        Ok(Self {
+            fw: self.fw,
+            id: GpuId::new(),
+            pipeline,
+            layouts: self.layouts.clone(),
+            pipeline_layout: Arc::clone(&self.pipeline_layout),
+            sets: self.sets.clone(),
+            entry_point: entry_point.to_string(),
+            label: entry_point.to_string(),
+            shader: shader.label.clone(),
+            workgroup_size: shader
+                .reflection
+                .as_ref()
+                .and_then(|reflection| reflection.workgroup_size(entry_point)),
+            slots: self.slots.clone(),
+        })
+    }
+
+    /// Rebuilds the compute pipeline of this [`Kernel`] from the entry point of the same name
+    /// in `shader`, e.g. after editing its source, keeping its layouts and [`DescriptorSet`]s.
+    ///
+    /// The bindings the entry point uses are checked against the layouts of this [`Kernel`],
+    /// without the sizes of the buffers bound to them.
+    /// On failure the previous pipeline is kept, so the [`Kernel`] can still be enqueued.
+    pub fn reload_shader(&mut self, shader: &Shader) -> KernelResult<()> {
+        if let Some(reflection) = &shader.reflection {
+            let bindings = self
+                .layouts
+                .iter()
+                .map(|(entries, _)| {
+                    entries
+                        .iter()
+                        .map(|entry| BindingInfo::new(entry, ResourceSize::Unknown, None))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let sets = bindings.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+            reflection.validate_bindings(
+                shader.label(),
+                &self.entry_point,
+                &sets,
+                self.fw.paranoid_checks(),
+            )?;
+        }
+
+        let pipeline = self
+            .create_pipeline(shader, &self.entry_point)
+            .map_err(|err| KernelError::InvalidPipeline {
+                kernel: self.label.clone(),
+                entry_point: self.entry_point.clone(),
+                shader: shader.label.clone(),
+                reason: err.to_string(),
+            })?;
+
+        self.pipeline = pipeline;
+        self.shader = shader.label.clone();
+        self.workgroup_size = shader
+            .reflection
+            .as_ref()
+            .and_then(|reflection| reflection.workgroup_size(&self.entry_point));
+
+        Ok(())
+    }
+
+    /// Creates (or reuses from the cache) the compute pipeline of the `entry_point` of `shader`
+    /// with the layout of this [`Kernel`], failing with the error `wgpu` reports.
+    fn create_pipeline(
+        &self,
+        shader: &Shader,
+        entry_point: &str,
+    ) -> Result<Arc<wgpu::ComputePipeline>, wgpu::Error> {
+        let group_entries = self
+            .layouts
+            .iter()
+            .map(|(entries, _)| entries.as_slice())
+            .collect::<Vec<_>>();
+
+        self.fw.pipeline_cache.pipeline(
+            self.fw,
+            shader,
+            entry_point,
+            &group_entries,
+            &self.pipeline_layout,
+        )
+    }
+
+    /// Replaces the [`DescriptorSet`] of the bind group `index` with `desc`, keeping the compute pipeline.
+    ///
+    /// Fails if `desc` does not have the same shape (binding indices and kinds)
+    /// as the [`DescriptorSet`] this [`Kernel`] was created with.
+    pub fn set_descriptor_set<'res>(
+        &mut self,
+        index: usize,
+        desc: impl Into<AnyDescriptorSet<'res>>,
+    ) -> KernelResult<()> {
+        let set = match desc.into() {
+            AnyDescriptorSet::Borrowed(desc) => Some(self.create_bind_group(index, &desc)?),
+            AnyDescriptorSet::Owned(desc) => {
+                self.check_shape(index, &desc.set_layout)?;
+                Some(desc.bind_group)
+            }
+            AnyDescriptorSet::Unbound(layout) => {
+                self.check_shape(index, layout.set_layout())?;
+                None
+            }
+        };
+        self.sets[index] = set;
+
+        Ok(())
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU.
     ///
     /// [`Kernel`] will dispatch `x`, `y` and `z` workgroups per dimension.
-    pub fn enqueue(&self, x: u32, y: u32, z: u32) {
+    ///
+    /// Fails with [`KernelError::TooManyWorkgroups`] if any of them exceeds [`Kernel::max_dispatch`],
+    /// and with [`KernelError::DescriptorSetNotBound`] if a descriptor set was only given
+    /// as a [`DescriptorLayout`] and no resources were bound to it with [`Kernel::set_descriptor_set`].
+    /// Use [`Kernel::enqueue_with_sets`] to provide them on each dispatch instead.
+    pub fn enqueue(&self, x: u32, y: u32, z: u32) -> KernelResult<()> {
+        self.enqueue_labeled(&self.label, x, y, z)
+    }
+
+    /// Dispatches this [`Kernel`] with no workgroups and waits for the GPU, so that the driver
+    /// finishes preparing its pipeline before the first real dispatch instead of stalling it.
+    ///
+    /// Nothing is executed, but the descriptor sets must be bound like for [`Kernel::enqueue`].
+    /// Use [`Framework::warm_up_all`] to warm up several kernels at once.
+    pub fn warm_up(&self) -> KernelResult<()> {
+        self.fw.warm_up_all(&[self])
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU like [`Kernel::enqueue`],
+    /// naming the dispatch `label` in GPU debuggers instead of the label of this [`Kernel`].
+    pub fn enqueue_labeled(&self, label: &str, x: u32, y: u32, z: u32) -> KernelResult<()> {
+        let sets = self
+            .sets
+            .iter()
+            .enumerate()
+            .map(|(index, set)| {
+                set.as_deref()
+                    .ok_or(KernelError::DescriptorSetNotBound(index))
+            })
+            .collect::<KernelResult<Vec<_>>>()?;
+
+        self.record_dispatch(label, sets.into_iter(), &[], x, y, z)
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU like [`Kernel::enqueue`],
+    /// and waits for it to return the [`DispatchStats`] the GPU reports, e.g. to check
+    /// that the dispatch covers exactly the intended domain.
+    ///
+    /// Fails with [`KernelError::MissingFeatures`] if the device does not support
+    /// [`wgpu::Features::PIPELINE_STATISTICS_QUERY`].
+    pub fn enqueue_with_stats(&self, x: u32, y: u32, z: u32) -> KernelResult<DispatchStats> {
+        futures::executor::block_on(self.enqueue_with_stats_async(x, y, z))
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU like [`Kernel::enqueue_with_stats`],
+    /// returning a future that resolves to its [`DispatchStats`] once the GPU has finished it.
+    ///
+    /// The work is submitted when this method is called, and the future resolves
+    /// from the polling thread of the [`Framework`] like the one of [`Kernel::enqueue_async`].
+    /// It fails with [`KernelError::StatsUnavailable`] if their buffer cannot be mapped,
+    /// e.g. when the device is lost.
+    pub fn enqueue_with_stats_async(
+        &self,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> impl std::future::Future<Output = KernelResult<DispatchStats>> {
+        let download = self.submit_with_stats(x, y, z);
+
+        async move {
+            let download = download?
+                .await
+                .unwrap_or(Err(wgpu::BufferAsyncError))
+                .map_err(KernelError::StatsUnavailable)?;
+            let invocations = <[u8; 8]>::try_from(&download[..8]).expect("The buffer holds a u64.");
+
+            Ok(DispatchStats {
+                compute_shader_invocations: u64::from_ne_bytes(invocations),
+            })
+        }
+    }
+
+    /// Submits a dispatch of this [`Kernel`] counting its pipeline statistics,
+    /// returning the receiver of their download.
+    fn submit_with_stats(
+        &self,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> KernelResult<
+        futures::channel::oneshot::Receiver<
+            Result<wgpu::util::DownloadBuffer, wgpu::BufferAsyncError>,
+        >,
+    > {
+        let required = wgpu::Features::PIPELINE_STATISTICS_QUERY;
+        if !self.fw.device.features().contains(required) {
+            return Err(KernelError::MissingFeatures(required));
+        }
+
+        let sets = self
+            .sets
+            .iter()
+            .enumerate()
+            .map(|(index, set)| {
+                set.as_deref()
+                    .ok_or(KernelError::DescriptorSetNotBound(index))
+            })
+            .collect::<KernelResult<Vec<_>>>()?;
+
+        let query_set = self.fw.device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Kernel::enqueue_with_stats"),
+            ty: wgpu::QueryType::PipelineStatistics(
+                wgpu::PipelineStatisticsTypes::COMPUTE_SHADER_INVOCATIONS,
+            ),
+            count: 1,
+        });
+        let resolve = self.fw.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Kernel::enqueue_with_stats"),
+            size: std::mem::size_of::<u64>() as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        self.record_dispatch_with(
+            &self.label,
+            sets.into_iter(),
+            &[],
+            (x, y, z),
+            Some((&query_set, &resolve)),
+        )?;
+
+        let (sender, receiver) = futures::channel::oneshot::channel();
+
+        wgpu::util::DownloadBuffer::read_buffer(
+            &self.fw.device,
+            &self.fw.queue,
+            &resolve.slice(..),
+            |arg| {
+                sender.send(arg).ok();
+            },
+        );
+
+        Ok(receiver)
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU like [`Kernel::enqueue`],
+    /// calling `callback` once the GPU has finished it, without blocking.
+    ///
+    /// The callback is called like the ones of [`Submission::on_done`].
+    ///
+    /// ```ignore
+    /// let (sender, receiver) = std::sync::mpsc::channel();
+    ///
+    /// kernel.enqueue_with_callback(64, 1, 1, move || sender.send(()).unwrap())?;
+    ///
+    /// // ... CPU work of this frame ...
+    ///
+    /// receiver.recv().unwrap(); // The dispatch is done.
+    /// ```
+    pub fn enqueue_with_callback(
+        &self,
+        x: u32,
+        y: u32,
+        z: u32,
+        callback: impl FnOnce() + Send + 'static,
+    ) -> KernelResult<()> {
+        self.enqueue(x, y, z)?;
+        self.fw.queue.on_submitted_work_done(callback);
+
+        Ok(())
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU like [`Kernel::enqueue`],
+    /// returning a future that resolves once the GPU has finished it.
+    ///
+    /// The work is submitted when this method is called, not when the future is first polled.
+    /// The future resolves from the polling thread of the [`Framework`], like the callbacks of
+    /// [`Kernel::enqueue_with_callback`], so no manual polling is needed.
+    ///
+    /// The future fails with [`KernelError::DispatchNotCompleted`] if `wgpu` drops the callback
+    /// without calling it, e.g. when the device is lost.
+    pub fn enqueue_async(
+        &self,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> impl std::future::Future<Output = KernelResult<()>> {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+
+        let enqueued = self.enqueue_with_callback(x, y, z, move || {
+            sender.send(()).ok();
+        });
+
+        async move {
+            enqueued?;
+            receiver
+                .await
+                .map_err(|_| KernelError::DispatchNotCompleted)?;
+
+            Ok(())
+        }
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU like [`Kernel::enqueue`],
+    /// with the `offsets` of its bindings with a dynamic offset, bound with
+    /// [`DescriptorSet::bind_buffer_dynamic`] or [`DescriptorSet::bind_uniform_buffer_dynamic`].
+    ///
+    /// `offsets` has the offsets of each bind group, in the order of their binding indices.
+    /// Bind groups past the end of `offsets` have no offsets, so an empty slice
+    /// is accepted when there are no bindings with a dynamic offset. The other ways
+    /// of enqueuing a [`Kernel`] give no offsets, so they fail when there are some.
+    ///
+    /// Fails with [`KernelError::DynamicOffsetCountMismatch`] if the number of offsets of a bind
+    /// group does not match its layout, or with [`KernelError::MisalignedDynamicOffset`]
+    /// if an offset is not aligned as the device requires.
+    pub fn enqueue_with_offsets(
+        &self,
+        x: u32,
+        y: u32,
+        z: u32,
+        offsets: &[&[u32]],
+    ) -> KernelResult<()> {
+        let sets = self
+            .sets
+            .iter()
+            .enumerate()
+            .map(|(index, set)| {
+                set.as_deref()
+                    .ok_or(KernelError::DescriptorSetNotBound(index))
+            })
+            .collect::<KernelResult<Vec<_>>>()?;
+
+        self.record_dispatch(&self.label, sets.into_iter(), offsets, x, y, z)
+    }
+
+    /// Checks the dynamic `offsets` of a dispatch, like [`Kernel::enqueue_with_offsets`] describes.
+    pub(crate) fn check_offsets(&self, offsets: &[&[u32]]) -> KernelResult<()> {
+        if offsets.len() > self.sets.len() {
+            return Err(KernelError::DescriptorSetNotFound(self.sets.len()));
+        }
+
+        let limits = self.fw.limits();
+
+        for (group, (entries, _)) in self.layouts.iter().enumerate() {
+            let group_offsets = offsets.get(group).copied().unwrap_or_default();
+
+            let dynamic = entries
+                .iter()
+                .filter_map(|entry| match entry.ty {
+                    wgpu::BindingType::Buffer {
+                        ty,
+                        has_dynamic_offset: true,
+                        ..
+                    } => Some((entry.binding, ty)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            if dynamic.len() != group_offsets.len() {
+                return Err(KernelError::DynamicOffsetCountMismatch {
+                    group,
+                    expected: dynamic.len(),
+                    found: group_offsets.len(),
+                });
+            }
+
+            for ((binding, ty), &offset) in dynamic.into_iter().zip(group_offsets) {
+                let alignment = match ty {
+                    wgpu::BufferBindingType::Uniform => limits.min_uniform_buffer_offset_alignment,
+                    wgpu::BufferBindingType::Storage { .. } => {
+                        limits.min_storage_buffer_offset_alignment
+                    }
+                };
+
+                if offset % alignment != 0 {
+                    return Err(KernelError::MisalignedDynamicOffset {
+                        group,
+                        binding,
+                        offset,
+                        alignment,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU with enough workgroups
+    /// to run `total` invocations, one per element.
+    ///
+    /// The workgroup size of the entry point must be one-dimensional, i.e. `(x, 1, 1)`.
+    /// The workgroups are laid out along `x`, and also along `y` once `x` reaches
+    /// [`Kernel::max_dispatch`]. The shader must then index the elements with
+    /// `global_id.x + global_id.y * num_workgroups.x * workgroup_size.x`.
+    /// The last workgroup can run past `total`, so the shader must check the index
+    /// against the element count.
+    ///
+    /// Fails with [`KernelError::UnknownWorkgroupSize`] if the shader could not be reflected,
+    /// with [`KernelError::MultidimensionalWorkgroupSize`] if the workgroup size is not
+    /// one-dimensional, as well as for the reasons of [`Kernel::enqueue`].
+    pub fn enqueue_elements(&self, total: u64) -> KernelResult<()> {
+        let (x, y, z) = self.element_workgroups(total)?;
+
+        self.enqueue(x, y, z)
+    }
+
+    /// Workgroups of [`Kernel::enqueue_elements`] for `total` invocations.
+    pub(crate) fn element_workgroups(&self, total: u64) -> KernelResult<(u32, u32, u32)> {
+        let (size, size_y, size_z) = self.known_workgroup_size()?;
+        if (size_y, size_z) != (1, 1) {
+            return Err(KernelError::MultidimensionalWorkgroupSize {
+                entry_point: self.entry_point.clone(),
+                workgroup_size: (size, size_y, size_z),
+            });
+        }
+        let (max, _, _) = self.max_dispatch();
+
+        let groups = total.div_ceil(size as u64);
+        let y = groups.div_ceil(max as u64).max(1);
+        let x = groups.div_ceil(y);
+
+        Ok((
+            u32::try_from(x).unwrap_or(u32::MAX),
+            u32::try_from(y).unwrap_or(u32::MAX),
+            1,
+        ))
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU with enough workgroups
+    /// to run one invocation per pixel of a `width` x `height` image.
+    ///
+    /// The last workgroups of each dimension can run past the image, so the shader
+    /// must check `global_id` against its dimensions.
+    ///
+    /// Fails with [`KernelError::UnknownWorkgroupSize`] if the shader could not be reflected,
+    /// as well as for the reasons of [`Kernel::enqueue`].
+    pub fn enqueue_elements_2d(&self, (width, height): (u32, u32)) -> KernelResult<()> {
+        self.enqueue_for_extent((width, height, 1))
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU with enough workgroups
+    /// to run one invocation per pixel of `img`, like [`Kernel::enqueue_elements_2d`].
+    ///
+    /// The last workgroups of each dimension can run past the image, so the shader
+    /// must check `global_id` against `textureDimensions`.
+    pub fn enqueue_for_image<'img>(&self, img: &impl ImgOps<'img>) -> KernelResult<()> {
+        let (width, height) = img.dimensions();
+
+        self.enqueue_for_extent((width, height, 1))
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU with enough workgroups
+    /// to run one invocation per cell of a `width` x `height` x `depth` grid.
+    ///
+    /// The last workgroups of each dimension run past the grid when the `workgroup_size`
+    /// of the shader does not divide it, so the shader must check `global_id` against it.
+    ///
+    /// Fails with [`KernelError::UnknownWorkgroupSize`] if the shader could not be reflected,
+    /// as well as for the reasons of [`Kernel::enqueue`].
+    pub fn enqueue_for_extent(&self, (width, height, depth): (u32, u32, u32)) -> KernelResult<()> {
+        let (size_x, size_y, size_z) = self.known_workgroup_size()?;
+
+        self.enqueue(
+            width.div_ceil(size_x),
+            height.div_ceil(size_y),
+            depth.div_ceil(size_z),
+        )
+    }
+
+    /// Returns the identifier of this [`Kernel`], see [`GpuId`].
+    pub fn id(&self) -> GpuId {
+        self.id
+    }
+
+    /// Returns the label naming this [`Kernel`] in GPU debuggers and profiles,
+    /// which is its entry point unless set with [`Program::label`].
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the entry point of the shader this [`Kernel`] runs.
+    pub fn entry_point(&self) -> &str {
+        &self.entry_point
+    }
+
+    /// Returns the label of the [`Shader`] this [`Kernel`] was created from, see [`Shader::label`].
+    pub fn shader_label(&self) -> Option<&str> {
+        self.shader.as_deref()
+    }
+
+    /// Returns the `workgroup_size` the entry point of this [`Kernel`] declares,
+    /// or `None` if its shader could not be reflected.
+    pub fn workgroup_size(&self) -> Option<(u32, u32, u32)> {
+        self.workgroup_size
+    }
+
+    fn known_workgroup_size(&self) -> KernelResult<(u32, u32, u32)> {
+        self.workgroup_size
+            .ok_or_else(|| KernelError::UnknownWorkgroupSize(self.entry_point.clone()))
+    }
+
+    /// Enqueues the execution of this [`Kernel`] onto the GPU using `descs` instead
+    /// of its own [`DescriptorSet`]s, which are left untouched.
+    ///
+    /// `descs` must have the same number and shape of [`DescriptorSet`]s
+    /// as the ones this [`Kernel`] was created with.
+    pub fn enqueue_with_sets(
+        &self,
+        x: u32,
+        y: u32,
+        z: u32,
+        descs: &[&DescriptorSet],
+    ) -> KernelResult<()> {
+        if descs.len() != self.sets.len() {
+            return Err(KernelError::DescriptorSetCountMismatch {
+                expected: self.sets.len(),
+                found: descs.len(),
+            });
+        }
+
+        let sets = descs
+            .iter()
+            .enumerate()
+            .map(|(index, desc)| self.create_bind_group(index, desc))
+            .collect::<KernelResult<Vec<_>>>()?;
+
+        self.record_dispatch(&self.label, sets.iter().map(Arc::as_ref), &[], x, y, z)
+    }
+
+    /// Enqueues `times` executions of this [`Kernel`] onto the GPU in a single submission,
+    /// each dispatching `x`, `y` and `z` workgroups per dimension.
+    ///
+    /// Each execution sees the writes of the previous ones, which makes it suitable
+    /// for the iterations of a solver. Fails for the reasons of [`Kernel::enqueue`].
+    pub fn enqueue_repeat(
+        &self,
+        times: u32,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> KernelResult<Submission<'fw>> {
+        let mut recorder = self.fw.create_command_recorder();
+
+        for _ in 0..times {
+            recorder.enqueue(self, x, y, z)?;
+        }
+
+        Ok(Submission {
+            fw: self.fw,
+            index: recorder.submit().index,
+        })
+    }
+
+    /// Enqueues `times` executions of this [`Kernel`] onto the GPU like [`Kernel::enqueue_repeat`],
+    /// using the [`DescriptorSet`]s `descs` returns for each iteration instead of its own.
+    ///
+    /// The bind groups of the [`DescriptorSet`]s are only created the first time they are returned,
+    /// so alternating between a few of them is cheap, e.g. to swap the input and output buffers:
+    /// `kernel.enqueue_repeat_with(1000, x, y, z, |i| &sets[i as usize % 2][..])`.
+    ///
+    /// Fails for the reasons of [`Kernel::enqueue_with_sets`].
+    pub fn enqueue_repeat_with<'d, 'res: 'd>(
+        &self,
+        times: u32,
+        x: u32,
+        y: u32,
+        z: u32,
+        mut descs: impl FnMut(u32) -> &'d [&'d DescriptorSet<'res>],
+    ) -> KernelResult<Submission<'fw>> {
+        let mut recorder = self.fw.create_command_recorder();
+        let mut bound: Vec<(usize, &DescriptorSet, Arc<wgpu::BindGroup>)> = Vec::new();
+
+        for iteration in 0..times {
+            let descs = descs(iteration);
+
+            if descs.len() != self.sets.len() {
+                return Err(KernelError::DescriptorSetCountMismatch {
+                    expected: self.sets.len(),
+                    found: descs.len(),
+                });
+            }
+
+            let mut sets = Vec::with_capacity(descs.len());
+
+            for (index, &desc) in descs.iter().enumerate() {
+                let known = bound
+                    .iter()
+                    .find(|(group, set, _)| *group == index && std::ptr::eq(*set, desc));
+
+                let set = match known {
+                    Some((_, _, set)) => Arc::clone(set),
+                    None => {
+                        let set = self.create_bind_group(index, desc)?;
+                        bound.push((index, desc, Arc::clone(&set)));
+                        set
+                    }
+                };
+                sets.push(set);
+            }
+
+            recorder.push_dispatch(self, &self.label, sets, (x, y, z))?;
+        }
+
+        Ok(Submission {
+            fw: self.fw,
+            index: recorder.submit().index,
+        })
+    }
+
+    /// Returns the maximum number of workgroups this [`Kernel`] can be dispatched with
+    /// in the `x`, `y` and `z` dimensions.
+    pub fn max_dispatch(&self) -> (u32, u32, u32) {
+        let limit = self.fw.limits().max_compute_workgroups_per_dimension;
+
+        (limit, limit, limit)
+    }
+
+    /// Checks `x`, `y` and `z` workgroups per dimension against [`Kernel::max_dispatch`].
+    pub(crate) fn check_workgroups(&self, x: u32, y: u32, z: u32) -> KernelResult<()> {
+        let (max_x, max_y, max_z) = self.max_dispatch();
+
+        if x > max_x || y > max_y || z > max_z {
+            return Err(KernelError::TooManyWorkgroups {
+                workgroups: (x, y, z),
+                limit: max_x,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Creates a bind group of `desc` using the layout of the [`DescriptorSet`] at `index`.
+    fn create_bind_group(
+        &self,
+        index: usize,
+        desc: &DescriptorSet,
+    ) -> KernelResult<Arc<wgpu::BindGroup>> {
+        let mut desc_entries = desc.set_layout.clone();
+        desc_entries.sort_by_key(|entry| entry.binding);
+
+        let layout = self.check_shape(index, &desc_entries)?;
+        validate_device_support(self.fw, &[&desc.bindings])?;
+
+        let set = self
+            .fw
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout,
+                entries: &desc.bind_group_entries(),
+            });
+
+        Ok(Arc::new(set))
+    }
+
+    /// Returns the layout of the [`DescriptorSet`] at `index` if its (sorted) entries are `entries`.
+    fn check_shape(
+        &self,
+        index: usize,
+        entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> KernelResult<&wgpu::BindGroupLayout> {
+        let (set_entries, layout) = self
+            .layouts
+            .get(index)
+            .ok_or(KernelError::DescriptorSetNotFound(index))?;
+
+        if set_entries != entries {
+            return Err(KernelError::DescriptorSetShapeMismatch(index));
+        }
+
+        Ok(layout)
+    }
+
+    fn record_dispatch<'a>(
+        &self,
+        label: &str,
+        sets: impl Iterator<Item = &'a wgpu::BindGroup>,
+        offsets: &[&[u32]],
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> KernelResult<()> {
+        self.record_dispatch_with(label, sets, offsets, (x, y, z), None)
+    }
+
+    /// Records a dispatch like [`Kernel::record_dispatch`], counting its pipeline statistics
+    /// into the first query of the `statistics` query set, resolved into its buffer.
+    fn record_dispatch_with<'a>(
+        &self,
+        label: &str,
+        sets: impl Iterator<Item = &'a wgpu::BindGroup>,
+        offsets: &[&[u32]],
+        (x, y, z): (u32, u32, u32),
+        statistics: Option<(&wgpu::QuerySet, &wgpu::Buffer)>,
+    ) -> KernelResult<()> {
+        self.check_workgroups(x, y, z)?;
+        self.check_offsets(offsets)?;
+
         let mut encoder = self
             .fw
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("Kernel::enqueue"),
             });
-        {
-            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("Kernel::enqueue"),
-            });
 
-            cpass.set_pipeline(&self.pipeline);
+        let marker = Some(label).filter(|_| self.fw.debug_markers());
+
+        #[cfg(feature = "profiler")]
+        let scope = self.fw.begin_profile_scope(&mut encoder, label);
+        {
+            let mut cpass =
+                encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: marker });
 
-            for (id_set, set) in self.sets.iter().enumerate() {
-                cpass.set_bind_group(id_set as u32, set, &[]);
+            if let Some((query_set, _)) = statistics {
+                cpass.begin_pipeline_statistics_query(query_set, 0);
             }
 
-            cpass.insert_debug_marker(&self.entry_point);
-            cpass.dispatch_workgroups(x, y, z);
+            record_dispatch_in(&mut cpass, &self.pipeline, sets, offsets, marker, (x, y, z));
+
+            if statistics.is_some() {
+                cpass.end_pipeline_statistics_query();
+            }
         }
+        if let Some((query_set, resolve)) = statistics {
+            encoder.resolve_query_set(query_set, 0..1, resolve, 0);
+        }
+        #[cfg(feature = "profiler")]
+        self.fw.end_profile_scope(&mut encoder, scope);
 
         self.fw.queue.submit(Some(encoder.finish()));
+
+        event!(
+            debug,
+            "enqueued kernel `{}` as `{}` with {}x{}x{} workgroups",
+            self.label,
+            label,
+            x,
+            y,
+            z
+        );
+
+        Ok(())
+    }
+}
+
+impl PartialEq for Kernel<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Kernel<'_> {}
+
+impl Hash for Kernel<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl fmt::Debug for Kernel<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Kernel")
+            .field("id", &self.id)
+            .field("label", &self.label)
+            .field("entry_point", &self.entry_point)
+            .field("shader", &self.shader)
+            .finish()
+    }
+}
+
+/// Records a dispatch of `pipeline` with `sets` bound in `cpass`,
+/// with the dynamic `offsets` of each of them, in a debug group named `marker` if any.
+fn record_dispatch_in<'a, 'set: 'a>(
+    cpass: &mut wgpu::ComputePass<'a>,
+    pipeline: &'a wgpu::ComputePipeline,
+    sets: impl Iterator<Item = &'set wgpu::BindGroup>,
+    offsets: &[&[u32]],
+    marker: Option<&str>,
+    (x, y, z): (u32, u32, u32),
+) {
+    if let Some(marker) = marker {
+        cpass.push_debug_group(marker);
+    }
+
+    cpass.set_pipeline(pipeline);
+
+    for (id_set, set) in sets.enumerate() {
+        let set_offsets = offsets.get(id_set).copied().unwrap_or_default();
+        cpass.set_bind_group(id_set as u32, set, set_offsets);
+    }
+
+    cpass.dispatch_workgroups(x, y, z);
+
+    if marker.is_some() {
+        cpass.pop_debug_group();
     }
 }
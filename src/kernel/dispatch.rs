@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use crate::{
+    primitives::PixelInfo, DescriptorSet, Dispatch, GpuBuffer, GpuBufferUsage, GpuConstImage,
+    GpuImage, GpuSampler, GpuUniformBuffer, Kernel,
+};
+
+use super::{DescriptorSetResult, KernelError, KernelResult};
+
+/// Resource that can be bound in a [`Dispatch`] without specifying how.
+///
+/// The binding is made to match the layout the [`Kernel`] was created with,
+/// e.g. a [`GpuBuffer`] takes the usage of the storage buffer it replaces.
+pub trait Bindable<'res> {
+    /// Binds `self` in `desc` at `binding`, following `expected` if it is known.
+    fn bind_in(
+        &'res self,
+        desc: DescriptorSet<'res>,
+        binding: u32,
+        expected: Option<&wgpu::BindGroupLayoutEntry>,
+    ) -> DescriptorSetResult<DescriptorSet<'res>>;
+}
+
+impl<'fw, 'res, T: bytemuck::Pod> Bindable<'res> for GpuBuffer<'fw, T> {
+    fn bind_in(
+        &'res self,
+        desc: DescriptorSet<'res>,
+        binding: u32,
+        expected: Option<&wgpu::BindGroupLayoutEntry>,
+    ) -> DescriptorSetResult<DescriptorSet<'res>> {
+        let usage = match expected.map(|entry| entry.ty) {
+            Some(wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                ..
+            }) => GpuBufferUsage::ReadWrite,
+            _ => GpuBufferUsage::ReadOnly,
+        };
+
+        desc.bind_buffer_at(binding, self, usage)
+    }
+}
+
+impl<'fw, 'res, T: bytemuck::Pod> Bindable<'res> for GpuUniformBuffer<'fw, T> {
+    fn bind_in(
+        &'res self,
+        desc: DescriptorSet<'res>,
+        binding: u32,
+        _expected: Option<&wgpu::BindGroupLayoutEntry>,
+    ) -> DescriptorSetResult<DescriptorSet<'res>> {
+        desc.bind_uniform_buffer_at(binding, self)
+    }
+}
+
+impl<'fw, 'res, P: PixelInfo> Bindable<'res> for GpuImage<'fw, P> {
+    fn bind_in(
+        &'res self,
+        desc: DescriptorSet<'res>,
+        binding: u32,
+        _expected: Option<&wgpu::BindGroupLayoutEntry>,
+    ) -> DescriptorSetResult<DescriptorSet<'res>> {
+        desc.bind_image_at(binding, self)
+    }
+}
+
+impl<'fw, 'res, P: PixelInfo> Bindable<'res> for GpuConstImage<'fw, P> {
+    fn bind_in(
+        &'res self,
+        desc: DescriptorSet<'res>,
+        binding: u32,
+        _expected: Option<&wgpu::BindGroupLayoutEntry>,
+    ) -> DescriptorSetResult<DescriptorSet<'res>> {
+        desc.bind_const_image_at(binding, self)
+    }
+}
+
+impl<'res> Bindable<'res> for GpuSampler {
+    fn bind_in(
+        &'res self,
+        desc: DescriptorSet<'res>,
+        binding: u32,
+        _expected: Option<&wgpu::BindGroupLayoutEntry>,
+    ) -> DescriptorSetResult<DescriptorSet<'res>> {
+        desc.bind_sampler_at(binding, self)
+    }
+}
+
+impl<'fw> Kernel<'fw> {
+    /// Starts a dispatch of `x`, `y` and `z` workgroups per dimension, binding its resources
+    /// on the fly with [`Dispatch::bind`].
+    ///
+    /// The bind groups are created for this dispatch only, which is slower than
+    /// reusing [`DescriptorSet`]s with [`Kernel::set_descriptor_set`]:
+    /// it is meant for quick experiments.
+    pub fn dispatch<'ker, 'res>(&'ker self, x: u32, y: u32, z: u32) -> Dispatch<'ker, 'fw, 'res> {
+        Dispatch {
+            kernel: self,
+            workgroups: (x, y, z),
+            sets: self.sets.iter().map(|_| None).collect(),
+            group: 0,
+            error: None,
+        }
+    }
+}
+
+impl<'ker, 'fw, 'res> Dispatch<'ker, 'fw, 'res> {
+    /// Binds `resource` at the lowest free binding index of the current group, 0 by default.
+    pub fn bind(mut self, resource: &'res impl Bindable<'res>) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+
+        let desc = match self.sets.get_mut(self.group) {
+            Some(desc) => desc.take().unwrap_or_default(),
+            None => {
+                self.error = Some(KernelError::DescriptorSetNotFound(self.group));
+                return self;
+            }
+        };
+
+        let binding = desc.next_binding();
+        let expected = self.kernel.layouts[self.group]
+            .0
+            .iter()
+            .find(|entry| entry.binding == binding);
+
+        match resource.bind_in(desc, binding, expected) {
+            Ok(desc) => self.sets[self.group] = Some(desc),
+            Err(err) => self.error = Some(err.into()),
+        }
+
+        self
+    }
+
+    /// Makes the following [`Dispatch::bind`] calls bind in the descriptor set of index `group`.
+    ///
+    /// Groups without any resource bound use the [`DescriptorSet`] of the [`Kernel`].
+    pub fn group(mut self, group: usize) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// Enqueues the dispatch onto the GPU.
+    ///
+    /// Fails if the resources bound to a group do not have the shape of the [`DescriptorSet`]
+    /// the [`Kernel`] was created with, if a group has no resources at all,
+    /// or if the workgroup counts exceed [`Kernel::max_dispatch`].
+    pub fn run(self) -> KernelResult<()> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let sets = self
+            .sets
+            .iter()
+            .enumerate()
+            .map(|(index, desc)| match desc {
+                Some(desc) => self.kernel.create_bind_group(index, desc),
+                None => self.kernel.sets[index]
+                    .clone()
+                    .ok_or(KernelError::DescriptorSetNotBound(index)),
+            })
+            .collect::<KernelResult<Vec<_>>>()?;
+
+        let (x, y, z) = self.workgroups;
+        self.kernel.record_dispatch(
+            &self.kernel.label,
+            sets.iter().map(Arc::as_ref),
+            &[],
+            x,
+            y,
+            z,
+        )
+    }
+}
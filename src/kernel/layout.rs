@@ -0,0 +1,206 @@
+use crate::{
+    primitives::PixelInfo, DescriptorLayout, DescriptorSet, DescriptorSetBuilder, GpuBuffer,
+    GpuBufferUsage, GpuConstImage, GpuImage, GpuUniformBuffer,
+};
+
+use super::{BindingInfo, DescriptorSetError, DescriptorSetResult, ResourceSize};
+
+impl DescriptorLayout {
+    /// Adds a uniform buffer slot named `name`.
+    ///
+    /// See [`DescriptorSet::bind_uniform_buffer`] for its shader representation.
+    pub fn add_uniform_buffer(self, name: impl Into<String>) -> Self {
+        self.push_slot(
+            name,
+            wgpu::BindingType::Buffer {
+                has_dynamic_offset: false,
+                min_binding_size: None,
+                ty: wgpu::BufferBindingType::Uniform,
+            },
+        )
+    }
+
+    /// Adds a storage buffer slot named `name` with a specific `usage`.
+    ///
+    /// See [`DescriptorSet::bind_buffer`] for its shader representation.
+    pub fn add_buffer(self, name: impl Into<String>, usage: GpuBufferUsage) -> Self {
+        self.push_slot(
+            name,
+            wgpu::BindingType::Buffer {
+                has_dynamic_offset: false,
+                min_binding_size: None,
+                ty: wgpu::BufferBindingType::Storage {
+                    read_only: usage == GpuBufferUsage::ReadOnly,
+                },
+            },
+        )
+    }
+
+    /// Adds a write-only storage image slot named `name`.
+    ///
+    /// See [`DescriptorSet::bind_image`] for its shader representation.
+    pub fn add_image<P: PixelInfo>(self, name: impl Into<String>) -> Self {
+        self.push_slot(
+            name,
+            wgpu::BindingType::StorageTexture {
+                access: wgpu::StorageTextureAccess::WriteOnly,
+                format: P::wgpu_format(),
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+        )
+    }
+
+    /// Adds a texture slot named `name`.
+    ///
+    /// See [`DescriptorSet::bind_const_image`] for its shader representation.
+    pub fn add_const_image<P: PixelInfo>(self, name: impl Into<String>) -> Self {
+        self.push_slot(
+            name,
+            wgpu::BindingType::Texture {
+                sample_type: P::wgpu_texture_sample(),
+                multisampled: false,
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+        )
+    }
+
+    /// Starts a [`DescriptorSet`] with the shape of this [`DescriptorLayout`].
+    pub fn instantiate<'res>(&self) -> DescriptorSetBuilder<'_, 'res> {
+        DescriptorSetBuilder {
+            layout: self,
+            desc: DescriptorSet::default(),
+        }
+    }
+
+    pub(crate) fn set_layout(&self) -> &[wgpu::BindGroupLayoutEntry] {
+        &self.set_layout
+    }
+
+    pub(crate) fn bindings(&self) -> &[BindingInfo] {
+        &self.bindings
+    }
+
+    fn push_slot(mut self, name: impl Into<String>, ty: wgpu::BindingType) -> Self {
+        let entry = wgpu::BindGroupLayoutEntry {
+            binding: self.set_layout.len() as u32,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty,
+            count: None,
+        };
+
+        self.names.push(name.into());
+        self.bindings
+            .push(BindingInfo::new(&entry, ResourceSize::Unknown, None));
+        self.set_layout.push(entry);
+
+        self
+    }
+
+    /// Returns the layout entry of the slot named `name`.
+    fn slot(&self, name: &str) -> DescriptorSetResult<&wgpu::BindGroupLayoutEntry> {
+        self.names
+            .iter()
+            .position(|slot| slot == name)
+            .map(|index| &self.set_layout[index])
+            .ok_or_else(|| DescriptorSetError::UnknownSlot(name.to_string()))
+    }
+}
+
+impl<'lay, 'res> DescriptorSetBuilder<'lay, 'res> {
+    /// Binds a [`GpuUniformBuffer`] to the uniform buffer slot named `slot`.
+    pub fn bind_uniform_buffer<T>(
+        self,
+        slot: &str,
+        uniform_buf: &'res GpuUniformBuffer<T>,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        self.bind_slot(slot, |desc, binding| {
+            desc.bind_uniform_buffer_at(binding, uniform_buf)
+        })
+    }
+
+    /// Binds a [`GpuBuffer`] to the storage buffer slot named `slot`,
+    /// with the usage declared by the slot.
+    pub fn bind_buffer<T>(
+        self,
+        slot: &str,
+        storage_buf: &'res GpuBuffer<T>,
+    ) -> DescriptorSetResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        let usage = match self.layout.slot(slot)?.ty {
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                ..
+            } => GpuBufferUsage::ReadWrite,
+            _ => GpuBufferUsage::ReadOnly,
+        };
+
+        self.bind_slot(slot, |desc, binding| {
+            desc.bind_buffer_at(binding, storage_buf, usage)
+        })
+    }
+
+    /// Binds a [`GpuImage`] to the storage image slot named `slot`.
+    pub fn bind_image<P: PixelInfo>(
+        self,
+        slot: &str,
+        img: &'res GpuImage<P>,
+    ) -> DescriptorSetResult<Self> {
+        self.bind_slot(slot, |desc, binding| desc.bind_image_at(binding, img))
+    }
+
+    /// Binds a [`GpuConstImage`] to the texture slot named `slot`.
+    pub fn bind_const_image<P: PixelInfo>(
+        self,
+        slot: &str,
+        img: &'res GpuConstImage<P>,
+    ) -> DescriptorSetResult<Self> {
+        self.bind_slot(slot, |desc, binding| desc.bind_const_image_at(binding, img))
+    }
+
+    /// Returns the [`DescriptorSet`]. Fails if any slot of the [`DescriptorLayout`] was not bound.
+    pub fn finish(self) -> DescriptorSetResult<DescriptorSet<'res>> {
+        let missing = self.layout.set_layout.iter().position(|slot| {
+            self.desc
+                .set_layout
+                .iter()
+                .all(|entry| entry.binding != slot.binding)
+        });
+
+        match missing {
+            Some(index) => Err(DescriptorSetError::MissingSlot(
+                self.layout.names[index].clone(),
+            )),
+            None => Ok(self.desc),
+        }
+    }
+
+    /// Binds a resource to the slot named `name` with `bind`, checking its type against the slot.
+    fn bind_slot(
+        self,
+        name: &str,
+        bind: impl FnOnce(DescriptorSet<'res>, u32) -> DescriptorSetResult<DescriptorSet<'res>>,
+    ) -> DescriptorSetResult<Self> {
+        let slot = self.layout.slot(name)?;
+        let desc = bind(self.desc, slot.binding)?;
+
+        let bound = desc.set_layout.last().expect("A resource was just bound.");
+
+        if bound.ty != slot.ty {
+            return Err(DescriptorSetError::SlotMismatch {
+                slot: name.to_string(),
+                expected: slot.ty,
+                bound: bound.ty,
+            });
+        }
+
+        Ok(Self {
+            layout: self.layout,
+            desc,
+        })
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{Framework, Program, Shader, ShaderLibrary};
+
+use super::{
+    check_legacy_wgsl, preprocess_file, spirv_words, ShaderError, ShaderReflection, ShaderResult,
+};
+
+/// Shader file read and reflected, whose module is still to be created.
+enum ParsedShader {
+    Wgsl(String, ShaderReflection),
+    Spirv(Vec<u32>, ShaderReflection),
+}
+
+impl ShaderLibrary {
+    /// Loads every `.wgsl` and `.spv` file in `dir` and its subdirectories.
+    ///
+    /// The include directives of the `WGSL` files are resolved like
+    /// [`Shader::from_wgsl_file_with_includes`], from the including file then from `dir`.
+    /// Every file is compiled as a shader, including those only meant to be included.
+    ///
+    /// Fails with a [`ShaderError::Library`] listing the error of every shader that could
+    /// not be loaded, or with the I/O error if `dir` cannot be read.
+    pub fn load_dir(fw: &Framework, dir: impl AsRef<Path>) -> ShaderResult<Self> {
+        let (dir, files) = list_shaders(dir.as_ref())?;
+        let migrate = fw.migrate_legacy_wgsl();
+
+        let parsed = files
+            .iter()
+            .map(|(name, path)| parse(name, path, &dir, migrate))
+            .collect::<Vec<_>>();
+
+        Self::create(fw, files, parsed)
+    }
+
+    /// Loads every shader file of `dir` like [`ShaderLibrary::load_dir`], parsing and
+    /// validating them on as many threads as the CPU has cores.
+    ///
+    /// The modules themselves are still created one after the other.
+    pub fn load_dir_parallel(fw: &Framework, dir: impl AsRef<Path>) -> ShaderResult<Self> {
+        let (dir, files) = list_shaders(dir.as_ref())?;
+        let migrate = fw.migrate_legacy_wgsl();
+
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk_size = files.len().div_ceil(threads).max(1);
+
+        let parsed = std::thread::scope(|scope| {
+            let handles = files
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let dir = &dir;
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(name, path)| parse(name, path, dir, migrate))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        Self::create(fw, files, parsed)
+    }
+
+    /// Returns the [`Shader`] loaded from the file at the path `name`, relative to the directory.
+    pub fn get(&self, name: &str) -> Option<&Shader> {
+        self.shaders.get(name)
+    }
+
+    /// Returns the names of the loaded shaders, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.shaders.keys().map(String::as_str)
+    }
+
+    /// Creates a [`Program`] using the `entry_point` of the shader `name`,
+    /// or `None` if there is no such shader.
+    pub fn program<'res>(&self, name: &str, entry_point: &str) -> Option<Program<'_, 'res>> {
+        self.get(name)
+            .map(|shader| Program::new(shader, entry_point))
+    }
+
+    /// Creates the modules of the `parsed` shader `files`, collecting the errors.
+    fn create(
+        fw: &Framework,
+        files: Vec<(String, PathBuf)>,
+        parsed: Vec<ShaderResult<ParsedShader>>,
+    ) -> ShaderResult<Self> {
+        let mut shaders = BTreeMap::new();
+        let mut failures = Vec::new();
+
+        for ((name, _), parsed) in files.into_iter().zip(parsed) {
+            let shader = parsed.and_then(|parsed| match parsed {
+                ParsedShader::Wgsl(source, reflection) => Shader::create(
+                    fw,
+                    wgpu::ShaderSource::Wgsl(Cow::Owned(source)),
+                    Some(&name),
+                    reflection,
+                ),
+                ParsedShader::Spirv(words, reflection) => Shader::create(
+                    fw,
+                    wgpu::ShaderSource::SpirV(Cow::Owned(words)),
+                    Some(&name),
+                    reflection,
+                ),
+            });
+
+            match shader {
+                Ok(shader) => {
+                    shaders.insert(name, shader);
+                }
+                Err(err) => failures.push((name, err)),
+            }
+        }
+
+        if !failures.is_empty() {
+            return Err(ShaderError::Library(failures));
+        }
+
+        Ok(Self { shaders })
+    }
+}
+
+/// Returns the canonical `dir` and the name and path of every shader file in it, sorted by name.
+fn list_shaders(dir: &Path) -> ShaderResult<(PathBuf, Vec<(String, PathBuf)>)> {
+    let dir = dir.canonicalize()?;
+
+    let mut files = Vec::new();
+    let mut pending = vec![dir.clone()];
+
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                pending.push(path);
+            } else if matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("wgsl" | "spv")
+            ) {
+                let name = path
+                    .strip_prefix(&dir)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+
+                files.push((name, path));
+            }
+        }
+    }
+
+    files.sort();
+
+    Ok((dir, files))
+}
+
+/// Reads and reflects the shader file `name` at `path`, resolving includes from `dir`
+/// and migrating the legacy `WGSL` syntax if `migrate` is set.
+fn parse(name: &str, path: &Path, dir: &Path, migrate: bool) -> ShaderResult<ParsedShader> {
+    if path.extension() == Some("spv".as_ref()) {
+        let words = spirv_words(&std::fs::read(path)?)?;
+        let reflection = ShaderReflection::from_spirv(&words)?;
+
+        Ok(ParsedShader::Spirv(words, reflection))
+    } else {
+        let (source, map) = preprocess_file(path, &[dir.to_path_buf()], &HashSet::new())?;
+        let source = check_legacy_wgsl(&source, name, migrate)?.unwrap_or(source);
+        let reflection = ShaderReflection::from_wgsl(&source, &map)?;
+
+        Ok(ParsedShader::Wgsl(source, reflection))
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use crate::{
+    primitives::BufOps, DescriptorLayout, DescriptorSet, Framework, GpuBuffer, Kernel, Pipeline,
+};
+
+use super::{Bindable, DescriptorSetError, KernelError, KernelResult};
+
+/// Resource provided to the slots of a [`Pipeline`].
+pub(crate) enum PipelineResource<'fw, 'res> {
+    Borrowed(&'res (dyn for<'r> Bindable<'r> + 'res)),
+    Temporary(Box<dyn for<'r> Bindable<'r> + 'fw>),
+}
+
+impl PipelineResource<'_, '_> {
+    fn as_bindable(&self) -> &dyn for<'r> Bindable<'r> {
+        match self {
+            Self::Borrowed(resource) => *resource,
+            Self::Temporary(resource) => resource.as_ref(),
+        }
+    }
+}
+
+impl<'fw, 'res> Pipeline<'fw, 'res> {
+    /// Creates an empty [`Pipeline`].
+    pub fn new(fw: &'fw Framework) -> Self {
+        Self {
+            fw,
+            resources: Vec::new(),
+            stages: Vec::new(),
+        }
+    }
+
+    /// Provides `resource` to the slots named `name` of the stages.
+    ///
+    /// It replaces any resource previously given the same `name`.
+    pub fn bind<R>(self, name: impl Into<String>, resource: &'res R) -> Self
+    where
+        R: for<'r> Bindable<'r>,
+    {
+        self.push_resource(name.into(), PipelineResource::Borrowed(resource))
+    }
+
+    /// Allocates a zeroed [`GpuBuffer`] of `len` elements for the slots named `name`
+    /// of the stages, e.g. to pass the output of a stage to the next one without
+    /// creating the buffer by hand.
+    ///
+    /// Every stage using the slot shares the same buffer.
+    /// It replaces any resource previously given the same `name`.
+    pub fn temp_buffer<T>(self, name: impl Into<String>, len: u64) -> Self
+    where
+        T: bytemuck::Pod,
+    {
+        let buffer = Box::new(GpuBuffer::<T>::with_capacity(self.fw, len));
+
+        self.push_resource(name.into(), PipelineResource::Temporary(buffer))
+    }
+
+    /// Adds a dispatch of `kernel` with `x`, `y` and `z` workgroups per dimension.
+    ///
+    /// The descriptor sets `kernel` was created with as [`DescriptorLayout`]s
+    /// are bound the resources named like their slots, while the others keep
+    /// the resources `kernel` has bound.
+    pub fn then(mut self, kernel: &'res Kernel<'fw>, (x, y, z): (u32, u32, u32)) -> Self {
+        self.stages.push((kernel, (x, y, z)));
+        self
+    }
+
+    /// Enqueues the dispatches of every stage onto the GPU in a single submission,
+    /// in the order they were added.
+    ///
+    /// Fails with [`DescriptorSetError::MissingSlot`] if a slot has no resource named like it,
+    /// as well as for the reasons of [`Kernel::enqueue`].
+    pub fn run(&self) -> KernelResult<()> {
+        let mut recorder = self.fw.create_command_recorder();
+
+        for (kernel, workgroups) in &self.stages {
+            let sets = kernel
+                .slots
+                .iter()
+                .enumerate()
+                .map(|(index, slots)| match slots {
+                    Some(layout) => self.bind_slots(kernel, index, layout),
+                    None => kernel.sets[index]
+                        .clone()
+                        .ok_or(KernelError::DescriptorSetNotBound(index)),
+                })
+                .collect::<KernelResult<Vec<_>>>()?;
+
+            recorder.push_dispatch(kernel, &kernel.label, sets, *workgroups)?;
+        }
+
+        recorder.submit();
+
+        Ok(())
+    }
+
+    fn push_resource(mut self, name: String, resource: PipelineResource<'fw, 'res>) -> Self {
+        self.resources.retain(|(slot, _)| *slot != name);
+        self.resources.push((name, resource));
+        self
+    }
+
+    /// Creates the bind group of the descriptor set `index` of `kernel`,
+    /// binding to each slot of `layout` the resource named like it.
+    fn bind_slots(
+        &self,
+        kernel: &Kernel,
+        index: usize,
+        layout: &DescriptorLayout,
+    ) -> KernelResult<Arc<wgpu::BindGroup>> {
+        let mut desc = DescriptorSet::default();
+
+        for (name, entry) in layout.names.iter().zip(&layout.set_layout) {
+            let resource = self
+                .resources
+                .iter()
+                .find(|(slot, _)| slot == name)
+                .map(|(_, resource)| resource.as_bindable())
+                .ok_or_else(|| DescriptorSetError::MissingSlot(name.clone()))?;
+
+            desc = resource.bind_in(desc, entry.binding, Some(entry))?;
+        }
+
+        kernel.create_bind_group(index, &desc)
+    }
+}
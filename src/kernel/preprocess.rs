@@ -0,0 +1,823 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{Framework, Shader, ShaderBuilder};
+
+use super::{ShaderError, ShaderResult};
+
+/// Origin of the lines of a preprocessed `WGSL` source, to report errors
+/// against the files they come from.
+pub(crate) struct SourceMap {
+    /// First line of each segment of the output, with the file and line it comes from. 1-based.
+    segments: Vec<(usize, String, usize)>,
+}
+
+impl SourceMap {
+    /// Maps each line of a source to the same line of the file `name`.
+    pub(crate) fn new(name: &str) -> Self {
+        Self {
+            segments: vec![(1, name.to_string(), 1)],
+        }
+    }
+
+    /// Returns the file and line the 1-based `line` of the output comes from.
+    pub(crate) fn locate(&self, line: usize) -> (&str, usize) {
+        match self
+            .segments
+            .iter()
+            .rev()
+            .find(|(start, ..)| *start <= line)
+        {
+            Some((start, file, file_line)) => (file, file_line + line - start),
+            None => (&self.segments[0].1, line),
+        }
+    }
+}
+
+/// Resolves the include and conditional directives of `WGSL` sources,
+/// see [`preprocess_wgsl`] and [`Shader::from_wgsl_file_with_defines`].
+struct Preprocessor<'d, R> {
+    /// Returns the name and source of the file included as its second argument
+    /// by the file named as its first argument.
+    resolver: R,
+    defines: &'d HashSet<String>,
+    output: String,
+    lines: usize,
+    map: SourceMap,
+    /// Files being processed, from the root one.
+    stack: Vec<String>,
+    included: Vec<String>,
+}
+
+/// Conditional block opened by a `//#if` directive.
+struct Condition {
+    /// Line of the `//#if`, 1-based.
+    line: usize,
+    /// Whether the lines around the block are kept.
+    outer_active: bool,
+    /// Whether the lines of the current branch are kept.
+    active: bool,
+    in_else: bool,
+}
+
+/// Conditional compilation directive.
+enum Conditional<'a> {
+    If(&'a str),
+    Else,
+    Endif,
+    Malformed,
+}
+
+impl<'d, R> Preprocessor<'d, R>
+where
+    R: FnMut(&str, &str) -> ShaderResult<(String, String)>,
+{
+    fn run(
+        name: &str,
+        source: &str,
+        defines: &'d HashSet<String>,
+        resolver: R,
+    ) -> ShaderResult<(String, SourceMap)> {
+        let mut preprocessor = Self {
+            resolver,
+            defines,
+            output: String::with_capacity(source.len()),
+            lines: 0,
+            map: SourceMap {
+                segments: Vec::new(),
+            },
+            stack: Vec::new(),
+            included: Vec::new(),
+        };
+
+        preprocessor.process(name, source)?;
+
+        if preprocessor.map.segments.is_empty() {
+            preprocessor.map = SourceMap::new(name);
+        }
+
+        Ok((preprocessor.output, preprocessor.map))
+    }
+
+    fn process(&mut self, name: &str, source: &str) -> ShaderResult<()> {
+        self.stack.push(name.to_string());
+        self.included.push(name.to_string());
+
+        let mut new_segment = true;
+        let mut conditions: Vec<Condition> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let active = !matches!(conditions.last(), Some(Condition { active: false, .. }));
+
+            if let Some(conditional) = parse_conditional(line) {
+                let malformed = || ShaderError::MalformedConditional {
+                    file: name.to_string(),
+                    line: index + 1,
+                };
+
+                match conditional {
+                    Conditional::If(define) => conditions.push(Condition {
+                        line: index + 1,
+                        outer_active: active,
+                        active: active && self.defines.contains(define),
+                        in_else: false,
+                    }),
+                    Conditional::Else => {
+                        let condition = conditions
+                            .last_mut()
+                            .filter(|condition| !condition.in_else)
+                            .ok_or_else(malformed)?;
+
+                        condition.active = condition.outer_active && !condition.active;
+                        condition.in_else = true;
+                    }
+                    Conditional::Endif => {
+                        conditions.pop().ok_or_else(malformed)?;
+                    }
+                    Conditional::Malformed => return Err(malformed()),
+                }
+
+                new_segment = true;
+                continue;
+            }
+
+            if !active {
+                new_segment = true;
+                continue;
+            }
+
+            if let Some(include) = parse_include(line) {
+                let include = include.ok_or_else(|| ShaderError::MalformedInclude {
+                    file: name.to_string(),
+                    line: index + 1,
+                })?;
+                let (included_name, included) = (self.resolver)(name, include)?;
+
+                if self.stack.contains(&included_name) {
+                    let mut cycle = self.stack.clone();
+                    cycle.push(included_name);
+
+                    return Err(ShaderError::IncludeCycle(cycle));
+                }
+
+                if !self.included.contains(&included_name) {
+                    self.process(&included_name, &included)?;
+                }
+
+                new_segment = true;
+                continue;
+            }
+
+            if new_segment {
+                self.map
+                    .segments
+                    .push((self.lines + 1, name.to_string(), index + 1));
+                new_segment = false;
+            }
+
+            self.output.push_str(line);
+            self.output.push('\n');
+            self.lines += 1;
+        }
+
+        if let Some(condition) = conditions.last() {
+            return Err(ShaderError::UnterminatedConditional {
+                file: name.to_string(),
+                line: condition.line,
+            });
+        }
+
+        self.stack.pop();
+
+        Ok(())
+    }
+}
+
+/// Returns the file named by `line` if it is an include directive,
+/// or `Some(None)` if the directive is malformed.
+fn parse_include(line: &str) -> Option<Option<&str>> {
+    let line = line.trim();
+    let rest = line
+        .strip_prefix("//!include")
+        .or_else(|| line.strip_prefix("#include"))?;
+
+    if !rest.starts_with(|c: char| c.is_whitespace() || c == '"') {
+        return None;
+    }
+
+    let name = rest
+        .trim_start()
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .filter(|name| !name.is_empty() && !name.contains('"'));
+
+    Some(name)
+}
+
+/// Returns the conditional directive of `line`, if it is one.
+fn parse_conditional(line: &str) -> Option<Conditional<'_>> {
+    let line = line.trim();
+    let (directive, rest) = line
+        .strip_prefix("//#")?
+        .split_once(char::is_whitespace)
+        .unwrap_or((&line[3..], ""));
+    let rest = rest.trim();
+
+    let conditional = match directive {
+        "if" => match rest.split_whitespace().count() {
+            1 => Conditional::If(rest),
+            _ => Conditional::Malformed,
+        },
+        "else" => Conditional::Else,
+        "endif" => Conditional::Endif,
+        _ => return None,
+    };
+
+    Some(conditional)
+}
+
+/// Resolves the `//!include "file.wgsl"` and `#include "file.wgsl"` directives
+/// of a `WGSL` `source`, replacing each of them with the source of the file
+/// `resolver` returns for its name, e.g. from a custom asset loader.
+///
+/// Included sources are preprocessed recursively. A file is only included
+/// the first time it is encountered, so several files can include the same
+/// shared structs. Fails with [`ShaderError::IncludeCycle`] if a file includes itself,
+/// directly or not.
+///
+/// The conditional blocks of the sources are removed, as nothing is defined,
+/// see [`Shader::from_wgsl_file_with_defines`].
+pub fn preprocess_wgsl(
+    source: &str,
+    mut resolver: impl FnMut(&str) -> ShaderResult<String>,
+) -> ShaderResult<String> {
+    let (source, _) =
+        Preprocessor::run("wgsl", source, &HashSet::new(), |_: &str, include: &str| {
+            Ok((include.to_string(), resolver(include)?))
+        })?;
+
+    Ok(source)
+}
+
+/// Replaces the `{{NAME}}` placeholders of a `WGSL` `source` with the value
+/// `substitutions` gives for `NAME`, e.g. to compile a type-generic kernel for a type:
+///
+/// ```ignore
+/// let source = substitute_wgsl(template, &[("T", "f32"), ("OP", "a + b")])?;
+/// ```
+///
+/// Fails with [`ShaderError::MissingSubstitution`] if a placeholder has no value.
+/// Values are inserted as is, not searched for placeholders themselves.
+/// It can be applied to the output of [`preprocess_wgsl`] to substitute included files too.
+pub fn substitute_wgsl(source: &str, substitutions: &[(&str, &str)]) -> ShaderResult<String> {
+    substitute("wgsl", source, substitutions).map(|(source, _)| source)
+}
+
+/// Substitutes the placeholders of `source` like [`substitute_wgsl`],
+/// mapping the lines of the output to the lines of the file `name`.
+fn substitute(
+    name: &str,
+    source: &str,
+    substitutions: &[(&str, &str)],
+) -> ShaderResult<(String, SourceMap)> {
+    let mut output = String::with_capacity(source.len());
+    let mut map = SourceMap::new(name);
+    let mut rest = source;
+    // Lines of `source` and `output` the end of `rest` and `output` are at.
+    let mut line = 1;
+    let mut output_line = 1;
+
+    while let Some(start) = rest.find("{{") {
+        let (before, placeholder) = rest.split_at(start);
+        line += before.matches('\n').count();
+        output_line += before.matches('\n').count();
+        output.push_str(before);
+
+        let end = placeholder
+            .find("}}")
+            .filter(|end| !placeholder[..*end].contains('\n'))
+            .ok_or(ShaderError::UnterminatedPlaceholder { line })?;
+        let key = placeholder[2..end].trim();
+
+        let value = substitutions
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| ShaderError::MissingSubstitution {
+                placeholder: key.to_string(),
+                line,
+            })?;
+        output.push_str(value);
+
+        // The lines after a multi-line value keep pointing at the lines of `source`.
+        let value_lines = value.matches('\n').count();
+        if value_lines > 0 {
+            output_line += value_lines;
+            map.segments.push((output_line, name.to_string(), line));
+        }
+
+        rest = &placeholder[end + 2..];
+    }
+
+    output.push_str(rest);
+
+    Ok((output, map))
+}
+
+/// Attributes of the legacy `[[attribute]]` syntax, telling it from nested indexing.
+const LEGACY_ATTRIBUTES: &[&str] = &[
+    "align",
+    "binding",
+    "block",
+    "builtin",
+    "group",
+    "id",
+    "interpolate",
+    "invariant",
+    "location",
+    "size",
+    "stage",
+    "stride",
+    "workgroup_size",
+];
+
+/// Returns the offset of the first list of attributes of the legacy syntax in `source`.
+fn find_legacy_attributes(source: &str) -> Option<usize> {
+    source
+        .match_indices("[[")
+        .map(|(start, _)| start)
+        .find(|start| {
+            let rest = source[start + 2..].trim_start();
+            let name = rest
+                .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .next()
+                .unwrap_or_default();
+
+            LEGACY_ATTRIBUTES.contains(&name)
+        })
+}
+
+/// Rewrites the legacy `[[attribute]]` syntax of a `WGSL` `source`, which `naga` no longer
+/// supports, to the `@attribute` one:
+///
+/// - `[[group(0), binding(0)]]` becomes `@group(0) @binding(0)`,
+/// - `[[stage(compute), workgroup_size(64)]]` becomes `@compute @workgroup_size(64)`,
+/// - `[[block]]` and `[[stride(N)]]` are removed, as structs no longer need to be marked
+///   and arrays always have the stride of their elements,
+/// - the members of structs are separated by `,` instead of `;`.
+///
+/// Lists of attributes can span several lines, which the output keeps, so errors
+/// in the migrated shader are at the same lines as in `source`. The rest of the source
+/// is left as is: removed `[[stride(N)]]` that did not match the size of the elements
+/// must be replaced by padding.
+///
+/// Fails with [`ShaderError::UnterminatedAttributes`] if a list is not closed by `]]`.
+pub fn migrate_legacy_wgsl(source: &str) -> ShaderResult<String> {
+    let mut output = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = find_legacy_attributes(rest) {
+        let (before, list) = rest.split_at(start);
+        output.push_str(before);
+
+        let end = list
+            .find("]]")
+            .ok_or_else(|| ShaderError::UnterminatedAttributes {
+                line: source[..source.len() - list.len()].matches('\n').count() + 1,
+            })?;
+        let attributes = migrate_attributes(&list[2..end]);
+        output.push_str(&attributes);
+
+        rest = &list[end + 2..];
+        // `data: [[stride(4)]] array<u32>` becomes `data: array<u32>`.
+        if attributes.trim().is_empty() {
+            rest = rest.strip_prefix(' ').unwrap_or(rest);
+        }
+    }
+
+    output.push_str(rest);
+
+    Ok(migrate_struct_members(&output))
+}
+
+/// Separates the members of the structs of `source` by `,` instead of `;`.
+fn migrate_struct_members(source: &str) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find("struct") {
+        let is_keyword = !rest[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_')
+            && rest[start + 6..].starts_with(char::is_whitespace);
+        let body = match rest[start..].find('{') {
+            Some(body) if is_keyword => start + body,
+            _ => {
+                output.push_str(&rest[..start + 6]);
+                rest = &rest[start + 6..];
+                continue;
+            }
+        };
+
+        output.push_str(&rest[..=body]);
+        rest = &rest[body + 1..];
+
+        let mut in_comment = false;
+        let mut end = rest.len();
+
+        for (index, c) in rest.char_indices() {
+            match c {
+                '}' if !in_comment => {
+                    end = index;
+                    break;
+                }
+                '/' if rest[index..].starts_with("//") => in_comment = true,
+                '\n' => in_comment = false,
+                _ => {}
+            }
+
+            output.push(if c == ';' && !in_comment { ',' } else { c });
+        }
+
+        rest = &rest[end..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Rewrites the comma-separated legacy `attributes` of a list, keeping their line breaks.
+fn migrate_attributes(attributes: &str) -> String {
+    let mut items = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+
+    for (index, c) in attributes.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&attributes[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&attributes[start..]);
+
+    items
+        .into_iter()
+        .map(|item| {
+            let attribute = item.trim();
+            let leading = &item[..item.len() - item.trim_start().len()];
+            let trailing = &item[item.trim_end().len()..];
+            let line_breaks = |space: &str| {
+                if space.contains('\n') {
+                    space.to_string()
+                } else {
+                    String::new()
+                }
+            };
+
+            let name = attribute
+                .split(|c: char| c == '(' || c.is_whitespace())
+                .next()
+                .unwrap_or_default();
+
+            let migrated = match name {
+                "block" | "stride" | "" => String::new(),
+                "stage" => format!(
+                    "@{}",
+                    attribute[name.len()..]
+                        .trim()
+                        .trim_start_matches('(')
+                        .trim_end_matches(')')
+                        .trim()
+                ),
+                _ => format!("@{}", attribute),
+            };
+
+            format!(
+                "{}{}{}",
+                line_breaks(leading),
+                migrated,
+                line_breaks(trailing)
+            )
+        })
+        .filter(|item| !item.is_empty())
+        .fold(String::new(), |mut list, item| {
+            // Items on their own line are already separated by it.
+            if !list.is_empty() && !item.starts_with(char::is_whitespace) {
+                list.push(' ');
+            }
+            list.push_str(&item);
+            list
+        })
+}
+
+/// Checks whether the `WGSL` `source` of the shader `name` uses the legacy attribute syntax,
+/// returning it migrated if `migrate` is set, see [`Framework::set_migrate_legacy_wgsl`].
+pub(crate) fn check_legacy_wgsl(
+    source: &str,
+    name: &str,
+    migrate: bool,
+) -> ShaderResult<Option<String>> {
+    if find_legacy_attributes(source).is_none() {
+        return Ok(None);
+    }
+
+    if !migrate {
+        return Err(ShaderError::LegacyWgsl(name.to_string()));
+    }
+
+    event!(
+        warn,
+        "`{}` uses the legacy `[[attribute]]` syntax, migrated at runtime: convert it with `migrate_legacy_wgsl`",
+        name
+    );
+
+    migrate_legacy_wgsl(source).map(Some)
+}
+
+/// Name of the injected constants in the errors of a [`ShaderBuilder`].
+const CONSTANTS_NAME: &str = "<constants>";
+
+impl<'a> ShaderBuilder<'a> {
+    /// Creates a [`ShaderBuilder`] of the `WGSL` `source`, without constants.
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            name: None,
+            constants: Vec::new(),
+        }
+    }
+
+    /// Names the [`Shader`], in the errors and in GPU debuggers.
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Declares the `u32` constant `name`, replacing any constant previously declared with the same name.
+    pub fn constant_u32(self, name: impl Into<String>, value: u32) -> Self {
+        self.constant(name.into(), format!("{}u", value), "u32")
+    }
+
+    /// Declares the `i32` constant `name`, replacing any constant previously declared with the same name.
+    pub fn constant_i32(self, name: impl Into<String>, value: i32) -> Self {
+        self.constant(name.into(), value.to_string(), "i32")
+    }
+
+    /// Declares the `f32` constant `name`, replacing any constant previously declared with the same name.
+    ///
+    /// [`ShaderBuilder::build`] fails if `value` is not finite, as `WGSL` has no literal for it.
+    pub fn constant_f32(self, name: impl Into<String>, value: f32) -> Self {
+        // The `Debug` representation always has a fractional part or an exponent.
+        self.constant(name.into(), format!("{:?}", value), "f32")
+    }
+
+    /// Declares the `bool` constant `name`, replacing any constant previously declared with the same name.
+    pub fn constant_bool(self, name: impl Into<String>, value: bool) -> Self {
+        self.constant(name.into(), value.to_string(), "bool")
+    }
+
+    fn constant(mut self, name: String, value: String, ty: &'static str) -> Self {
+        self.constants.retain(|(constant, ..)| *constant != name);
+        self.constants.push((name, value, ty));
+        self
+    }
+
+    /// Creates the [`Shader`] from the source with the constants declared before it.
+    ///
+    /// Fails with [`ShaderError::ConstantCollision`] if the source already declares
+    /// something named like a constant, and like [`Shader::from_wgsl_source`] if the shader
+    /// is not valid. Errors are reported against the lines of the source.
+    pub fn build(&self, fw: &Framework) -> ShaderResult<Shader> {
+        let declared = declared_names(self.source);
+        let mut source = String::new();
+
+        for (name, value, ty) in &self.constants {
+            let invalid = |reason| ShaderError::InvalidConstant {
+                name: name.clone(),
+                reason,
+            };
+
+            if !is_identifier(name) {
+                return Err(invalid("its name is not an identifier"));
+            }
+            if *ty == "f32" && !matches!(value.parse::<f32>(), Ok(value) if value.is_finite()) {
+                return Err(invalid("its value is not finite"));
+            }
+            if declared.contains(&name.as_str()) {
+                return Err(ShaderError::ConstantCollision(name.clone()));
+            }
+
+            source.push_str(&format!("let {}: {} = {};\n", name, ty, value));
+        }
+
+        let file = self.name.unwrap_or("wgsl");
+        let map = SourceMap {
+            segments: vec![
+                (1, CONSTANTS_NAME.to_string(), 1),
+                (self.constants.len() + 1, file.to_string(), 1),
+            ],
+        };
+        source.push_str(self.source);
+
+        Shader::from_wgsl_mapped(fw, &source, self.name, &map)
+    }
+}
+
+/// `name` can name a `WGSL` declaration.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Names following a declaration keyword in `source`. Comments are not skipped,
+/// so a name mentioned after a keyword in a comment counts as declared.
+fn declared_names(source: &str) -> Vec<&str> {
+    const KEYWORDS: [&str; 6] = ["let", "var", "fn", "struct", "type", "override"];
+
+    let words = source
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    words
+        .windows(2)
+        .filter(|pair| KEYWORDS.contains(&pair[0]))
+        .map(|pair| pair[1])
+        .collect()
+}
+
+impl Shader {
+    /// Initialises a [`Shader`] from a `WGSL` template with an optional `name`,
+    /// replacing its placeholders like [`substitute_wgsl`].
+    ///
+    /// Errors in the shader are reported against the lines of the template.
+    pub fn from_wgsl_template(
+        fw: &Framework,
+        source: &str,
+        substitutions: &[(&str, &str)],
+        name: Option<&str>,
+    ) -> ShaderResult<Self> {
+        let (source, map) = substitute(name.unwrap_or("wgsl"), source, substitutions)?;
+
+        Self::from_wgsl_mapped(fw, &source, name, &map)
+    }
+
+    /// Initialises a [`Shader`] from a `WGSL` file, resolving its include directives
+    /// like [`preprocess_wgsl`].
+    ///
+    /// Included files are looked up relative to the file including them, then in each
+    /// of the `include_dirs` in order. Errors in the shader are reported against the
+    /// file and line they come from.
+    pub fn from_wgsl_file_with_includes(
+        fw: &Framework,
+        path: impl AsRef<Path>,
+        include_dirs: &[PathBuf],
+    ) -> ShaderResult<Self> {
+        let path = path.as_ref().canonicalize()?;
+        let (source, map) = preprocess_file(&path, include_dirs, &HashSet::new())?;
+
+        Self::from_wgsl_mapped(fw, &source, path.to_str(), &map)
+    }
+
+    /// Initialises a [`Shader`] from a `WGSL` file, keeping the conditional blocks
+    /// of the names in `defines`:
+    ///
+    /// ```ignore
+    /// //#if BOUNDS_CHECKS
+    /// if (index >= arrayLength(&input.data)) { return; }
+    /// //#else
+    /// // Unchecked.
+    /// //#endif
+    /// ```
+    ///
+    /// Blocks can be nested, and must be closed in the file opening them.
+    /// The include directives are resolved relative to the including file like
+    /// [`Shader::from_wgsl_file_with_includes`], except in removed blocks.
+    /// Errors in the shader are reported against the file and line they come from.
+    pub fn from_wgsl_file_with_defines(
+        fw: &Framework,
+        path: impl AsRef<Path>,
+        defines: &HashSet<String>,
+    ) -> ShaderResult<Self> {
+        let path = path.as_ref().canonicalize()?;
+        let (source, map) = preprocess_file(&path, &[], defines)?;
+
+        Self::from_wgsl_mapped(fw, &source, path.to_str(), &map)
+    }
+}
+
+/// Reads the `WGSL` file at the canonical `path` and resolves its include directives,
+/// looking the included files up like [`Shader::from_wgsl_file_with_includes`],
+/// and its conditional blocks from `defines`.
+pub(crate) fn preprocess_file(
+    path: &Path,
+    include_dirs: &[PathBuf],
+    defines: &HashSet<String>,
+) -> ShaderResult<(String, SourceMap)> {
+    let source = std::fs::read_to_string(path)?;
+
+    let resolver = |file: &str, include: &str| {
+        let dir = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+
+        let included = std::iter::once(dir)
+            .chain(include_dirs.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(include))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| ShaderError::IncludeNotFound {
+                include: include.to_string(),
+                file: file.to_string(),
+            })?
+            .canonicalize()?;
+
+        let source = std::fs::read_to_string(&included)?;
+
+        Ok((included.display().to_string(), source))
+    };
+
+    Preprocessor::run(&path.display().to_string(), &source, defines, resolver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY: &str = r#"[[block]]
+struct Params {
+    len: u32;
+    // Elements; not members.
+    factor: f32;
+};
+
+[[group(0), binding(0)]] var<storage, read_write> data: [[stride(4)]] array<f32>;
+[[group(0),
+  binding(1)]] var<uniform> params: Params;
+
+[[stage(compute), workgroup_size(64, 1, 1)]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {
+    if (global_id.x < params.len) {
+        data[global_id.x] = data[global_id.x] * params.factor;
+    }
+}
+"#;
+
+    const MIGRATED: &str = r#"
+struct Params {
+    len: u32,
+    // Elements; not members.
+    factor: f32,
+};
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+@group(0)
+  @binding(1) var<uniform> params: Params;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if (global_id.x < params.len) {
+        data[global_id.x] = data[global_id.x] * params.factor;
+    }
+}
+"#;
+
+    #[test]
+    fn legacy_attributes_are_migrated() {
+        let migrated = migrate_legacy_wgsl(LEGACY).unwrap();
+
+        assert_eq!(migrated, MIGRATED);
+        // Errors in the migrated shader are at the same lines as in the legacy one.
+        assert_eq!(migrated.lines().count(), LEGACY.lines().count());
+        naga::front::wgsl::parse_str(&migrated).unwrap();
+    }
+
+    #[test]
+    fn migrated_sources_are_left_as_is() {
+        let migrated = migrate_legacy_wgsl(LEGACY).unwrap();
+
+        assert_eq!(find_legacy_attributes(&migrated), None);
+        assert_eq!(migrate_legacy_wgsl(&migrated).unwrap(), migrated);
+    }
+
+    #[test]
+    fn arrays_of_arrays_are_not_attributes() {
+        let source = "var<private> m: array<array<f32, 2>, 2>;\nfn f() { let x = m[0][1]; }\n";
+
+        assert_eq!(find_legacy_attributes(source), None);
+        assert_eq!(migrate_legacy_wgsl(source).unwrap(), source);
+    }
+
+    #[test]
+    fn unterminated_attributes_are_located() {
+        let source = "struct Params {\n    len: u32,\n};\n\n[[group(0), binding(0)\nvar<uniform> params: Params;\n";
+
+        assert!(matches!(
+            migrate_legacy_wgsl(source),
+            Err(ShaderError::UnterminatedAttributes { line: 5 })
+        ));
+    }
+}
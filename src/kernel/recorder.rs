@@ -0,0 +1,351 @@
+use std::cell::Cell;
+use std::sync::Arc;
+
+use crate::{
+    primitives::{BufOps, ImgOps, PixelInfo},
+    CommandRecorder, Framework, GpuBuffer, GpuConstImage, GpuImage, Kernel, Submission,
+};
+
+use super::{KernelError, KernelResult};
+
+/// Command recorded by a [`CommandRecorder`].
+pub(crate) enum RecordedCommand<'rec> {
+    /// The pipeline is shared with the kernel, so that the dispatches of kernels
+    /// created for a single recording outlive them.
+    Dispatch {
+        pipeline: Arc<wgpu::ComputePipeline>,
+        sets: Vec<Arc<wgpu::BindGroup>>,
+        label: &'rec str,
+        workgroups: (u32, u32, u32),
+    },
+    CopyBuffer {
+        src: &'rec wgpu::Buffer,
+        dst: &'rec wgpu::Buffer,
+        size: u64,
+    },
+    CopyImage {
+        src: &'rec wgpu::Texture,
+        dst: &'rec wgpu::Texture,
+        size: wgpu::Extent3d,
+    },
+    /// Taken out when the commands are encoded, as they are encoded by reference.
+    Encoder(Cell<Option<EncoderCallback<'rec>>>),
+}
+
+/// Commands of the user, recorded by [`CommandRecorder::with_encoder`].
+pub(crate) type EncoderCallback<'rec> = Box<dyn FnOnce(&mut wgpu::CommandEncoder) + 'rec>;
+
+impl Submission<'_> {
+    /// Blocks until the GPU has finished the work of this submission.
+    pub fn wait(&self) {
+        self.fw
+            .device
+            .poll(wgpu::Maintain::WaitForSubmissionIndex(self.index));
+    }
+
+    /// Returns a future that resolves once the GPU has finished the work of this submission,
+    /// from the polling thread of the [`Framework`] like the callback of [`Submission::on_done`].
+    pub fn wait_async(&self) -> impl std::future::Future<Output = ()> {
+        self.fw.work_done()
+    }
+
+    /// Registers `callback` to be called once the GPU has finished the work of this submission,
+    /// without blocking.
+    ///
+    /// The callback is called from the polling thread of the [`Framework`], so it runs
+    /// at most its `polling_time` after the work is done. It runs after every submission
+    /// enqueued before `on_done` is called has finished, which can be later than
+    /// this one alone.
+    pub fn on_done(&self, callback: impl FnOnce() + Send + 'static) {
+        self.fw.queue.on_submitted_work_done(callback);
+    }
+}
+
+impl Framework {
+    /// Creates an empty [`CommandRecorder`].
+    pub fn create_command_recorder(&self) -> CommandRecorder<'_> {
+        CommandRecorder {
+            fw: self,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Warms up each of the `kernels` like [`Kernel::warm_up`], in a single submission.
+    pub fn warm_up_all(&self, kernels: &[&Kernel]) -> KernelResult<()> {
+        let mut recorder = self.create_command_recorder();
+
+        for kernel in kernels {
+            recorder.enqueue(kernel, 0, 0, 0)?;
+        }
+
+        recorder.submit().wait();
+
+        Ok(())
+    }
+}
+
+impl<'rec> CommandRecorder<'rec> {
+    /// Records the execution of `kernel` with its current [`DescriptorSet`](crate::DescriptorSet)s,
+    /// dispatching `x`, `y` and `z` workgroups per dimension.
+    ///
+    /// Fails if a descriptor set of `kernel` has no resources bound,
+    /// or if the workgroup counts exceed [`Kernel::max_dispatch`].
+    pub fn enqueue(&mut self, kernel: &'rec Kernel, x: u32, y: u32, z: u32) -> KernelResult<()> {
+        self.enqueue_labeled(kernel, &kernel.label, x, y, z)
+    }
+
+    /// Records the execution of `kernel` like [`CommandRecorder::enqueue`],
+    /// naming the dispatch `label` in GPU debuggers instead of the label of `kernel`.
+    pub fn enqueue_labeled(
+        &mut self,
+        kernel: &'rec Kernel,
+        label: &'rec str,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> KernelResult<()> {
+        self.push_bound_dispatch(kernel, label, (x, y, z))
+    }
+
+    /// Records the execution of `kernel` with its current descriptor sets, named `label`,
+    /// like [`CommandRecorder::push_dispatch`].
+    pub(crate) fn push_bound_dispatch(
+        &mut self,
+        kernel: &Kernel,
+        label: &'rec str,
+        workgroups: (u32, u32, u32),
+    ) -> KernelResult<()> {
+        let sets = kernel
+            .sets
+            .iter()
+            .enumerate()
+            .map(|(index, set)| set.clone().ok_or(KernelError::DescriptorSetNotBound(index)))
+            .collect::<KernelResult<Vec<_>>>()?;
+
+        self.push_dispatch(kernel, label, sets, workgroups)
+    }
+
+    /// Records the execution of `kernel` with `sets` bound, named `label`.
+    ///
+    /// `kernel` can be dropped before the submission: its pipeline and bind groups are
+    /// kept until then, and the resources bound in them by the bind groups.
+    pub(crate) fn push_dispatch(
+        &mut self,
+        kernel: &Kernel,
+        label: &'rec str,
+        sets: Vec<Arc<wgpu::BindGroup>>,
+        (x, y, z): (u32, u32, u32),
+    ) -> KernelResult<()> {
+        kernel.check_workgroups(x, y, z)?;
+        // Recorded dispatches have no dynamic offsets.
+        kernel.check_offsets(&[])?;
+
+        self.commands.push(RecordedCommand::Dispatch {
+            pipeline: Arc::clone(&kernel.pipeline),
+            sets,
+            label,
+            workgroups: (x, y, z),
+        });
+
+        Ok(())
+    }
+
+    /// Records a copy of the elements of `src` into `dst`.
+    ///
+    /// Only as many elements as both buffers can hold are copied.
+    pub fn copy_buffer<T>(&mut self, src: &'rec GpuBuffer<T>, dst: &'rec GpuBuffer<T>)
+    where
+        T: bytemuck::Pod,
+    {
+        self.commands.push(RecordedCommand::CopyBuffer {
+            src: src.as_gpu_buffer(),
+            dst: dst.as_gpu_buffer(),
+            size: src.size().min(dst.size()),
+        });
+    }
+
+    /// Records a copy of the pixels of `src` into `dst`.
+    ///
+    /// Only the area both images share from their top-left corner is copied.
+    pub fn copy_image<P: PixelInfo>(&mut self, src: &'rec GpuImage<P>, dst: &'rec GpuImage<P>) {
+        self.push_image_copy(src, dst);
+    }
+
+    /// Records a copy of the pixels of `src` into `dst`, e.g. to read the output
+    /// of a [`Kernel`] as a texture in the next one.
+    ///
+    /// Only the area both images share from their top-left corner is copied.
+    pub fn copy_to_const_image<P: PixelInfo>(
+        &mut self,
+        src: &'rec GpuImage<P>,
+        dst: &'rec GpuConstImage<P>,
+    ) {
+        self.push_image_copy(src, dst);
+    }
+
+    /// Records `f`, called with the [`wgpu::CommandEncoder`] of the submission between the
+    /// commands recorded before and after it, e.g. to draw the output of a [`Kernel`]
+    /// with a render pass in the same submission.
+    ///
+    /// No pass of the recorder is open while `f` runs. `f` can record its own passes
+    /// and copies, but must not finish the encoder.
+    ///
+    /// ```ignore
+    /// // The vertex shader reads the particles the kernel just moved:
+    /// // @group(0) @binding(0) var<storage, read> particles: array<vec2<f32>>;
+    /// //
+    /// // @vertex
+    /// // fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    /// //     return vec4<f32>(particles[index], 0.0, 1.0);
+    /// // }
+    /// let particles = GpuBuffer::<[f32; 2]>::from_slice(&fw, &positions);
+    /// let particles_group = fw.as_gpu_device().create_bind_group(&wgpu::BindGroupDescriptor {
+    ///     label: None,
+    ///     layout: &points_pipeline.get_bind_group_layout(0),
+    ///     entries: &[wgpu::BindGroupEntry {
+    ///         binding: 0,
+    ///         resource: particles.as_gpu_buffer().as_entire_binding(),
+    ///     }],
+    /// });
+    ///
+    /// let mut recorder = fw.create_command_recorder();
+    /// recorder.enqueue(&simulate, workgroups, 1, 1)?;
+    /// recorder.with_encoder(|encoder| {
+    ///     let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+    ///         label: Some("points"),
+    ///         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+    ///             view: &target_view,
+    ///             resolve_target: None,
+    ///             ops: wgpu::Operations {
+    ///                 load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+    ///                 store: true,
+    ///             },
+    ///         })],
+    ///         depth_stencil_attachment: None,
+    ///     });
+    ///     pass.set_pipeline(&points_pipeline); // `PrimitiveTopology::PointList`
+    ///     pass.set_bind_group(0, &particles_group, &[]);
+    ///     pass.draw(0..particles.capacity() as u32, 0..1);
+    /// });
+    /// recorder.submit();
+    /// ```
+    pub fn with_encoder(&mut self, f: impl FnOnce(&mut wgpu::CommandEncoder) + 'rec) {
+        self.commands
+            .push(RecordedCommand::Encoder(Cell::new(Some(Box::new(f)))));
+    }
+
+    /// Enqueues every recorded command onto the GPU in a single submission,
+    /// in the order they were recorded.
+    ///
+    /// Consecutive dispatches share a compute pass. Each of them still sees
+    /// the writes of the previous ones.
+    pub fn submit(self) -> Submission<'rec> {
+        let mut encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("CommandRecorder::submit"),
+            });
+
+        #[cfg(feature = "profiler")]
+        let submission_scope = self
+            .fw
+            .begin_profile_scope(&mut encoder, "CommandRecorder::submit");
+
+        let markers = self.fw.debug_markers();
+        let mut commands = self.commands.iter().peekable();
+
+        while let Some(command) = commands.next() {
+            match command {
+                RecordedCommand::Dispatch {
+                    pipeline,
+                    sets,
+                    label,
+                    workgroups,
+                } => {
+                    #[cfg(feature = "profiler")]
+                    let scope = self.fw.begin_profile_scope(&mut encoder, label);
+                    // Profiled dispatches get their own pass to be timed separately.
+                    #[cfg(feature = "profiler")]
+                    let share_pass = scope.is_none();
+                    #[cfg(not(feature = "profiler"))]
+                    let share_pass = true;
+
+                    let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                        label: Some("CommandRecorder::submit"),
+                    });
+
+                    super::record_dispatch_in(
+                        &mut cpass,
+                        pipeline,
+                        sets.iter().map(Arc::as_ref),
+                        &[],
+                        Some(*label).filter(|_| markers),
+                        *workgroups,
+                    );
+
+                    if share_pass {
+                        while let Some(RecordedCommand::Dispatch {
+                            pipeline,
+                            sets,
+                            label,
+                            workgroups,
+                        }) = commands.peek()
+                        {
+                            super::record_dispatch_in(
+                                &mut cpass,
+                                pipeline,
+                                sets.iter().map(Arc::as_ref),
+                                &[],
+                                Some(*label).filter(|_| markers),
+                                *workgroups,
+                            );
+                            commands.next();
+                        }
+                    }
+
+                    drop(cpass);
+                    #[cfg(feature = "profiler")]
+                    self.fw.end_profile_scope(&mut encoder, scope);
+                }
+                RecordedCommand::CopyBuffer { src, dst, size } => {
+                    encoder.copy_buffer_to_buffer(src, 0, dst, 0, *size);
+                }
+                RecordedCommand::CopyImage { src, dst, size } => {
+                    encoder.copy_texture_to_texture(
+                        src.as_image_copy(),
+                        dst.as_image_copy(),
+                        *size,
+                    );
+                }
+                RecordedCommand::Encoder(callback) => {
+                    if let Some(callback) = callback.take() {
+                        callback(&mut encoder);
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "profiler")]
+        self.fw.end_profile_scope(&mut encoder, submission_scope);
+
+        let index = self.fw.queue.submit(Some(encoder.finish()));
+
+        Submission { fw: self.fw, index }
+    }
+
+    fn push_image_copy<'fw>(&mut self, src: &'rec impl ImgOps<'fw>, dst: &'rec impl ImgOps<'fw>) {
+        let (src_width, src_height) = src.dimensions();
+        let (dst_width, dst_height) = dst.dimensions();
+
+        self.commands.push(RecordedCommand::CopyImage {
+            src: src.as_gpu_texture(),
+            dst: dst.as_gpu_texture(),
+            size: wgpu::Extent3d {
+                width: src_width.min(dst_width),
+                height: src_height.min(dst_height),
+                depth_or_array_layers: 1,
+            },
+        });
+    }
+}
@@ -0,0 +1,922 @@
+use std::fmt;
+
+use super::{
+    spirv_words, BindingInfo, KernelError, KernelResult, ResourceSize, ShaderDiagnostic,
+    ShaderError, ShaderResult, SourceMap, SPIRV_MAGIC_NUMBER,
+};
+
+/// Kind of resource expected by a shader binding or provided by a [`DescriptorSet`](crate::DescriptorSet) binding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BindingKind {
+    UniformBuffer,
+    StorageBuffer { read_only: bool },
+    Texture { sample: SampleKind },
+    StorageTexture,
+    Sampler { comparison: bool },
+}
+
+/// Kind of the values read from a texture in the shader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SampleKind {
+    Float,
+    Sint,
+    Uint,
+    Depth,
+}
+
+impl BindingKind {
+    pub(crate) fn from_layout(ty: &wgpu::BindingType) -> Self {
+        match ty {
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                ..
+            } => Self::UniformBuffer,
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                ..
+            } => Self::StorageBuffer {
+                read_only: *read_only,
+            },
+            wgpu::BindingType::Texture { sample_type, .. } => Self::Texture {
+                sample: match sample_type {
+                    wgpu::TextureSampleType::Float { .. } => SampleKind::Float,
+                    wgpu::TextureSampleType::Sint => SampleKind::Sint,
+                    wgpu::TextureSampleType::Uint => SampleKind::Uint,
+                    wgpu::TextureSampleType::Depth => SampleKind::Depth,
+                },
+            },
+            wgpu::BindingType::StorageTexture { .. } => Self::StorageTexture,
+            wgpu::BindingType::Sampler(ty) => Self::Sampler {
+                comparison: *ty == wgpu::SamplerBindingType::Comparison,
+            },
+        }
+    }
+
+    fn from_global(module: &naga::Module, global: &naga::GlobalVariable) -> Option<Self> {
+        match global.space {
+            naga::AddressSpace::Uniform => Some(Self::UniformBuffer),
+            naga::AddressSpace::Storage { access } => Some(Self::StorageBuffer {
+                read_only: !access.contains(naga::StorageAccess::STORE),
+            }),
+            naga::AddressSpace::Handle => match Self::element_type(module, global.ty) {
+                naga::TypeInner::Image {
+                    class: naga::ImageClass::Storage { .. },
+                    ..
+                } => Some(Self::StorageTexture),
+                naga::TypeInner::Image { class, .. } => Some(Self::Texture {
+                    sample: match class {
+                        naga::ImageClass::Sampled {
+                            kind: naga::ScalarKind::Sint,
+                            ..
+                        } => SampleKind::Sint,
+                        naga::ImageClass::Sampled {
+                            kind: naga::ScalarKind::Uint,
+                            ..
+                        } => SampleKind::Uint,
+                        naga::ImageClass::Depth { .. } => SampleKind::Depth,
+                        _ => SampleKind::Float,
+                    },
+                }),
+                naga::TypeInner::Sampler { comparison } => Some(Self::Sampler {
+                    comparison: *comparison,
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Type of the elements of a binding array, or `ty` itself for single bindings.
+    fn element_type(module: &naga::Module, ty: naga::Handle<naga::Type>) -> &naga::TypeInner {
+        match module.types[ty].inner {
+            naga::TypeInner::BindingArray { base, .. } => &module.types[base].inner,
+            ref inner => inner,
+        }
+    }
+
+    /// A shader binding of kind `self` accepts a resource bound as `bound`.
+    ///
+    /// As in `wgpu`, read-only storage buffers in the shader can be bound as read-write.
+    fn accepts(self, bound: Self) -> bool {
+        match (self, bound) {
+            (Self::StorageBuffer { read_only: true }, Self::StorageBuffer { .. }) => true,
+            (expected, bound) => expected == bound,
+        }
+    }
+}
+
+impl fmt::Display for BindingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UniformBuffer => write!(f, "uniform buffer"),
+            Self::StorageBuffer { read_only: true } => write!(f, "read-only storage buffer"),
+            Self::StorageBuffer { read_only: false } => write!(f, "read_write storage buffer"),
+            Self::Texture {
+                sample: SampleKind::Float,
+            } => write!(f, "float texture"),
+            Self::Texture {
+                sample: SampleKind::Sint,
+            } => write!(f, "sint texture"),
+            Self::Texture {
+                sample: SampleKind::Uint,
+            } => write!(f, "uint texture"),
+            Self::Texture {
+                sample: SampleKind::Depth,
+            } => write!(f, "depth texture"),
+            Self::StorageTexture => write!(f, "storage texture"),
+            Self::Sampler { comparison: false } => write!(f, "sampler"),
+            Self::Sampler { comparison: true } => write!(f, "comparison sampler"),
+        }
+    }
+}
+
+/// Interface of a shader: its compute entry points and the resources they use,
+/// e.g. to generate the code binding them.
+///
+/// It is displayed as a summary of one line per entry point and binding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ShaderInfo {
+    pub entry_points: Vec<EntryPointInfo>,
+}
+
+/// Compute entry point of a shader, described by [`ShaderInfo`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EntryPointInfo {
+    pub name: String,
+    pub workgroup_size: (u32, u32, u32),
+    /// Resources used by the entry point, sorted by group and binding.
+    pub bindings: Vec<ShaderBinding>,
+}
+
+/// Resource a shader declares at a binding, described by [`ShaderInfo`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ShaderBinding {
+    pub group: u32,
+    pub binding: u32,
+    /// Name of the variable in the shader, if it has one.
+    pub name: Option<String>,
+    pub kind: BindingKind,
+    /// `WGSL` name of the type of the variable, e.g. `array<f32>`.
+    pub type_name: String,
+    /// Minimum size in bytes of the buffer bound, for buffer bindings.
+    /// Runtime-sized arrays count for one element.
+    pub min_size: Option<u64>,
+}
+
+impl ShaderInfo {
+    /// Reflects a `WGSL` source, without a [`Framework`](crate::Framework).
+    ///
+    /// Fails like [`Shader::from_wgsl_source`](crate::Shader::from_wgsl_source) if the shader is not valid.
+    pub fn from_wgsl(source: &str) -> ShaderResult<Self> {
+        ShaderReflection::from_wgsl(source, &SourceMap::new("wgsl"))
+            .map(|reflection| reflection.info())
+    }
+
+    /// Reflects SPIR-V bytes, without a [`Framework`](crate::Framework).
+    ///
+    /// Fails like [`Shader::from_spirv_bytes`](crate::Shader::from_spirv_bytes) if the shader is not valid.
+    pub fn from_spirv(bytes: &[u8]) -> ShaderResult<Self> {
+        ShaderReflection::from_spirv(&spirv_words(bytes)?).map(|reflection| reflection.info())
+    }
+}
+
+impl fmt::Display for ShaderInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry_point in &self.entry_points {
+            let (x, y, z) = entry_point.workgroup_size;
+            writeln!(
+                f,
+                "entry point `{}`, workgroup_size({}, {}, {})",
+                entry_point.name, x, y, z
+            )?;
+
+            for binding in &entry_point.bindings {
+                write!(f, "    group {} binding {}", binding.group, binding.binding)?;
+                if let Some(name) = &binding.name {
+                    write!(f, " `{}`", name)?;
+                }
+                write!(f, ": {} of `{}`", binding.kind, binding.type_name)?;
+                if let Some(size) = binding.min_size {
+                    write!(f, ", at least {} bytes", size)?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// `naga` representation of a shader, used to check the [`DescriptorSet`](crate::DescriptorSet)s
+/// of a [`Program`](crate::Program) against what the shader declares.
+pub(crate) struct ShaderReflection {
+    module: naga::Module,
+    info: naga::valid::ModuleInfo,
+}
+
+impl ShaderReflection {
+    /// Reflects a `WGSL` source, failing with the `naga` error if it is not valid.
+    /// The error points at the file and line `map` gives for the offending line.
+    pub(crate) fn from_wgsl(source: &str, map: &SourceMap) -> ShaderResult<Self> {
+        let module = naga::front::wgsl::parse_str(source).map_err(|err| {
+            compilation_error(
+                err.message().to_string(),
+                source,
+                err.location(source),
+                map,
+                ShaderError::InvalidWgsl,
+            )
+        })?;
+
+        Self::from_module(module).map_err(|err| {
+            // The last span is the innermost one, e.g. the expression in a function.
+            let location = err
+                .spans()
+                .filter(|(span, _)| span.is_defined())
+                .last()
+                .map(|(span, _)| span.location(source));
+
+            compilation_error(
+                error_chain(&err),
+                source,
+                location,
+                map,
+                ShaderError::InvalidShader,
+            )
+        })
+    }
+
+    /// Reflects a SPIR-V binary, failing with the `naga` error if it is not valid,
+    /// or with a [`ShaderError::UnsupportedShader`] if it uses what `naga` does not support.
+    pub(crate) fn from_spirv(words: &[u32]) -> ShaderResult<Self> {
+        let magic = words.first().copied().unwrap_or_default();
+        if magic != SPIRV_MAGIC_NUMBER {
+            return Err(ShaderError::InvalidMagicNumber(magic));
+        }
+
+        // The options `wgpu` parses the shader with, as it panics on the errors.
+        let options = naga::front::spv::Options {
+            adjust_coordinate_space: false,
+            strict_capabilities: true,
+            block_ctx_dump_prefix: None,
+        };
+
+        let module = naga::front::spv::Parser::new(words.iter().copied(), &options)
+            .parse()
+            .map_err(|err| spirv_error(words, err))?;
+
+        Self::from_module(module).map_err(|err| ShaderError::InvalidShader(error_chain(&err)))
+    }
+
+    fn from_module(
+        module: naga::Module,
+    ) -> Result<Self, naga::WithSpan<naga::valid::ValidationError>> {
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)?;
+
+        Ok(Self { module, info })
+    }
+
+    /// Checks that every resource used by the compute `entry_point` is bound
+    /// with a compatible kind in `sets`, indexed by bind group.
+    /// `shader` is the label of the shader, named by the errors.
+    ///
+    /// If `paranoid`, the elements of the bound buffers are also checked against
+    /// the arrays the shader declares, see [`Framework::set_paranoid_checks`](crate::Framework::set_paranoid_checks).
+    pub(crate) fn validate_bindings(
+        &self,
+        shader: Option<&str>,
+        entry_point: &str,
+        sets: &[&[BindingInfo]],
+        paranoid: bool,
+    ) -> KernelResult<()> {
+        let entry_index = self
+            .module
+            .entry_points
+            .iter()
+            .position(|ep| ep.stage == naga::ShaderStage::Compute && ep.name == entry_point)
+            .ok_or_else(|| KernelError::EntryPointNotFound {
+                shader: shader.map(str::to_string),
+                entry_point: entry_point.to_string(),
+                available: self.compute_entry_points(),
+            })?;
+
+        let entry_info = self.info.get_entry_point(entry_index);
+
+        for (handle, global) in self.module.global_variables.iter() {
+            let binding = match &global.binding {
+                Some(binding) if !entry_info[handle].is_empty() => binding,
+                _ => continue,
+            };
+
+            let expected = match BindingKind::from_global(&self.module, global) {
+                Some(kind) => kind,
+                None => continue,
+            };
+
+            let group_bindings = sets
+                .get(binding.group as usize)
+                .copied()
+                .unwrap_or_default();
+
+            let bound = group_bindings
+                .iter()
+                .find(|info| info.binding == binding.binding);
+
+            match bound.map(|info| info.kind) {
+                None => {
+                    return Err(KernelError::MissingBinding {
+                        group: binding.group,
+                        binding: binding.binding,
+                        expected,
+                        group_bindings: group_bindings.to_vec(),
+                    })
+                }
+                Some(bound) if !expected.accepts(bound) => {
+                    return Err(KernelError::BindingMismatch {
+                        group: binding.group,
+                        binding: binding.binding,
+                        expected,
+                        bound,
+                        group_bindings: group_bindings.to_vec(),
+                    })
+                }
+                _ => (),
+            }
+
+            if let Some(info) = bound {
+                self.validate_buffer_size(binding, global, info)?;
+
+                if paranoid {
+                    self.validate_element_type(binding, global, info)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a bound buffer holds at least the type the shader declares for it.
+    fn validate_buffer_size(
+        &self,
+        binding: &naga::ResourceBinding,
+        global: &naga::GlobalVariable,
+        info: &BindingInfo,
+    ) -> KernelResult<()> {
+        let size = match (info.kind, info.size) {
+            (
+                BindingKind::UniformBuffer | BindingKind::StorageBuffer { .. },
+                ResourceSize::Bytes(size),
+            ) => size,
+            _ => return Ok(()),
+        };
+
+        let required =
+            BindingKind::element_type(&self.module, global.ty).size(&self.module.constants) as u64;
+
+        if size < required {
+            return Err(KernelError::BufferTooSmall {
+                group: binding.group,
+                binding: binding.binding,
+                kind: info.kind,
+                element: info.buffer_element(),
+                size,
+                required,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the elements of a bound buffer have the stride of the elements of
+    /// the array the shader declares for it, directly or as the last member of a struct,
+    /// and the same type if they are scalars or vectors.
+    ///
+    /// Elements of a Rust type that is neither a primitive nor an array are only checked by size.
+    fn validate_element_type(
+        &self,
+        binding: &naga::ResourceBinding,
+        global: &naga::GlobalVariable,
+        info: &BindingInfo,
+    ) -> KernelResult<()> {
+        let element = match (info.kind, info.element) {
+            (BindingKind::UniformBuffer | BindingKind::StorageBuffer { .. }, Some(element)) => {
+                element
+            }
+            _ => return Ok(()),
+        };
+
+        let ty = BindingKind::element_type(&self.module, global.ty);
+        let ty = match ty {
+            naga::TypeInner::Struct { members, .. } => match members.last() {
+                Some(member) => &self.module.types[member.ty].inner,
+                None => return Ok(()),
+            },
+            ty => ty,
+        };
+
+        let (base, stride) = match ty {
+            naga::TypeInner::Array { base, stride, .. } => (*base, *stride),
+            _ => return Ok(()),
+        };
+
+        let base = &self.module.types[base];
+        let (shader_element, rust_element) = match base.inner {
+            naga::TypeInner::Scalar { kind, width } => match scalar_name(kind, width) {
+                Some(name) => (name.to_string(), Some(name.to_string())),
+                None => return Ok(()),
+            },
+            naga::TypeInner::Vector { size, kind, width } => match scalar_name(kind, width) {
+                Some(name) => (
+                    format!("vec{}<{}>", size as u8, name),
+                    Some(format!("[{}; {}]", name, size as u8)),
+                ),
+                None => return Ok(()),
+            },
+            _ => (
+                base.name.clone().unwrap_or_else(|| "struct".to_string()),
+                None,
+            ),
+        };
+
+        let comparable = element.name.starts_with('[')
+            || [
+                "u32", "i32", "f32", "u64", "i64", "f64", "u16", "i16", "u8", "i8",
+            ]
+            .contains(&element.name);
+        let same_type = match rust_element {
+            Some(rust_element) if comparable => rust_element == element.name,
+            _ => true,
+        };
+
+        if stride as usize != element.size || !same_type {
+            return Err(KernelError::ElementTypeMismatch {
+                group: binding.group,
+                binding: binding.binding,
+                kind: info.kind,
+                shader_element,
+                stride,
+                element,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Compiles the shader to SPIR-V, keeping the names of its variables.
+    #[cfg(feature = "shader-cache")]
+    pub(crate) fn to_spirv(&self) -> ShaderResult<Vec<u32>> {
+        let options = naga::back::spv::Options {
+            flags: naga::back::spv::WriterFlags::DEBUG,
+            ..Default::default()
+        };
+
+        naga::back::spv::write_vec(&self.module, &self.info, &options, None)
+            .map_err(|err| ShaderError::InvalidShader(err.to_string()))
+    }
+
+    /// Describes the compute entry points of the shader and the resources they use.
+    pub(crate) fn info(&self) -> ShaderInfo {
+        let entry_points = self
+            .module
+            .entry_points
+            .iter()
+            .enumerate()
+            .filter(|(_, ep)| ep.stage == naga::ShaderStage::Compute)
+            .map(|(index, ep)| {
+                let entry_info = self.info.get_entry_point(index);
+
+                let mut bindings = self
+                    .module
+                    .global_variables
+                    .iter()
+                    .filter(|(handle, _)| !entry_info[*handle].is_empty())
+                    .filter_map(|(_, global)| {
+                        let binding = global.binding.as_ref()?;
+                        let kind = BindingKind::from_global(&self.module, global)?;
+                        let min_size = match kind {
+                            BindingKind::UniformBuffer | BindingKind::StorageBuffer { .. } => Some(
+                                BindingKind::element_type(&self.module, global.ty)
+                                    .size(&self.module.constants)
+                                    as u64,
+                            ),
+                            _ => None,
+                        };
+
+                        Some(ShaderBinding {
+                            group: binding.group,
+                            binding: binding.binding,
+                            name: global.name.clone(),
+                            kind,
+                            type_name: type_name(&self.module, global.ty),
+                            min_size,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                bindings.sort_by_key(|binding| (binding.group, binding.binding));
+
+                let [x, y, z] = ep.workgroup_size;
+
+                EntryPointInfo {
+                    name: ep.name.clone(),
+                    workgroup_size: (x, y, z),
+                    bindings,
+                }
+            })
+            .collect();
+
+        ShaderInfo { entry_points }
+    }
+
+    /// Returns the `workgroup_size` of the compute `entry_point`, if it exists.
+    pub(crate) fn workgroup_size(&self, entry_point: &str) -> Option<(u32, u32, u32)> {
+        self.module
+            .entry_points
+            .iter()
+            .find(|ep| ep.stage == naga::ShaderStage::Compute && ep.name == entry_point)
+            .map(|ep| {
+                let [x, y, z] = ep.workgroup_size;
+                (x, y, z)
+            })
+    }
+
+    /// Returns the names of the compute entry points of the shader.
+    pub(crate) fn compute_entry_points(&self) -> Vec<String> {
+        self.module
+            .entry_points
+            .iter()
+            .filter(|ep| ep.stage == naga::ShaderStage::Compute)
+            .map(|ep| ep.name.clone())
+            .collect()
+    }
+}
+
+/// `WGSL` name of the type `ty`.
+fn type_name(module: &naga::Module, ty: naga::Handle<naga::Type>) -> String {
+    fn scalar(kind: naga::ScalarKind, width: naga::Bytes) -> String {
+        match kind {
+            naga::ScalarKind::Sint => format!("i{}", width as u32 * 8),
+            naga::ScalarKind::Uint => format!("u{}", width as u32 * 8),
+            naga::ScalarKind::Float => format!("f{}", width as u32 * 8),
+            naga::ScalarKind::Bool => "bool".to_string(),
+        }
+    }
+
+    fn array_size(module: &naga::Module, size: naga::ArraySize) -> Option<String> {
+        match size {
+            naga::ArraySize::Constant(constant) => match module.constants[constant].inner {
+                naga::ConstantInner::Scalar {
+                    value: naga::ScalarValue::Uint(len),
+                    ..
+                } => Some(len.to_string()),
+                naga::ConstantInner::Scalar {
+                    value: naga::ScalarValue::Sint(len),
+                    ..
+                } => Some(len.to_string()),
+                _ => None,
+            },
+            naga::ArraySize::Dynamic => None,
+        }
+    }
+
+    let ty = &module.types[ty];
+    match ty.inner {
+        naga::TypeInner::Scalar { kind, width } => scalar(kind, width),
+        naga::TypeInner::Vector { size, kind, width } => {
+            format!("vec{}<{}>", size as u8, scalar(kind, width))
+        }
+        naga::TypeInner::Matrix {
+            columns,
+            rows,
+            width,
+        } => format!("mat{}x{}<f{}>", columns as u8, rows as u8, width as u32 * 8),
+        naga::TypeInner::Atomic { kind, width } => format!("atomic<{}>", scalar(kind, width)),
+        naga::TypeInner::Array { base, size, .. } => match array_size(module, size) {
+            Some(len) => format!("array<{}, {}>", type_name(module, base), len),
+            None => format!("array<{}>", type_name(module, base)),
+        },
+        naga::TypeInner::BindingArray { base, size } => match array_size(module, size) {
+            Some(len) => format!("binding_array<{}, {}>", type_name(module, base), len),
+            None => format!("binding_array<{}>", type_name(module, base)),
+        },
+        naga::TypeInner::Image {
+            dim,
+            arrayed,
+            class,
+        } => {
+            let dim = match (dim, arrayed) {
+                (naga::ImageDimension::D1, _) => "1d",
+                (naga::ImageDimension::D2, false) => "2d",
+                (naga::ImageDimension::D2, true) => "2d_array",
+                (naga::ImageDimension::D3, _) => "3d",
+                (naga::ImageDimension::Cube, false) => "cube",
+                (naga::ImageDimension::Cube, true) => "cube_array",
+            };
+
+            match class {
+                naga::ImageClass::Sampled { kind, multi } => format!(
+                    "texture_{}{}<{}>",
+                    if multi { "multisampled_" } else { "" },
+                    dim,
+                    scalar(kind, 4)
+                ),
+                naga::ImageClass::Depth { multi } => format!(
+                    "texture_depth_{}{}",
+                    if multi { "multisampled_" } else { "" },
+                    dim
+                ),
+                naga::ImageClass::Storage { format, access } => format!(
+                    "texture_storage_{}<{}, {}>",
+                    dim,
+                    format!("{:?}", format).to_lowercase(),
+                    if access.contains(naga::StorageAccess::LOAD | naga::StorageAccess::STORE) {
+                        "read_write"
+                    } else if access.contains(naga::StorageAccess::STORE) {
+                        "write"
+                    } else {
+                        "read"
+                    }
+                ),
+            }
+        }
+        naga::TypeInner::Sampler { comparison: false } => "sampler".to_string(),
+        naga::TypeInner::Sampler { comparison: true } => "sampler_comparison".to_string(),
+        naga::TypeInner::Struct { .. } => ty.name.clone().unwrap_or_else(|| "struct".to_string()),
+        _ => ty.name.clone().unwrap_or_else(|| format!("{:?}", ty.inner)),
+    }
+}
+
+/// Capabilities of SPIR-V compiled for OpenCL rather than Vulkan.
+const OPENCL_CAPABILITIES: &[&str] = &[
+    "Addresses",
+    "Linkage",
+    "Kernel",
+    "Vector16",
+    "Float16Buffer",
+    "Int64Atomics",
+    "ImageBasic",
+    "ImageReadWrite",
+    "ImageMipmap",
+    "Pipes",
+    "DeviceEnqueue",
+    "LiteralSampler",
+    "GenericPointer",
+];
+
+/// Hint for SPIR-V compiled for OpenCL.
+const VULKAN_HINT: &str = "OpenCL kernels are not supported: compile for Vulkan compute, e.g. with `glslangValidator -V` or `--target-env vulkan1.1`.";
+
+/// Maps the error of the `naga` SPIR-V frontend to a [`ShaderError::UnsupportedShader`]
+/// naming what is not supported, or to a [`ShaderError::InvalidSpirv`].
+fn spirv_error(words: &[u32], err: naga::front::spv::Error) -> ShaderError {
+    use naga::front::spv::Error;
+
+    let (capability, hint) = match &err {
+        Error::UnsupportedCapability(capability) => {
+            let name = format!("{:?}", capability);
+            let hint = if OPENCL_CAPABILITIES.contains(&name.as_str()) {
+                VULKAN_HINT
+            } else {
+                "Only request the capabilities the shader needs from the compiler."
+            };
+
+            (format!("the capability {}", name), hint)
+        }
+        Error::UnsupportedExecutionModel(6) => ("the Kernel execution model".to_string(), VULKAN_HINT),
+        Error::UnsupportedExecutionModel(model) => (
+            format!("the execution model {}", model),
+            "Only compute shaders (the GLCompute execution model) can be dispatched.",
+        ),
+        Error::UnsupportedStorageClass(class @ (5 | 8)) => (
+            format!("the {} storage class", if *class == 5 { "CrossWorkgroup" } else { "Generic" }),
+            VULKAN_HINT,
+        ),
+        Error::UnsupportedStorageClass(class) => (
+            format!("the storage class {}", class),
+            "Compute shaders can only use the Function, Private, Workgroup, Uniform, StorageBuffer, UniformConstant and PushConstant storage classes.",
+        ),
+        Error::UnsupportedExtension(name) | Error::UnsupportedExtSet(name) => (
+            format!("the extension {}", name),
+            "Only the GLSL.std.450 extended instructions are supported.",
+        ),
+        _ => return ShaderError::InvalidSpirv(error_chain(&err)),
+    };
+
+    let version = words.get(1).copied().unwrap_or_default();
+
+    ShaderError::UnsupportedShader {
+        capability,
+        hint,
+        version: format!("{}.{}", (version >> 16) & 0xff, (version >> 8) & 0xff),
+        generator: words.get(2).copied().unwrap_or_default(),
+    }
+}
+
+/// Formats `err` followed by its sources, as `naga` nests the cause of validation errors.
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+
+    message
+}
+
+/// Returns a [`ShaderError::Compilation`] pointing at the file and line `map` gives
+/// for `location` in `source`, or `otherwise` with `message` if `naga` reported no location.
+fn compilation_error(
+    message: String,
+    source: &str,
+    location: Option<naga::SourceLocation>,
+    map: &SourceMap,
+    otherwise: fn(String) -> ShaderError,
+) -> ShaderError {
+    let location = match location {
+        Some(location) => location,
+        None => return otherwise(format!("{}: {}", map.locate(1).0, message)),
+    };
+
+    let line_number = location.line_number as usize;
+    let (file, line) = map.locate(line_number);
+
+    let start = location.offset as usize;
+    let length = source
+        .get(start..start + location.length as usize)
+        .and_then(|span| span.lines().next())
+        .map_or(0, |span| span.chars().count())
+        .max(1);
+
+    ShaderError::Compilation(ShaderDiagnostic {
+        file: file.to_string(),
+        line,
+        column: location.line_position as usize,
+        length,
+        source_line: source
+            .lines()
+            .nth(line_number - 1)
+            .unwrap_or_default()
+            .to_string(),
+        message,
+    })
+}
+
+/// Rust name of a `WGSL` scalar, which are 32-bit.
+fn scalar_name(kind: naga::ScalarKind, width: naga::Bytes) -> Option<&'static str> {
+    match (kind, width) {
+        (naga::ScalarKind::Uint, 4) => Some("u32"),
+        (naga::ScalarKind::Sint, 4) => Some("i32"),
+        (naga::ScalarKind::Float, 4) => Some("f32"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(source: &str) -> ShaderDiagnostic {
+        match ShaderReflection::from_wgsl(source, &SourceMap::new("shader.wgsl")) {
+            Err(ShaderError::Compilation(diagnostic)) => diagnostic,
+            other => panic!("expected a located diagnostic, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn parse_errors_are_located() {
+        let source = "@group(0) @binding(0) var<storage, read_write> data: array<u32>;\n\nfn main() {\n    let x = data[0] +;\n}\n";
+
+        assert_eq!(
+            diagnostic(source),
+            ShaderDiagnostic {
+                file: "shader.wgsl".to_string(),
+                line: 4,
+                column: 22,
+                length: 1,
+                source_line: "    let x = data[0] +;".to_string(),
+                message: "expected expression, found ';'".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn validation_errors_are_located() {
+        let diagnostic = diagnostic("fn f() -> u32 {\n    return 1.0;\n}\n");
+
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 11));
+        assert_eq!(diagnostic.length, "1.0;".len());
+        assert!(diagnostic
+            .message
+            .starts_with("Function [1] 'f' is invalid"));
+    }
+
+    #[test]
+    fn diagnostics_underline_the_error() {
+        let diagnostic = diagnostic("fn f() {\n\tlet x: u32 = unknown;\n}\n");
+
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 15));
+        // The tab is kept so the carets line up with `unknown`.
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: no definition in scope for identifier: 'unknown'\n\
+             \x20--> shader.wgsl:2:15\n\
+             \x20 |\n\
+             2 | \tlet x: u32 = unknown;\n\
+             \x20 | \t             ^^^^^^^"
+        );
+    }
+
+    /// Header of a SPIR-V 1.3 module generated by `glslang`, followed by `instructions`.
+    fn spirv(instructions: &[u32]) -> Vec<u32> {
+        [
+            &[SPIRV_MAGIC_NUMBER, 0x0001_0300, 0x0008_000b, 16, 0][..],
+            instructions,
+        ]
+        .concat()
+    }
+
+    /// `OpCapability capability`.
+    fn capability(capability: u32) -> [u32; 2] {
+        [2 << 16 | 17, capability]
+    }
+
+    #[test]
+    fn opencl_capabilities_are_reported_with_a_hint() {
+        // `Kernel`.
+        let result = ShaderReflection::from_spirv(&spirv(&capability(6)));
+
+        match result {
+            Err(ShaderError::UnsupportedShader {
+                capability,
+                hint,
+                version,
+                generator,
+            }) => {
+                assert_eq!(capability, "the capability Kernel");
+                assert_eq!(hint, VULKAN_HINT);
+                assert_eq!(version, "1.3");
+                assert_eq!(generator, 0x0008_000b);
+            }
+            other => panic!("expected an unsupported shader, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn other_capabilities_are_reported() {
+        // `Shader` then `Tessellation`.
+        let words = spirv(&[capability(1), capability(3)].concat());
+
+        match ShaderReflection::from_spirv(&words) {
+            Err(ShaderError::UnsupportedShader {
+                capability, hint, ..
+            }) => {
+                assert_eq!(capability, "the capability Tessellation");
+                assert_ne!(hint, VULKAN_HINT);
+            }
+            other => panic!("expected an unsupported shader, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn opencl_entry_points_are_reported() {
+        let words = spirv(
+            &[
+                &capability(1)[..],
+                // `OpMemoryModel Logical GLSL450`.
+                &[3 << 16 | 14, 0, 1],
+                // `OpEntryPoint Kernel %1 "main"`.
+                &[5 << 16 | 15, 6, 1, u32::from_le_bytes(*b"main"), 0],
+            ]
+            .concat(),
+        );
+
+        match ShaderReflection::from_spirv(&words) {
+            Err(ShaderError::UnsupportedShader {
+                capability, hint, ..
+            }) => {
+                assert_eq!(capability, "the Kernel execution model");
+                assert_eq!(hint, VULKAN_HINT);
+            }
+            other => panic!("expected an unsupported shader, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn other_binaries_are_not_spirv() {
+        assert!(matches!(
+            ShaderReflection::from_spirv(&[0x1234_5678, 0, 0, 0, 0]),
+            Err(ShaderError::InvalidMagicNumber(0x1234_5678))
+        ));
+    }
+}
@@ -1,7 +1,7 @@
 //! An experimental async GPU compute library based on [`wgpu`](https://github.com/gfx-rs/wgpu).
 //! It is meant to be used alongside `wgpu` if desired.
 //!
-//! To start using `gpgpu`, just create a [`Framework`](crate::Framework) instance
+//! To start using `gpgpu`, just import its [`prelude`] and create a [`Framework`](crate::Framework) instance
 //! and follow the [examples](https://github.com/UpsettingBoy/gpgpu-rs/tree/dev/examples) in the main repository.
 //!
 //! # Example
@@ -9,8 +9,9 @@
 //! result in another vector C.
 //! ## Rust program
 //! ```no_run
-//!     use gpgpu::*;
-//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use gpgpu::prelude::*;
+//! # const WGSL_SOURCE: &str = "";
+//! fn main() -> GpuResult<()> {
 //!     // Framework initialization
 //!     let fw = Framework::default();
 //!
@@ -26,6 +27,8 @@
 //!     let shader = Shader::from_spirv_file(&fw, "<SPIR-V shader path>")?;
 //!     //  or from a WGSL source file
 //!     let shader = Shader::from_wgsl_file(&fw, "<WGSL shader path>")?;    
+//!     //  or from a WGSL source in memory, e.g. embedded with `include_str!`
+//!     let shader = Shader::from_wgsl_source(&fw, WGSL_SOURCE, None)?;
 //!
 //!     // Descriptor set and program creation
 //!     let desc = DescriptorSet::default()
@@ -35,7 +38,7 @@
 //!     let program = Program::new(&shader, "main").add_descriptor_set(desc); // Entry point
 //!
 //!     // Kernel creation and enqueuing
-//!     Kernel::new(&fw, program).enqueue(cpu_data.len() as u32, 1, 1); // Enqueuing, not very optimus 😅
+//!     Kernel::new(&fw, program)?.enqueue(cpu_data.len() as u32, 1, 1)?; // Enqueuing, not very optimus 😅
 //!
 //!     let output = buf_c.read_vec_blocking()?;                        // Read back C from GPU
 //!     for (a, b) in cpu_data.into_iter().zip(output) {
@@ -51,37 +54,259 @@
 //! ```ignore
 //! // Vector type definition. Used for both input and output
 //! struct Vector {
-//!     data: [[stride(4)]] array<u32>;
+//!     data: array<u32>,
 //! };
 //!
 //! // A, B and C vectors
-//! [[group(0), binding(0)]] var<storage, read>  a: Vector;
-//! [[group(0), binding(1)]] var<storage, read>  b: Vector;
-//! [[group(0), binding(2)]] var<storage, read_write> c: Vector;
+//! @group(0) @binding(0) var<storage, read>  a: Vector;
+//! @group(0) @binding(1) var<storage, read>  b: Vector;
+//! @group(0) @binding(2) var<storage, read_write> c: Vector;
 //!
-//! [[stage(compute), workgroup_size(1)]]
-//! fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {
+//! @compute @workgroup_size(1)
+//! fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 //!     c.data[global_id.x] = a.data[global_id.x] * b.data[global_id.x];
 //! }
 //! ```
 //!
+//! # Async
+//! Every operation waiting for the GPU has an async twin, e.g. `GpuBuffer::read` for
+//! `GpuBuffer::read_blocking`, `Kernel::new_async` for `Kernel::new` or `Kernel::enqueue_async`
+//! for a dispatch followed by `Submission::wait`. Their futures are driven by the polling thread
+//! of the `Framework`, so they work on any executor without being polled manually.
+//!
+//! # Panics
+//! The operations of `gpgpu` return errors for invalid inputs, e.g. sizes, dimensions or shaders,
+//! instead of panicking. The only panics are deliberate:
+//! - The infallible constructors panic where their fallible twin fails, e.g. `Framework::default`
+//!   for `Framework::try_default`, or `GpuBuffer::with_capacity` for `GpuBuffer::try_with_capacity`.
+//! - The `DescriptorSet::bind_*` methods panic where their `_at` twin fails, e.g.
+//!   `DescriptorSet::bind_image` for an image built without the storage usage.
+//!   `GpuBindings::descriptor_set` returns these errors instead.
+//! - The `_unchecked` functions, e.g. `Kernel::new_unchecked`, leave the validation to `wgpu`,
+//!   which panics on errors.
+//! - The caches of a `Framework` panic if another thread panicked while using them.
+//!
+//! # Features
+//! The core of `gpgpu` only depends on `wgpu`, `naga` and a few small crates. Every integration with another crate,
+//! and every tool not needed by a plain compute program, is an optional feature, off by default:
+//!
+//! | Feature             | Description                                                                 |
+//! |---------------------|-----------------------------------------------------------------------------|
+//! | `integrate-image`   | Conversions between `image::ImageBuffer` and the images of `gpgpu`          |
+//! | `integrate-ndarray` | `GpuArray` and `GpuBuffer::from_array`, to upload and read `ndarray` arrays |
+//! | `num-complex`       | `GpuComplex32`, to upload and read `num_complex::Complex<f32>` buffers      |
+//! | `serde`             | Serialization of the reflected shader information and of `PipelineDesc`     |
+//! | `derive`            | `#[derive(GpuBindings)]` to bind a struct of resources at once              |
+//! | `include-wgsl`      | `include_wgsl!`, validating a `WGSL` shader at compile time                 |
+//! | `hot-reload`        | Reloading of the shaders when their files are modified                      |
+//! | `shader-cache`      | On-disk cache of the SPIR-V of the `WGSL` shaders                           |
+//! | `rust-gpu`          | Loading of the shaders written in Rust with rust-gpu                        |
+//! | `spirv-passthrough` | Loading of SPIR-V shaders bypassing `naga`, on the backends supporting it   |
+//! | `profiler`          | GPU timings of the dispatches                                               |
+//! | `tracing`           | `log` events for the buffers, kernels and validation errors                 |
+//! | `ffi`               | Raw pointer reads and writes of buffers, and the native handles of devices  |
+//! | `video`             | Conversion of YUV video frames into images                                  |
+//! | `viewer`            | `GpuImage::present_to`, presenting the images to a window                   |
 
-use std::{marker::PhantomData, sync::Arc};
+use std::{
+    marker::PhantomData,
+    sync::{atomic::AtomicBool, Arc, Mutex},
+};
 
+/// Re-export of `bytemuck`, the casts of `gpgpu` rely on: see [`gpu_struct!`] to declare
+/// the structs uploaded to the GPU without depending on it.
+pub use bytemuck;
+#[cfg(feature = "num-complex")]
+pub use features::integrate_complex::GpuComplex32;
 #[cfg(feature = "integrate-ndarray")]
 pub use features::integrate_ndarray::GpuArray;
+#[cfg(feature = "viewer")]
+pub use features::viewer::SurfaceContext;
+#[cfg(feature = "include-wgsl")]
+pub use gpgpu_derive::include_wgsl;
+#[cfg(feature = "derive")]
+pub use gpgpu_derive::GpuBindings;
+pub use kernel::GpuBindings;
 pub use primitives::{BufOps, ImgOps};
 
+/// Emits a `log` event of `level` with the `gpgpu` target when the `tracing` feature is enabled.
+///
+/// Without the feature, the event is compiled out, its arguments included.
+macro_rules! event {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        log::$level!(target: "gpgpu", $($arg)+)
+    };
+}
+
 pub mod features;
 pub mod framework;
 pub mod kernel;
+pub mod ops;
+pub mod prelude;
 pub mod primitives;
 
+mod deprecated;
+
+pub type GpuResult<T> = Result<T, GpuError>;
+
+/// Any error of `gpgpu`, to propagate the errors of its operations with `?`
+/// while still telling them apart, e.g. a shader that failed to compile
+/// ([`GpuError::Shader`]) from an allocation failure ([`GpuError::Buffer`]).
+///
+/// Each operation keeps returning its own error type, converted by `?`.
+/// The conversion sorts out the failures shared by all operations: a lost device
+/// ([`GpuError::DeviceLost`]), a timeout ([`GpuError::Timeout`]), a shader `wgpu`
+/// does not validate ([`GpuError::Validation`]) and a feature the device does not
+/// support ([`GpuError::MissingCapability`]).
+/// Like any error, it converts to a `Box<dyn std::error::Error>`.
+#[derive(thiserror::Error, Debug)]
+pub enum GpuError {
+    #[error("The device was lost, e.g. because the driver crashed or was updated.")]
+    DeviceLost,
+    #[error("The GPU did not answer in time.")]
+    Timeout,
+    #[error("The shader is not valid: {0}")]
+    Validation(String),
+    #[error("The {0:?} features are required, not supported by the device.")]
+    MissingCapability(wgpu::Features),
+    #[error(transparent)]
+    Framework(#[from] framework::FrameworkError),
+    #[error(transparent)]
+    PipelineDesc(#[from] framework::PipelineDescError),
+    #[error(transparent)]
+    Buffer(primitives::buffers::BufferError),
+    #[error(transparent)]
+    BufferFile(#[from] primitives::buffers::BufferFileError),
+    #[error(transparent)]
+    Image(#[from] primitives::images::ImageError),
+    #[error(transparent)]
+    ImageInput(#[from] primitives::images::ImageInputError),
+    #[error(transparent)]
+    ImageOutput(primitives::images::ImageOutputError),
+    #[error(transparent)]
+    DescriptorSet(kernel::DescriptorSetError),
+    #[error(transparent)]
+    Shader(kernel::ShaderError),
+    #[error(transparent)]
+    Kernel(kernel::KernelError),
+    #[error(transparent)]
+    Ops(#[from] ops::OpsError),
+    #[cfg(feature = "profiler")]
+    #[error(transparent)]
+    Profiler(framework::ProfilerError),
+    #[cfg(feature = "hot-reload")]
+    #[error(transparent)]
+    HotReload(#[from] features::hot_reload::HotReloadError),
+    #[cfg(feature = "integrate-image")]
+    #[error(transparent)]
+    ImageFile(#[from] features::integrate_image::ImageFileError),
+    #[cfg(feature = "integrate-ndarray")]
+    #[error(transparent)]
+    Array(#[from] features::integrate_ndarray::ArrayError),
+    #[cfg(feature = "video")]
+    #[error(transparent)]
+    VideoInput(#[from] features::video::VideoInputError),
+    #[cfg(feature = "viewer")]
+    #[error(transparent)]
+    Viewer(features::viewer::ViewerError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl From<primitives::buffers::BufferError> for GpuError {
+    fn from(err: primitives::buffers::BufferError) -> Self {
+        match err {
+            // Mapping a buffer only fails once the device is lost.
+            primitives::buffers::BufferError::AsyncMapError(_) => Self::DeviceLost,
+            err => Self::Buffer(err),
+        }
+    }
+}
+
+impl From<primitives::images::ImageOutputError> for GpuError {
+    fn from(err: primitives::images::ImageOutputError) -> Self {
+        match err {
+            primitives::images::ImageOutputError::BufferError(err) => err.into(),
+            err => Self::ImageOutput(err),
+        }
+    }
+}
+
+impl From<kernel::DescriptorSetError> for GpuError {
+    fn from(err: kernel::DescriptorSetError) -> Self {
+        match err {
+            kernel::DescriptorSetError::MissingFeatures(features) => {
+                Self::MissingCapability(features)
+            }
+            err => Self::DescriptorSet(err),
+        }
+    }
+}
+
+impl From<kernel::ShaderError> for GpuError {
+    fn from(err: kernel::ShaderError) -> Self {
+        match err {
+            kernel::ShaderError::InvalidShader(reason) => Self::Validation(reason),
+            err => Self::Shader(err),
+        }
+    }
+}
+
+impl From<kernel::KernelError> for GpuError {
+    fn from(err: kernel::KernelError) -> Self {
+        match err {
+            kernel::KernelError::DispatchNotCompleted
+            | kernel::KernelError::StatsUnavailable(_) => Self::DeviceLost,
+            kernel::KernelError::MissingFeatures(features) => Self::MissingCapability(features),
+            kernel::KernelError::DescriptorSetError(err) => err.into(),
+            err => Self::Kernel(err),
+        }
+    }
+}
+
+#[cfg(feature = "profiler")]
+impl From<framework::ProfilerError> for GpuError {
+    fn from(err: framework::ProfilerError) -> Self {
+        match err {
+            framework::ProfilerError::Unsupported => {
+                Self::MissingCapability(wgpu::Features::TIMESTAMP_QUERY)
+            }
+            framework::ProfilerError::AsyncMapError(_) => Self::DeviceLost,
+            err => Self::Profiler(err),
+        }
+    }
+}
+
+#[cfg(feature = "viewer")]
+impl From<features::viewer::ViewerError> for GpuError {
+    fn from(err: features::viewer::ViewerError) -> Self {
+        match err {
+            features::viewer::ViewerError::Surface(wgpu::SurfaceError::Timeout) => Self::Timeout,
+            err => Self::Viewer(err),
+        }
+    }
+}
+
 /// Entry point of `gpgpu`. A [`Framework`] must be created
 /// first as all GPU primitives needs it to be created.
 pub struct Framework {
     device: Arc<wgpu::Device>,
-    queue: wgpu::Queue,
+    queue: Arc<wgpu::Queue>,
+    _poller: framework::Poller,
+    backend: wgpu::Backend,
+    layout_cache: Mutex<framework::LayoutCache>,
+    pipeline_cache: framework::PipelineCache,
+    error_scopes: Mutex<()>,
+    placeholders: Mutex<framework::PlaceholderPool>,
+    scratch: Mutex<framework::ScratchPool>,
+    op_shaders: Mutex<framework::ShaderCache>,
+    memory: framework::MemoryTracker,
+    debug_markers: AtomicBool,
+    paranoid_checks: AtomicBool,
+    migrate_legacy_wgsl: AtomicBool,
+    #[cfg(feature = "profiler")]
+    profiler: Mutex<Option<framework::Profiler>>,
 }
 
 #[derive(PartialEq, Eq)]
@@ -89,17 +314,29 @@ pub enum GpuBufferUsage {
     /// Read-only object.
     /// ### Example WGSL syntax:
     /// ```ignore
-    /// [[group(0), binding(0)]] var<storage, read> input: Vector;
+    /// @group(0) @binding(0) var<storage, read> input: Vector;
     /// ```
     ReadOnly,
     /// Read-write object.
     /// ### Example WGSL syntax:
     /// ```ignore
-    /// [[group(0), binding(0)]] var<storage, read_write> input: Vector;
+    /// @group(0) @binding(0) var<storage, read_write> input: Vector;
     /// ```
     ReadWrite,
 }
 
+/// Former name of [`GpuBufferUsage`].
+#[deprecated(since = "0.2.0", note = "use `GpuBufferUsage` instead")]
+pub type AccessMode = GpuBufferUsage;
+
+/// Identifier of a buffer, an image or a [`Kernel`], unique in the process
+/// and stable across moves, e.g. to key a registry of GPU resources.
+///
+/// Identifiers are assigned in creation order. Buffers, images and [`Kernel`]s compare
+/// and hash by their identifier, not by their contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GpuId(u64);
+
 /// Vector of contiguous homogeneous elements on GPU memory.
 /// Its elements must implement [`bytemuck::Pod`].
 ///
@@ -109,8 +346,10 @@ pub enum GpuBufferUsage {
 /// under the [`DescriptorSet::bind_buffer`](crate::DescriptorSet::bind_buffer) documentation.
 pub struct GpuBuffer<'fw, T> {
     fw: &'fw Framework,
+    id: GpuId,
     buf: wgpu::Buffer,
     size: u64,
+    _allocation: framework::Allocation<'fw>,
     marker: PhantomData<T>,
 }
 
@@ -124,8 +363,10 @@ pub struct GpuBuffer<'fw, T> {
 /// under the [`DescriptorSet::bind_uniform_buffer`](crate::DescriptorSet::bind_uniform_buffer) documentation.
 pub struct GpuUniformBuffer<'fw, T> {
     fw: &'fw Framework,
+    id: GpuId,
     buf: wgpu::Buffer,
     size: u64,
+    _allocation: framework::Allocation<'fw>,
     marker: PhantomData<T>,
 }
 
@@ -137,9 +378,31 @@ pub struct GpuUniformBuffer<'fw, T> {
 /// under the [`DescriptorSet::bind_image`](crate::DescriptorSet::bind_image) documentation.
 pub struct GpuImage<'fw, P> {
     fw: &'fw Framework,
+    id: GpuId,
     texture: wgpu::Texture,
     size: wgpu::Extent3d,
+    usage: wgpu::TextureUsages,
+    mip_levels: u32,
     full_view: wgpu::TextureView,
+    _allocation: framework::Allocation<'fw>,
+    pixel: PhantomData<P>,
+}
+
+/// Options of a [`GpuImage`], created by [`GpuImage::builder`].
+///
+/// ```ignore
+/// let img = GpuImage::<Rgba8Uint>::builder(&fw, 1920, 1080)
+///     .label("frame")
+///     .usage(wgpu::TextureUsages::TEXTURE_BINDING)
+///     .mip_levels(4)
+///     .build()?;
+/// ```
+pub struct ImageBuilder<'fw, P> {
+    fw: &'fw Framework,
+    size: wgpu::Extent3d,
+    label: Option<String>,
+    usage: wgpu::TextureUsages,
+    mip_levels: u32,
     pixel: PhantomData<P>,
 }
 
@@ -151,37 +414,279 @@ pub struct GpuImage<'fw, P> {
 /// under the [`DescriptorSet::bind_const_image`](crate::DescriptorSet::bind_const_image) documentation.
 pub struct GpuConstImage<'fw, P> {
     fw: &'fw Framework,
+    id: GpuId,
     texture: wgpu::Texture,
     size: wgpu::Extent3d,
     full_view: wgpu::TextureView,
+    _allocation: framework::Allocation<'fw>,
     pixel: PhantomData<P>,
 }
 
+/// Sampler used to read [`GpuConstImage`]s with filtering or depth comparison.
+///
+/// More information about its shader representation is
+/// under the [`DescriptorSet::bind_sampler`](crate::DescriptorSet::bind_sampler) documentation.
+pub struct GpuSampler {
+    sampler: wgpu::Sampler,
+    kind: primitives::samplers::SamplerKind,
+}
+
 /// Represents a shader.
 ///
-/// It's just a wrapper over [`wgpu::ShaderModule`].
-pub struct Shader(wgpu::ShaderModule);
+/// It's a wrapper over [`wgpu::ShaderModule`] that also keeps its reflection,
+/// used to validate the bindings of the [`Kernel`]s created from it.
+///
+/// GLSL compute shaders cannot be compiled directly yet, as `gpgpu` is not built with
+/// the GLSL frontend of `naga`. They can be compiled to SPIR-V beforehand, e.g. with
+/// `glslangValidator -V shader.comp`, and loaded with [`Shader::from_spirv_file`].
+///
+/// HLSL compute shaders go through SPIR-V the same way, as neither `naga` nor `wgpu` can compile
+/// HLSL. This needs the [DirectX Shader Compiler](https://github.com/microsoft/DirectXShaderCompiler)
+/// built with SPIR-V code generation, e.g. `dxc -spirv -T cs_6_0 -E main shader.hlsl -Fo shader.spv`.
+pub struct Shader {
+    id: kernel::ShaderId,
+    module: wgpu::ShaderModule,
+    label: Option<String>,
+    reflection: Option<kernel::ShaderReflection>,
+}
+
+/// `WGSL` shader embedded in the binary and validated at compile time by [`include_wgsl!`],
+/// to be loaded with [`Shader::from_embedded`].
+#[cfg(feature = "include-wgsl")]
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedShader {
+    /// Path of the shader, relative to the root of the crate it is embedded in.
+    pub name: &'static str,
+    pub source: &'static str,
+}
+
+/// Builder of a [`Shader`] from a `WGSL` source and constants declared from Rust values,
+/// e.g. the length of an array in workgroup memory.
+///
+/// The constants are declared before the source as module-scope `let`s, which the shader
+/// refers to by name. `WGSL` as supported by `naga` 0.9 only takes literals as `workgroup_size`:
+/// it can be set with [`Shader::from_wgsl_template`] instead.
+///
+/// ```ignore
+/// let shader = ShaderBuilder::new(source)
+///     .constant_u32("N", 1024)
+///     .constant_f32("EPS", 1e-6)
+///     .build(&fw)?;
+/// ```
+pub struct ShaderBuilder<'a> {
+    source: &'a str,
+    name: Option<&'a str>,
+    constants: Vec<(String, String, &'static str)>,
+}
+
+/// [`Shader`]s loaded from the `WGSL` and SPIR-V files of a directory at once,
+/// named by their path relative to it, e.g. `filters/blur.wgsl`.
+///
+/// ```ignore
+/// let library = ShaderLibrary::load_dir(&fw, "shaders")?;
+///
+/// let program = library
+///     .program("filters/blur.wgsl", "main")
+///     .unwrap()
+///     .add_descriptor_set(desc);
+/// ```
+pub struct ShaderLibrary {
+    shaders: std::collections::BTreeMap<String, Shader>,
+}
 
 /// Represents an entry point with its bindings on a [`Shader`].
+///
+/// Pipeline-overridable constants (`override` declarations in WGSL) cannot be set yet,
+/// as the `wgpu` version `gpgpu` is built on does not support them.
+/// Values a [`Kernel`] must be specialized with can be passed in a [`GpuUniformBuffer`] instead.
 pub struct Program<'sha, 'res> {
     shader: &'sha Shader,
     entry_point: String,
-    descriptors: Vec<DescriptorSet<'res>>,
+    label: Option<String>,
+    descriptors: Vec<AnyDescriptorSet<'res>>,
+    allow_aliasing: bool,
 }
 
+/// Former name of [`Program`], created by [`Framework::create_kernel_builder`].
+#[deprecated(since = "0.2.0", note = "use `Program` instead")]
+pub type KernelBuilder<'sha, 'res> = Program<'sha, 'res>;
+
 /// Contains a binding group of resources.
+///
+/// Resources bound with the `bind_*` methods take the lowest binding index not
+/// used yet in the set, which usually means the call order. The `bind_*_at`
+/// methods take an explicit index instead, so their order does not matter.
+/// When both are mixed, explicit indices win: implicit bindings just skip them.
+///
+/// The same resource can be bound at several indices, or in several [`DescriptorSet`]s,
+/// since they only borrow it: each binding is a distinct entry referencing the same GPU resource.
+/// A buffer can be bound read-only or read-write as many times as needed, but not both within
+/// the same [`Kernel`] (see [`Program::allow_aliasing`]).
+///
+/// Its [`Debug`](std::fmt::Debug) output lists the [`BindingInfo`](kernel::BindingInfo)
+/// of every bound resource.
 #[derive(Default, Clone)]
 pub struct DescriptorSet<'res> {
     set_layout: Vec<wgpu::BindGroupLayoutEntry>,
-    binds: Vec<wgpu::BindGroupEntry<'res>>,
+    bindings: Vec<kernel::BindingInfo>,
+    binds: Vec<(u32, kernel::BindResource<'res>)>,
+    buffers: Vec<kernel::BoundBuffer>,
+}
+
+/// Contains a binding group of resources without borrowing them.
+///
+/// It is created from a [`DescriptorSet`] with [`DescriptorSet::into_owned`] and keeps
+/// the bound GPU resources alive, so it can be stored alongside them.
+#[derive(Clone)]
+pub struct OwnedDescriptorSet {
+    set_layout: Vec<wgpu::BindGroupLayoutEntry>,
+    bindings: Vec<kernel::BindingInfo>,
+    buffers: Vec<kernel::BoundBuffer>,
+    layout: Arc<wgpu::BindGroupLayout>,
+    bind_group: Arc<wgpu::BindGroup>,
+}
+
+/// Shape of a [`DescriptorSet`]: the kind of resource of each binding, identified by a name.
+///
+/// Slots take binding indices in the order they are added. [`DescriptorSet`]s with this shape
+/// are created with [`DescriptorLayout::instantiate`], and a [`Kernel`] can be created
+/// from it with [`Program::add_descriptor_layout`] before any resource exists.
+/// A [`Pipeline`] binds the resources of such a [`Kernel`] by the names of its slots.
+/// Its [`wgpu::BindGroupLayout`] is shared with every other set of the same shape
+/// through the [`Framework`] layout cache.
+#[derive(Default, Clone)]
+pub struct DescriptorLayout {
+    names: Vec<String>,
+    set_layout: Vec<wgpu::BindGroupLayoutEntry>,
+    bindings: Vec<kernel::BindingInfo>,
+}
+
+/// Builds a [`DescriptorSet`] binding resources to the named slots of a [`DescriptorLayout`].
+///
+/// Each resource is checked against the slot it is bound to.
+pub struct DescriptorSetBuilder<'lay, 'res> {
+    layout: &'lay DescriptorLayout,
+    desc: DescriptorSet<'res>,
+}
+
+/// Either a [`DescriptorSet`], an [`OwnedDescriptorSet`] or just a [`DescriptorLayout`].
+///
+/// The first two can be used wherever this type is expected thanks to their [`From`] implementations.
+pub enum AnyDescriptorSet<'res> {
+    Borrowed(DescriptorSet<'res>),
+    Owned(OwnedDescriptorSet),
+    /// Only the shape of a descriptor set, its resources are provided later.
+    Unbound(DescriptorLayout),
+}
+
+/// Dispatch of a [`Kernel`] binding its resources on the fly.
+///
+/// Created with [`Kernel::dispatch`].
+pub struct Dispatch<'ker, 'fw, 'res> {
+    kernel: &'ker Kernel<'fw>,
+    workgroups: (u32, u32, u32),
+    sets: Vec<Option<DescriptorSet<'res>>>,
+    group: usize,
+    error: Option<kernel::KernelError>,
+}
+
+/// Records [`Kernel`] dispatches and copies between resources, enqueued together onto the GPU.
+///
+/// Created with [`Framework::create_command_recorder`].
+pub struct CommandRecorder<'rec> {
+    fw: &'rec Framework,
+    commands: Vec<kernel::RecordedCommand<'rec>>,
+}
+
+/// Work enqueued onto the GPU in a single submission.
+///
+/// Returned by [`CommandRecorder::submit`] and [`Kernel::enqueue_repeat`].
+pub struct Submission<'fw> {
+    fw: &'fw Framework,
+    index: wgpu::SubmissionIndex,
+}
+
+/// Chain of [`Kernel`] dispatches sharing resources by slot name, enqueued in a single submission.
+///
+/// Created with [`Pipeline::new`].
+pub struct Pipeline<'fw, 'res> {
+    fw: &'fw Framework,
+    resources: Vec<(String, kernel::PipelineResource<'fw, 'res>)>,
+    stages: Vec<(&'res Kernel<'fw>, (u32, u32, u32))>,
 }
 
 /// Used to enqueue the execution of a shader with the bidings provided.
 ///
 /// Equivalent to OpenCL's Kernel.
+///
+/// A [`Kernel`] only borrows the [`Framework`]: it owns its compute pipeline and bind groups,
+/// so neither the [`Shader`] nor the resources of the [`Program`] it was created from
+/// need to outlive it.
+///
+/// The bind groups keep the GPU memory of the bound resources alive: a [`GpuBuffer`] or image
+/// dropped after creating the [`Kernel`] is still read and written by its dispatches, it just
+/// cannot be accessed from the CPU anymore. The memory is freed once no [`Kernel`] binds it,
+/// e.g. after [`Kernel::set_descriptor_set`] replaced the bind group holding it.
+///
+/// Kernels can then be created once and kept as long as the [`Framework`]:
+///
+/// ```no_run
+/// use std::collections::HashMap;
+///
+/// use gpgpu::*;
+///
+/// struct Engine<'fw> {
+///     kernels: HashMap<String, Kernel<'fw>>,
+/// }
+///
+/// fn load<'fw>(
+///     fw: &'fw Framework,
+///     values: &GpuBuffer<u32>,
+/// ) -> Result<Engine<'fw>, Box<dyn std::error::Error>> {
+///     let mut kernels = HashMap::new();
+///
+///     for name in ["square", "double"] {
+///         let shader = Shader::from_wgsl_file(fw, format!("{}.wgsl", name))?;
+///         let desc = DescriptorSet::default().bind_buffer(values, GpuBufferUsage::ReadWrite);
+///         let program = Program::new(&shader, "main").add_descriptor_set(desc);
+///
+///         kernels.insert(name.to_string(), Kernel::new(fw, program)?);
+///     } // `shader` and `desc` are dropped here, the kernels are still usable.
+///
+///     Ok(Engine { kernels })
+/// }
+/// ```
+///
+/// A [`Kernel`] is [`Send`] and [`Sync`], and enqueuing it only needs a shared reference:
+/// it can be enqueued from several threads at once, each dispatch being its own submission.
+/// Dispatches from different threads are not ordered, so they should write disjoint regions.
+/// Any state added to [`Kernel`] that enqueuing updates must keep this true, which the example
+/// below checks at compile time:
+///
+/// ```no_run
+/// # use gpgpu::*;
+/// # fn run(kernel: &Kernel, values: &GpuBuffer<u32>, params: &[GpuUniformBuffer<u32>]) {
+/// std::thread::scope(|scope| {
+///     for param in params {
+///         let desc = DescriptorSet::default()
+///             .bind_buffer(values, GpuBufferUsage::ReadWrite)
+///             .bind_uniform_buffer(param); // Region of `values` written by this thread
+///
+///         scope.spawn(move || kernel.enqueue_with_sets(16, 1, 1, &[&desc]).unwrap());
+///     }
+/// });
+/// # }
+/// ```
 pub struct Kernel<'fw> {
     fw: &'fw Framework,
-    pipeline: wgpu::ComputePipeline,
-    sets: Vec<wgpu::BindGroup>,
+    id: GpuId,
+    pipeline: Arc<wgpu::ComputePipeline>,
+    layouts: Vec<(Vec<wgpu::BindGroupLayoutEntry>, Arc<wgpu::BindGroupLayout>)>,
+    pipeline_layout: Arc<wgpu::PipelineLayout>,
+    sets: Vec<Option<Arc<wgpu::BindGroup>>>,
     entry_point: String,
+    label: String,
+    shader: Option<String>,
+    workgroup_size: Option<(u32, u32, u32)>,
+    slots: Vec<Option<DescriptorLayout>>,
 }
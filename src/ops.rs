@@ -0,0 +1,127 @@
+//! Parallel algorithms over the buffers of `gpgpu`, so that programs do not have to write
+//! their own kernels for the common ones.
+//!
+//! ```no_run
+//! use gpgpu::{ops::{self, ReduceOp, ScanKind}, prelude::*};
+//!
+//! # fn main() -> GpuResult<()> {
+//! let fw = Framework::try_default()?;
+//! let buf = GpuBuffer::from_slice(&fw, &[3.0f32, -1.0, 4.0, 1.0]);
+//!
+//! assert_eq!(ops::reduce(&fw, &buf, ReduceOp::Sum)?, 7.0);
+//! assert_eq!(ops::argmin(&fw, &buf)?, (1, -1.0));
+//!
+//! let scaled = ops::map(&fw, &buf, "x * 2.0 + 1.0")?;
+//! assert_eq!(scaled.read_vec_blocking()?, [7.0, -1.0, 9.0, 3.0]);
+//!
+//! let mut counts = GpuBuffer::from_slice(&fw, &[2u32, 0, 3, 1]);
+//! ops::scan_in_place(&fw, &mut counts, ScanKind::Exclusive)?;
+//! assert_eq!(counts.read_vec_blocking()?, [0, 2, 2, 5]);
+//!
+//! let mut keys = GpuBuffer::from_slice(&fw, &[5u32, 1, 4, 1]);
+//! ops::radix_sort(&fw, &mut keys)?;
+//! assert_eq!(keys.read_vec_blocking()?, [1, 1, 4, 5]);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::ops::Deref;
+
+use thiserror::Error;
+
+use crate::{kernel::ShaderDiagnostic, BufOps, Framework, GpuBuffer};
+
+pub use self::blas1::{axpy, copy, record_axpy, record_copy, record_scal, scal};
+pub use self::compact::{compact, compact_by_expr};
+pub use self::dot::{dot, dot_into, norm2, norm2_into};
+pub use self::histogram::{histogram, histogram_with, OutOfRange};
+pub use self::map::{map, map_into, zip, zip_into};
+pub use self::matmul::{matmul, matmul_with, MatmulOptions};
+pub use self::radix_sort::{radix_sort, radix_sort_pairs};
+pub use self::random::{fill_random, fill_random_u32, Distribution};
+pub use self::reduce::{argmax, argmin, reduce, ReduceElement, ReduceOp};
+pub use self::scan::{scan, scan_in_place, ScanKind};
+pub use self::segmented::segmented_reduce;
+pub use self::spmv::spmv_csr;
+pub use self::transpose::{transpose, transpose_in_place};
+
+mod blas1;
+mod compact;
+mod dot;
+mod histogram;
+mod map;
+mod matmul;
+mod radix_sort;
+mod random;
+mod reduce;
+mod scan;
+mod segmented;
+mod spmv;
+mod transpose;
+
+pub type OpsResult<T> = Result<T, OpsError>;
+
+#[derive(Error, Debug)]
+pub enum OpsError {
+    #[error("The buffer is empty: it has no minimum nor maximum.")]
+    Empty,
+    #[error("A buffer of {0} elements is too long to be indexed by a `u32`.")]
+    TooLong(u64),
+    #[error("The output holds {current} elements, {required} elements required.")]
+    OutputTooSmall { required: u64, current: u64 },
+    #[error("There are {keys} keys but {values} values.")]
+    LengthMismatch { keys: u64, values: u64 },
+    #[error("The matrix `{matrix}` holds {current} elements, {required} elements required.")]
+    MatrixTooSmall {
+        matrix: &'static str,
+        required: u64,
+        current: u64,
+    },
+    #[error("A histogram needs at least one bin.")]
+    NoBins,
+    #[error("The range of the histogram is empty: its start is not below its end.")]
+    EmptyRange,
+    #[error("The operands hold {a} and {b} elements.")]
+    OperandMismatch { a: u64, b: u64 },
+    #[error("The expression could not be compiled:\n{0}")]
+    InvalidExpression(ShaderDiagnostic),
+    #[error("A CSR matrix of {rows} rows needs {rows} + 1 row offsets, {offsets} given.")]
+    RowOffsetsMismatch { rows: u64, offsets: u64 },
+}
+
+/// Buffer of `T`s taken from the [`ScratchPool`](crate::framework::ScratchPool)
+/// of a [`Framework`], returned to it when dropped.
+struct Scratch<'fw, T: bytemuck::Pod> {
+    fw: &'fw Framework,
+    buf: Option<GpuBuffer<'fw, T>>,
+}
+
+impl<'fw, T: bytemuck::Pod> Scratch<'fw, T> {
+    /// Takes a buffer of `capacity` elements, whose contents are unspecified.
+    fn new(fw: &'fw Framework, capacity: u64) -> Self {
+        let size = capacity * std::mem::size_of::<T>() as u64;
+        let buf = fw.scratch.lock().unwrap().take(&fw.device, size);
+
+        Self {
+            fw,
+            buf: Some(GpuBuffer::from_gpu_parts(fw, buf, size)),
+        }
+    }
+}
+
+impl<'fw, T: bytemuck::Pod> Deref for Scratch<'fw, T> {
+    type Target = GpuBuffer<'fw, T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.buf.as_ref().unwrap()
+    }
+}
+
+impl<T: bytemuck::Pod> Drop for Scratch<'_, T> {
+    fn drop(&mut self) {
+        if let Some(buf) = self.buf.take() {
+            let (buf, size) = buf.into_gpu_parts();
+            self.fw.scratch.lock().unwrap().give(buf, size);
+        }
+    }
+}
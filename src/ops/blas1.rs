@@ -0,0 +1,186 @@
+use crate::{
+    BufOps, CommandRecorder, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult,
+    GpuUniformBuffer, Kernel, Program, Shader,
+};
+
+use super::{OpsError, ReduceElement};
+
+/// Workgroups of a dispatch.
+type Workgroups = (u32, u32, u32);
+
+crate::gpu_struct! {
+    /// `Params` of `blas1.wgsl`.
+    uniform struct Params {
+        len: u32,
+        alpha: u32,
+        _padding: [u32; 2],
+    }
+}
+
+/// Computes `y = alpha * x + y` on the GPU. Integers wrap on overflow.
+///
+/// The shaders of the BLAS-1 operations are compiled once per element type and kept by the
+/// [`Framework`], so that the operations of the iterations of a solver only create their
+/// bind groups. See [`record_axpy`] to enqueue them in a single submission.
+///
+/// Fails with [`OpsError::OperandMismatch`] if `x` and `y` have different lengths.
+pub fn axpy<T: ReduceElement>(
+    fw: &Framework,
+    alpha: T,
+    x: &GpuBuffer<T>,
+    y: &mut GpuBuffer<T>,
+) -> GpuResult<()> {
+    check_lengths(x, y)?;
+
+    if let Some((kernel, workgroups)) = kernel(fw, "axpy", alpha, Some(x), y)? {
+        let (gx, gy, gz) = workgroups;
+        kernel.enqueue(gx, gy, gz)?;
+    }
+
+    Ok(())
+}
+
+/// Computes `x = alpha * x` on the GPU, like [`axpy`].
+pub fn scal<T: ReduceElement>(fw: &Framework, alpha: T, x: &mut GpuBuffer<T>) -> GpuResult<()> {
+    if let Some((kernel, (gx, gy, gz))) = kernel(fw, "scal", alpha, None, x)? {
+        kernel.enqueue(gx, gy, gz)?;
+    }
+
+    Ok(())
+}
+
+/// Copies the elements of `x` into `y` on the GPU, with a copy between the buffers.
+///
+/// Fails with [`OpsError::OperandMismatch`] if `x` and `y` have different lengths.
+pub fn copy<T: bytemuck::Pod>(
+    fw: &Framework,
+    x: &GpuBuffer<T>,
+    y: &mut GpuBuffer<T>,
+) -> GpuResult<()> {
+    check_lengths(x, y)?;
+
+    let mut recorder = fw.create_command_recorder();
+    recorder.copy_buffer(x, y);
+    recorder.submit();
+
+    Ok(())
+}
+
+/// Records `y = alpha * x + y` like [`axpy`] into `recorder`, to be enqueued with the other
+/// commands of its submission, e.g. all the operations of an iteration of a solver:
+///
+/// ```ignore
+/// let mut recorder = fw.create_command_recorder();
+/// ops::record_axpy(&mut recorder, alpha, &p, &x)?;
+/// ops::record_axpy(&mut recorder, -alpha, &ap, &r)?;
+/// recorder.submit();
+/// ```
+///
+/// The kernel of the operation is only kept by the recording, and `alpha` is captured
+/// when it is recorded.
+pub fn record_axpy<'rec, T: ReduceElement>(
+    recorder: &mut CommandRecorder<'rec>,
+    alpha: T,
+    x: &'rec GpuBuffer<T>,
+    y: &'rec GpuBuffer<T>,
+) -> GpuResult<()> {
+    check_lengths(x, y)?;
+
+    if let Some((kernel, workgroups)) = kernel(recorder.fw, "axpy", alpha, Some(x), y)? {
+        recorder.push_bound_dispatch(&kernel, "ops::axpy", workgroups)?;
+    }
+
+    Ok(())
+}
+
+/// Records `x = alpha * x` like [`scal`] into `recorder`, like [`record_axpy`].
+pub fn record_scal<'rec, T: ReduceElement>(
+    recorder: &mut CommandRecorder<'rec>,
+    alpha: T,
+    x: &'rec GpuBuffer<T>,
+) -> GpuResult<()> {
+    if let Some((kernel, workgroups)) = kernel(recorder.fw, "scal", alpha, None, x)? {
+        recorder.push_bound_dispatch(&kernel, "ops::scal", workgroups)?;
+    }
+
+    Ok(())
+}
+
+/// Records the copy of the elements of `x` into `y` like [`copy`] into `recorder`,
+/// like [`record_axpy`].
+pub fn record_copy<'rec, T: bytemuck::Pod>(
+    recorder: &mut CommandRecorder<'rec>,
+    x: &'rec GpuBuffer<T>,
+    y: &'rec GpuBuffer<T>,
+) -> GpuResult<()> {
+    check_lengths(x, y)?;
+
+    recorder.copy_buffer(x, y);
+
+    Ok(())
+}
+
+fn check_lengths<T: bytemuck::Pod>(x: &GpuBuffer<T>, y: &GpuBuffer<T>) -> GpuResult<()> {
+    if x.capacity() != y.capacity() {
+        return Err(OpsError::OperandMismatch {
+            a: x.capacity(),
+            b: y.capacity(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Creates the kernel of the `entry_point` of `blas1.wgsl` over `y`, reading `x` if any,
+/// and returns it with its workgroups, or `None` if `y` is empty.
+fn kernel<'fw, T: ReduceElement>(
+    fw: &'fw Framework,
+    entry_point: &str,
+    alpha: T,
+    x: Option<&GpuBuffer<T>>,
+    y: &GpuBuffer<T>,
+) -> GpuResult<Option<(Kernel<'fw>, Workgroups)>> {
+    let len = y.capacity();
+    if len == 0 {
+        return Ok(None);
+    }
+    if len > u32::MAX as u64 {
+        return Err(OpsError::TooLong(len).into());
+    }
+
+    let key = format!("ops::blas1 {}", T::WGSL_TYPE);
+    let shader = fw.op_shaders.lock().unwrap().get_or_compile(&key, || {
+        Shader::from_wgsl_template(
+            fw,
+            include_str!("blas1.wgsl"),
+            &[("T", T::WGSL_TYPE)],
+            Some("ops::blas1"),
+        )
+    })?;
+
+    // Kept alive by the bind group of the kernel.
+    let params = GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            len: len as u32,
+            alpha: bytemuck::cast(alpha),
+            _padding: [0; 2],
+        }],
+    );
+
+    let mut set = DescriptorSet::default()
+        .bind_buffer_at(1, y, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(2, &params)?;
+    if let Some(x) = x {
+        set = set.bind_buffer_at(0, x, GpuBufferUsage::ReadOnly)?;
+    }
+
+    let kernel = Kernel::new(
+        fw,
+        Program::new(&shader, entry_point).add_descriptor_set(set),
+    )?;
+    let workgroups = kernel.element_workgroups(len)?;
+
+    Ok(Some((kernel, workgroups)))
+}
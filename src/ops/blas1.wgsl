@@ -0,0 +1,43 @@
+// Element-wise BLAS-1 operations over the first `params.len` elements of `x` and `y`:
+// `axpy` computes `y = alpha * x + y`, and `scal` computes `y = alpha * y`.
+
+struct Params {
+    len: u32,
+    alpha: u32,
+    _padding: vec2<u32>,
+}
+
+@group(0) @binding(0) var<storage, read> x: array<{{T}}>;
+@group(0) @binding(1) var<storage, read_write> y: array<{{T}}>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+// Index of the element of the invocation, an element per invocation.
+fn element_index(global_id: vec3<u32>, groups: vec3<u32>) -> u32 {
+    return global_id.x + global_id.y * groups.x * 256u;
+}
+
+@compute @workgroup_size(256)
+fn axpy(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = element_index(global_id, groups);
+    if (i >= params.len) {
+        return;
+    }
+
+    y[i] = bitcast<{{T}}>(params.alpha) * x[i] + y[i];
+}
+
+@compute @workgroup_size(256)
+fn scal(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = element_index(global_id, groups);
+    if (i >= params.len) {
+        return;
+    }
+
+    y[i] = bitcast<{{T}}>(params.alpha) * y[i];
+}
@@ -0,0 +1,130 @@
+use crate::{
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult, Kernel, Program, Shader,
+};
+
+use super::{map, scan, OpsError, ReduceElement, Scratch};
+
+/// Moves the elements of `input` whose flag in `flags` is not 0 to the first elements of
+/// `output`, keeping their order, and returns how many there are.
+///
+/// The flags are turned into 0 or 1 and scanned with [`scan_in_place`](super::scan_in_place)
+/// into the index of each kept element in `output`, in a buffer the [`Framework`] keeps between
+/// operations. Only the count, 4 bytes, is read back, before the elements are moved, and the
+/// elements of `output` after the kept ones are left as they are.
+///
+/// Fails with [`OpsError::OperandMismatch`] if `input` and `flags` have different lengths, and
+/// with [`OpsError::OutputTooSmall`] with the count of kept elements if `output` cannot hold
+/// them, in which case `output` is not written.
+pub fn compact<T: ReduceElement>(
+    fw: &Framework,
+    input: &GpuBuffer<T>,
+    flags: &GpuBuffer<u32>,
+    output: &mut GpuBuffer<T>,
+) -> GpuResult<u32> {
+    if input.capacity() != flags.capacity() {
+        return Err(OpsError::OperandMismatch {
+            a: input.capacity(),
+            b: flags.capacity(),
+        }
+        .into());
+    }
+
+    compact_flagged(fw, input, flags, output)
+}
+
+/// Moves the elements `x` of `input` the `WGSL` `predicate` holds for to the first elements of
+/// `output` like [`compact`], returning how many there are:
+///
+/// ```ignore
+/// let kept = ops::compact_by_expr(&fw, &samples, "x > 0.5", &mut output)?;
+/// ```
+///
+/// The flags are computed by a kernel generated from `predicate` like the one of
+/// [`map`](super::map), with the same compromise on safety, into a buffer the [`Framework`]
+/// keeps between operations. The predicate must evaluate to a `bool`, and can also use
+/// the index `i` of the element.
+pub fn compact_by_expr<T: ReduceElement>(
+    fw: &Framework,
+    input: &GpuBuffer<T>,
+    predicate: &str,
+    output: &mut GpuBuffer<T>,
+) -> GpuResult<u32> {
+    let flags = Scratch::<u32>::new(fw, input.capacity());
+    map::predicate_flags(fw, input, &flags, predicate)?;
+
+    compact_flagged(fw, input, &flags, output)
+}
+
+/// Compacts `input` with `flags` of the same length.
+fn compact_flagged<T: ReduceElement>(
+    fw: &Framework,
+    input: &GpuBuffer<T>,
+    flags: &GpuBuffer<u32>,
+    output: &GpuBuffer<T>,
+) -> GpuResult<u32> {
+    let len = input.capacity();
+    if len == 0 {
+        return Ok(0);
+    }
+    if len > u32::MAX as u64 {
+        return Err(OpsError::TooLong(len).into());
+    }
+
+    let substitutions = [("T", T::WGSL_TYPE)];
+    let key = format!("ops::compact{:?}", substitutions);
+    let shader = fw.op_shaders.lock().unwrap().get_or_compile(&key, || {
+        Shader::from_wgsl_template(
+            fw,
+            include_str!("compact.wgsl"),
+            &substitutions,
+            Some("ops::compact"),
+        )
+    })?;
+
+    let offsets = Scratch::<u32>::new(fw, len);
+    let count = Scratch::<u32>::new(fw, 1);
+    // `output` cannot be bound while it is empty: nothing is moved then, or it is too small.
+    let no_output = Scratch::<T>::new(fw, 1);
+    let bound_output = if output.capacity() == 0 {
+        &*no_output
+    } else {
+        output
+    };
+
+    let set = || -> GpuResult<DescriptorSet> {
+        Ok(DescriptorSet::default()
+            .bind_buffer_at(0, input, GpuBufferUsage::ReadOnly)?
+            .bind_buffer_at(1, flags, GpuBufferUsage::ReadOnly)?
+            .bind_buffer_at(2, &offsets, GpuBufferUsage::ReadWrite)?
+            .bind_buffer_at(3, bound_output, GpuBufferUsage::ReadWrite)?
+            .bind_buffer_at(4, &count, GpuBufferUsage::ReadWrite)?)
+    };
+
+    Kernel::new(fw, Program::new(&shader, "mark").add_descriptor_set(set()?))?
+        .enqueue_elements(len)?;
+    scan::scan_level(fw, &scan::ScanKernels::new(fw)?, &offsets, len as u32, true)?;
+    Kernel::new(
+        fw,
+        Program::new(&shader, "total").add_descriptor_set(set()?),
+    )?
+    .enqueue(1, 1, 1)?;
+
+    let kept = count.read_vec_blocking()?[0];
+    if kept as u64 > output.capacity() {
+        return Err(OpsError::OutputTooSmall {
+            required: kept as u64,
+            current: output.capacity(),
+        }
+        .into());
+    }
+
+    if kept > 0 {
+        Kernel::new(
+            fw,
+            Program::new(&shader, "scatter").add_descriptor_set(set()?),
+        )?
+        .enqueue_elements(len)?;
+    }
+
+    Ok(kept)
+}
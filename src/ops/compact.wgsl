@@ -0,0 +1,50 @@
+// Stream compaction of the elements of `input` whose flag is not 0: `mark` turns the flags
+// into 0 or 1 in `offsets`, which are then scanned into the index of each kept element in
+// `output`, `total` counts the kept elements into `count`, and `scatter` moves them.
+
+@group(0) @binding(0) var<storage, read> input: array<{{T}}>;
+@group(0) @binding(1) var<storage, read> flags: array<u32>;
+@group(0) @binding(2) var<storage, read_write> offsets: array<u32>;
+@group(0) @binding(3) var<storage, read_write> output: array<{{T}}>;
+@group(0) @binding(4) var<storage, read_write> count: u32;
+
+let WORKGROUP_SIZE: u32 = 256u;
+
+// Index of the element of the invocation, an element per invocation.
+fn element_index(global_id: vec3<u32>, groups: vec3<u32>) -> u32 {
+    return global_id.x + global_id.y * groups.x * WORKGROUP_SIZE;
+}
+
+@compute @workgroup_size(256)
+fn mark(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = element_index(global_id, groups);
+    if (i >= arrayLength(&flags)) {
+        return;
+    }
+
+    offsets[i] = u32(flags[i] != 0u);
+}
+
+@compute @workgroup_size(1)
+fn total() {
+    let last = arrayLength(&flags) - 1u;
+    count = offsets[last] + u32(flags[last] != 0u);
+}
+
+@compute @workgroup_size(256)
+fn scatter(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = element_index(global_id, groups);
+    if (i >= arrayLength(&flags)) {
+        return;
+    }
+
+    if (flags[i] != 0u) {
+        output[offsets[i]] = input[i];
+    }
+}
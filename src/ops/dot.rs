@@ -0,0 +1,127 @@
+use crate::{BufOps, Framework, GpuBuffer, GpuResult};
+
+use super::{reduce, OpsError, ReduceOp};
+
+/// Computes the dot product of `a` and `b` on the GPU, blocking until it is read back.
+///
+/// The products are summed by the kernels of [`reduce`](super::reduce), which multiply the
+/// elements as they load them: no buffer of the products is written, and only the result,
+/// a few bytes, is read back. The sum has the rounding of the float sums of `reduce`.
+/// The dot product of empty buffers is 0.
+///
+/// Fails with [`OpsError::OperandMismatch`] if `a` and `b` have different lengths.
+pub fn dot(fw: &Framework, a: &GpuBuffer<f32>, b: &GpuBuffer<f32>) -> GpuResult<f32> {
+    check_lengths(a, b)?;
+
+    read(fw, a, Some(b), "")
+}
+
+/// Computes the Euclidean norm of `a` on the GPU like [`dot`], the square root of the sum
+/// of the squares of its elements.
+pub fn norm2(fw: &Framework, a: &GpuBuffer<f32>) -> GpuResult<f32> {
+    read(fw, a, None, "sqrt")
+}
+
+/// Computes the dot product of `a` and `b` like [`dot`], writing it into the element `index`
+/// of `result` instead of reading it back, for the next kernels to consume on the GPU:
+/// iterations of a solver can then be enqueued without waiting for each other.
+///
+/// Fails with [`OpsError::OperandMismatch`] if `a` and `b` have different lengths,
+/// and with [`OpsError::OutputTooSmall`] if `index` is not an index of `result`.
+pub fn dot_into(
+    fw: &Framework,
+    a: &GpuBuffer<f32>,
+    b: &GpuBuffer<f32>,
+    result: &mut GpuBuffer<f32>,
+    index: u64,
+) -> GpuResult<()> {
+    check_lengths(a, b)?;
+
+    write(fw, a, Some(b), "", result, index)
+}
+
+/// Computes the Euclidean norm of `a` like [`norm2`], writing it into the element `index`
+/// of `result` like [`dot_into`].
+///
+/// Fails with [`OpsError::OutputTooSmall`] if `index` is not an index of `result`.
+pub fn norm2_into(
+    fw: &Framework,
+    a: &GpuBuffer<f32>,
+    result: &mut GpuBuffer<f32>,
+    index: u64,
+) -> GpuResult<()> {
+    write(fw, a, None, "sqrt", result, index)
+}
+
+fn check_lengths(a: &GpuBuffer<f32>, b: &GpuBuffer<f32>) -> GpuResult<()> {
+    if a.capacity() != b.capacity() {
+        return Err(OpsError::OperandMismatch {
+            a: a.capacity(),
+            b: b.capacity(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Reduces the products of `a` and `b`, or the squares of `a` without `b`, mapped by `finish`.
+fn read(
+    fw: &Framework,
+    a: &GpuBuffer<f32>,
+    b: Option<&GpuBuffer<f32>>,
+    finish: &str,
+) -> GpuResult<f32> {
+    if a.capacity() == 0 {
+        return Ok(0.0);
+    }
+
+    let result = reduce::enqueue(fw, a, Some(b.unwrap_or(a)), ReduceOp::Sum, finish)?;
+    let [value, _] = result.read_vec_blocking()?[0];
+
+    Ok(f32::from_bits(value))
+}
+
+/// Reduces like [`read`] into the element `index` of `output`.
+fn write(
+    fw: &Framework,
+    a: &GpuBuffer<f32>,
+    b: Option<&GpuBuffer<f32>>,
+    finish: &str,
+    output: &GpuBuffer<f32>,
+    index: u64,
+) -> GpuResult<()> {
+    if index >= output.capacity() {
+        return Err(OpsError::OutputTooSmall {
+            required: index + 1,
+            current: output.capacity(),
+        }
+        .into());
+    }
+
+    let offset = index * std::mem::size_of::<f32>() as u64;
+    if a.capacity() == 0 {
+        fw.queue
+            .write_buffer(output.as_gpu_buffer(), offset, bytemuck::bytes_of(&0.0f32));
+        return Ok(());
+    }
+
+    let result = reduce::enqueue(fw, a, Some(b.unwrap_or(a)), ReduceOp::Sum, finish)?;
+
+    // The value of the `Pair` of the result is its first word.
+    let mut encoder = fw
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("ops::dot"),
+        });
+    encoder.copy_buffer_to_buffer(
+        result.as_gpu_buffer(),
+        0,
+        output.as_gpu_buffer(),
+        offset,
+        std::mem::size_of::<f32>() as u64,
+    );
+    fw.queue.submit(Some(encoder.finish()));
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use crate::{
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult, GpuUniformBuffer,
+    Kernel, Program, Shader,
+};
+
+use super::{OpsError, ReduceElement};
+
+/// Threads of the workgroups of the histogram kernels.
+const WORKGROUP_SIZE: u32 = 256;
+
+/// Most workgroups counting the elements, each adding its counts to the histogram once.
+const MAX_GROUPS: u32 = 1024;
+
+/// Most bins counted in workgroup memory, overflow bin included: as many counters as the
+/// workgroup memory of the device holds, 4088 with the default limits.
+fn shared_bins(fw: &Framework) -> u32 {
+    fw.limits().max_compute_workgroup_storage_size / std::mem::size_of::<u32>() as u32
+}
+
+crate::gpu_struct! {
+    /// `Params` of `histogram.wgsl`.
+    uniform struct Params {
+        bins: u32,
+        overflow: u32,
+        lo: u32,
+        hi: u32,
+    }
+}
+
+/// Counting of the values outside of the range of a [`histogram_with`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OutOfRange {
+    /// Values below the range are counted in the first bin, and values above it in the last one.
+    Clamp,
+    /// Values outside of the range are counted in an extra bin after the others.
+    Overflow,
+}
+
+/// Counts the elements of `buf` in `bins` bins of equal width splitting `range` on the GPU,
+/// blocking until the counts are read back.
+///
+/// The range includes its start but not its end: an element `x` of `range.0 <= x < range.1` is
+/// counted in the bin `floor((x - range.0) * bins / (range.1 - range.0))`. The bins of integers
+/// are computed exactly, and the ones of floats in `f32`, so elements very close to the border
+/// of two bins can be counted in the other one. Elements outside of the range are counted in
+/// the first or last bin, see [`histogram_with`] to count them apart.
+///
+/// Each workgroup counts a strided share of the elements into its own histogram in workgroup
+/// memory for as many bins as it holds, 4088 with the default limits, and adds it to the result
+/// with atomics, so that many equal elements do not serialize the threads on a few counters.
+/// Larger histograms are counted with atomics on the result directly.
+///
+/// Fails with [`OpsError::NoBins`] if `bins` is 0, and with [`OpsError::EmptyRange`]
+/// if `range.0` is not below `range.1`.
+pub fn histogram<T: ReduceElement + PartialOrd>(
+    fw: &Framework,
+    buf: &GpuBuffer<T>,
+    bins: u32,
+    range: (T, T),
+) -> GpuResult<Vec<u32>> {
+    histogram_with(fw, buf, bins, range, OutOfRange::Clamp)
+}
+
+/// Counts the elements of `buf` like [`histogram`], counting the ones outside of `range`
+/// as `out_of_range` tells. With [`OutOfRange::Overflow`], `bins + 1` counts are returned.
+///
+/// NaNs are out of range, and counted like the elements above it: in the last bin when
+/// clamping, and in the overflow bin otherwise.
+pub fn histogram_with<T: ReduceElement + PartialOrd>(
+    fw: &Framework,
+    buf: &GpuBuffer<T>,
+    bins: u32,
+    range: (T, T),
+    out_of_range: OutOfRange,
+) -> GpuResult<Vec<u32>> {
+    let (lo, hi) = range;
+
+    if bins == 0 {
+        return Err(OpsError::NoBins.into());
+    }
+    // Also rejects NaN bounds.
+    if !matches!(lo.partial_cmp(&hi), Some(std::cmp::Ordering::Less)) {
+        return Err(OpsError::EmptyRange.into());
+    }
+
+    let overflow = out_of_range == OutOfRange::Overflow;
+    let total_bins = bins
+        .checked_add(overflow as u32)
+        .ok_or(OpsError::TooLong(bins as u64 + 1))?;
+
+    let len = buf.capacity();
+    if len == 0 {
+        return Ok(vec![0; total_bins as usize]);
+    }
+    if len > u32::MAX as u64 {
+        return Err(OpsError::TooLong(len).into());
+    }
+
+    let bin = if T::WGSL_TYPE == "f32" {
+        "min(u32((x - lo) / (hi - lo) * f32(params.bins)), params.bins - 1u)"
+    } else {
+        "mul_div(u32(x) - u32(lo), params.bins, u32(hi) - u32(lo))"
+    };
+    let shared_bins = shared_bins(fw);
+    let array_len = shared_bins.to_string();
+    let substitutions = [
+        ("T", T::WGSL_TYPE),
+        ("BIN", bin),
+        ("SHARED_BINS", array_len.as_str()),
+    ];
+    let key = format!("ops::histogram{:?}", substitutions);
+    let shader = fw.op_shaders.lock().unwrap().get_or_compile(&key, || {
+        Shader::from_wgsl_template(
+            fw,
+            include_str!("histogram.wgsl"),
+            &substitutions,
+            Some("ops::histogram"),
+        )
+    })?;
+
+    let counts = GpuBuffer::<u32>::try_from_slice(fw, &vec![0; total_bins as usize])?;
+    let params = GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            bins,
+            overflow: overflow as u32,
+            lo: bytemuck::cast(lo),
+            hi: bytemuck::cast(hi),
+        }],
+    );
+
+    let set = DescriptorSet::default()
+        .bind_buffer_at(0, buf, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(1, &counts, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(2, &params)?;
+
+    let entry_point = if total_bins <= shared_bins {
+        "count_shared"
+    } else {
+        "count_global"
+    };
+    let groups = (len as u32).div_ceil(WORKGROUP_SIZE).min(MAX_GROUPS);
+
+    Kernel::new(
+        fw,
+        Program::new(&shader, entry_point).add_descriptor_set(set),
+    )?
+    .enqueue(groups, 1, 1)?;
+
+    Ok(counts.read_vec_blocking()?)
+}
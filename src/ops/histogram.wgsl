@@ -0,0 +1,114 @@
+// Histogram of `input` into the `params.bins` bins of the range `[lo, hi)`, with an overflow
+// bin counting the values outside of it after them if `params.overflow` is set.
+// `count_shared` counts the strided share of each workgroup into a private histogram in
+// workgroup memory, then adds it to `counts`: less contention on the atomics of `counts`, but
+// only for up to `SHARED_BINS` bins, as many as the workgroup memory of the device holds.
+// `count_global` counts straight into `counts`.
+
+struct Params {
+    bins: u32,
+    overflow: u32,
+    lo: u32,
+    hi: u32,
+}
+
+let WORKGROUP_SIZE: u32 = 256u;
+
+@group(0) @binding(0) var<storage, read> input: array<{{T}}>;
+@group(0) @binding(1) var<storage, read_write> counts: array<atomic<u32>>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+var<workgroup> shared_counts: array<atomic<u32>, {{SHARED_BINS}}>;
+
+// `floor(a * b / d)` for `a < d`, computed exactly with a 64-bit product.
+fn mul_div(a: u32, b: u32, d: u32) -> u32 {
+    let a0 = a & 0xffffu;
+    let a1 = a >> 16u;
+    let b0 = b & 0xffffu;
+    let b1 = b >> 16u;
+
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let mid = (p00 >> 16u) + (p01 & 0xffffu) + (p10 & 0xffffu);
+    let low = (p00 & 0xffffu) | (mid << 16u);
+    let high = a1 * b1 + (p01 >> 16u) + (p10 >> 16u) + (mid >> 16u);
+
+    if (high == 0u) {
+        return low / d;
+    }
+
+    // Long division, whose quotient fits in 32 bits as `high < d` since `a < d`.
+    var rem = high;
+    var quotient = 0u;
+    for (var bit = 31; bit >= 0; bit = bit - 1) {
+        let carry = rem >> 31u;
+        rem = (rem << 1u) | ((low >> u32(bit)) & 1u);
+        quotient = quotient << 1u;
+        if (carry == 1u || rem >= d) {
+            rem = rem - d;
+            quotient = quotient | 1u;
+        }
+    }
+    return quotient;
+}
+
+// Bin of `x`, NaNs counting as above the range.
+fn bin_of(x: {{T}}) -> u32 {
+    let lo = bitcast<{{T}}>(params.lo);
+    let hi = bitcast<{{T}}>(params.hi);
+
+    if (x >= lo && x < hi) {
+        return {{BIN}};
+    }
+    if (params.overflow != 0u) {
+        return params.bins;
+    }
+    if (x < lo) {
+        return 0u;
+    }
+    return params.bins - 1u;
+}
+
+@compute @workgroup_size(256)
+fn count_shared(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let local = local_id.x;
+    let len = arrayLength(&input);
+    let stride = groups.x * WORKGROUP_SIZE;
+    let bins = params.bins + params.overflow;
+
+    for (var bin = local; bin < bins; bin = bin + WORKGROUP_SIZE) {
+        atomicStore(&shared_counts[bin], 0u);
+    }
+    workgroupBarrier();
+
+    for (var i = group_id.x * WORKGROUP_SIZE + local; i < len; i = i + stride) {
+        atomicAdd(&shared_counts[bin_of(input[i])], 1u);
+    }
+    workgroupBarrier();
+
+    for (var bin = local; bin < bins; bin = bin + WORKGROUP_SIZE) {
+        let count = atomicLoad(&shared_counts[bin]);
+        if (count != 0u) {
+            atomicAdd(&counts[bin], count);
+        }
+    }
+}
+
+@compute @workgroup_size(256)
+fn count_global(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let len = arrayLength(&input);
+    let stride = groups.x * WORKGROUP_SIZE;
+
+    for (var i = group_id.x * WORKGROUP_SIZE + local_id.x; i < len; i = i + stride) {
+        atomicAdd(&counts[bin_of(input[i])], 1u);
+    }
+}
@@ -0,0 +1,279 @@
+use crate::{
+    kernel::{ShaderDiagnostic, ShaderError},
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuError, GpuResult,
+    GpuUniformBuffer, Kernel, Program, Shader,
+};
+
+use super::{OpsError, ReduceElement};
+
+const TEMPLATE: &str = include_str!("map.wgsl");
+
+/// Placeholder of [`TEMPLATE`] replaced by the expression, alone at the end of its line.
+const EXPRESSION: &str = "{{EXPRESSION}}";
+
+crate::gpu_struct! {
+    /// `Params` of `map.wgsl`.
+    uniform struct Params {
+        len: u32,
+        _padding: [u32; 3],
+    }
+}
+
+/// Applies the `WGSL` `expression` to each element `x` of `input` on the GPU,
+/// returning a new buffer of the results:
+///
+/// ```ignore
+/// let scaled = ops::map(&fw, &buf, "x * 2.0 + 1.0")?;
+/// let clamped = ops::map(&fw, &buf, "clamp(x, 0.0, 1.0)")?;
+/// ```
+///
+/// The expression is spliced as is into the body of a generated kernel, where it can also use
+/// the index `i` of the element as a `u32`, and must evaluate to the type of the elements.
+/// Its shader is compiled on the first call and kept by the [`Framework`], so that repeated
+/// operations with the same expression and types do not compile it again.
+///
+/// This trades safety for convenience: the expression is not parsed by `gpgpu`, so it can
+/// break out of the generated kernel and read or write anything its bindings give access to.
+/// It must not come from an untrusted source. Errors in it fail with
+/// [`OpsError::InvalidExpression`], displayed against the expression rather than the
+/// generated source, but an expression that compiles can still make another valid kernel
+/// than the one meant.
+pub fn map<'fw, T: ReduceElement>(
+    fw: &'fw Framework,
+    input: &GpuBuffer<T>,
+    expression: &str,
+) -> GpuResult<GpuBuffer<'fw, T>> {
+    let mut output = GpuBuffer::try_with_capacity(fw, input.capacity())?;
+    map_into(fw, input, &mut output, expression)?;
+
+    Ok(output)
+}
+
+/// Applies the `WGSL` `expression` to each element `x` of `input` like [`map`], writing the
+/// results into the first elements of `output`, whose element type can differ from the one
+/// of `input`: `"f32(x) / 255.0"` maps `u32`s to `f32`s.
+///
+/// Fails with [`OpsError::OutputTooSmall`] if `output` holds less elements than `input`.
+pub fn map_into<T: ReduceElement, U: ReduceElement>(
+    fw: &Framework,
+    input: &GpuBuffer<T>,
+    output: &mut GpuBuffer<U>,
+    expression: &str,
+) -> GpuResult<()> {
+    let parameters = format!("x: {}", T::WGSL_TYPE);
+    let substitutions = [
+        ("A", T::WGSL_TYPE),
+        ("B", T::WGSL_TYPE),
+        ("OUTPUT", U::WGSL_TYPE),
+        ("RESULT", U::WGSL_TYPE),
+        ("CONVERT", ""),
+        ("PARAMETERS", &parameters),
+        ("ARGUMENTS", "operand_a[i]"),
+    ];
+
+    run(
+        fw,
+        "ops::map",
+        &substitutions,
+        expression,
+        input,
+        input,
+        output,
+    )
+}
+
+/// Applies the `WGSL` `expression` to each pair of elements `a` and `b` at the same index
+/// of `a` and `b` on the GPU, returning a new buffer of the results:
+///
+/// ```ignore
+/// let sums = ops::zip(&fw, &a, &b, "a + b")?;
+/// let lerped = ops::zip(&fw, &a, &b, "mix(a, b, 0.25)")?;
+/// ```
+///
+/// The expression is spliced into a generated kernel like the one of [`map`],
+/// with the same compromise on safety.
+///
+/// Fails with [`OpsError::OperandMismatch`] if `a` and `b` have different lengths.
+pub fn zip<'fw, T: ReduceElement>(
+    fw: &'fw Framework,
+    a: &GpuBuffer<T>,
+    b: &GpuBuffer<T>,
+    expression: &str,
+) -> GpuResult<GpuBuffer<'fw, T>> {
+    let mut output = GpuBuffer::try_with_capacity(fw, a.capacity())?;
+    zip_into(fw, a, b, &mut output, expression)?;
+
+    Ok(output)
+}
+
+/// Applies the `WGSL` `expression` to each pair of elements `a` and `b` like [`zip`], writing
+/// the results into the first elements of `output`. The element types of `a`, `b` and `output`
+/// can all differ: `"select(a, 0.0, b == 0u)"` masks `f32`s with `u32`s.
+///
+/// Fails with [`OpsError::OperandMismatch`] if `a` and `b` have different lengths,
+/// and with [`OpsError::OutputTooSmall`] if `output` holds less elements than them.
+pub fn zip_into<A: ReduceElement, B: ReduceElement, U: ReduceElement>(
+    fw: &Framework,
+    a: &GpuBuffer<A>,
+    b: &GpuBuffer<B>,
+    output: &mut GpuBuffer<U>,
+    expression: &str,
+) -> GpuResult<()> {
+    if a.capacity() != b.capacity() {
+        return Err(OpsError::OperandMismatch {
+            a: a.capacity(),
+            b: b.capacity(),
+        }
+        .into());
+    }
+
+    let parameters = format!("a: {}, b: {}", A::WGSL_TYPE, B::WGSL_TYPE);
+    let substitutions = [
+        ("A", A::WGSL_TYPE),
+        ("B", B::WGSL_TYPE),
+        ("OUTPUT", U::WGSL_TYPE),
+        ("RESULT", U::WGSL_TYPE),
+        ("CONVERT", ""),
+        ("PARAMETERS", &parameters),
+        ("ARGUMENTS", "operand_a[i], operand_b[i]"),
+    ];
+
+    run(fw, "ops::zip", &substitutions, expression, a, b, output)
+}
+
+/// Writes into the first elements of `flags` 1 for the elements `x` of `input` the `WGSL`
+/// `predicate` holds for, and 0 for the others, like [`map_into`].
+pub(super) fn predicate_flags<T: ReduceElement>(
+    fw: &Framework,
+    input: &GpuBuffer<T>,
+    flags: &GpuBuffer<u32>,
+    predicate: &str,
+) -> GpuResult<()> {
+    let parameters = format!("x: {}", T::WGSL_TYPE);
+    let substitutions = [
+        ("A", T::WGSL_TYPE),
+        ("B", T::WGSL_TYPE),
+        ("OUTPUT", "u32"),
+        ("RESULT", "bool"),
+        ("CONVERT", "u32"),
+        ("PARAMETERS", &parameters),
+        ("ARGUMENTS", "operand_a[i]"),
+    ];
+
+    run(
+        fw,
+        "ops::compact_by_expr",
+        &substitutions,
+        predicate,
+        input,
+        input,
+        flags,
+    )
+}
+
+/// Compiles the kernel of `expression` with the `substitutions` of the other placeholders
+/// of [`TEMPLATE`], or takes it from the cache of the [`Framework`], and runs it
+/// over the elements of `a` and `b`.
+fn run<A: ReduceElement, B: ReduceElement, U: ReduceElement>(
+    fw: &Framework,
+    name: &str,
+    substitutions: &[(&str, &str)],
+    expression: &str,
+    a: &GpuBuffer<A>,
+    b: &GpuBuffer<B>,
+    output: &GpuBuffer<U>,
+) -> GpuResult<()> {
+    // Flattened so that the expression stays on the line of its placeholder, which errors are
+    // located against. Each line break is replaced by one space, keeping the columns.
+    let expression = expression.replace(['\r', '\n'], " ");
+
+    let mut substitutions = substitutions.to_vec();
+    substitutions.push(("EXPRESSION", &expression));
+    let key = format!("{}{:?}", name, substitutions);
+
+    // Compiled first, so that invalid expressions are reported even for empty buffers.
+    let shader = fw
+        .op_shaders
+        .lock()
+        .unwrap()
+        .get_or_compile(&key, || {
+            Shader::from_wgsl_template(fw, TEMPLATE, &substitutions, Some(name))
+        })
+        .map_err(|err| expression_error(err, &expression))?;
+
+    let len = a.capacity();
+    if output.capacity() < len {
+        return Err(OpsError::OutputTooSmall {
+            required: len,
+            current: output.capacity(),
+        }
+        .into());
+    }
+    if len == 0 {
+        return Ok(());
+    }
+    if len > u32::MAX as u64 {
+        return Err(OpsError::TooLong(len).into());
+    }
+
+    let params = GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            len: len as u32,
+            _padding: [0; 3],
+        }],
+    );
+
+    let set = DescriptorSet::default()
+        .bind_buffer_at(0, a, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(1, b, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(2, output, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(3, &params)?;
+
+    Kernel::new(fw, Program::new(&shader, "main").add_descriptor_set(set))?
+        .enqueue_elements(len)?;
+
+    Ok(())
+}
+
+/// Turns the error of the kernel of `expression` into an [`OpsError::InvalidExpression`]
+/// pointing into `expression`. Errors located outside of it, e.g. a result of the wrong type
+/// reported on the `return` it is spliced after, underline the whole expression.
+fn expression_error(err: ShaderError, expression: &str) -> GpuError {
+    let (message, location) = match err {
+        ShaderError::Compilation(diagnostic) => {
+            let location = (diagnostic.line, diagnostic.column, diagnostic.length);
+            (diagnostic.message, Some(location))
+        }
+        ShaderError::InvalidWgsl(message) | ShaderError::InvalidShader(message) => (message, None),
+        err => return err.into(),
+    };
+
+    let (line, prefix) = TEMPLATE
+        .lines()
+        .enumerate()
+        .find_map(|(i, line)| line.find(EXPRESSION).map(|start| (i + 1, &line[..start])))
+        .expect("the template has an expression placeholder");
+    let start = prefix.chars().count() + 1;
+    let len = expression.chars().count();
+
+    let (column, length) = match location {
+        Some((error_line, column, length))
+            if error_line == line && column >= start && column < start + len =>
+        {
+            let column = column - start + 1;
+            (column, length.min(len + 1 - column))
+        }
+        _ => (1, len.max(1)),
+    };
+
+    OpsError::InvalidExpression(ShaderDiagnostic {
+        file: "expression".to_string(),
+        line: 1,
+        column,
+        length,
+        source_line: expression.to_string(),
+        message,
+    })
+    .into()
+}
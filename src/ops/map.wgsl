@@ -0,0 +1,32 @@
+// Element-wise operation of `ops::map` and `ops::zip`: each invocation applies `apply`,
+// whose body is the expression of the program, to the operands at its index, and stores
+// its result converted by `CONVERT`. `map` binds its input as both operands.
+
+struct Params {
+    len: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+@group(0) @binding(0) var<storage, read> operand_a: array<{{A}}>;
+@group(0) @binding(1) var<storage, read> operand_b: array<{{B}}>;
+@group(0) @binding(2) var<storage, read_write> output: array<{{OUTPUT}}>;
+@group(0) @binding(3) var<uniform> params: Params;
+
+fn apply({{PARAMETERS}}, i: u32) -> {{RESULT}} {
+    return {{EXPRESSION}};
+}
+
+@compute @workgroup_size(256)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = global_id.x + global_id.y * groups.x * 256u;
+    if (i >= params.len) {
+        return;
+    }
+
+    output[i] = {{CONVERT}}(apply({{ARGUMENTS}}, i));
+}
@@ -0,0 +1,175 @@
+use crate::{
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult, GpuUniformBuffer,
+    Kernel, Program, Shader,
+};
+
+use super::{OpsError, Scratch};
+
+/// Rows and columns of the tiles of `c` computed by each workgroup of the product kernel.
+const TILE: u32 = 16;
+
+crate::gpu_struct! {
+    /// `Params` of `matmul.wgsl`.
+    uniform struct Params {
+        m: u32,
+        n: u32,
+        k: u32,
+        transpose_b: u32,
+        alpha: f32,
+        beta: f32,
+        _padding: [u32; 2],
+    }
+}
+
+/// Scaling and layout of a product computed by [`matmul_with`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MatmulOptions {
+    alpha: f32,
+    beta: f32,
+    transpose_b: bool,
+}
+
+impl Default for MatmulOptions {
+    /// `c = a * b`.
+    fn default() -> Self {
+        Self {
+            alpha: 1.0,
+            beta: 0.0,
+            transpose_b: false,
+        }
+    }
+}
+
+impl MatmulOptions {
+    /// Scales the product of `a` and `b` by `alpha`, 1 by default.
+    pub fn alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Adds the elements of `c` scaled by `beta` to the product, 0 by default.
+    /// They are not read when `beta` is 0, so that their NaNs do not propagate.
+    pub fn beta(mut self, beta: f32) -> Self {
+        self.beta = beta;
+        self
+    }
+
+    /// Reads `b` as a `n` x `k` matrix the transpose of which is multiplied, e.g. a matrix of
+    /// weights stored by output, instead of a `k` x `n` matrix.
+    pub fn transpose_b(mut self, transpose_b: bool) -> Self {
+        self.transpose_b = transpose_b;
+        self
+    }
+}
+
+/// Checks that `matrix` holds at least the `rows * cols` elements of its matrix.
+pub(super) fn check_matrix<T: bytemuck::Pod>(
+    name: &'static str,
+    matrix: &GpuBuffer<T>,
+    rows: u32,
+    cols: u32,
+) -> GpuResult<()> {
+    let required = rows as u64 * cols as u64;
+
+    if required > u32::MAX as u64 {
+        return Err(OpsError::TooLong(required).into());
+    }
+    if matrix.capacity() < required {
+        return Err(OpsError::MatrixTooSmall {
+            matrix: name,
+            required,
+            current: matrix.capacity(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Multiplies the `m` x `k` matrix `a` by the `k` x `n` matrix `b` on the GPU, writing the
+/// `m` x `n` product into `c`. The matrices are stored by rows, and the elements of
+/// the buffers after them are left as they are.
+///
+/// Each workgroup computes a 16 x 16 tile of `c`, loading the tiles of `a` and `b` it depends on
+/// into workgroup memory one after the other. Dimensions that are not multiples of 16 are
+/// supported, most efficiently when they are large. The sums are sequential along `k`, so their
+/// rounding is the one of a naive loop in `f32`.
+///
+/// Fails with [`OpsError::MatrixTooSmall`] if a buffer holds less elements than its matrix, and
+/// with [`KernelError`](crate::kernel::KernelError) if `m` or `n` exceed 16 times the maximum
+/// number of workgroups per dimension.
+pub fn matmul(
+    fw: &Framework,
+    a: &GpuBuffer<f32>,
+    b: &GpuBuffer<f32>,
+    c: &mut GpuBuffer<f32>,
+    m: u32,
+    n: u32,
+    k: u32,
+) -> GpuResult<()> {
+    matmul_with(fw, a, b, c, m, n, k, MatmulOptions::default())
+}
+
+/// Computes `c = alpha * a * b + beta * c` on the GPU like [`matmul`], with the `alpha`, `beta`
+/// and layout of `b` of `options`.
+#[allow(clippy::too_many_arguments)]
+pub fn matmul_with(
+    fw: &Framework,
+    a: &GpuBuffer<f32>,
+    b: &GpuBuffer<f32>,
+    c: &mut GpuBuffer<f32>,
+    m: u32,
+    n: u32,
+    k: u32,
+    options: MatmulOptions,
+) -> GpuResult<()> {
+    check_matrix("a", a, m, k)?;
+    check_matrix("b", b, k, n)?;
+    check_matrix("c", c, m, n)?;
+
+    if m == 0 || n == 0 {
+        return Ok(());
+    }
+
+    // Empty buffers cannot be bound: `a` and `b` are not read when `k` is 0.
+    let placeholders = (k == 0).then(|| (Scratch::<f32>::new(fw, 1), Scratch::<f32>::new(fw, 1)));
+    let (a, b) = match &placeholders {
+        Some((a, b)) => (&**a, &**b),
+        None => (a, b),
+    };
+
+    let shader = fw
+        .op_shaders
+        .lock()
+        .unwrap()
+        .get_or_compile("ops::matmul", || {
+            Shader::from_wgsl_source(fw, include_str!("matmul.wgsl"), Some("ops::matmul"))
+        })?;
+
+    let params = GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            m,
+            n,
+            k,
+            transpose_b: options.transpose_b as u32,
+            alpha: options.alpha,
+            beta: options.beta,
+            _padding: [0; 2],
+        }],
+    );
+
+    let set = DescriptorSet::default()
+        .bind_buffer_at(0, a, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(1, b, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(2, c, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(3, &params)?;
+
+    Kernel::new(fw, Program::new(&shader, "main").add_descriptor_set(set))?.enqueue(
+        n.div_ceil(TILE),
+        m.div_ceil(TILE),
+        1,
+    )?;
+
+    Ok(())
+}
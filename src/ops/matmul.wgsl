@@ -0,0 +1,73 @@
+// Tiled product `c = alpha * a * b + beta * c` of a `m` x `k` matrix `a` and a `k` x `n` matrix `b`,
+// stored by rows, `b` being stored transposed if `params.transpose_b`. Each workgroup computes
+// a `TILE` x `TILE` tile of `c`, going through the tiles of `a` and `b` it depends on in workgroup memory.
+
+struct Params {
+    m: u32,
+    n: u32,
+    k: u32,
+    transpose_b: u32,
+    alpha: f32,
+    beta: f32,
+    _padding: vec2<u32>,
+}
+
+let TILE: u32 = 16u;
+
+@group(0) @binding(0) var<storage, read> a: array<f32>;
+@group(0) @binding(1) var<storage, read> b: array<f32>;
+@group(0) @binding(2) var<storage, read_write> c: array<f32>;
+@group(0) @binding(3) var<uniform> params: Params;
+
+var<workgroup> tile_a: array<array<f32, 16>, 16>;
+var<workgroup> tile_b: array<array<f32, 16>, 16>;
+
+@compute @workgroup_size(16, 16, 1)
+fn main(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+) {
+    let x = local_id.x;
+    let y = local_id.y;
+    let row = group_id.y * TILE + y;
+    let col = group_id.x * TILE + x;
+
+    var acc = 0.0;
+    for (var t = 0u; t * TILE < params.k; t = t + 1u) {
+        // The elements past the edges of the matrices are loaded as zeros.
+        let a_col = t * TILE + x;
+        var a_value = 0.0;
+        if (row < params.m && a_col < params.k) {
+            a_value = a[row * params.k + a_col];
+        }
+        tile_a[y][x] = a_value;
+
+        let b_row = t * TILE + y;
+        var b_value = 0.0;
+        if (b_row < params.k && col < params.n) {
+            if (params.transpose_b != 0u) {
+                b_value = b[col * params.k + b_row];
+            } else {
+                b_value = b[b_row * params.n + col];
+            }
+        }
+        tile_b[y][x] = b_value;
+
+        workgroupBarrier();
+        for (var i = 0u; i < TILE; i = i + 1u) {
+            acc = acc + tile_a[y][i] * tile_b[i][x];
+        }
+        workgroupBarrier();
+    }
+
+    if (row < params.m && col < params.n) {
+        let index = row * params.n + col;
+
+        // As in BLAS, `c` is not read when `beta` is zero, so that its NaNs do not propagate.
+        if (params.beta == 0.0) {
+            c[index] = params.alpha * acc;
+        } else {
+            c[index] = params.alpha * acc + params.beta * c[index];
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult, GpuUniformBuffer,
+    Kernel, Program, Shader,
+};
+
+use super::{scan, OpsError, Scratch};
+
+/// Keys sorted by each workgroup of the sort kernels.
+const BLOCK_SIZE: u32 = 1024;
+
+/// Bits of the keys sorted by each pass.
+const DIGIT_BITS: u32 = 4;
+
+crate::gpu_struct! {
+    /// `Params` of `radix_sort.wgsl`.
+    uniform struct Params {
+        len: u32,
+        shift: u32,
+        blocks: u32,
+        pairs: u32,
+    }
+}
+
+/// Sorts the keys of `keys` in ascending order on the GPU.
+///
+/// A least significant digit radix sort: each of its 8 passes sorts the keys by 4 of their bits,
+/// counting the digits of each block of 1024 keys, scanning these counts with
+/// [`scan_in_place`](super::scan_in_place) into the offsets of the blocks, then scattering the keys
+/// into a buffer the [`Framework`] keeps between operations and back. Its work only depends on the
+/// number of keys, not on their order: sorted, reverse sorted and constant keys take as long as
+/// random ones.
+pub fn radix_sort(fw: &Framework, keys: &mut GpuBuffer<u32>) -> GpuResult<()> {
+    sort(fw, keys, None)
+}
+
+/// Sorts the keys of `keys` in ascending order on the GPU like [`radix_sort`], moving the
+/// element of `values` at the same index along with each key, e.g. the index of an object.
+///
+/// The sort is stable: the values of equal keys keep their order.
+/// Fails with [`OpsError::LengthMismatch`] if `keys` and `values` have different lengths.
+pub fn radix_sort_pairs(
+    fw: &Framework,
+    keys: &mut GpuBuffer<u32>,
+    values: &mut GpuBuffer<u32>,
+) -> GpuResult<()> {
+    if keys.capacity() != values.capacity() {
+        return Err(OpsError::LengthMismatch {
+            keys: keys.capacity(),
+            values: values.capacity(),
+        }
+        .into());
+    }
+
+    sort(fw, keys, Some(values))
+}
+
+fn sort(fw: &Framework, keys: &GpuBuffer<u32>, values: Option<&GpuBuffer<u32>>) -> GpuResult<()> {
+    let len = keys.capacity();
+    if len <= 1 {
+        return Ok(());
+    }
+    if len > u32::MAX as u64 {
+        return Err(OpsError::TooLong(len).into());
+    }
+    let len = len as u32;
+
+    let shader = fw
+        .op_shaders
+        .lock()
+        .unwrap()
+        .get_or_compile("ops::radix_sort", || {
+            Shader::from_wgsl_source(fw, include_str!("radix_sort.wgsl"), Some("ops::radix_sort"))
+        })?;
+    let scan_kernels = scan::ScanKernels::new(fw)?;
+
+    let blocks = len.div_ceil(BLOCK_SIZE);
+    let digits = 1 << DIGIT_BITS;
+
+    let offsets = Scratch::<u32>::new(fw, (digits * blocks) as u64);
+    let other_keys = Scratch::<u32>::new(fw, len as u64);
+    // Without values, small buffers the kernels do not touch are bound in their place.
+    let pairs = values.is_some();
+    let other_values = Scratch::<u32>::new(fw, if pairs { len as u64 } else { 1 });
+    let no_values = Scratch::<u32>::new(fw, 1);
+    let values = values.unwrap_or(&no_values);
+
+    // The blocks are laid out in rows of workgroups, as a dimension is limited to 65535 of them.
+    let x = blocks.min(fw.limits().max_compute_workgroups_per_dimension);
+    let y = blocks.div_ceil(x);
+
+    // Created with the buffers of the first pass, and enqueued with the ones of each pass.
+    let mut kernels: Option<(Kernel, Kernel)> = None;
+
+    for pass in 0..u32::BITS / DIGIT_BITS {
+        // The keys go back and forth between `keys` and `other_keys`, ending in `keys`.
+        let (keys_in, keys_out, values_in, values_out) = if pass % 2 == 0 {
+            (keys, &*other_keys, values, &*other_values)
+        } else {
+            (&*other_keys, keys, &*other_values, values)
+        };
+
+        let params = GpuUniformBuffer::from_slice(
+            fw,
+            &[Params {
+                len,
+                shift: pass * DIGIT_BITS,
+                blocks,
+                pairs: pairs as u32,
+            }],
+        );
+
+        let set = DescriptorSet::default()
+            .bind_buffer_at(0, keys_in, GpuBufferUsage::ReadOnly)?
+            .bind_buffer_at(1, keys_out, GpuBufferUsage::ReadWrite)?
+            .bind_buffer_at(2, values_in, GpuBufferUsage::ReadOnly)?
+            .bind_buffer_at(3, values_out, GpuBufferUsage::ReadWrite)?
+            .bind_buffer_at(4, &offsets, GpuBufferUsage::ReadWrite)?
+            .bind_uniform_buffer_at(5, &params)?;
+
+        let (count_digits, scatter) = match &kernels {
+            Some(kernels) => kernels,
+            None => {
+                let kernel = |entry_point| {
+                    Kernel::new(
+                        fw,
+                        Program::new(&shader, entry_point).add_descriptor_set(set.clone()),
+                    )
+                };
+
+                kernels.insert((kernel("count_digits")?, kernel("scatter")?))
+            }
+        };
+
+        count_digits.enqueue_with_sets(x, y, 1, &[&set])?;
+        scan::scan_level(fw, &scan_kernels, &offsets, digits * blocks, true)?;
+        scatter.enqueue_with_sets(x, y, 1, &[&set])?;
+    }
+
+    Ok(())
+}
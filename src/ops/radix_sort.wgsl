@@ -0,0 +1,125 @@
+// Pass of a least significant digit radix sort, sorting by the 4 bits of the keys at
+// `params.shift`: `count_digits` counts the digits of each block of `BLOCK_SIZE` keys into
+// `offsets`, laid out digit by digit, which are then scanned, and `scatter` moves each key
+// to the offset of its digit and block plus its rank among the keys of this digit in its block.
+
+struct Params {
+    len: u32,
+    shift: u32,
+    blocks: u32,
+    pairs: u32,
+}
+
+let WORKGROUP_SIZE: u32 = 256u;
+let ITEMS: u32 = 4u;
+let BLOCK_SIZE: u32 = 1024u;
+let DIGITS: u32 = 16u;
+
+@group(0) @binding(0) var<storage, read> keys_in: array<u32>;
+@group(0) @binding(1) var<storage, read_write> keys_out: array<u32>;
+@group(0) @binding(2) var<storage, read> values_in: array<u32>;
+@group(0) @binding(3) var<storage, read_write> values_out: array<u32>;
+@group(0) @binding(4) var<storage, read_write> offsets: array<u32>;
+@group(0) @binding(5) var<uniform> params: Params;
+
+var<workgroup> histogram: array<atomic<u32>, 16>;
+var<workgroup> counts: array<u32, 256>;
+
+fn digit(key: u32) -> u32 {
+    return (key >> params.shift) & (DIGITS - 1u);
+}
+
+@compute @workgroup_size(256)
+fn count_digits(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let block = group_id.x + group_id.y * groups.x;
+    if (block >= params.blocks) {
+        return;
+    }
+
+    let local = local_id.x;
+    if (local < DIGITS) {
+        atomicStore(&histogram[local], 0u);
+    }
+    workgroupBarrier();
+
+    let first = block * BLOCK_SIZE + local * ITEMS;
+    for (var k = 0u; k < ITEMS; k = k + 1u) {
+        if (first + k < params.len) {
+            atomicAdd(&histogram[digit(keys_in[first + k])], 1u);
+        }
+    }
+    workgroupBarrier();
+
+    if (local < DIGITS) {
+        offsets[local * params.blocks + block] = atomicLoad(&histogram[local]);
+    }
+}
+
+@compute @workgroup_size(256)
+fn scatter(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let block = group_id.x + group_id.y * groups.x;
+    if (block >= params.blocks) {
+        return;
+    }
+
+    let local = local_id.x;
+    let first = block * BLOCK_SIZE + local * ITEMS;
+
+    var digits: array<u32, 4>;
+    for (var k = 0u; k < ITEMS; k = k + 1u) {
+        // Out of range items get a digit matching none.
+        digits[k] = DIGITS;
+        if (first + k < params.len) {
+            digits[k] = digit(keys_in[first + k]);
+        }
+    }
+
+    // The rank of each item among the items of its digit in the block, in order, so that the sort is stable.
+    var ranks: array<u32, 4>;
+    for (var d = 0u; d < DIGITS; d = d + 1u) {
+        var count = 0u;
+        for (var k = 0u; k < ITEMS; k = k + 1u) {
+            if (digits[k] == d) {
+                count = count + 1u;
+            }
+        }
+
+        workgroupBarrier();
+        counts[local] = count;
+        for (var offset = 1u; offset < WORKGROUP_SIZE; offset = offset * 2u) {
+            workgroupBarrier();
+            var before = 0u;
+            if (local >= offset) {
+                before = counts[local - offset];
+            }
+            workgroupBarrier();
+            counts[local] = counts[local] + before;
+        }
+        workgroupBarrier();
+
+        var rank = offsets[d * params.blocks + block] + counts[local] - count;
+        for (var k = 0u; k < ITEMS; k = k + 1u) {
+            if (digits[k] == d) {
+                ranks[k] = rank;
+                rank = rank + 1u;
+            }
+        }
+    }
+
+    for (var k = 0u; k < ITEMS; k = k + 1u) {
+        if (digits[k] < DIGITS) {
+            keys_out[ranks[k]] = keys_in[first + k];
+            if (params.pairs != 0u) {
+                values_out[ranks[k]] = values_in[first + k];
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult, GpuUniformBuffer,
+    Kernel, Program, Shader,
+};
+
+use super::OpsError;
+
+crate::gpu_struct! {
+    /// `Params` of `random.wgsl`.
+    uniform struct Params {
+        len: u32,
+        distribution: u32,
+        key0: u32,
+        key1: u32,
+    }
+}
+
+/// Distribution of the floats drawn by [`fill_random`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Distribution {
+    /// Uniform over `[0, 1)`, in steps of `2^-24`.
+    Uniform01,
+    /// Standard normal: a mean of 0 and a standard deviation of 1.
+    Normal,
+}
+
+/// Fills `buf` with pseudo-random floats of `distribution` on the GPU.
+///
+/// The numbers are drawn with Philox4x32-10, a counter-based generator: the elements at indices
+/// `4n` to `4n + 3` are made of the 4 words of the block `n` encrypted with the key `seed`, so
+/// each element only depends on `seed` and its index. The same seed fills the same buffer with
+/// the same bits on every run, however the invocations are scheduled, and longer buffers start
+/// with the elements of shorter ones. Uniform floats take the 24 high bits of their word, and
+/// normal ones are made of pairs of words with the Box-Muller transform, whose `log`, `cos` and
+/// `sin` can round differently on other devices.
+///
+/// This is not a cryptographically secure generator.
+pub fn fill_random(
+    fw: &Framework,
+    buf: &mut GpuBuffer<f32>,
+    seed: u64,
+    distribution: Distribution,
+) -> GpuResult<()> {
+    let distribution = match distribution {
+        Distribution::Uniform01 => 1,
+        Distribution::Normal => 2,
+    };
+
+    fill(fw, buf, "f32", seed, distribution)
+}
+
+/// Fills `buf` with pseudo-random `u32`s uniform over all their values on the GPU: the words
+/// of the generator of [`fill_random`] themselves, with the same reproducibility.
+pub fn fill_random_u32(fw: &Framework, buf: &mut GpuBuffer<u32>, seed: u64) -> GpuResult<()> {
+    fill(fw, buf, "u32", seed, 0)
+}
+
+fn fill<T: bytemuck::Pod>(
+    fw: &Framework,
+    buf: &GpuBuffer<T>,
+    wgsl_type: &str,
+    seed: u64,
+    distribution: u32,
+) -> GpuResult<()> {
+    let len = buf.capacity();
+    if len == 0 {
+        return Ok(());
+    }
+    if len > u32::MAX as u64 {
+        return Err(OpsError::TooLong(len).into());
+    }
+
+    let substitutions = [("T", wgsl_type)];
+    let key = format!("ops::random{:?}", substitutions);
+    let shader = fw.op_shaders.lock().unwrap().get_or_compile(&key, || {
+        Shader::from_wgsl_template(
+            fw,
+            include_str!("random.wgsl"),
+            &substitutions,
+            Some("ops::random"),
+        )
+    })?;
+
+    let params = GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            len: len as u32,
+            distribution,
+            key0: seed as u32,
+            key1: (seed >> 32) as u32,
+        }],
+    );
+
+    let set = DescriptorSet::default()
+        .bind_buffer_at(0, buf, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(1, &params)?;
+
+    // One invocation per block of 4 elements.
+    Kernel::new(fw, Program::new(&shader, "main").add_descriptor_set(set))?
+        .enqueue_elements(len.div_ceil(4))?;
+
+    Ok(())
+}
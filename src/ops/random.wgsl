@@ -0,0 +1,100 @@
+// Pseudo-random fill of `output` with Philox4x32-10: each invocation encrypts its block index
+// with the key of the seed into 4 random words, the bits of its 4 elements, so that the element
+// at each index only depends on the seed and the index.
+
+struct Params {
+    len: u32,
+    distribution: u32,
+    key0: u32,
+    key1: u32,
+}
+
+let WORKGROUP_SIZE: u32 = 256u;
+let ROUNDS: u32 = 10u;
+
+let DISTRIBUTION_BITS: u32 = 0u;
+let DISTRIBUTION_UNIFORM: u32 = 1u;
+let DISTRIBUTION_NORMAL: u32 = 2u;
+
+let TAU: f32 = 6.283185307179586;
+
+@group(0) @binding(0) var<storage, read_write> output: array<{{T}}>;
+@group(0) @binding(1) var<uniform> params: Params;
+
+// High and low words of the 64-bit product of `a` and `b`.
+fn mul_wide(a: u32, b: u32) -> vec2<u32> {
+    let a0 = a & 0xffffu;
+    let a1 = a >> 16u;
+    let b0 = b & 0xffffu;
+    let b1 = b >> 16u;
+
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let mid = (p00 >> 16u) + (p01 & 0xffffu) + (p10 & 0xffffu);
+    let low = (p00 & 0xffffu) | (mid << 16u);
+    let high = a1 * b1 + (p01 >> 16u) + (p10 >> 16u) + (mid >> 16u);
+
+    return vec2<u32>(high, low);
+}
+
+fn philox(counter: vec4<u32>, key: vec2<u32>) -> vec4<u32> {
+    var x = counter;
+    var k = key;
+
+    for (var r = 0u; r < ROUNDS; r = r + 1u) {
+        let p0 = mul_wide(0xd2511f53u, x.x);
+        let p1 = mul_wide(0xcd9e8d57u, x.z);
+        x = vec4<u32>(p1.x ^ x.y ^ k.x, p1.y, p0.x ^ x.w ^ k.y, p0.y);
+        k = k + vec2<u32>(0x9e3779b9u, 0xbb67ae85u);
+    }
+
+    return x;
+}
+
+// Uniform float of `[0, 1)` from the 24 high bits of `word`.
+fn to_uniform(word: u32) -> f32 {
+    return f32(word >> 8u) * (1.0 / 16777216.0);
+}
+
+// Two independent standard normal floats from two random words, with the Box-Muller transform.
+fn to_normal(a: u32, b: u32) -> vec2<f32> {
+    // In `(0, 1]`, so that its logarithm is finite.
+    let u1 = f32((a >> 8u) + 1u) * (1.0 / 16777216.0);
+    let radius = sqrt(-2.0 * log(u1));
+    let angle = TAU * to_uniform(b);
+
+    return radius * vec2<f32>(cos(angle), sin(angle));
+}
+
+@compute @workgroup_size(256)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let block = global_id.x + global_id.y * groups.x * WORKGROUP_SIZE;
+    // `params.len` is at least 1.
+    if (block > (params.len - 1u) / 4u) {
+        return;
+    }
+
+    let words = philox(vec4<u32>(block, 0u, 0u, 0u), vec2<u32>(params.key0, params.key1));
+
+    var bits = words;
+    if (params.distribution == DISTRIBUTION_UNIFORM) {
+        bits = bitcast<vec4<u32>>(vec4<f32>(
+            to_uniform(words.x),
+            to_uniform(words.y),
+            to_uniform(words.z),
+            to_uniform(words.w),
+        ));
+    } else if (params.distribution == DISTRIBUTION_NORMAL) {
+        bits = bitcast<vec4<u32>>(vec4<f32>(to_normal(words.x, words.y), to_normal(words.z, words.w)));
+    }
+
+    // Compared without computing the indices past the last one, which could overflow.
+    let first = block * 4u;
+    for (var k = 0u; k < min(4u, params.len - first); k = k + 1u) {
+        output[first + k] = bitcast<{{T}}>(bits[k]);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult, Kernel, Program, Shader,
+};
+
+use super::{OpsError, Scratch};
+
+/// Threads of the workgroups of the reduction kernels.
+const WORKGROUP_SIZE: u32 = 256;
+
+/// Most workgroups of the first pass of a reduction, whose partial results the second pass combines.
+const MAX_PARTIALS: u32 = 1024;
+
+/// Reduction computed by [`reduce`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReduceOp {
+    Sum,
+    Min,
+    Max,
+}
+
+/// Element of a buffer that can be reduced on the GPU, or read and written by the kernels
+/// [`map`](super::map) and [`zip`](super::zip) generate: `u32`, `i32` and `f32`.
+pub trait ReduceElement: bytemuck::Pod {
+    /// Name of the type in `WGSL`.
+    const WGSL_TYPE: &'static str;
+    /// `WGSL` expression of the smallest value of the type.
+    const WGSL_MIN: &'static str;
+    /// `WGSL` expression of the largest value of the type.
+    const WGSL_MAX: &'static str;
+}
+
+impl ReduceElement for u32 {
+    const WGSL_TYPE: &'static str = "u32";
+    const WGSL_MIN: &'static str = "0u";
+    const WGSL_MAX: &'static str = "0xffffffffu";
+}
+
+impl ReduceElement for i32 {
+    const WGSL_TYPE: &'static str = "i32";
+    const WGSL_MIN: &'static str = "bitcast<i32>(0x80000000u)";
+    const WGSL_MAX: &'static str = "2147483647";
+}
+
+impl ReduceElement for f32 {
+    const WGSL_TYPE: &'static str = "f32";
+    const WGSL_MIN: &'static str = "bitcast<f32>(0xff800000u)";
+    const WGSL_MAX: &'static str = "bitcast<f32>(0x7f800000u)";
+}
+
+/// Reduces the elements of `buf` with `op` on the GPU, blocking until the result is read back.
+///
+/// The reduction runs in two passes: up to 1024 workgroups each reduce a strided share
+/// of the elements, first sequentially in each thread, then in a tree in workgroup memory,
+/// and a single workgroup reduces their partial results the same way.
+/// Only the result, a few bytes, is read back.
+///
+/// Integer sums wrap on overflow. Float sums are not sequential: the elements of each
+/// thread, about `len / 262144` of them, are summed in order, and these sums pairwise. The error
+/// grows with the length of each thread share and the logarithm of the thread count, far slower
+/// than the one of a sequential sum, but the result can differ from a CPU sum in the last bits.
+/// `Min` and `Max` skip the NaNs of float buffers: a buffer of NaNs only reduces to an infinity.
+///
+/// The sum of an empty buffer is zero; its minimum and maximum fail with [`OpsError::Empty`].
+pub fn reduce<T: ReduceElement>(fw: &Framework, buf: &GpuBuffer<T>, op: ReduceOp) -> GpuResult<T> {
+    match (run::<T>(fw, buf, op)?, op) {
+        (Some((_, value)), _) => Ok(value),
+        (None, ReduceOp::Sum) => Ok(T::zeroed()),
+        (None, _) => Err(OpsError::Empty.into()),
+    }
+}
+
+/// Finds the smallest element of `buf` on the GPU like [`reduce`] with [`ReduceOp::Min`],
+/// returning its index along with it. Ties resolve to the lowest index, and a float buffer
+/// of NaNs only to `u32::MAX` and an infinity.
+///
+/// Fails with [`OpsError::Empty`] if `buf` is empty.
+pub fn argmin<T: ReduceElement>(fw: &Framework, buf: &GpuBuffer<T>) -> GpuResult<(u32, T)> {
+    run(fw, buf, ReduceOp::Min)?.ok_or_else(|| OpsError::Empty.into())
+}
+
+/// Finds the largest element of `buf` on the GPU like [`reduce`] with [`ReduceOp::Max`],
+/// returning its index along with it. Ties resolve to the lowest index, and a float buffer
+/// of NaNs only to `u32::MAX` and an infinity.
+///
+/// Fails with [`OpsError::Empty`] if `buf` is empty.
+pub fn argmax<T: ReduceElement>(fw: &Framework, buf: &GpuBuffer<T>) -> GpuResult<(u32, T)> {
+    run(fw, buf, ReduceOp::Max)?.ok_or_else(|| OpsError::Empty.into())
+}
+
+/// Runs the two passes of the reduction of `buf` with `op`, returning the index and value
+/// of the result, or `None` if `buf` is empty. The index of a sum is meaningless.
+fn run<T: ReduceElement>(
+    fw: &Framework,
+    buf: &GpuBuffer<T>,
+    op: ReduceOp,
+) -> GpuResult<Option<(u32, T)>> {
+    if buf.capacity() == 0 {
+        return Ok(None);
+    }
+
+    let result = enqueue(fw, buf, None, op, "")?;
+    let [value, index] = result.read_vec_blocking()?[0];
+
+    Ok(Some((index, bytemuck::cast(value))))
+}
+
+/// `WGSL` expression of the identity of `op` over `T`s, and body of a `combine` function of
+/// two `Pair`s `a` and `b` of a `value` and an `index`, reducing them with `op`.
+pub(super) fn combine<T: ReduceElement>(op: ReduceOp) -> (String, &'static str) {
+    let (identity, combine) = match op {
+        ReduceOp::Sum => ("{{T}}(0)", "return Pair(a.value + b.value, 0u);"),
+        ReduceOp::Min => (
+            T::WGSL_MAX,
+            "if (b.value < a.value || (b.value == a.value && b.index < a.index)) { return b; } return a;",
+        ),
+        ReduceOp::Max => (
+            T::WGSL_MIN,
+            "if (b.value > a.value || (b.value == a.value && b.index < a.index)) { return b; } return a;",
+        ),
+    };
+
+    (identity.replace("{{T}}", T::WGSL_TYPE), combine)
+}
+
+/// Enqueues the two passes of the reduction of the non-empty `buf` with `op`, its elements being
+/// multiplied by the ones of `other` if any, and returns the buffer the `Pair` of the result is
+/// written to, its value mapped by the `WGSL` function `finish` unless it is empty.
+pub(super) fn enqueue<'fw, T: ReduceElement>(
+    fw: &'fw Framework,
+    buf: &GpuBuffer<T>,
+    other: Option<&GpuBuffer<T>>,
+    op: ReduceOp,
+    finish: &str,
+) -> GpuResult<Scratch<'fw, [u32; 2]>> {
+    let len = buf.capacity();
+    if len > u32::MAX as u64 {
+        return Err(OpsError::TooLong(len).into());
+    }
+
+    let (identity, combine) = combine::<T>(op);
+    let workgroup_size = WORKGROUP_SIZE.to_string();
+
+    let substitutions = [
+        ("T", T::WGSL_TYPE),
+        ("WORKGROUP_SIZE", &workgroup_size),
+        ("IDENTITY", &identity),
+        ("COMBINE", combine),
+        (
+            "LOAD",
+            if other.is_some() {
+                "input[i] * other[i]"
+            } else {
+                "input[i]"
+            },
+        ),
+        ("FINISH", finish),
+    ];
+    let key = format!("ops::reduce{:?}", substitutions);
+    let shader = fw.op_shaders.lock().unwrap().get_or_compile(&key, || {
+        Shader::from_wgsl_template(
+            fw,
+            include_str!("reduce.wgsl"),
+            &substitutions,
+            Some("ops::reduce"),
+        )
+    })?;
+
+    let groups = (len as u32).div_ceil(WORKGROUP_SIZE).min(MAX_PARTIALS);
+
+    // `Pair`s of a 4 bytes value and a `u32` index.
+    let partials = Scratch::<[u32; 2]>::new(fw, groups as u64);
+    let result = Scratch::<[u32; 2]>::new(fw, 1);
+
+    let mut input_set = DescriptorSet::default()
+        .bind_buffer_at(0, buf, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(1, &partials, GpuBufferUsage::ReadWrite)?;
+    if let Some(other) = other {
+        input_set = input_set.bind_buffer_at(3, other, GpuBufferUsage::ReadOnly)?;
+    }
+    let partials_set = DescriptorSet::default()
+        .bind_buffer_at(1, &partials, GpuBufferUsage::ReadWrite)?
+        .bind_buffer_at(2, &result, GpuBufferUsage::ReadWrite)?;
+
+    Kernel::new(
+        fw,
+        Program::new(&shader, "reduce_input").add_descriptor_set(input_set),
+    )?
+    .enqueue(groups, 1, 1)?;
+    Kernel::new(
+        fw,
+        Program::new(&shader, "reduce_partials").add_descriptor_set(partials_set),
+    )?
+    .enqueue(1, 1, 1)?;
+
+    Ok(result)
+}
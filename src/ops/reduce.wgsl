@@ -0,0 +1,71 @@
+// Two-pass reduction of `input` with `combine`: each workgroup of the first pass reduces
+// a strided share of `input` into one of the `partials`, which a single workgroup
+// of the second pass reduces into `result`. Each element is loaded by `LOAD`, e.g. multiplied
+// by the element of `other` at its index, and the value of `result` is mapped by `FINISH`.
+
+struct Pair {
+    value: {{T}},
+    index: u32,
+}
+
+@group(0) @binding(0) var<storage, read> input: array<{{T}}>;
+@group(0) @binding(1) var<storage, read_write> partials: array<Pair>;
+@group(0) @binding(2) var<storage, read_write> result: Pair;
+@group(0) @binding(3) var<storage, read> other: array<{{T}}>;
+
+var<workgroup> scratch: array<Pair, {{WORKGROUP_SIZE}}>;
+
+fn combine(a: Pair, b: Pair) -> Pair {
+    {{COMBINE}}
+}
+
+// Reduces the `scratch` of the workgroup in a tree, into its first element.
+fn reduce_scratch(local: u32) {
+    for (var stride = {{WORKGROUP_SIZE}}u / 2u; stride > 0u; stride = stride / 2u) {
+        workgroupBarrier();
+        if (local < stride) {
+            scratch[local] = combine(scratch[local], scratch[local + stride]);
+        }
+    }
+}
+
+@compute @workgroup_size({{WORKGROUP_SIZE}})
+fn reduce_input(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let local = local_id.x;
+    let len = arrayLength(&input);
+    let stride = groups.x * {{WORKGROUP_SIZE}}u;
+
+    var acc = Pair({{IDENTITY}}, 0xffffffffu);
+    for (var i = group_id.x * {{WORKGROUP_SIZE}}u + local; i < len; i = i + stride) {
+        acc = combine(acc, Pair({{LOAD}}, i));
+    }
+    scratch[local] = acc;
+
+    reduce_scratch(local);
+
+    if (local == 0u) {
+        partials[group_id.x] = scratch[0];
+    }
+}
+
+@compute @workgroup_size({{WORKGROUP_SIZE}})
+fn reduce_partials(@builtin(local_invocation_id) local_id: vec3<u32>) {
+    let local = local_id.x;
+    let len = arrayLength(&partials);
+
+    var acc = Pair({{IDENTITY}}, 0xffffffffu);
+    for (var i = local; i < len; i = i + {{WORKGROUP_SIZE}}u) {
+        acc = combine(acc, partials[i]);
+    }
+    scratch[local] = acc;
+
+    reduce_scratch(local);
+
+    if (local == 0u) {
+        result = Pair({{FINISH}}(scratch[0].value), scratch[0].index);
+    }
+}
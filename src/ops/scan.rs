@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use crate::{
+    BufOps, DescriptorLayout, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult,
+    GpuUniformBuffer, Kernel, Program, Shader,
+};
+
+use super::{OpsError, Scratch};
+
+/// Elements scanned by each workgroup of the scan kernels.
+const BLOCK_SIZE: u32 = 1024;
+
+crate::gpu_struct! {
+    /// `Params` of `scan.wgsl`.
+    uniform struct Params {
+        len: u32,
+        exclusive: u32,
+        blocks: u32,
+        _padding: u32,
+    }
+}
+
+/// Prefix sum computed by [`scan`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScanKind {
+    /// Each element becomes the sum of the elements up to it, included: `[1, 2, 3]` into `[1, 3, 6]`.
+    Inclusive,
+    /// Each element becomes the sum of the elements before it: `[1, 2, 3]` into `[0, 1, 3]`.
+    Exclusive,
+}
+
+/// Writes the prefix sums of the elements of `input` into the first elements of `output`,
+/// leaving the others as they are. Sums wrap on overflow.
+///
+/// The elements are copied into `output` and scanned there like [`scan_in_place`].
+///
+/// Fails with [`OpsError::OutputTooSmall`] if `output` holds less elements than `input`.
+pub fn scan(
+    fw: &Framework,
+    input: &GpuBuffer<u32>,
+    output: &mut GpuBuffer<u32>,
+    kind: ScanKind,
+) -> GpuResult<()> {
+    let len = input.capacity();
+    if output.capacity() < len {
+        return Err(OpsError::OutputTooSmall {
+            required: len,
+            current: output.capacity(),
+        }
+        .into());
+    }
+    if len == 0 {
+        return Ok(());
+    }
+
+    let mut encoder = fw
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("ops::scan"),
+        });
+    encoder.copy_buffer_to_buffer(
+        input.as_gpu_buffer(),
+        0,
+        output.as_gpu_buffer(),
+        0,
+        input.size(),
+    );
+    fw.queue.submit(Some(encoder.finish()));
+
+    scan_len(fw, output, len, kind)
+}
+
+/// Replaces the elements of `buf` with their prefix sums. Sums wrap on overflow.
+///
+/// Each workgroup scans a block of 1024 elements, and writes the total of its block into
+/// a buffer of block sums. These are scanned the same way, recursively, and added to the
+/// elements of the blocks after them in a last pass, so any length up to the maximum buffer
+/// size is supported. The block sums are taken from buffers the [`Framework`] keeps between
+/// operations, so repeated scans of the same length do not allocate.
+pub fn scan_in_place(fw: &Framework, buf: &mut GpuBuffer<u32>, kind: ScanKind) -> GpuResult<()> {
+    scan_len(fw, buf, buf.capacity(), kind)
+}
+
+/// Scans the first `len` elements of `buf`.
+fn scan_len(fw: &Framework, buf: &GpuBuffer<u32>, len: u64, kind: ScanKind) -> GpuResult<()> {
+    if len == 0 {
+        return Ok(());
+    }
+    if len > u32::MAX as u64 {
+        return Err(OpsError::TooLong(len).into());
+    }
+
+    let kernels = ScanKernels::new(fw)?;
+
+    scan_level(fw, &kernels, buf, len as u32, kind == ScanKind::Exclusive)
+}
+
+/// Kernels of the passes of a scan, created once per operation and enqueued with the buffers
+/// of each level of its recursion, see [`scan_level`].
+pub(super) struct ScanKernels<'fw> {
+    scan_blocks: Kernel<'fw>,
+    add_block_sums: Kernel<'fw>,
+}
+
+impl<'fw> ScanKernels<'fw> {
+    pub(super) fn new(fw: &'fw Framework) -> GpuResult<Self> {
+        let shader = shader(fw)?;
+        let layout = DescriptorLayout::default()
+            .add_buffer("data", GpuBufferUsage::ReadWrite)
+            .add_buffer("block_sums", GpuBufferUsage::ReadWrite)
+            .add_uniform_buffer("params");
+
+        let kernel = |entry_point| {
+            Kernel::new(
+                fw,
+                Program::new(&shader, entry_point).add_descriptor_layout(&layout),
+            )
+        };
+
+        Ok(Self {
+            scan_blocks: kernel("scan_blocks")?,
+            add_block_sums: kernel("add_block_sums")?,
+        })
+    }
+}
+
+/// Returns the shader of the scan kernels from the op shader cache.
+fn shader(fw: &Framework) -> GpuResult<Arc<Shader>> {
+    let shader = fw
+        .op_shaders
+        .lock()
+        .unwrap()
+        .get_or_compile("ops::scan", || {
+            Shader::from_wgsl_source(fw, include_str!("scan.wgsl"), Some("ops::scan"))
+        })?;
+
+    Ok(shader)
+}
+
+/// Scans the first `len` elements of `data`, scanning the sums of its blocks recursively.
+pub(super) fn scan_level(
+    fw: &Framework,
+    kernels: &ScanKernels,
+    data: &GpuBuffer<u32>,
+    len: u32,
+    exclusive: bool,
+) -> GpuResult<()> {
+    let blocks = len.div_ceil(BLOCK_SIZE);
+    let block_sums = Scratch::<u32>::new(fw, blocks as u64);
+
+    let params = GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            len,
+            exclusive: exclusive as u32,
+            blocks,
+            _padding: 0,
+        }],
+    );
+
+    // The blocks are laid out in rows of workgroups, as a dimension is limited to 65535 of them.
+    let x = blocks.min(fw.limits().max_compute_workgroups_per_dimension);
+    let y = blocks.div_ceil(x);
+
+    let set = DescriptorSet::default()
+        .bind_buffer_at(0, data, GpuBufferUsage::ReadWrite)?
+        .bind_buffer_at(1, &block_sums, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(2, &params)?;
+
+    kernels.scan_blocks.enqueue_with_sets(x, y, 1, &[&set])?;
+
+    if blocks > 1 {
+        scan_level(fw, kernels, &block_sums, blocks, true)?;
+
+        kernels.add_block_sums.enqueue_with_sets(x, y, 1, &[&set])?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+// Prefix sum of the first `params.len` elements of `data`, in place: `scan_blocks` scans
+// each block of `BLOCK_SIZE` elements and stores its total in `block_sums`, which are then
+// scanned the same way, and `add_block_sums` adds to each block the sum of the blocks before it.
+
+struct Params {
+    len: u32,
+    exclusive: u32,
+    blocks: u32,
+    _padding: u32,
+}
+
+let WORKGROUP_SIZE: u32 = 256u;
+let ITEMS: u32 = 4u;
+let BLOCK_SIZE: u32 = 1024u;
+
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+@group(0) @binding(1) var<storage, read_write> block_sums: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+var<workgroup> sums: array<u32, 256>;
+
+@compute @workgroup_size(256)
+fn scan_blocks(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let block = group_id.x + group_id.y * groups.x;
+    if (block >= params.blocks) {
+        return;
+    }
+
+    let local = local_id.x;
+    let first = block * BLOCK_SIZE + local * ITEMS;
+
+    // Each thread sums its consecutive items sequentially...
+    var items: array<u32, 4>;
+    var total = 0u;
+    for (var k = 0u; k < ITEMS; k = k + 1u) {
+        var item = 0u;
+        if (first + k < params.len) {
+            item = data[first + k];
+        }
+        items[k] = item;
+        total = total + item;
+    }
+    sums[local] = total;
+
+    // ... and the sums of the threads are scanned in workgroup memory.
+    for (var offset = 1u; offset < WORKGROUP_SIZE; offset = offset * 2u) {
+        workgroupBarrier();
+        var before = 0u;
+        if (local >= offset) {
+            before = sums[local - offset];
+        }
+        workgroupBarrier();
+        sums[local] = sums[local] + before;
+    }
+    workgroupBarrier();
+
+    var running = sums[local] - total;
+    for (var k = 0u; k < ITEMS; k = k + 1u) {
+        let i = first + k;
+        if (params.exclusive == 0u) {
+            running = running + items[k];
+        }
+        if (i < params.len) {
+            data[i] = running;
+        }
+        if (params.exclusive != 0u) {
+            running = running + items[k];
+        }
+    }
+
+    if (local == WORKGROUP_SIZE - 1u) {
+        block_sums[block] = sums[local];
+    }
+}
+
+@compute @workgroup_size(256)
+fn add_block_sums(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let block = group_id.x + group_id.y * groups.x;
+    if (block >= params.blocks) {
+        return;
+    }
+
+    let before = block_sums[block];
+    for (var k = 0u; k < ITEMS; k = k + 1u) {
+        let i = block * BLOCK_SIZE + k * WORKGROUP_SIZE + local_id.x;
+        if (i < params.len) {
+            data[i] = data[i] + before;
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult, GpuUniformBuffer,
+    Kernel, Program, Shader,
+};
+
+use super::{reduce, OpsError, ReduceElement, ReduceOp, Scratch};
+
+/// Most elements of the segments reduced by a single thread.
+const PACKED_MAX: u32 = 64;
+
+/// Most workgroups reducing the longer segments.
+const MAX_GROUPS: u32 = 1024;
+
+crate::gpu_struct! {
+    /// `Params` of `segmented.wgsl`.
+    uniform struct Params {
+        segments: u32,
+        len: u32,
+        packed_max: u32,
+        _padding: u32,
+    }
+}
+
+/// Reduces each segment of `values` with `op` on the GPU, writing the result of the
+/// segment `s` to `output[s]`.
+///
+/// `segment_offsets` holds the index of the first element of each segment, like the row
+/// offsets of a CSR matrix without the last one: the segment `s` goes from `segment_offsets[s]`
+/// up to `segment_offsets[s + 1]`, and the last one up to the end of `values`. The offsets are
+/// expected in increasing order; they are clamped to the length of `values`, and a segment
+/// starting after the start of the next one is empty. Empty segments reduce to the identity of
+/// `op`: zero for [`ReduceOp::Sum`], and the largest or smallest value of `T` for
+/// [`ReduceOp::Min`] and [`ReduceOp::Max`], an infinity for floats.
+///
+/// A thread reduces each segment of up to 64 elements sequentially, so that many tiny segments
+/// are reduced in a single pass, and a workgroup each longer segment in a tree like [`reduce`],
+/// up to 1024 of them at once. Sums of floats thus round as the ones of [`reduce`], and
+/// NaNs are skipped by `Min` and `Max` the same way.
+///
+/// Fails with [`OpsError::OutputTooSmall`] if `output` holds fewer elements than there
+/// are segments.
+pub fn segmented_reduce<T: ReduceElement>(
+    fw: &Framework,
+    values: &GpuBuffer<T>,
+    segment_offsets: &GpuBuffer<u32>,
+    output: &mut GpuBuffer<T>,
+    op: ReduceOp,
+) -> GpuResult<()> {
+    let len = values.capacity();
+    let segments = segment_offsets.capacity();
+
+    if output.capacity() < segments {
+        return Err(OpsError::OutputTooSmall {
+            required: segments,
+            current: output.capacity(),
+        }
+        .into());
+    }
+    for len in [len, segments] {
+        if len > u32::MAX as u64 {
+            return Err(OpsError::TooLong(len).into());
+        }
+    }
+    if segments == 0 {
+        return Ok(());
+    }
+
+    let (identity, combine) = reduce::combine::<T>(op);
+    let substitutions = [
+        ("T", T::WGSL_TYPE),
+        ("IDENTITY", &identity),
+        ("COMBINE", combine),
+    ];
+    let key = format!("ops::segmented_reduce{:?}", substitutions);
+    let shader = fw.op_shaders.lock().unwrap().get_or_compile(&key, || {
+        Shader::from_wgsl_template(
+            fw,
+            include_str!("segmented.wgsl"),
+            &substitutions,
+            Some("ops::segmented_reduce"),
+        )
+    })?;
+
+    // Bound instead of an empty `values`, whose segments are all empty.
+    let placeholder;
+    let values: &GpuBuffer<T> = if len == 0 {
+        placeholder = Scratch::<T>::new(fw, 1);
+        &placeholder
+    } else {
+        values
+    };
+
+    let params = GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            segments: segments as u32,
+            len: len as u32,
+            packed_max: PACKED_MAX,
+            _padding: 0,
+        }],
+    );
+
+    let set = DescriptorSet::default()
+        .bind_buffer_at(0, values, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(1, segment_offsets, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(2, output, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(3, &params)?;
+
+    Kernel::new(
+        fw,
+        Program::new(&shader, "reduce_packed").add_descriptor_set(set.clone()),
+    )?
+    .enqueue_elements(segments)?;
+
+    // No segment is longer than `values`.
+    if len > PACKED_MAX as u64 {
+        let groups = (segments as u32).min(MAX_GROUPS);
+
+        Kernel::new(
+            fw,
+            Program::new(&shader, "reduce_wide").add_descriptor_set(set),
+        )?
+        .enqueue(groups, 1, 1)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+// Reduction of each segment of `values` with `combine` into the element of `output`
+// at its index: the segment `s` goes from `offsets[s]` up to the next offset, or up to
+// the end of `values` for the last one. `reduce_packed` reduces each segment of up to
+// `params.packed_max` elements in a thread, and `reduce_wide` the longer ones, each
+// in a workgroup taking the segments of its index modulo the workgroup count in turn.
+
+struct Pair {
+    value: {{T}},
+    index: u32,
+}
+
+struct Params {
+    segments: u32,
+    len: u32,
+    packed_max: u32,
+    _padding: u32,
+}
+
+let WORKGROUP_SIZE: u32 = 256u;
+
+@group(0) @binding(0) var<storage, read> values: array<{{T}}>;
+@group(0) @binding(1) var<storage, read> offsets: array<u32>;
+@group(0) @binding(2) var<storage, read_write> output: array<{{T}}>;
+@group(0) @binding(3) var<uniform> params: Params;
+
+var<workgroup> scratch: array<Pair, 256>;
+
+fn combine(a: Pair, b: Pair) -> Pair {
+    {{COMBINE}}
+}
+
+// Start and end of the elements of `segment`, clamped to `values`: a segment
+// starting after its end is empty.
+fn bounds(segment: u32) -> vec2<u32> {
+    var end = params.len;
+    if (segment + 1u < params.segments) {
+        end = min(offsets[segment + 1u], params.len);
+    }
+
+    return vec2<u32>(min(offsets[segment], end), end);
+}
+
+@compute @workgroup_size(256)
+fn reduce_packed(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let segment = global_id.x + global_id.y * groups.x * WORKGROUP_SIZE;
+    if (segment >= params.segments) {
+        return;
+    }
+
+    let range = bounds(segment);
+    if (range.y - range.x > params.packed_max) {
+        return;
+    }
+
+    var acc = Pair({{IDENTITY}}, 0xffffffffu);
+    for (var i = range.x; i < range.y; i = i + 1u) {
+        acc = combine(acc, Pair(values[i], i));
+    }
+    output[segment] = acc.value;
+}
+
+@compute @workgroup_size(256)
+fn reduce_wide(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let local = local_id.x;
+
+    for (var segment = group_id.x; segment < params.segments; segment = segment + groups.x) {
+        // The same for all the threads of the workgroup.
+        let range = bounds(segment);
+        if (range.y - range.x <= params.packed_max) {
+            continue;
+        }
+
+        var acc = Pair({{IDENTITY}}, 0xffffffffu);
+        for (var i = range.x + local; i < range.y; i = i + WORKGROUP_SIZE) {
+            acc = combine(acc, Pair(values[i], i));
+        }
+        scratch[local] = acc;
+
+        for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride = stride / 2u) {
+            workgroupBarrier();
+            if (local < stride) {
+                scratch[local] = combine(scratch[local], scratch[local + stride]);
+            }
+        }
+
+        if (local == 0u) {
+            output[segment] = scratch[0].value;
+        }
+        // Before the `scratch` of the next segment is written.
+        workgroupBarrier();
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult, GpuUniformBuffer,
+    Kernel, Program, Shader,
+};
+
+use super::OpsError;
+
+/// Threads computing each row with the vectorized kernel.
+const LANES: u64 = 32;
+
+/// Smallest average row length computed with the vectorized kernel.
+const VECTOR_MIN_AVERAGE: u64 = 16;
+
+crate::gpu_struct! {
+    /// `Params` of `spmv.wgsl`.
+    uniform struct Params {
+        rows: u32,
+        nonzeros: u32,
+        columns: u32,
+        _padding: u32,
+    }
+}
+
+/// Computes `y = A * x` on the GPU, for the sparse matrix `A` in the CSR format.
+///
+/// The matrix has as many rows as `y` elements, and its elements are the `values` in
+/// row-major order, in the columns of `col_indices`: the elements of the row `r` are the ones
+/// from `row_offsets[r]` up to `row_offsets[r + 1]`. `row_offsets` thus holds one more offset
+/// than there are rows, and the last one is expected to be the number of elements. The offsets
+/// are clamped to it, and a row starting after its end is empty; an empty row computes 0.
+/// The columns past the end of `x` are read as zeros.
+///
+/// A thread computes each row when the rows average fewer than 16 elements, so that short rows
+/// use all the threads of their workgroup, and 32 threads of a workgroup each row otherwise,
+/// summing a strided share of its elements each, then their sums in a tree in workgroup
+/// memory, so that long rows are read in contiguous blocks by many threads. The sums of their
+/// elements are thus not sequential between the two kernels, and can differ in the last bits.
+///
+/// Fails with [`OpsError::RowOffsetsMismatch`] if `row_offsets` does not hold one more offset
+/// than `y` holds elements, and with [`OpsError::OperandMismatch`] if `col_indices` and `values`
+/// have different lengths.
+pub fn spmv_csr(
+    fw: &Framework,
+    row_offsets: &GpuBuffer<u32>,
+    col_indices: &GpuBuffer<u32>,
+    values: &GpuBuffer<f32>,
+    x: &GpuBuffer<f32>,
+    y: &mut GpuBuffer<f32>,
+) -> GpuResult<()> {
+    let rows = y.capacity();
+    let nonzeros = values.capacity();
+
+    if row_offsets.capacity() != rows + 1 {
+        return Err(OpsError::RowOffsetsMismatch {
+            rows,
+            offsets: row_offsets.capacity(),
+        }
+        .into());
+    }
+    if col_indices.capacity() != nonzeros {
+        return Err(OpsError::OperandMismatch {
+            a: col_indices.capacity(),
+            b: nonzeros,
+        }
+        .into());
+    }
+    for len in [row_offsets.capacity(), nonzeros, x.capacity()] {
+        if len > u32::MAX as u64 {
+            return Err(OpsError::TooLong(len).into());
+        }
+    }
+    if rows == 0 {
+        return Ok(());
+    }
+    // Every row sums to zero, and the empty buffers cannot be bound.
+    if nonzeros == 0 || x.capacity() == 0 {
+        fw.queue.write_buffer(
+            y.as_gpu_buffer(),
+            0,
+            bytemuck::cast_slice(&vec![0.0f32; rows as usize]),
+        );
+        return Ok(());
+    }
+
+    let shader = fw
+        .op_shaders
+        .lock()
+        .unwrap()
+        .get_or_compile("ops::spmv_csr", || {
+            Shader::from_wgsl_source(fw, include_str!("spmv.wgsl"), Some("ops::spmv_csr"))
+        })?;
+
+    let params = GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            rows: rows as u32,
+            nonzeros: nonzeros as u32,
+            columns: x.capacity() as u32,
+            _padding: 0,
+        }],
+    );
+
+    let set = DescriptorSet::default()
+        .bind_buffer_at(0, row_offsets, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(1, col_indices, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(2, values, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(3, x, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(4, y, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(5, &params)?;
+
+    // The threads of the vectorized kernel are indexed by `u32`s.
+    let vector = nonzeros / rows >= VECTOR_MIN_AVERAGE && rows * LANES <= u32::MAX as u64;
+    let (entry_point, threads) = if vector {
+        ("spmv_vector", rows * LANES)
+    } else {
+        ("spmv_scalar", rows)
+    };
+
+    Kernel::new(
+        fw,
+        Program::new(&shader, entry_point).add_descriptor_set(set),
+    )?
+    .enqueue_elements(threads)?;
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+// Product `y = A * x` of the CSR matrix `A` of `row_offsets`, `col_indices` and `values`:
+// `spmv_scalar` computes each row in a thread, and `spmv_vector` each row in `LANES`
+// threads of a workgroup, which sum a strided share of its elements each and then
+// their sums in a tree in workgroup memory.
+
+struct Params {
+    rows: u32,
+    nonzeros: u32,
+    columns: u32,
+    _padding: u32,
+}
+
+let WORKGROUP_SIZE: u32 = 256u;
+let LANES: u32 = 32u;
+
+@group(0) @binding(0) var<storage, read> row_offsets: array<u32>;
+@group(0) @binding(1) var<storage, read> col_indices: array<u32>;
+@group(0) @binding(2) var<storage, read> values: array<f32>;
+@group(0) @binding(3) var<storage, read> x: array<f32>;
+@group(0) @binding(4) var<storage, read_write> y: array<f32>;
+@group(0) @binding(5) var<uniform> params: Params;
+
+var<workgroup> sums: array<f32, 256>;
+
+// Start and end of the elements of `row`, clamped to the elements of the matrix:
+// a row starting after its end is empty.
+fn bounds(row: u32) -> vec2<u32> {
+    let end = min(row_offsets[row + 1u], params.nonzeros);
+
+    return vec2<u32>(min(row_offsets[row], end), end);
+}
+
+// Product of the element `k` of the matrix by the element of `x` of its column,
+// zero past the end of `x`.
+fn product(k: u32) -> f32 {
+    let column = col_indices[k];
+    if (column >= params.columns) {
+        return 0.0;
+    }
+
+    return values[k] * x[column];
+}
+
+@compute @workgroup_size(256)
+fn spmv_scalar(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let row = global_id.x + global_id.y * groups.x * WORKGROUP_SIZE;
+    if (row >= params.rows) {
+        return;
+    }
+
+    let range = bounds(row);
+    var sum = 0.0;
+    for (var k = range.x; k < range.y; k = k + 1u) {
+        sum = sum + product(k);
+    }
+    y[row] = sum;
+}
+
+@compute @workgroup_size(256)
+fn spmv_vector(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let local = local_id.x;
+    let lane = local % LANES;
+    let row = (global_id.x + global_id.y * groups.x * WORKGROUP_SIZE) / LANES;
+
+    // The rows past the last one still take part in the barriers of their workgroup.
+    var sum = 0.0;
+    if (row < params.rows) {
+        let range = bounds(row);
+        for (var k = range.x + lane; k < range.y; k = k + LANES) {
+            sum = sum + product(k);
+        }
+    }
+    sums[local] = sum;
+
+    for (var stride = LANES / 2u; stride > 0u; stride = stride / 2u) {
+        workgroupBarrier();
+        if (lane < stride) {
+            sums[local] = sums[local] + sums[local + stride];
+        }
+    }
+
+    if (lane == 0u && row < params.rows) {
+        y[row] = sums[local];
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use crate::{
+    BufOps, DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, GpuResult, GpuUniformBuffer,
+    Kernel, Program, Shader,
+};
+
+use super::{matmul::check_matrix, ReduceElement};
+
+/// Rows and columns of the tiles transposed by each workgroup of the transpose kernels.
+const TILE: u32 = 32;
+
+crate::gpu_struct! {
+    /// `Params` of `transpose.wgsl`.
+    uniform struct Params {
+        rows: u32,
+        cols: u32,
+        _padding: [u32; 2],
+    }
+}
+
+/// Writes the transpose of the `rows` x `cols` matrix `input` into the `cols` x `rows` matrix
+/// `output` on the GPU. The matrices are stored by rows, and the elements of the buffers after
+/// them are left as they are.
+///
+/// Each workgroup transposes a 32 x 32 tile through workgroup memory, so that both its reads and
+/// its writes are contiguous, where reading `input` by rows and writing `output` by columns
+/// would waste most of the bandwidth of the writes. The rows of the tiles are padded to avoid
+/// the bank conflicts of reading them by columns. Any dimensions are supported, most
+/// efficiently when they are multiples of 32.
+///
+/// Fails with [`OpsError::MatrixTooSmall`](super::OpsError::MatrixTooSmall) if a buffer holds
+/// less elements than its matrix, and with [`KernelError`](crate::kernel::KernelError) if `rows`
+/// or `cols` exceed 32 times the maximum number of workgroups per dimension.
+pub fn transpose<T: ReduceElement>(
+    fw: &Framework,
+    input: &GpuBuffer<T>,
+    output: &mut GpuBuffer<T>,
+    rows: u32,
+    cols: u32,
+) -> GpuResult<()> {
+    check_matrix("input", input, rows, cols)?;
+    check_matrix("output", output, cols, rows)?;
+
+    if rows == 0 || cols == 0 {
+        return Ok(());
+    }
+
+    let params = params(fw, rows, cols);
+    let set = DescriptorSet::default()
+        .bind_buffer_at(0, input, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(1, output, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(2, &params)?;
+
+    let shader = shader::<T>(fw)?;
+    Kernel::new(
+        fw,
+        Program::new(&shader, "transpose").add_descriptor_set(set),
+    )?
+    .enqueue(cols.div_ceil(TILE), rows.div_ceil(TILE), 1)?;
+
+    Ok(())
+}
+
+/// Transposes the `size` x `size` matrix `buf` in place on the GPU, like [`transpose`].
+///
+/// Each workgroup above the diagonal swaps its tile with the mirrored one below it, transposing
+/// both, so that no other buffer is needed.
+pub fn transpose_in_place<T: ReduceElement>(
+    fw: &Framework,
+    buf: &mut GpuBuffer<T>,
+    size: u32,
+) -> GpuResult<()> {
+    check_matrix("buf", buf, size, size)?;
+
+    if size == 0 {
+        return Ok(());
+    }
+
+    let params = params(fw, size, size);
+    let set = DescriptorSet::default()
+        .bind_buffer_at(1, buf, GpuBufferUsage::ReadWrite)?
+        .bind_uniform_buffer_at(2, &params)?;
+
+    let tiles = size.div_ceil(TILE);
+    let shader = shader::<T>(fw)?;
+    Kernel::new(
+        fw,
+        Program::new(&shader, "transpose_in_place").add_descriptor_set(set),
+    )?
+    .enqueue(tiles, tiles, 1)?;
+
+    Ok(())
+}
+
+/// Returns the shader of the transpositions of `T` from the op shader cache.
+fn shader<T: ReduceElement>(fw: &Framework) -> GpuResult<Arc<Shader>> {
+    let substitutions = [("T", T::WGSL_TYPE)];
+    let key = format!("ops::transpose{:?}", substitutions);
+
+    Ok(fw.op_shaders.lock().unwrap().get_or_compile(&key, || {
+        Shader::from_wgsl_template(
+            fw,
+            include_str!("transpose.wgsl"),
+            &substitutions,
+            Some("ops::transpose"),
+        )
+    })?)
+}
+
+fn params(fw: &Framework, rows: u32, cols: u32) -> GpuUniformBuffer<'_, Params> {
+    GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            rows,
+            cols,
+            _padding: [0; 2],
+        }],
+    )
+}
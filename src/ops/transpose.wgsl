@@ -0,0 +1,87 @@
+// Tiled transposes of `rows` x `cols` matrices stored by rows: each workgroup reads a `TILE` x `TILE`
+// tile of the matrix by rows into workgroup memory, and writes it back by rows of the transposed
+// tile, so that both the reads and the writes of its threads are contiguous. The rows of the tiles
+// are padded by one element, so that reading a column of a tile hits a different bank per element.
+// `transpose` writes the transpose of `input` into `output`, and `transpose_in_place` swaps the
+// tiles on both sides of the diagonal of the square `output`, each workgroup above it swapping
+// its tile with the mirrored one.
+
+struct Params {
+    rows: u32,
+    cols: u32,
+    _padding: vec2<u32>,
+}
+
+let TILE: u32 = 32u;
+// Rows of the tile each thread goes through, `TILE / ROWS` apart.
+let ROWS: u32 = 8u;
+
+@group(0) @binding(0) var<storage, read> input: array<{{T}}>;
+@group(0) @binding(1) var<storage, read_write> output: array<{{T}}>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+var<workgroup> tile: array<array<{{T}}, 33>, 32>;
+var<workgroup> mirrored_tile: array<array<{{T}}, 33>, 32>;
+
+@compute @workgroup_size(32, 8, 1)
+fn transpose(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+) {
+    let x = local_id.x;
+    let first_row = group_id.y * TILE;
+    let first_col = group_id.x * TILE;
+
+    for (var y = local_id.y; y < TILE; y = y + ROWS) {
+        let row = first_row + y;
+        let col = first_col + x;
+        if (row < params.rows && col < params.cols) {
+            tile[y][x] = input[row * params.cols + col];
+        }
+    }
+    workgroupBarrier();
+
+    // Row `first_col + y` of the transpose, of `params.rows` elements.
+    for (var y = local_id.y; y < TILE; y = y + ROWS) {
+        let row = first_col + y;
+        let col = first_row + x;
+        if (row < params.cols && col < params.rows) {
+            output[row * params.rows + col] = tile[x][y];
+        }
+    }
+}
+
+@compute @workgroup_size(32, 8, 1)
+fn transpose_in_place(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+) {
+    // Tiles below the diagonal are swapped by the workgroup of their mirror.
+    if (group_id.x < group_id.y) {
+        return;
+    }
+
+    let x = local_id.x;
+    let size = params.rows;
+    let first_row = group_id.y * TILE;
+    let first_col = group_id.x * TILE;
+
+    for (var y = local_id.y; y < TILE; y = y + ROWS) {
+        if (first_row + y < size && first_col + x < size) {
+            tile[y][x] = output[(first_row + y) * size + first_col + x];
+        }
+        if (first_col + y < size && first_row + x < size) {
+            mirrored_tile[y][x] = output[(first_col + y) * size + first_row + x];
+        }
+    }
+    workgroupBarrier();
+
+    for (var y = local_id.y; y < TILE; y = y + ROWS) {
+        if (first_col + y < size && first_row + x < size) {
+            output[(first_col + y) * size + first_row + x] = tile[x][y];
+        }
+        if (first_row + y < size && first_col + x < size) {
+            output[(first_row + y) * size + first_col + x] = mirrored_tile[x][y];
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Re-exports of the types a typical `gpgpu` program needs, to glob-import them at once:
+//!
+//! ```no_run
+//! use gpgpu::prelude::*;
+//!
+//! # fn main() -> GpuResult<()> {
+//! let fw = Framework::try_default()?;
+//! let buf = GpuBuffer::<u32>::with_capacity(&fw, 1024);
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::{
+    primitives::pixels, BufOps, CommandRecorder, DescriptorSet, Framework, GpuBuffer,
+    GpuBufferUsage, GpuConstImage, GpuError, GpuImage, GpuResult, GpuSampler, GpuUniformBuffer,
+    ImgOps, Kernel, Program, Shader, ShaderLibrary,
+};
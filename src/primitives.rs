@@ -15,11 +15,22 @@
 //!
 //! ## GpuConstImage
 //! Intended for read-only (in th shader) images on the GPU.
+//!
+//! # Samplers
+//! ## GpuSampler
+//! Intended for sampling [`GpuConstImage`](crate::GpuConstImage)s with filtering or comparison in the shader.
 
 use crate::Framework;
 
+use self::{
+    buffers::{BufferError, BufferResult},
+    images::ImageResult,
+};
+
 pub mod buffers;
+mod gpu_struct;
 pub mod images;
+pub mod samplers;
 
 /// Interface to get information, create and decompose GPU allocated buffers.
 pub trait BufOps<'fw, T>
@@ -49,13 +60,47 @@ where
     /// Constructs a new zeroed buffer with the specified capacity.
     ///
     /// The buffer will be able to hold exactly `capacity` elements.
+    ///
+    /// # Panics
+    /// If the buffer cannot be allocated, see [`BufOps::try_with_capacity`].
     fn with_capacity(fw: &'fw Framework, capacity: u64) -> Self;
 
     /// Constructs a new buffer from a slice.
     ///
     /// The buffer `capacity` will be the `slice` length.
+    ///
+    /// # Panics
+    /// If the buffer cannot be allocated, see [`BufOps::try_from_slice`].
     fn from_slice(fw: &'fw Framework, slice: &[T]) -> Self;
 
+    /// Constructs a new zeroed buffer with the specified capacity like [`BufOps::with_capacity`].
+    ///
+    /// Fails with [`BufferError::CapacityOverflow`] if its size in bytes does not fit in a `u64`,
+    /// and with [`BufferError::Creation`] if the device rejects it, e.g. because it exceeds
+    /// the maximum buffer size or the device is out of memory.
+    fn try_with_capacity(fw: &'fw Framework, capacity: u64) -> BufferResult<Self>
+    where
+        Self: Sized,
+    {
+        capacity
+            .checked_mul(std::mem::size_of::<T>() as u64)
+            .ok_or(BufferError::CapacityOverflow(capacity))?;
+
+        fw.capture_errors(|| Self::with_capacity(fw, capacity))
+            .map_err(|err| BufferError::Creation(err.to_string()))
+    }
+
+    /// Constructs a new buffer from a slice like [`BufOps::from_slice`].
+    ///
+    /// Fails with [`BufferError::Creation`] if the device rejects the buffer.
+    fn try_from_slice(fw: &'fw Framework, slice: &[T]) -> BufferResult<Self>
+    where
+        Self: Sized,
+    {
+        fw.capture_errors(|| Self::from_slice(fw, slice))
+            .map_err(|err| BufferError::Creation(err.to_string()))
+    }
+
     /// Constructs a new buffer from a [`wgpu::Buffer`] and its byte `size`.
     ///
     /// # Safety
@@ -69,6 +114,24 @@ where
 
     /// Decomposes a buffer into a [`wgpu::Buffer`] and its byte `size`.
     fn into_gpu_parts(self) -> (wgpu::Buffer, u64);
+
+    /// Frees the GPU memory of the buffer now.
+    ///
+    /// Dropping the buffer only frees its memory once nothing uses it anymore:
+    /// the [`Kernel`](crate::Kernel)s and [`OwnedDescriptorSet`](crate::OwnedDescriptorSet)s
+    /// it is bound to keep it alive. Once destroyed, they must not be enqueued anymore.
+    /// The dispatches already enqueued are not affected.
+    ///
+    /// Dropping the buffer does not destroy it, so that these kernels keep working.
+    /// When nothing binds it, it is freed as soon as if it were destroyed: `wgpu` reclaims
+    /// the memory of both when the device is polled, which the polling thread of the
+    /// [`Framework`] does every `polling_time`.
+    fn destroy(self)
+    where
+        Self: Sized,
+    {
+        self.as_gpu_buffer().destroy();
+    }
 }
 
 /// Interface to get information, create and decompose GPU allocated images.
@@ -90,13 +153,38 @@ pub trait ImgOps<'fw> {
     // ----------- Creation fns ---------------
 
     /// Constructs an empty image with the desired `width` and `height`.
+    ///
+    /// # Panics
+    /// If the image cannot be allocated, see [`ImgOps::try_new`].
     fn new(fw: &'fw Framework, width: u32, height: u32) -> Self;
 
     /// Construct a new image from a bytes source `data` and its `width` and `height`.
     ///
-    /// If `data` doesn't fit the image perfectly, it panics.
+    /// If `data` doesn't fit the image perfectly, it panics, see [`ImgOps::try_from_bytes`].
     fn from_bytes(fw: &'fw Framework, data: &[u8], width: u32, height: u32) -> Self;
 
+    /// Constructs an empty image with the desired `width` and `height` like [`ImgOps::new`].
+    ///
+    /// Fails with [`ImageError::Creation`](images::ImageError::Creation) if the device rejects it,
+    /// e.g. because a dimension is zero or exceeds the maximum texture size.
+    fn try_new(fw: &'fw Framework, width: u32, height: u32) -> ImageResult<Self>
+    where
+        Self: Sized;
+
+    /// Construct a new image from a bytes source `data` and its `width` and `height`
+    /// like [`ImgOps::from_bytes`].
+    ///
+    /// Fails with [`ImageError::InvalidData`](images::ImageError::InvalidData) if `data` does not
+    /// fit the image perfectly, and like [`ImgOps::try_new`] if the device rejects the image.
+    fn try_from_bytes(
+        fw: &'fw Framework,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> ImageResult<Self>
+    where
+        Self: Sized;
+
     fn from_gpu_parts(
         fw: &'fw Framework,
         texture: wgpu::Texture,
@@ -107,6 +195,21 @@ pub trait ImgOps<'fw> {
 
     /// Decomposes an image into a [`wgpu::Texture`] and its [`wgpu::Extent3d`].
     fn into_gpu_parts(self) -> (wgpu::Texture, wgpu::Extent3d);
+
+    /// Frees the GPU memory of the image now.
+    ///
+    /// Dropping the image only frees its memory once nothing uses it anymore:
+    /// the [`Kernel`](crate::Kernel)s and [`OwnedDescriptorSet`](crate::OwnedDescriptorSet)s
+    /// it is bound to keep it alive. Once destroyed, they must not be enqueued anymore.
+    /// The dispatches already enqueued are not affected.
+    ///
+    /// Like buffers, dropped images are not destroyed, see [`BufOps::destroy`].
+    fn destroy(self)
+    where
+        Self: Sized,
+    {
+        self.as_gpu_texture().destroy();
+    }
 }
 
 /// Gives some information about the pixel format.
@@ -146,7 +249,8 @@ pub mod pixels {
         Rgba8Uint, 4, wgpu::TextureFormat::Rgba8Uint, wgpu::TextureSampleType::Uint, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. Unsigned in shader."];
         Rgba8UintNorm, 4, wgpu::TextureFormat::Rgba8Unorm, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. [0, 255] converted to/from float [0, 1] in shader."];
         Rgba8Sint, 4, wgpu::TextureFormat::Rgba8Sint, wgpu::TextureSampleType::Sint, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. Signed in shader."];
-        Rgba8SintNorm, 4, wgpu::TextureFormat::Rgba8Snorm, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. [-127, 127] converted to/from float [-1, 1] in shader."]
+        Rgba8SintNorm, 4, wgpu::TextureFormat::Rgba8Snorm, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. [-127, 127] converted to/from float [-1, 1] in shader."];
+        Rgba32Float, 16, wgpu::TextureFormat::Rgba32Float, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Red, green, blue, and alpha channels. 32 bit float per channel. Float in shader, values stored as is."]
         // Luma8, 1, wgpu::TextureFormat::R8Uint, wgpu::TextureSampleType::Uint, #[doc = "Grayscale 8 bit integer channel. Unsigned in shader."];
         // Luma8Norm, 1, wgpu::TextureFormat::R8Unorm, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Grayscale 8 bit integer channel. Unsigned in shader. [0, 255] converted to/from float [0, 1] in shader."]
     }
@@ -1,13 +1,24 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 
 use thiserror::Error;
-use wgpu::BufferAsyncError;
 use wgpu::util::{DeviceExt, DownloadBuffer};
+use wgpu::BufferAsyncError;
 
-use crate::{GpuBuffer, GpuUniformBuffer};
+use crate::{GpuBuffer, GpuId, GpuUniformBuffer};
 
 use super::BufOps;
 
+pub(crate) mod file;
+mod map;
+mod matrix;
+mod stream;
+mod validity;
+
+pub use file::{BufferFileError, BufferFileResult, ByteOrder, ScalarElement};
+pub use matrix::{std140_mat3, Std140Mat3, Std140Mat4};
+
 // TODO https://github.com/bitflags/bitflags/issues/180
 const GPU_BUFFER_USAGES: wgpu::BufferUsages = wgpu::BufferUsages::from_bits_truncate(
     wgpu::BufferUsages::STORAGE.bits()
@@ -24,6 +35,34 @@ pub type BufferResult<T> = Result<T, BufferError>;
 pub enum BufferError {
     #[error(transparent)]
     AsyncMapError(#[from] wgpu::BufferAsyncError),
+    #[error("A buffer of {0} elements is larger than `u64::MAX` bytes.")]
+    CapacityOverflow(u64),
+    #[error("The buffer could not be created: {0}")]
+    Creation(String),
+    #[error(
+        "A transfer of {0} bytes is not a multiple of {} bytes, as `wgpu` requires.",
+        wgpu::COPY_BUFFER_ALIGNMENT
+    )]
+    MisalignedTransfer(u64),
+    #[error(
+        "A region of {len} bytes does not hold a whole number of elements of {element_size} bytes."
+    )]
+    ElementLength { len: u64, element_size: u64 },
+    #[error("`wgpu` downloaded {found} bytes instead of the {expected} bytes requested.")]
+    StagingLength { expected: u64, found: u64 },
+    #[error(
+        "A validity bitmap of {bytes} bytes has less than one bit for each of the {values} values."
+    )]
+    ValidityLength { values: u64, bytes: u64 },
+}
+
+/// Checks that a write of `size` bytes is aligned as `wgpu` requires.
+fn check_write_alignment(size: u64) -> BufferResult<()> {
+    if !size.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT) {
+        return Err(BufferError::MisalignedTransfer(size));
+    }
+
+    Ok(())
 }
 
 impl<'fw, T> BufOps<'fw, T> for GpuBuffer<'fw, T>
@@ -46,11 +85,18 @@ where
             usage: GPU_BUFFER_USAGES,
             mapped_at_creation: false,
         });
+        event!(
+            debug,
+            "created `GpuBuffer::with_capacity` of {} bytes",
+            size
+        );
 
         Self {
             fw,
+            id: GpuId::new(),
             buf,
             size,
+            _allocation: fw.memory.buffer(size),
             marker: PhantomData,
         }
     }
@@ -64,11 +110,14 @@ where
                 contents: bytemuck::cast_slice(slice),
                 usage: GPU_BUFFER_USAGES,
             });
+        event!(debug, "created `GpuBuffer::from_slice` of {} bytes", size);
 
         Self {
             fw,
+            id: GpuId::new(),
             buf,
             size,
+            _allocation: fw.memory.buffer(size),
             marker: PhantomData,
         }
     }
@@ -76,8 +125,10 @@ where
     fn from_gpu_parts(fw: &'fw crate::Framework, buf: wgpu::Buffer, size: u64) -> Self {
         Self {
             fw,
+            id: GpuId::new(),
             buf,
             size,
+            _allocation: fw.memory.buffer(size),
             marker: PhantomData,
         }
     }
@@ -91,26 +142,75 @@ impl<'fw, T> GpuBuffer<'fw, T>
 where
     T: bytemuck::Pod,
 {
+    /// Returns the identifier of this [`GpuBuffer`], see [`GpuId`].
+    pub fn id(&self) -> GpuId {
+        self.id
+    }
+
     /// Pulls some elements from the [`GpuBuffer`] into `buf`, returning how many elements were read.
+    ///
+    /// Fails with [`BufferError::MisalignedTransfer`] if the bytes to read end in the last
+    /// [`wgpu::COPY_BUFFER_ALIGNMENT`] bytes of a buffer whose size is not a multiple of it.
     pub async fn read(&self, buf: &mut [T]) -> BufferResult<u64> {
-        let output_size = (buf.len() * std::mem::size_of::<T>()) as u64;
+        self.read_bytes(bytemuck::cast_slice_mut(buf)).await
+    }
+
+    /// Pulls the first bytes of the [`GpuBuffer`] into `buf` like [`GpuBuffer::read`].
+    pub(crate) async fn read_bytes(&self, buf: &mut [u8]) -> BufferResult<u64> {
+        let output_size = buf.len() as u64;
         let download_size = if output_size > self.size {
             self.size
         } else {
             output_size
         };
 
-        let (sender, receiver) = futures::channel::oneshot::channel::<Result<DownloadBuffer, BufferAsyncError>>();
+        if download_size == 0 {
+            return Ok(0);
+        }
 
-        DownloadBuffer::read_buffer(&self.fw.device, &self.fw.queue, &self.buf.slice(..download_size as u64), |arg| {
-            sender.send(arg).ok();
-        });
+        // `wgpu` only copies whole words: the bytes up to the next one are downloaded too.
+        let aligned_size =
+            download_size.div_ceil(wgpu::COPY_BUFFER_ALIGNMENT) * wgpu::COPY_BUFFER_ALIGNMENT;
+        if aligned_size > self.size {
+            return Err(BufferError::MisalignedTransfer(download_size));
+        }
 
-        let download = receiver.await.unwrap().unwrap();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
 
-        buf.copy_from_slice(bytemuck::cast_slice(&download));
+        let (sender, receiver) =
+            futures::channel::oneshot::channel::<Result<DownloadBuffer, BufferAsyncError>>();
 
-        Ok(download_size)
+        DownloadBuffer::read_buffer(
+            &self.fw.device,
+            &self.fw.queue,
+            &self.buf.slice(..aligned_size),
+            |arg| {
+                sender.send(arg).ok();
+            },
+        );
+
+        // The callback is only dropped without being called if the device is lost.
+        let download = receiver.await.unwrap_or(Err(BufferAsyncError))?;
+
+        if self.fw.paranoid_checks() && download.len() as u64 != aligned_size {
+            return Err(BufferError::StagingLength {
+                expected: aligned_size,
+                found: download.len() as u64,
+            });
+        }
+
+        let download_size = download_size as usize;
+        buf[..download_size].copy_from_slice(&download[..download_size]);
+
+        event!(
+            debug,
+            "read {} bytes from a GpuBuffer in {:?}",
+            download_size,
+            start.elapsed()
+        );
+
+        Ok(download_size as u64)
     }
 
     /// Pulls all the elements from the [`GpuBuffer`] into a [`Vec`].
@@ -136,17 +236,27 @@ where
     ///
     /// This function will attempt to write the entire contents of `buf` unless its capacity
     /// exceeds the one of the source buffer, in which case `GpuBuffer::capacity()` elements are written.
+    ///
+    /// Fails with [`BufferError::MisalignedTransfer`] if the bytes to write are not
+    /// a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`].
     pub fn write(&self, buf: &[T]) -> BufferResult<u64> {
-        let input_size = (buf.len() * std::mem::size_of::<T>()) as u64;
+        self.write_bytes(bytemuck::cast_slice(buf))
+    }
+
+    /// Writes `bytes` at the start of this [`GpuBuffer`] like [`GpuBuffer::write`].
+    pub(crate) fn write_bytes(&self, bytes: &[u8]) -> BufferResult<u64> {
+        let input_size = bytes.len() as u64;
         let upload_size = if input_size > self.size {
             self.size
         } else {
             input_size
         };
 
+        check_write_alignment(upload_size)?;
+
         self.fw
             .queue
-            .write_buffer(&self.buf, 0, bytemuck::cast_slice(buf));
+            .write_buffer(&self.buf, 0, &bytes[..upload_size as usize]);
 
         let encoder = self
             .fw
@@ -156,8 +266,26 @@ where
             });
         self.fw.queue.submit(Some(encoder.finish()));
 
+        event!(debug, "wrote {} bytes into a GpuBuffer", upload_size);
+
         Ok(upload_size)
     }
+
+    /// Writes a buffer into this [`GpuBuffer`] like [`GpuBuffer::write`], returning a future
+    /// that resolves to how many elements were written once the GPU has received them.
+    ///
+    /// The data is copied when this method is called, and the future resolves from the polling
+    /// thread of the [`Framework`](crate::Framework) like the one of
+    /// [`Kernel::enqueue_async`](crate::Kernel::enqueue_async).
+    pub fn write_async(&self, buf: &[T]) -> impl std::future::Future<Output = BufferResult<u64>> {
+        let written = self.write(buf);
+        let done = self.fw.work_done();
+
+        async move {
+            done.await;
+            written
+        }
+    }
 }
 
 impl<'fw, T> BufOps<'fw, T> for GpuUniformBuffer<'fw, T>
@@ -181,11 +309,18 @@ where
             usage: GPU_UNIFORM_USAGES,
             mapped_at_creation: false,
         });
+        event!(
+            debug,
+            "created `GpuUniformBuffer::with_capacity` of {} bytes",
+            size
+        );
 
         Self {
             fw,
+            id: GpuId::new(),
             buf,
             size,
+            _allocation: fw.memory.buffer(size),
             marker: PhantomData,
         }
     }
@@ -199,11 +334,18 @@ where
                 contents: bytemuck::cast_slice(slice),
                 usage: GPU_UNIFORM_USAGES,
             });
+        event!(
+            debug,
+            "created `GpuUniformBuffer::from_slice` of {} bytes",
+            size
+        );
 
         Self {
             fw,
+            id: GpuId::new(),
             buf,
             size,
+            _allocation: fw.memory.buffer(size),
             marker: PhantomData,
         }
     }
@@ -211,8 +353,10 @@ where
     fn from_gpu_parts(fw: &'fw crate::Framework, buf: wgpu::Buffer, size: u64) -> Self {
         Self {
             fw,
+            id: GpuId::new(),
             buf,
             size,
+            _allocation: fw.memory.buffer(size),
             marker: PhantomData,
         }
     }
@@ -226,10 +370,18 @@ impl<'fw, T> GpuUniformBuffer<'fw, T>
 where
     T: bytemuck::Pod,
 {
+    /// Returns the identifier of this [`GpuUniformBuffer`], see [`GpuId`].
+    pub fn id(&self) -> GpuId {
+        self.id
+    }
+
     /// Writes a buffer into this [`GpuUniformBuffer`], returning how many elements were written. The operation is instantly offloaded.
     ///
     /// This function will attempt to write the entire contents of `buf` unless its capacity
     /// exceeds the one of the source buffer, in which case `GpuBuffer::capacity()` elements are written.
+    ///
+    /// Fails with [`BufferError::MisalignedTransfer`] if the bytes to write are not
+    /// a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`].
     pub fn write(&self, buf: &[T]) -> BufferResult<u64> {
         let input_size = (buf.len() * std::mem::size_of::<T>()) as u64;
         let upload_size = if input_size > self.size {
@@ -238,9 +390,12 @@ where
             input_size
         };
 
+        check_write_alignment(upload_size)?;
+
+        let bytes: &[u8] = bytemuck::cast_slice(buf);
         self.fw
             .queue
-            .write_buffer(&self.buf, 0, bytemuck::cast_slice(buf));
+            .write_buffer(&self.buf, 0, &bytes[..upload_size as usize]);
 
         let encoder = self
             .fw
@@ -250,6 +405,70 @@ where
             });
         self.fw.queue.submit(Some(encoder.finish()));
 
+        event!(debug, "wrote {} bytes into a GpuUniformBuffer", upload_size);
+
         Ok(upload_size)
     }
+
+    /// Writes a buffer into this [`GpuUniformBuffer`] like [`GpuUniformBuffer::write`], returning a future
+    /// that resolves to how many elements were written once the GPU has received them.
+    ///
+    /// The data is copied when this method is called, and the future resolves from the polling
+    /// thread of the [`Framework`](crate::Framework) like the one of
+    /// [`Kernel::enqueue_async`](crate::Kernel::enqueue_async).
+    pub fn write_async(&self, buf: &[T]) -> impl std::future::Future<Output = BufferResult<u64>> {
+        let written = self.write(buf);
+        let done = self.fw.work_done();
+
+        async move {
+            done.await;
+            written
+        }
+    }
+}
+
+impl<T> PartialEq for GpuBuffer<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for GpuBuffer<'_, T> {}
+
+impl<T> Hash for GpuBuffer<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for GpuBuffer<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GpuBuffer")
+            .field("id", &self.id)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl<T> PartialEq for GpuUniformBuffer<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for GpuUniformBuffer<'_, T> {}
+
+impl<T> Hash for GpuUniformBuffer<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for GpuUniformBuffer<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GpuUniformBuffer")
+            .field("id", &self.id)
+            .field("size", &self.size)
+            .finish()
+    }
 }
@@ -0,0 +1,192 @@
+use std::{convert::TryInto, io::Write, path::Path};
+
+use thiserror::Error;
+
+use crate::{primitives::BufOps, Framework, GpuBuffer};
+
+use super::BufferError;
+
+/// Magic bytes starting a buffer file.
+const MAGIC: &[u8; 8] = b"GPGPUBUF";
+
+/// Size of the header of a buffer file: its magic bytes, byte order, element size and length.
+const HEADER_SIZE: usize = MAGIC.len() + 1 + 4 + 8;
+
+pub type BufferFileResult<T> = Result<T, BufferFileError>;
+
+#[derive(Error, Debug)]
+pub enum BufferFileError {
+    #[error(transparent)]
+    Buffer(#[from] BufferError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("The file is not a buffer file saved by `gpgpu`.")]
+    InvalidHeader,
+    #[error("The file holds elements of {found} bytes, not of {expected} bytes.")]
+    ElementSize { expected: u32, found: u32 },
+    #[error("The file holds {expected} bytes of elements, but {found} bytes follow its header.")]
+    Truncated { expected: u64, found: u64 },
+}
+
+/// Order of the bytes of the elements stored in a buffer file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    Little,
+    Big,
+}
+
+impl ByteOrder {
+    /// Returns the byte order of the target, which is the one of the GPU memory.
+    pub fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Self::Big
+        } else {
+            Self::Little
+        }
+    }
+
+    fn flag(self) -> u8 {
+        match self {
+            Self::Little => 0,
+            Self::Big => 1,
+        }
+    }
+
+    fn from_flag(flag: u8) -> Option<Self> {
+        match flag {
+            0 => Some(Self::Little),
+            1 => Some(Self::Big),
+            _ => None,
+        }
+    }
+}
+
+/// Element of a buffer whose bytes can be swapped to store it in a given [`ByteOrder`].
+///
+/// Implemented for the numeric primitives and the arrays of them, e.g. `[f32; 4]`
+/// for a `vec4<f32>`. Structs of several fields cannot be swapped as a whole, so they are not.
+pub trait ScalarElement: bytemuck::Pod {
+    /// Reverses the order of the bytes of each scalar of this element.
+    fn swap_bytes(self) -> Self;
+}
+
+macro_rules! scalar_element_impl {
+    ($($name:ty),+) => {
+        $(
+            impl ScalarElement for $name {
+                fn swap_bytes(self) -> Self {
+                    <$name>::swap_bytes(self)
+                }
+            }
+        )+
+    };
+}
+
+scalar_element_impl!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+impl ScalarElement for f32 {
+    fn swap_bytes(self) -> Self {
+        f32::from_bits(self.to_bits().swap_bytes())
+    }
+}
+
+impl ScalarElement for f64 {
+    fn swap_bytes(self) -> Self {
+        f64::from_bits(self.to_bits().swap_bytes())
+    }
+}
+
+impl<T: ScalarElement, const N: usize> ScalarElement for [T; N]
+where
+    [T; N]: bytemuck::Pod,
+{
+    fn swap_bytes(self) -> Self {
+        self.map(T::swap_bytes)
+    }
+}
+
+/// Converts `elements` between the native byte order and `order`, in place.
+fn convert<T: ScalarElement>(elements: &mut [T], order: ByteOrder) {
+    if order != ByteOrder::native() {
+        elements
+            .iter_mut()
+            .for_each(|element| *element = element.swap_bytes());
+    }
+}
+
+impl<'fw, T> GpuBuffer<'fw, T>
+where
+    T: ScalarElement,
+{
+    /// Saves the elements of this [`GpuBuffer`] to the file at `path` in `order`,
+    /// after a header recording it.
+    ///
+    /// The elements are converted while they are copied on the CPU, so a file saved
+    /// on a big-endian target is read back correctly on a little-endian one and vice versa.
+    pub fn save_to_file(&self, path: impl AsRef<Path>, order: ByteOrder) -> BufferFileResult<()> {
+        let mut elements = self.read_vec_blocking()?;
+        convert(&mut elements, order);
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+        file.write_all(MAGIC)?;
+        file.write_all(&[order.flag()])?;
+        file.write_all(&(std::mem::size_of::<T>() as u32).to_le_bytes())?;
+        file.write_all(&(elements.len() as u64).to_le_bytes())?;
+        file.write_all(bytemuck::cast_slice(&elements))?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Saves the elements of this [`GpuBuffer`] to the file at `path` in little endian,
+    /// see [`GpuBuffer::save_to_file`].
+    pub fn save_to_file_le(&self, path: impl AsRef<Path>) -> BufferFileResult<()> {
+        self.save_to_file(path, ByteOrder::Little)
+    }
+
+    /// Constructs a new [`GpuBuffer`] from the elements of a file saved by
+    /// [`GpuBuffer::save_to_file`], converted from the byte order its header records.
+    ///
+    /// Fails with [`BufferFileError::InvalidHeader`] if the file was not saved by `gpgpu`,
+    /// and with [`BufferFileError::ElementSize`] if it holds elements of another size than `T`.
+    pub fn load_from_file(fw: &'fw Framework, path: impl AsRef<Path>) -> BufferFileResult<Self> {
+        Ok(Self::try_from_slice(fw, &read_elements(path)?)?)
+    }
+}
+
+/// Reads the elements of a file saved by [`GpuBuffer::save_to_file`] in the native byte order.
+pub(crate) fn read_elements<T: ScalarElement>(path: impl AsRef<Path>) -> BufferFileResult<Vec<T>> {
+    let bytes = std::fs::read(path)?;
+
+    if bytes.len() < HEADER_SIZE || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(BufferFileError::InvalidHeader);
+    }
+
+    let (header, payload) = bytes.split_at(HEADER_SIZE);
+    let order = ByteOrder::from_flag(header[8]).ok_or(BufferFileError::InvalidHeader)?;
+    let element_size = u32::from_le_bytes(header[9..13].try_into().unwrap());
+    let len = u64::from_le_bytes(header[13..].try_into().unwrap());
+
+    if element_size as usize != std::mem::size_of::<T>() {
+        return Err(BufferFileError::ElementSize {
+            expected: std::mem::size_of::<T>() as u32,
+            found: element_size,
+        });
+    }
+
+    let expected = len.saturating_mul(element_size as u64);
+    if expected != payload.len() as u64 {
+        return Err(BufferFileError::Truncated {
+            expected,
+            found: payload.len() as u64,
+        });
+    }
+
+    // The payload is not aligned for `T` inside the file bytes.
+    let mut elements = vec![T::zeroed(); len as usize];
+    bytemuck::cast_slice_mut::<T, u8>(&mut elements).copy_from_slice(payload);
+    convert(&mut elements, order);
+
+    Ok(elements)
+}
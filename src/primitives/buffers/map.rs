@@ -0,0 +1,87 @@
+//! Uploads converting their elements straight into the memory of the new buffer.
+
+use std::marker::PhantomData;
+
+use crate::{primitives::BufOps, Framework, GpuBuffer, GpuId};
+
+use super::GPU_BUFFER_USAGES;
+
+/// Inputs of at least this many elements are converted on every core of the CPU.
+const PARALLEL_ELEMENTS: usize = 1 << 16;
+
+impl<'fw, T> GpuBuffer<'fw, T>
+where
+    T: bytemuck::Pod + Send,
+{
+    /// Constructs a new [`GpuBuffer`] of the elements of `input` converted by `f`,
+    /// e.g. narrowing `f64`s into `f32`s or gathering the fields of a struct.
+    ///
+    /// The elements are written straight into the memory the buffer is mapped to at creation,
+    /// without a `Vec` in between. Inputs of 65536 elements or more are converted
+    /// on as many threads as the CPU has cores, like [`ShaderLibrary::load_dir_parallel`](crate::ShaderLibrary::load_dir_parallel).
+    pub fn from_slice_map<U>(fw: &'fw Framework, input: &[U], f: impl Fn(&U) -> T + Sync) -> Self
+    where
+        U: Sync,
+    {
+        let size = (input.len() * std::mem::size_of::<T>()) as u64;
+        if size == 0 {
+            return Self::from_slice(fw, &[]);
+        }
+
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        // Mapped buffers must be a whole number of words long.
+        let buf = fw.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuBuffer::from_slice_map"),
+            size: size.div_ceil(wgpu::COPY_BUFFER_ALIGNMENT) * wgpu::COPY_BUFFER_ALIGNMENT,
+            usage: GPU_BUFFER_USAGES,
+            mapped_at_creation: true,
+        });
+
+        {
+            let mut mapped = buf.slice(..).get_mapped_range_mut();
+            let output = bytemuck::cast_slice_mut::<u8, T>(&mut mapped[..size as usize]);
+
+            if input.len() < PARALLEL_ELEMENTS {
+                convert(output, input, &f);
+            } else {
+                let threads =
+                    std::thread::available_parallelism().map_or(1, |threads| threads.get());
+                let chunk_size = input.len().div_ceil(threads);
+
+                std::thread::scope(|scope| {
+                    for (output, input) in
+                        output.chunks_mut(chunk_size).zip(input.chunks(chunk_size))
+                    {
+                        let f = &f;
+                        scope.spawn(move || convert(output, input, f));
+                    }
+                });
+            }
+        }
+        buf.unmap();
+
+        event!(
+            debug,
+            "created `GpuBuffer::from_slice_map` of {} bytes in {:?}",
+            size,
+            start.elapsed()
+        );
+
+        Self {
+            fw,
+            id: GpuId::new(),
+            buf,
+            size,
+            _allocation: fw.memory.buffer(size),
+            marker: PhantomData,
+        }
+    }
+}
+
+fn convert<U, T>(output: &mut [T], input: &[U], f: &impl Fn(&U) -> T) {
+    for (output, input) in output.iter_mut().zip(input) {
+        *output = f(input);
+    }
+}
@@ -0,0 +1,83 @@
+//! Matrices in the layouts of `WGSL`: column-major, each column of a uniform matrix
+//! aligned like a `vec4`, so the columns of a `mat3x3<f32>` are padded to 16 bytes.
+
+use crate::{primitives::BufOps, Framework, GpuBuffer, GpuUniformBuffer};
+
+use super::BufferResult;
+
+/// Columns of a `mat3x3<f32>` in the layout of uniform buffers, each padded to a `vec4<f32>`.
+pub type Std140Mat3 = [[f32; 4]; 3];
+
+/// Columns of a `mat4x4<f32>`, whose layout does not need any padding.
+pub type Std140Mat4 = [[f32; 4]; 4];
+
+/// Pads the `columns` of a 3x3 matrix to the layout of a `mat3x3<f32>` uniform.
+pub fn std140_mat3(columns: &[[f32; 3]; 3]) -> Std140Mat3 {
+    columns.map(|[x, y, z]| [x, y, z, 0.0])
+}
+
+/// Copies the `rows` x `cols` elements of a column-major matrix into row-major order.
+fn transpose<T: Copy>(data: &[T], rows: usize, cols: usize) -> Vec<T> {
+    (0..rows)
+        .flat_map(|row| (0..cols).map(move |col| data[col * rows + row]))
+        .collect()
+}
+
+impl<'fw, T> GpuBuffer<'fw, T>
+where
+    T: bytemuck::Pod,
+{
+    /// Constructs a new [`GpuBuffer`] from the elements of a column-major matrix of `rows` rows,
+    /// like the storage of `nalgebra` matrices, stored in row-major order so that
+    /// `buf[row * cols + col]` in the shader is the element at `row` and `col`.
+    ///
+    /// # Panics
+    /// If the length of `data` is not a multiple of `rows`.
+    pub fn from_column_major(fw: &'fw Framework, data: &[T], rows: usize) -> Self {
+        assert!(
+            rows != 0 && data.len().is_multiple_of(rows),
+            "{} elements do not make a matrix of {} rows",
+            data.len(),
+            rows
+        );
+
+        Self::from_slice(fw, &transpose(data, rows, data.len() / rows))
+    }
+
+    /// Pulls the elements of a row-major `rows` x `cols` matrix from the [`GpuBuffer`]
+    /// into a [`Vec`] in column-major order, like the storage of `nalgebra` matrices.
+    ///
+    /// # Panics
+    /// If the [`GpuBuffer`] holds less than `rows * cols` elements.
+    pub async fn read_to_column_major(&self, rows: usize, cols: usize) -> BufferResult<Vec<T>> {
+        let buf = self.read_vec().await?;
+
+        Ok(transpose(&buf[..rows * cols], cols, rows))
+    }
+
+    /// Blocking version of `GpuBuffer::read_to_column_major()`.
+    pub fn read_to_column_major_blocking(&self, rows: usize, cols: usize) -> BufferResult<Vec<T>> {
+        futures::executor::block_on(self.read_to_column_major(rows, cols))
+    }
+}
+
+impl<'fw> GpuUniformBuffer<'fw, Std140Mat3> {
+    /// Constructs a new [`GpuUniformBuffer`] holding a `mat3x3<f32>` from its `columns`,
+    /// padded to the layout of uniform buffers.
+    pub fn from_mat3(fw: &'fw Framework, columns: &[[f32; 3]; 3]) -> Self {
+        Self::from_slice(fw, &[std140_mat3(columns)])
+    }
+
+    /// Writes the `columns` of a `mat3x3<f32>` into this [`GpuUniformBuffer`],
+    /// padded to the layout of uniform buffers.
+    pub fn write_mat3(&self, columns: &[[f32; 3]; 3]) -> BufferResult<u64> {
+        self.write(&[std140_mat3(columns)])
+    }
+}
+
+impl<'fw> GpuUniformBuffer<'fw, Std140Mat4> {
+    /// Constructs a new [`GpuUniformBuffer`] holding a `mat4x4<f32>` from its `columns`.
+    pub fn from_mat4(fw: &'fw Framework, columns: &Std140Mat4) -> Self {
+        Self::from_slice(fw, &[*columns])
+    }
+}
@@ -0,0 +1,140 @@
+//! Transfers between buffers and large memory regions, e.g. memory-mapped files,
+//! made in chunks so that no copy of the whole region is ever held on the CPU.
+
+use crate::{primitives::BufOps, Framework, GpuBuffer};
+
+use super::{check_write_alignment, BufferError, BufferResult};
+
+/// Bytes transferred by each chunk: the most memory a transfer holds besides its region.
+const CHUNK_SIZE: u64 = 64 << 20;
+
+/// Checks that a region of `len` bytes holds a whole number of `T`s.
+fn check_element_length<T>(len: usize) -> BufferResult<()> {
+    let element_size = std::mem::size_of::<T>();
+
+    if !len.is_multiple_of(element_size) {
+        return Err(BufferError::ElementLength {
+            len: len as u64,
+            element_size: element_size as u64,
+        });
+    }
+
+    Ok(())
+}
+
+impl<'fw, T> GpuBuffer<'fw, T>
+where
+    T: bytemuck::Pod,
+{
+    /// Constructs a new [`GpuBuffer`] of the elements stored in `bytes`, blocking until
+    /// they are all uploaded.
+    ///
+    /// Meant for regions too large to be copied on the CPU, e.g. the `&mmap[..]` of a
+    /// `memmap2::Mmap` of a file holding the elements: they are uploaded 64 MiB at a time,
+    /// and the staging memory of each chunk is released before the next one is written.
+    /// `bytes` does not need to be aligned for `T`.
+    ///
+    /// Fails with [`BufferError::ElementLength`] if `bytes` does not hold a whole number of `T`s,
+    /// [`BufferError::MisalignedTransfer`] if its length is not a multiple of 4 bytes, and
+    /// like [`BufOps::try_with_capacity`] if the buffer cannot be allocated.
+    pub fn from_mapped_bytes(fw: &'fw Framework, bytes: &[u8]) -> BufferResult<Self> {
+        check_element_length::<T>(bytes.len())?;
+        check_write_alignment(bytes.len() as u64)?;
+
+        let buf = Self::try_with_capacity(fw, (bytes.len() / std::mem::size_of::<T>()) as u64)?;
+
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        for (i, chunk) in bytes.chunks(CHUNK_SIZE as usize).enumerate() {
+            fw.queue
+                .write_buffer(&buf.buf, i as u64 * CHUNK_SIZE, chunk);
+            fw.queue.submit(None);
+
+            // Waits for the chunk to reach the GPU, freeing its staging memory.
+            fw.device.poll(wgpu::Maintain::Wait);
+        }
+
+        event!(
+            debug,
+            "streamed {} bytes into a GpuBuffer in {:?}",
+            bytes.len(),
+            start.elapsed()
+        );
+
+        Ok(buf)
+    }
+
+    /// Pulls the first elements of the [`GpuBuffer`] into `out`, blocking,
+    /// returning how many bytes were read, like [`GpuBuffer::read_blocking`].
+    ///
+    /// The symmetric of [`GpuBuffer::from_mapped_bytes`], e.g. for the `&mut mmap[..]`
+    /// of a `memmap2::MmapMut`: the elements are downloaded 64 MiB at a time through
+    /// a single staging buffer, and written straight into `out`.
+    ///
+    /// Fails with [`BufferError::ElementLength`] if `out` does not hold a whole number of `T`s.
+    pub fn read_into_mapped_bytes(&self, out: &mut [u8]) -> BufferResult<u64> {
+        check_element_length::<T>(out.len())?;
+
+        let download_size = (out.len() as u64).min(self.size);
+        if download_size == 0 {
+            return Ok(0);
+        }
+
+        // `wgpu` only copies whole words: the bytes up to the next one are downloaded too.
+        let aligned_size =
+            download_size.div_ceil(wgpu::COPY_BUFFER_ALIGNMENT) * wgpu::COPY_BUFFER_ALIGNMENT;
+        if aligned_size > self.size {
+            return Err(BufferError::MisalignedTransfer(download_size));
+        }
+
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        let staging = self.fw.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuBuffer::read_into_mapped_bytes"),
+            size: aligned_size.min(CHUNK_SIZE),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let out = &mut out[..download_size as usize];
+
+        for (i, chunk) in out.chunks_mut(CHUNK_SIZE as usize).enumerate() {
+            let offset = i as u64 * CHUNK_SIZE;
+            let chunk_size = (chunk.len() as u64).div_ceil(wgpu::COPY_BUFFER_ALIGNMENT)
+                * wgpu::COPY_BUFFER_ALIGNMENT;
+
+            let mut encoder =
+                self.fw
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: Some("GpuBuffer::read_into_mapped_bytes"),
+                    });
+            encoder.copy_buffer_to_buffer(&self.buf, offset, &staging, 0, chunk_size);
+            self.fw.queue.submit(Some(encoder.finish()));
+
+            let slice = staging.slice(..chunk_size);
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            slice.map_async(wgpu::MapMode::Read, |res| {
+                sender.send(res).ok();
+            });
+            self.fw.device.poll(wgpu::Maintain::Wait);
+
+            // The callback is only dropped without being called if the device is lost.
+            futures::executor::block_on(receiver).unwrap_or(Err(wgpu::BufferAsyncError))?;
+
+            chunk.copy_from_slice(&slice.get_mapped_range()[..chunk.len()]);
+            staging.unmap();
+        }
+
+        event!(
+            debug,
+            "streamed {} bytes from a GpuBuffer in {:?}",
+            download_size,
+            start.elapsed()
+        );
+
+        Ok(download_size)
+    }
+}
@@ -0,0 +1,87 @@
+//! Nullable columns in the layout of Apache Arrow: the values, and a validity bitmap
+//! whose bit `i`, least significant first, is set if the value `i` is not null.
+
+use crate::{primitives::BufOps, Framework, GpuBuffer};
+
+use super::{BufferError, BufferResult};
+
+/// Number of bytes of the validity bitmap of `len` values.
+fn bitmap_bytes(len: usize) -> usize {
+    len.div_ceil(8)
+}
+
+impl<'fw, T> GpuBuffer<'fw, T>
+where
+    T: bytemuck::Pod,
+{
+    /// Constructs a [`GpuBuffer`] of `values` and, if there is one, a second one of their
+    /// `validity` bitmap, like the buffers of an Arrow `PrimitiveArray`.
+    ///
+    /// The bitmap is packed into `u32` words, as shaders cannot index bytes: the value `i`
+    /// is not null if `(validity[i / 32u] >> (i % 32u)) & 1u` is set, which is the Arrow
+    /// bit order. Kernels see the raw values of the null slots, whatever they hold,
+    /// so they must check the bitmap themselves.
+    ///
+    /// Fails with [`BufferError::ValidityLength`] if `validity` has less than one bit per value.
+    pub fn from_values_with_validity(
+        fw: &'fw Framework,
+        values: &[T],
+        validity: Option<&[u8]>,
+    ) -> BufferResult<(Self, Option<GpuBuffer<'fw, u32>>)> {
+        let validity = validity
+            .map(|bitmap| {
+                let required = bitmap_bytes(values.len());
+
+                if bitmap.len() < required {
+                    return Err(BufferError::ValidityLength {
+                        values: values.len() as u64,
+                        bytes: bitmap.len() as u64,
+                    });
+                }
+
+                let words = bitmap[..required]
+                    .chunks(4)
+                    .map(|word| {
+                        let mut bytes = [0u8; 4];
+                        bytes[..word.len()].copy_from_slice(word);
+                        u32::from_le_bytes(bytes)
+                    })
+                    .collect::<Vec<_>>();
+
+                GpuBuffer::try_from_slice(fw, &words)
+            })
+            .transpose()?;
+
+        Ok((Self::try_from_slice(fw, values)?, validity))
+    }
+
+    /// Pulls all the values from the [`GpuBuffer`] and, if there is one, their `validity`
+    /// bitmap from the second buffer returned by [`GpuBuffer::from_values_with_validity`],
+    /// as the bytes of an Arrow validity bitmap.
+    pub async fn read_with_validity(
+        &self,
+        validity: Option<&GpuBuffer<'fw, u32>>,
+    ) -> BufferResult<(Vec<T>, Option<Vec<u8>>)> {
+        let values = self.read_vec().await?;
+
+        let bitmap = match validity {
+            Some(validity) => {
+                let mut bytes =
+                    bytemuck::cast_slice::<u32, u8>(&validity.read_vec().await?).to_vec();
+                bytes.truncate(bitmap_bytes(values.len()));
+                Some(bytes)
+            }
+            None => None,
+        };
+
+        Ok((values, bitmap))
+    }
+
+    /// Blocking version of `GpuBuffer::read_with_validity()`.
+    pub fn read_with_validity_blocking(
+        &self,
+        validity: Option<&GpuBuffer<'fw, u32>>,
+    ) -> BufferResult<(Vec<T>, Option<Vec<u8>>)> {
+        futures::executor::block_on(self.read_with_validity(validity))
+    }
+}
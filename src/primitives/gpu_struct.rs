@@ -0,0 +1,96 @@
+/// Declares a `#[repr(C)]` struct that can be uploaded to the GPU, implementing
+/// [`bytemuck::Pod`] and [`bytemuck::Zeroable`] for it without depending on `bytemuck`.
+///
+/// Every field must be [`bytemuck::Pod`] itself, e.g. `u32`, `f32` or `[f32; 4]`, and the
+/// struct must have no padding: a struct whose fields are not laid out back to back fails
+/// to compile, asking for explicit padding fields instead. Declaring it as a `uniform struct`
+/// also requires its size to be a multiple of 16 bytes, as the `uniform` address space of
+/// `WGSL` rounds structs to. Generic structs are not supported.
+///
+/// ```
+/// gpgpu::gpu_struct! {
+///     /// Parameters of the kernel, bound to `var<uniform> params: Params`.
+///     #[derive(Debug, PartialEq)]
+///     pub uniform struct Params {
+///         pub scale: f32,
+///         pub offset: f32,
+///         pub len: u32,
+///         pub _padding: u32,
+///     }
+/// }
+///
+/// let bytes: &[u8] = gpgpu::bytemuck::bytes_of(&Params {
+///     scale: 2.0,
+///     offset: 1.0,
+///     len: 64,
+///     _padding: 0,
+/// });
+/// assert_eq!(bytes.len(), 16);
+/// ```
+///
+/// ```compile_fail
+/// gpgpu::gpu_struct! {
+///     // 12 bytes: does not compile, `uniform` structs are 16-byte multiples.
+///     pub uniform struct Params {
+///         pub scale: f32,
+///         pub offset: f32,
+///         pub len: u32,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! gpu_struct {
+    (
+        $(#[$attr:meta])*
+        $vis:vis uniform struct $name:ident {
+            $($field_vis:vis $field:ident : $ty:ty),* $(,)?
+        }
+    ) => {
+        $crate::gpu_struct! {
+            $(#[$attr])*
+            $vis struct $name {
+                $($field_vis $field: $ty),*
+            }
+        }
+
+        const _: () = ::core::assert!(
+            ::core::mem::size_of::<$name>() % 16 == 0,
+            ::core::concat!(
+                "`",
+                ::core::stringify!($name),
+                "` is used as a uniform: its size must be a multiple of 16 bytes, add padding fields at its end"
+            )
+        );
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($field_vis:vis $field:ident : $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        $vis struct $name {
+            $($field_vis $field: $ty),*
+        }
+
+        const _: () = ::core::assert!(
+            ::core::mem::size_of::<$name>() == 0 $(+ ::core::mem::size_of::<$ty>())*,
+            ::core::concat!(
+                "`",
+                ::core::stringify!($name),
+                "` has padding between its fields: add explicit padding fields so that it is `Pod`"
+            )
+        );
+
+        const _: fn() = || {
+            fn field_is_pod<T: $crate::bytemuck::Pod>() {}
+            $(field_is_pod::<$ty>();)*
+        };
+
+        // Safety: `repr(C)`, its fields are `Pod` and it has no padding, as checked above.
+        unsafe impl $crate::bytemuck::Zeroable for $name {}
+        unsafe impl $crate::bytemuck::Pod for $name {}
+    };
+}
@@ -1,10 +1,12 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 
 use thiserror::Error;
-use wgpu::BufferAsyncError;
 use wgpu::util::{DeviceExt, DownloadBuffer};
+use wgpu::BufferAsyncError;
 
-use crate::{GpuConstImage, GpuImage};
+use crate::{GpuConstImage, GpuId, GpuImage, ImageBuilder};
 
 use super::{ImgOps, PixelInfo};
 
@@ -18,6 +20,25 @@ const GPU_CONST_IMAGE_USAGES: wgpu::TextureUsages = wgpu::TextureUsages::from_bi
     wgpu::TextureUsages::TEXTURE_BINDING.bits() | wgpu::TextureUsages::COPY_DST.bits(),
 );
 
+pub type ImageResult<T> = Result<T, ImageError>;
+
+#[derive(Error, Debug)]
+pub enum ImageError {
+    #[error("Image data is {current} bytes, {required} bytes required.")]
+    InvalidData { required: usize, current: usize },
+    #[error("The image could not be created: {0}")]
+    Creation(String),
+    #[error("The texture is {found:?}, pixels of the image are {expected:?}.")]
+    FormatMismatch {
+        expected: wgpu::TextureFormat,
+        found: wgpu::TextureFormat,
+    },
+    #[error("The texture was created without the {0:?} usage the image requires.")]
+    MissingUsage(wgpu::TextureUsages),
+    #[error("The texture is not a single-sampled 2D texture of one layer.")]
+    UnsupportedTexture,
+}
+
 #[derive(Error, Debug)]
 pub enum ImageOutputError {
     #[error(transparent)]
@@ -26,6 +47,10 @@ pub enum ImageOutputError {
         "Output is too small (required size {required} bytes, current size {current} bytes). "
     )]
     BufferTooSmall { required: usize, current: usize },
+    #[error("The image was created without the {0:?} usage required to read it.")]
+    MissingUsage(wgpu::TextureUsages),
+    #[error("The image readback holds {found} bytes instead of the {expected} bytes expected.")]
+    StagingLength { expected: usize, found: usize },
 }
 
 #[derive(Error, Debug)]
@@ -34,6 +59,61 @@ pub enum ImageInputError {
     NotIntegerPixelNumber,
     #[error("Input does not contains an integer number of rows.")]
     NotIntegerRowNumber,
+    #[error("The image was created without the {0:?} usage required to write it.")]
+    MissingUsage(wgpu::TextureUsages),
+}
+
+/// Returns the bytes of the pixels of a `size` image with `mip_levels` mip levels.
+fn image_bytes<P: PixelInfo>(size: wgpu::Extent3d, mip_levels: u32) -> u64 {
+    (0..mip_levels)
+        .map(|level| {
+            let width = (size.width >> level).max(1) as u64;
+            let height = (size.height >> level).max(1) as u64;
+
+            width * height * P::byte_size() as u64
+        })
+        .sum()
+}
+
+/// Checks that the texture `desc` describes can back an image of `P` pixels
+/// used with the `required` usages.
+fn check_texture_desc<P: PixelInfo>(
+    desc: &wgpu::TextureDescriptor,
+    required: wgpu::TextureUsages,
+) -> ImageResult<()> {
+    if desc.format != P::wgpu_format() {
+        return Err(ImageError::FormatMismatch {
+            expected: P::wgpu_format(),
+            found: desc.format,
+        });
+    }
+
+    if !desc.usage.contains(required) {
+        return Err(ImageError::MissingUsage(required - desc.usage));
+    }
+
+    if desc.dimension != wgpu::TextureDimension::D2
+        || desc.size.depth_or_array_layers != 1
+        || desc.sample_count != 1
+    {
+        return Err(ImageError::UnsupportedTexture);
+    }
+
+    Ok(())
+}
+
+/// Checks that `data` holds exactly the pixels of a `width` x `height` image.
+fn check_data_len<P: PixelInfo>(data: &[u8], width: u32, height: u32) -> ImageResult<()> {
+    let required = width as usize * height as usize * P::byte_size();
+
+    if data.len() != required {
+        return Err(ImageError::InvalidData {
+            required,
+            current: data.len(),
+        });
+    }
+
+    Ok(())
 }
 
 impl<'fw, P> ImgOps<'fw> for GpuImage<'fw, P>
@@ -57,33 +137,27 @@ where
     }
 
     fn new(fw: &'fw crate::Framework, width: u32, height: u32) -> Self {
-        let size = wgpu::Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        };
-
-        let format = P::wgpu_format();
-
-        let texture = fw.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("GpuImage::new"),
-            size,
-            dimension: wgpu::TextureDimension::D2,
-            mip_level_count: 1,
-            sample_count: 1,
-            format,
-            usage: GPU_IMAGE_USAGES,
-        });
+        Self::builder(fw, width, height)
+            .label("GpuImage::new")
+            .create()
+    }
 
-        let full_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    fn try_new(fw: &'fw crate::Framework, width: u32, height: u32) -> ImageResult<Self> {
+        Self::builder(fw, width, height)
+            .label("GpuImage::new")
+            .build()
+    }
 
-        Self {
-            fw,
-            texture,
-            size,
-            full_view,
-            pixel: PhantomData,
-        }
+    fn try_from_bytes(
+        fw: &'fw crate::Framework,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> ImageResult<Self> {
+        check_data_len::<P>(data, width, height)?;
+
+        fw.capture_errors(|| Self::from_bytes(fw, data, width, height))
+            .map_err(|err| ImageError::Creation(err.to_string()))
     }
 
     fn from_bytes(fw: &'fw crate::Framework, data: &[u8], width: u32, height: u32) -> Self {
@@ -113,9 +187,13 @@ where
 
         Self {
             fw,
+            id: GpuId::new(),
             texture,
             size,
+            usage: GPU_IMAGE_USAGES,
+            mip_levels: 1,
             full_view,
+            _allocation: fw.memory.image(image_bytes::<P>(size, 1)),
             pixel: PhantomData,
         }
     }
@@ -138,9 +216,13 @@ where
 
         Self {
             fw,
+            id: GpuId::new(),
             texture,
             size: dimensions,
+            usage: GPU_IMAGE_USAGES,
+            mip_levels: 1,
             full_view,
+            _allocation: fw.memory.image(image_bytes::<P>(dimensions, 1)),
             pixel: PhantomData,
         }
     }
@@ -150,14 +232,166 @@ where
     }
 }
 
+impl<'fw, P> ImageBuilder<'fw, P>
+where
+    P: PixelInfo,
+{
+    /// Names the image `label` in GPU debuggers and `wgpu` errors.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Adds the `extra` usages to the ones of a [`GpuImage`], e.g.
+    /// [`wgpu::TextureUsages::TEXTURE_BINDING`] to sample it through its [`wgpu::Texture`].
+    pub fn usage(mut self, extra: wgpu::TextureUsages) -> Self {
+        self.usage |= extra;
+        self
+    }
+
+    /// Removes the `usages` of the image, e.g. [`wgpu::TextureUsages::COPY_SRC`]
+    /// for an image never read back, which [`GpuImage::read`] then refuses.
+    pub fn without_usage(mut self, usages: wgpu::TextureUsages) -> Self {
+        self.usage -= usages;
+        self
+    }
+
+    /// Sets the number of mip levels of the image, 1 by default.
+    ///
+    /// Only the first one is bound, read and written by `gpgpu`.
+    pub fn mip_levels(mut self, count: u32) -> Self {
+        self.mip_levels = count;
+        self
+    }
+
+    /// Creates the [`GpuImage`].
+    ///
+    /// Fails with [`ImageError::Creation`] if `wgpu` rejects the options,
+    /// e.g. more mip levels than the size of the image allows.
+    pub fn build(self) -> ImageResult<GpuImage<'fw, P>> {
+        let fw = self.fw;
+
+        fw.capture_errors(|| self.create())
+            .map_err(|err| ImageError::Creation(err.to_string()))
+    }
+
+    /// Creates the [`GpuImage`], panicking if `wgpu` rejects the options.
+    fn create(self) -> GpuImage<'fw, P> {
+        let texture = self.fw.device.create_texture(&wgpu::TextureDescriptor {
+            label: self.label.as_deref(),
+            size: self.size,
+            dimension: wgpu::TextureDimension::D2,
+            mip_level_count: self.mip_levels,
+            sample_count: 1,
+            format: P::wgpu_format(),
+            usage: self.usage,
+        });
+
+        // Storage images are bound with a single mip level.
+        let full_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            mip_level_count: std::num::NonZeroU32::new(1),
+            ..Default::default()
+        });
+
+        GpuImage {
+            fw: self.fw,
+            id: GpuId::new(),
+            texture,
+            size: self.size,
+            usage: self.usage,
+            mip_levels: self.mip_levels,
+            full_view,
+            _allocation: self
+                .fw
+                .memory
+                .image(image_bytes::<P>(self.size, self.mip_levels)),
+            pixel: PhantomData,
+        }
+    }
+}
+
 impl<'fw, P> GpuImage<'fw, P>
 where
     P: PixelInfo,
 {
+    /// Returns an [`ImageBuilder`] of a `width` x `height` image, to set the options
+    /// [`ImgOps::new`] does not take, like its label, usages or mip levels.
+    pub fn builder(fw: &'fw crate::Framework, width: u32, height: u32) -> ImageBuilder<'fw, P> {
+        ImageBuilder {
+            fw,
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            label: None,
+            usage: GPU_IMAGE_USAGES,
+            mip_levels: 1,
+            pixel: PhantomData,
+        }
+    }
+
+    /// Constructs a [`GpuImage`] from a `texture` created by another library on the device
+    /// of `fw`, see [`Framework::from_wgpu`](crate::Framework::from_wgpu), and the `desc`
+    /// it was created with.
+    ///
+    /// The image owns `texture` until it is given back by [`ImgOps::into_gpu_parts`].
+    /// Unlike [`ImgOps::from_gpu_parts`], `desc` is checked: `texture` must be a 2D texture
+    /// of `P` pixels with the [`wgpu::TextureUsages::STORAGE_BINDING`] usage.
+    /// Reads and writes need its [`wgpu::TextureUsages::COPY_SRC`] and
+    /// [`wgpu::TextureUsages::COPY_DST`] usages too.
+    pub fn from_wgpu_texture(
+        fw: &'fw crate::Framework,
+        texture: wgpu::Texture,
+        desc: &wgpu::TextureDescriptor,
+    ) -> ImageResult<Self> {
+        check_texture_desc::<P>(desc, wgpu::TextureUsages::STORAGE_BINDING)?;
+
+        let full_view = texture.create_view(&Default::default());
+
+        Ok(Self {
+            fw,
+            id: GpuId::new(),
+            texture,
+            size: desc.size,
+            usage: desc.usage,
+            mip_levels: desc.mip_level_count,
+            full_view,
+            _allocation: fw
+                .memory
+                .image(image_bytes::<P>(desc.size, desc.mip_level_count)),
+            pixel: PhantomData,
+        })
+    }
+
+    /// Returns the identifier of this [`GpuImage`], see [`GpuId`].
+    pub fn id(&self) -> GpuId {
+        self.id
+    }
+
+    /// Returns the usages of the [`wgpu::Texture`] of this [`GpuImage`].
+    pub fn usage(&self) -> wgpu::TextureUsages {
+        self.usage
+    }
+
+    /// Returns the number of mip levels of this [`GpuImage`].
+    pub fn mip_levels(&self) -> u32 {
+        self.mip_levels
+    }
+
     /// Pulls some elements from the [`GpuImage`] into `buf`, returning how many pixels were read.
+    ///
+    /// Fails with [`ImageOutputError::MissingUsage`] if the image was built without
+    /// [`wgpu::TextureUsages::COPY_SRC`].
     pub async fn read(&self, buf: &mut [u8]) -> Result<usize, ImageOutputError> {
         use std::num::NonZeroU32;
 
+        if !self.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(ImageOutputError::MissingUsage(
+                wgpu::TextureUsages::COPY_SRC,
+            ));
+        }
+
         let (width, height) = self.dimensions();
 
         let img_bytes = (width * height) as usize * P::byte_size();
@@ -177,6 +411,19 @@ where
 
         let staging_size = (padded_bytes_per_row * self.size.height) as usize;
 
+        if padded_bytes_per_row_padding != 0 {
+            event!(
+                warn,
+                "reading a GpuImage of {}x{} through a padded staging buffer of {} bytes: \
+                 its rows of {} bytes are not aligned to {} bytes",
+                self.size.width,
+                self.size.height,
+                staging_size,
+                unpadded_bytes_per_row,
+                align
+            );
+        }
+
         let staging = self.fw.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("GpuImage::read staging and copy"),
             size: staging_size as u64,
@@ -211,14 +458,26 @@ where
 
         self.fw.queue.submit(Some(encoder.finish()));
 
+        let (sender, receiver) =
+            futures::channel::oneshot::channel::<Result<DownloadBuffer, BufferAsyncError>>();
 
-        let (sender, receiver) = futures::channel::oneshot::channel::<Result<DownloadBuffer, BufferAsyncError>>();
-
-
-         DownloadBuffer::read_buffer(&self.fw.device, &self.fw.queue, &staging.slice(..), |arg| {
+        DownloadBuffer::read_buffer(&self.fw.device, &self.fw.queue, &staging.slice(..), |arg| {
             sender.send(arg).ok();
         });
-        let download = receiver.await.unwrap().unwrap();
+        // The callback is only dropped without being called if the device is lost.
+        let download = receiver
+            .await
+            .unwrap_or(Err(BufferAsyncError))
+            .map_err(crate::primitives::buffers::BufferError::from)?;
+
+        let paranoid = self.fw.paranoid_checks();
+
+        if paranoid && download.len() != staging_size {
+            return Err(ImageOutputError::StagingLength {
+                expected: staging_size,
+                found: download.len(),
+            });
+        }
 
         let bytes_read: usize = download
             .chunks(padded_bytes_per_row as usize)
@@ -229,6 +488,13 @@ where
             })
             .sum();
 
+        if paranoid && bytes_read != img_bytes {
+            return Err(ImageOutputError::StagingLength {
+                expected: img_bytes,
+                found: bytes_read,
+            });
+        }
+
         Ok(bytes_read / P::byte_size())
     }
 
@@ -257,9 +523,16 @@ where
     ///
     /// This function will attempt to write the entire contents of `buf`, unless its capacity
     /// exceeds the one of the image, in which case the first `width * height` pixels are written.
+    ///
+    /// Fails with [`ImageInputError::MissingUsage`] if the image was built without
+    /// [`wgpu::TextureUsages::COPY_DST`].
     pub fn write(&self, buf: &[u8]) -> Result<usize, ImageInputError> {
         use std::num::NonZeroU32;
 
+        if !self.usage.contains(wgpu::TextureUsages::COPY_DST) {
+            return Err(ImageInputError::MissingUsage(wgpu::TextureUsages::COPY_DST));
+        }
+
         if buf.len() % P::byte_size() != 0 {
             return Err(ImageInputError::NotIntegerPixelNumber);
         }
@@ -306,6 +579,25 @@ where
 
         Ok((size.width * size.height) as usize)
     }
+
+    /// Writes a buffer into this [`GpuImage`] like [`GpuImage::write`], returning a future
+    /// that resolves to how many pixels were written once the GPU has received them.
+    ///
+    /// The data is copied when this method is called, and the future resolves from the polling
+    /// thread of the [`Framework`](crate::Framework) like the one of
+    /// [`Kernel::enqueue_async`](crate::Kernel::enqueue_async).
+    pub fn write_async(
+        &self,
+        buf: &[u8],
+    ) -> impl std::future::Future<Output = Result<usize, ImageInputError>> {
+        let written = self.write(buf);
+        let done = self.fw.work_done();
+
+        async move {
+            done.await;
+            written
+        }
+    }
 }
 
 impl<'fw, P> ImgOps<'fw> for GpuConstImage<'fw, P>
@@ -351,13 +643,32 @@ where
 
         Self {
             fw,
+            id: GpuId::new(),
             texture,
             size,
             full_view,
+            _allocation: fw.memory.image(image_bytes::<P>(size, 1)),
             pixel: PhantomData,
         }
     }
 
+    fn try_new(fw: &'fw crate::Framework, width: u32, height: u32) -> ImageResult<Self> {
+        fw.capture_errors(|| Self::new(fw, width, height))
+            .map_err(|err| ImageError::Creation(err.to_string()))
+    }
+
+    fn try_from_bytes(
+        fw: &'fw crate::Framework,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> ImageResult<Self> {
+        check_data_len::<P>(data, width, height)?;
+
+        fw.capture_errors(|| Self::from_bytes(fw, data, width, height))
+            .map_err(|err| ImageError::Creation(err.to_string()))
+    }
+
     fn from_bytes(fw: &'fw crate::Framework, data: &[u8], width: u32, height: u32) -> Self {
         let size = wgpu::Extent3d {
             width,
@@ -385,9 +696,11 @@ where
 
         Self {
             fw,
+            id: GpuId::new(),
             texture,
             size,
             full_view,
+            _allocation: fw.memory.image(image_bytes::<P>(size, 1)),
             pixel: PhantomData,
         }
     }
@@ -409,9 +722,11 @@ where
 
         Self {
             fw,
+            id: GpuId::new(),
             texture,
             size: dimensions,
             full_view,
+            _allocation: fw.memory.image(image_bytes::<P>(dimensions, 1)),
             pixel: PhantomData,
         }
     }
@@ -425,6 +740,39 @@ impl<'fw, P> GpuConstImage<'fw, P>
 where
     P: PixelInfo,
 {
+    /// Constructs a [`GpuConstImage`] from a `texture` created by another library on the device
+    /// of `fw`, e.g. a decoded video frame, like [`GpuImage::from_wgpu_texture`].
+    ///
+    /// `texture` must be a 2D texture of `P` pixels with the
+    /// [`wgpu::TextureUsages::TEXTURE_BINDING`] usage. Writes need its
+    /// [`wgpu::TextureUsages::COPY_DST`] usage too.
+    pub fn from_wgpu_texture(
+        fw: &'fw crate::Framework,
+        texture: wgpu::Texture,
+        desc: &wgpu::TextureDescriptor,
+    ) -> ImageResult<Self> {
+        check_texture_desc::<P>(desc, wgpu::TextureUsages::TEXTURE_BINDING)?;
+
+        let full_view = texture.create_view(&Default::default());
+
+        Ok(Self {
+            fw,
+            id: GpuId::new(),
+            texture,
+            size: desc.size,
+            full_view,
+            _allocation: fw
+                .memory
+                .image(image_bytes::<P>(desc.size, desc.mip_level_count)),
+            pixel: PhantomData,
+        })
+    }
+
+    /// Returns the identifier of this [`GpuConstImage`], see [`GpuId`].
+    pub fn id(&self) -> GpuId {
+        self.id
+    }
+
     /// Writes a buffer into this [`GpuConstImage`], returning how many pixels were written. The operation is instantly offloaded.
     ///
     /// This function will attempt to write the entire contents of `buf`, unless its capacity
@@ -478,4 +826,73 @@ where
 
         Ok((size.width * size.height) as usize)
     }
+
+    /// Writes a buffer into this [`GpuConstImage`] like [`GpuConstImage::write`], returning a future
+    /// that resolves to how many pixels were written once the GPU has received them.
+    ///
+    /// The data is copied when this method is called, and the future resolves from the polling
+    /// thread of the [`Framework`](crate::Framework) like the one of
+    /// [`Kernel::enqueue_async`](crate::Kernel::enqueue_async).
+    pub fn write_async(
+        &self,
+        buf: &[u8],
+    ) -> impl std::future::Future<Output = Result<usize, ImageInputError>> {
+        let written = self.write(buf);
+        let done = self.fw.work_done();
+
+        async move {
+            done.await;
+            written
+        }
+    }
+}
+
+impl<P> PartialEq for GpuImage<'_, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<P> Eq for GpuImage<'_, P> {}
+
+impl<P> Hash for GpuImage<'_, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<P: PixelInfo> fmt::Debug for GpuImage<'_, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GpuImage")
+            .field("id", &self.id)
+            .field("width", &self.size.width)
+            .field("height", &self.size.height)
+            .field("format", &P::wgpu_format())
+            .finish()
+    }
+}
+
+impl<P> PartialEq for GpuConstImage<'_, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<P> Eq for GpuConstImage<'_, P> {}
+
+impl<P> Hash for GpuConstImage<'_, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<P: PixelInfo> fmt::Debug for GpuConstImage<'_, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GpuConstImage")
+            .field("id", &self.id)
+            .field("width", &self.size.width)
+            .field("height", &self.size.height)
+            .field("format", &P::wgpu_format())
+            .finish()
+    }
 }
@@ -0,0 +1,107 @@
+use crate::{Framework, GpuSampler};
+
+/// Kind of a [`GpuSampler`] binding in the shader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SamplerKind {
+    /// Interpolates between texels. Only float textures can be sampled with it.
+    Filtering,
+    /// Reads the nearest texel. Any texture but depth ones can be sampled with it.
+    NonFiltering,
+    /// Compares depth textures against a reference value.
+    Comparison,
+}
+
+/// Options to create a [`GpuSampler`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplerOptions {
+    /// How to handle texture coordinates out of the `[0, 1]` range.
+    pub address_mode: wgpu::AddressMode,
+    /// Filter used when magnifying and minifying the texture.
+    pub filter: wgpu::FilterMode,
+    /// Comparison function of comparison samplers.
+    pub compare: Option<wgpu::CompareFunction>,
+    /// Kind of the binding. When `None`, [`SamplerKind::Comparison`] is used if `compare`
+    /// is set, [`SamplerKind::Filtering`] for the linear `filter` and
+    /// [`SamplerKind::NonFiltering`] otherwise.
+    pub kind: Option<SamplerKind>,
+}
+
+impl Default for SamplerOptions {
+    fn default() -> Self {
+        Self {
+            address_mode: wgpu::AddressMode::ClampToEdge,
+            filter: wgpu::FilterMode::Nearest,
+            compare: None,
+            kind: None,
+        }
+    }
+}
+
+impl SamplerOptions {
+    fn kind(&self) -> SamplerKind {
+        match (self.kind, self.compare, self.filter) {
+            (Some(kind), _, _) => kind,
+            (None, Some(_), _) => SamplerKind::Comparison,
+            (None, None, wgpu::FilterMode::Linear) => SamplerKind::Filtering,
+            (None, None, wgpu::FilterMode::Nearest) => SamplerKind::NonFiltering,
+        }
+    }
+}
+
+impl GpuSampler {
+    /// Constructs a new [`GpuSampler`] with the given `options`.
+    pub fn new(fw: &Framework, options: SamplerOptions) -> Self {
+        let sampler = fw.device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("GpuSampler::new"),
+            address_mode_u: options.address_mode,
+            address_mode_v: options.address_mode,
+            address_mode_w: options.address_mode,
+            mag_filter: options.filter,
+            min_filter: options.filter,
+            mipmap_filter: options.filter,
+            compare: options.compare,
+            ..Default::default()
+        });
+
+        Self {
+            sampler,
+            kind: options.kind(),
+        }
+    }
+
+    /// Returns the [`SamplerKind`] this [`GpuSampler`] is bound as.
+    pub fn kind(&self) -> SamplerKind {
+        self.kind
+    }
+
+    /// Returns the [`wgpu::BindingResource`] of the sampler.
+    pub fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+        wgpu::BindingResource::Sampler(&self.sampler)
+    }
+
+    /// Returns the [`wgpu::BindingType`] of the sampler.
+    pub(crate) fn binding_type(&self) -> wgpu::BindingType {
+        wgpu::BindingType::Sampler(match self.kind {
+            SamplerKind::Filtering => wgpu::SamplerBindingType::Filtering,
+            SamplerKind::NonFiltering => wgpu::SamplerBindingType::NonFiltering,
+            SamplerKind::Comparison => wgpu::SamplerBindingType::Comparison,
+        })
+    }
+
+    /// Returns the [`wgpu::TextureSampleType`] a texture of `sample` type must be bound as
+    /// to be sampled by this sampler, or `None` if it cannot be.
+    pub(crate) fn texture_sample(
+        &self,
+        sample: wgpu::TextureSampleType,
+    ) -> Option<wgpu::TextureSampleType> {
+        match (self.kind, sample) {
+            (SamplerKind::Filtering, wgpu::TextureSampleType::Float { .. }) => {
+                Some(wgpu::TextureSampleType::Float { filterable: true })
+            }
+            (SamplerKind::NonFiltering, wgpu::TextureSampleType::Depth) => None,
+            (SamplerKind::NonFiltering, sample) => Some(sample),
+            (SamplerKind::Comparison, wgpu::TextureSampleType::Depth) => Some(sample),
+            _ => None,
+        }
+    }
+}
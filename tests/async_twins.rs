@@ -0,0 +1,112 @@
+//! Async twins of the blocking methods, driven by a minimal executor,
+//! skipped when no adapter is available.
+
+mod common;
+
+use futures::executor::block_on;
+use gpgpu::{kernel::KernelError, prelude::*, primitives::pixels::Rgba8UintNorm};
+
+/// Squares each element of `data`.
+const SQUARE_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&data)) {
+        data[i] = data[i] * data[i];
+    }
+}
+"#;
+
+const LEN: u32 = 256;
+
+#[test]
+fn frameworks_are_created_asynchronously() {
+    // Only tried where the blocking constructor finds an adapter.
+    if common::framework().is_none() {
+        return;
+    }
+
+    let fw = block_on(Framework::try_default_async()).unwrap();
+    let buf = GpuBuffer::from_slice(&fw, &[1u32, 2, 3, 4]);
+    assert_eq!(block_on(buf.read_vec()).unwrap(), [1, 2, 3, 4]);
+}
+
+#[test]
+fn buffers_and_dispatches_are_awaited() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, SQUARE_SHADER, Some("square"))?;
+    let data = GpuBuffer::<u32>::with_capacity(&fw, LEN as u64);
+    let uniform = GpuUniformBuffer::<u32>::with_capacity(&fw, 4);
+
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    block_on(async {
+        let input = (0..LEN).collect::<Vec<_>>();
+        assert_eq!(data.write_async(&input).await?, LEN as u64 * 4);
+        assert_eq!(uniform.write_async(&[7, 8, 9, 10]).await?, 16);
+
+        kernel.enqueue_async(LEN / 64, 1, 1).await?;
+
+        let mut recorder = fw.create_command_recorder();
+        recorder.enqueue(&kernel, LEN / 64, 1, 1)?;
+        recorder.submit().wait_async().await;
+
+        let expected = (0..LEN).map(|i| i.pow(4)).collect::<Vec<_>>();
+        assert_eq!(data.read_vec().await?, expected);
+
+        GpuResult::Ok(())
+    })
+}
+
+#[test]
+fn images_are_awaited() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let image = GpuImage::<Rgba8UintNorm>::new(&fw, 4, 2);
+    let const_image = GpuConstImage::<Rgba8UintNorm>::new(&fw, 4, 2);
+    let pixels = (0..32).collect::<Vec<u8>>();
+
+    block_on(async {
+        // Both report the 4 x 2 pixels written.
+        assert_eq!(image.write_async(&pixels).await?, 8);
+        assert_eq!(const_image.write_async(&pixels).await?, 8);
+        assert_eq!(image.read_vec().await?, pixels);
+
+        GpuResult::Ok(())
+    })
+}
+
+#[test]
+fn dispatch_statistics_are_awaited() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, SQUARE_SHADER, Some("square"))?;
+    let data = GpuBuffer::<u32>::with_capacity(&fw, LEN as u64);
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    match block_on(kernel.enqueue_with_stats_async(LEN / 64, 1, 1)) {
+        Ok(stats) => assert_eq!(stats.compute_shader_invocations, LEN as u64),
+        // Not every device counts them, e.g. the GL backend.
+        Err(KernelError::MissingFeatures(features)) => {
+            assert_eq!(features, wgpu::Features::PIPELINE_STATISTICS_QUERY)
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! BLAS-1 operations on the GPU compared against the CPU, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError, ReduceElement},
+    prelude::*,
+    GpuError,
+};
+
+fn check<T>(fw: &Framework, alpha: T, x: &[T], y: &[T], axpy: &[T], scal: &[T]) -> GpuResult<()>
+where
+    T: ReduceElement + PartialEq + std::fmt::Debug,
+{
+    let gpu_x = GpuBuffer::from_slice(fw, x);
+    let mut gpu_y = GpuBuffer::from_slice(fw, y);
+
+    ops::axpy(fw, alpha, &gpu_x, &mut gpu_y)?;
+    assert_eq!(gpu_y.read_vec_blocking()?, axpy);
+
+    ops::scal(fw, alpha, &mut gpu_y)?;
+    assert_eq!(gpu_y.read_vec_blocking()?, scal);
+
+    ops::copy(fw, &gpu_x, &mut gpu_y)?;
+    assert_eq!(gpu_y.read_vec_blocking()?, x);
+
+    Ok(())
+}
+
+#[test]
+fn operations_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for &len in &[1usize, 255, 257, 100_000] {
+        // Exact in `f32`, whether the products are fused with the sums or not.
+        let x = (0..len).map(|i| (i % 64) as f32 - 32.0).collect::<Vec<_>>();
+        let y = (0..len).map(|i| (i % 7) as f32 * 0.25).collect::<Vec<_>>();
+        let axpy = x
+            .iter()
+            .zip(&y)
+            .map(|(x, y)| 0.5 * x + y)
+            .collect::<Vec<_>>();
+        let scal = axpy.iter().map(|y| 0.5 * y).collect::<Vec<_>>();
+        check(&fw, 0.5f32, &x, &y, &axpy, &scal)?;
+
+        let x = (0..len as i32).map(|i| i % 100 - 50).collect::<Vec<_>>();
+        let y = (0..len as i32).map(|i| i * 3).collect::<Vec<_>>();
+        let axpy = x
+            .iter()
+            .zip(&y)
+            .map(|(x, y)| -3 * x + y)
+            .collect::<Vec<_>>();
+        let scal = axpy.iter().map(|y| -3 * y).collect::<Vec<_>>();
+        check(&fw, -3i32, &x, &y, &axpy, &scal)?;
+
+        // Wrapping on overflow.
+        let x = (0..len as u32)
+            .map(|i| i.wrapping_mul(0x9e37_79b9))
+            .collect::<Vec<_>>();
+        let y = (0..len as u32).collect::<Vec<_>>();
+        let axpy = x
+            .iter()
+            .zip(&y)
+            .map(|(x, y)| 7u32.wrapping_mul(*x).wrapping_add(*y))
+            .collect::<Vec<_>>();
+        let scal = axpy
+            .iter()
+            .map(|y| 7u32.wrapping_mul(*y))
+            .collect::<Vec<_>>();
+        check(&fw, 7u32, &x, &y, &axpy, &scal)?;
+    }
+
+    check::<f32>(&fw, 2.0, &[], &[], &[], &[])?;
+
+    Ok(())
+}
+
+#[test]
+fn operands_of_different_lengths_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let x = GpuBuffer::from_slice(&fw, &[1.0f32; 4]);
+    let mut y = GpuBuffer::from_slice(&fw, &[2.0f32; 5]);
+
+    let mismatch = |result: GpuResult<()>| {
+        assert!(matches!(
+            result,
+            Err(GpuError::Ops(OpsError::OperandMismatch { a: 4, b: 5 }))
+        ));
+    };
+    mismatch(ops::axpy(&fw, 1.0, &x, &mut y));
+    mismatch(ops::copy(&fw, &x, &mut y));
+
+    let mut recorder = fw.create_command_recorder();
+    mismatch(ops::record_axpy(&mut recorder, 1.0, &x, &y));
+    mismatch(ops::record_copy(&mut recorder, &x, &y));
+    recorder.submit();
+
+    assert_eq!(y.read_vec_blocking()?, [2.0; 5]);
+
+    Ok(())
+}
+
+#[test]
+fn recorded_operations_match_the_enqueued_ones() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let len = 10_000;
+    let a = (0..len).map(|i| (i % 13) as f32).collect::<Vec<_>>();
+    let b = (0..len).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>();
+
+    // `c = b`, `b += 2 * a`, `a *= -1`, `c += 0.5 * b`, in order.
+    let enqueued = {
+        let mut gpu_a = GpuBuffer::from_slice(&fw, &a);
+        let mut gpu_b = GpuBuffer::from_slice(&fw, &b);
+        let mut gpu_c = GpuBuffer::<f32>::with_capacity(&fw, len as u64);
+
+        ops::copy(&fw, &gpu_b, &mut gpu_c)?;
+        ops::axpy(&fw, 2.0, &gpu_a, &mut gpu_b)?;
+        ops::scal(&fw, -1.0, &mut gpu_a)?;
+        ops::axpy(&fw, 0.5, &gpu_b, &mut gpu_c)?;
+
+        (gpu_a.read_vec_blocking()?, gpu_c.read_vec_blocking()?)
+    };
+
+    let recorded = {
+        let gpu_a = GpuBuffer::from_slice(&fw, &a);
+        let gpu_b = GpuBuffer::from_slice(&fw, &b);
+        let gpu_c = GpuBuffer::<f32>::with_capacity(&fw, len as u64);
+
+        let mut recorder = fw.create_command_recorder();
+        ops::record_copy(&mut recorder, &gpu_b, &gpu_c)?;
+        ops::record_axpy(&mut recorder, 2.0, &gpu_a, &gpu_b)?;
+        ops::record_scal(&mut recorder, -1.0, &gpu_a)?;
+        ops::record_axpy(&mut recorder, 0.5, &gpu_b, &gpu_c)?;
+        recorder.submit().wait();
+
+        (gpu_a.read_vec_blocking()?, gpu_c.read_vec_blocking()?)
+    };
+
+    assert_eq!(recorded, enqueued);
+    assert_eq!(
+        enqueued.1,
+        a.iter()
+            .zip(&b)
+            .map(|(a, b)| b + 0.5 * (b + 2.0 * a))
+            .collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! Saves buffers to files and loads them back, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    primitives::buffers::{BufferFileError, ByteOrder},
+    BufOps, GpuBuffer,
+};
+
+fn temp_file(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("gpgpu-{}-{}.bin", name, std::process::id()))
+}
+
+#[test]
+fn little_endian_round_trip() -> gpgpu::GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let data = (0..1000).map(|i| i as f32 * 0.5).collect::<Vec<_>>();
+    let path = temp_file("le");
+
+    GpuBuffer::from_slice(&fw, &data).save_to_file_le(&path)?;
+    let loaded = GpuBuffer::<f32>::load_from_file(&fw, &path)?;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(data, loaded.read_vec_blocking()?);
+
+    Ok(())
+}
+
+#[test]
+fn byte_swapped_fixture() -> gpgpu::GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let data = (0..1000u32)
+        .map(|i| i.wrapping_mul(0x0101_0203))
+        .collect::<Vec<_>>();
+    let path = temp_file("be");
+
+    // Simulates a file saved on a big-endian target: same header, swapped elements.
+    GpuBuffer::from_slice(&fw, &data).save_to_file_le(&path)?;
+    let mut bytes = std::fs::read(&path)?;
+    bytes[8] = 1;
+    bytes[21..]
+        .chunks_mut(4)
+        .for_each(|element| element.reverse());
+    std::fs::write(&path, &bytes)?;
+
+    let loaded = GpuBuffer::<u32>::load_from_file(&fw, &path)?;
+    assert_eq!(data, loaded.read_vec_blocking()?);
+
+    // Saving it back in big endian gives the fixture again.
+    loaded.save_to_file(&path, ByteOrder::Big)?;
+    assert_eq!(bytes, std::fs::read(&path)?);
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn mismatched_files() -> gpgpu::GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let path = temp_file("mismatched");
+
+    GpuBuffer::from_slice(&fw, &[1u16, 2, 3, 4]).save_to_file_le(&path)?;
+    let err = GpuBuffer::<u32>::load_from_file(&fw, &path).unwrap_err();
+    assert!(matches!(
+        err,
+        BufferFileError::ElementSize {
+            expected: 4,
+            found: 2
+        }
+    ));
+
+    std::fs::write(&path, b"not a buffer file")?;
+    let err = GpuBuffer::<u32>::load_from_file(&fw, &path).unwrap_err();
+    assert!(matches!(err, BufferFileError::InvalidHeader));
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
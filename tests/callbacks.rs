@@ -0,0 +1,93 @@
+//! Callbacks of finished submissions, skipped when no adapter is available.
+
+mod common;
+
+use std::{sync::mpsc, time::Duration};
+
+use gpgpu::prelude::*;
+
+/// Doubles each element of `data`.
+const DOUBLE_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&data)) {
+        data[i] = data[i] * 2u;
+    }
+}
+"#;
+
+/// Generous bound on the time the polling thread takes to call a callback.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[test]
+fn submissions_call_back_once_done() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, DOUBLE_SHADER, Some("double"))?;
+
+    let len = 1000u32;
+    let data = GpuBuffer::from_slice(&fw, &(0..len).collect::<Vec<_>>());
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    let (sender, receiver) = mpsc::channel();
+
+    let mut recorder = fw.create_command_recorder();
+    recorder.enqueue(&kernel, len.div_ceil(64), 1, 1)?;
+    recorder.enqueue(&kernel, len.div_ceil(64), 1, 1)?;
+    recorder.submit().on_done(move || sender.send(()).unwrap());
+
+    // Called from the polling thread, without any wait of this one.
+    receiver
+        .recv_timeout(TIMEOUT)
+        .expect("on_done was not called");
+
+    let expected = (0..len).map(|i| i * 4).collect::<Vec<_>>();
+    assert_eq!(data.read_vec_blocking()?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn kernels_call_back_once_done() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, DOUBLE_SHADER, Some("double"))?;
+
+    let len = 1000u32;
+    let data = GpuBuffer::from_slice(&fw, &(0..len).collect::<Vec<_>>());
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    let (sender, receiver) = mpsc::channel();
+
+    for pass in 0..3 {
+        let sender = sender.clone();
+        kernel.enqueue_with_callback(len.div_ceil(64), 1, 1, move || sender.send(pass).unwrap())?;
+    }
+
+    // Each callback is called once, in the order of the submissions.
+    let passes = (0..3)
+        .map(|_| {
+            receiver
+                .recv_timeout(TIMEOUT)
+                .expect("callback was not called")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(passes, [0, 1, 2]);
+
+    let expected = (0..len).map(|i| i * 8).collect::<Vec<_>>();
+    assert_eq!(data.read_vec_blocking()?, expected);
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+//! Compact versions of the scenarios of the examples, run through the public API.
+//!
+//! Each scenario checks its own results and returns an error if any call of `gpgpu` fails.
+//!
+//! The tests needing a GPU pass without checking anything on machines without an adapter.
+//! Set `GPGPU_REQUIRE_ADAPTER=1` where one is expected, e.g. on CI runners with a GPU or a
+//! software rasterizer, to make them fail instead: `GPGPU_REQUIRE_ADAPTER=1 cargo test`.
+//! The benches reuse [`framework`] with `#[path = "../tests/common/mod.rs"] mod common;`.
+
+#![allow(dead_code)]
+
+use gpgpu::prelude::*;
+
+gpgpu::gpu_struct! {
+    /// `Params` of [`SCALE_SHADER`].
+    uniform struct Params {
+        factor: u32,
+        offset: u32,
+        _padding: [u32; 2],
+    }
+}
+
+/// Returns a [`Framework`], or `None` if the machine has no suitable adapter,
+/// in which case the scenarios are skipped.
+///
+/// # Panics
+/// If there is no adapter while `GPGPU_REQUIRE_ADAPTER` is set to anything but `0`.
+pub fn framework() -> Option<Framework> {
+    match Framework::try_default() {
+        Ok(fw) => Some(fw),
+        Err(err) if adapter_required() => {
+            panic!(
+                "no adapter available although GPGPU_REQUIRE_ADAPTER is set: {}",
+                err
+            )
+        }
+        Err(err) => {
+            eprintln!("skipped: no adapter available ({})", err);
+            None
+        }
+    }
+}
+
+/// Whether the tests must fail rather than be skipped without an adapter.
+pub fn adapter_required() -> bool {
+    std::env::var_os("GPGPU_REQUIRE_ADAPTER").is_some_and(|value| value != "0" && !value.is_empty())
+}
+
+/// Pseudo-random `u32`s, from a linear congruential generator seeded with `seed`.
+pub fn random(len: usize, seed: u32) -> Vec<u32> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            state
+        })
+        .collect()
+}
+
+/// Pseudo-random values in [-1, 1), from the generator of [`random`].
+pub fn random_floats(len: usize, seed: u32) -> Vec<f32> {
+    random(len, seed)
+        .into_iter()
+        .map(|x| (x >> 8) as f32 / (1 << 23) as f32 - 1.0)
+        .collect()
+}
+
+/// `simple-compute`: multiplies two vectors of `size` elements.
+pub fn vector_multiply(fw: &Framework, size: u32) -> GpuResult<()> {
+    let shader = Shader::from_wgsl_file(fw, "examples/simple-compute/shader.wgsl")?;
+
+    let data_a = (0..size).collect::<Vec<u32>>();
+    let data_b = (0..size).rev().collect::<Vec<u32>>();
+
+    let gpu_vec_a = GpuBuffer::from_slice(fw, &data_a);
+    let gpu_vec_b = GpuBuffer::from_slice(fw, &data_b);
+    let gpu_vec_c = GpuBuffer::<u32>::with_capacity(fw, size as u64);
+
+    let bindings = DescriptorSet::default()
+        .bind_buffer(&gpu_vec_a, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_vec_b, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_vec_c, GpuBufferUsage::ReadWrite);
+
+    let program = Program::new(&shader, "main").add_descriptor_set(bindings);
+    Kernel::new(fw, program)?.enqueue(size, 1, 1)?;
+
+    let gpu_result = gpu_vec_c.read_vec_blocking()?;
+
+    for (a, (b, c)) in data_a.into_iter().zip(data_b.into_iter().zip(gpu_result)) {
+        assert_eq!(a * b, c);
+    }
+
+    Ok(())
+}
+
+const GRAYSCALE_SHADER: &str = r#"
+@group(0) @binding(0) var input: texture_2d<u32>;
+@group(0) @binding(1) var output: texture_storage_2d<rgba8uint, write>;
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let coord = vec2<i32>(global_id.xy);
+    let pixel = textureLoad(input, coord, 0);
+    let gray = (pixel.r + pixel.g + pixel.b) / 3u;
+
+    textureStore(output, coord, vec4<u32>(gray, gray, gray, pixel.a));
+}
+"#;
+
+/// `mirror-image`, converting a `width` x `height` image to grayscale instead.
+pub fn image_grayscale(fw: &Framework, width: u32, height: u32) -> GpuResult<()> {
+    let shader = Shader::from_wgsl_source(fw, GRAYSCALE_SHADER, Some("grayscale"))?;
+
+    let pixels = (0..width * height)
+        .flat_map(|i| [i as u8, (i * 2) as u8, (i * 3) as u8, 255])
+        .collect::<Vec<u8>>();
+
+    let input = GpuConstImage::<pixels::Rgba8Uint>::from_bytes(fw, &pixels, width, height);
+    let output = GpuImage::<pixels::Rgba8Uint>::new(fw, width, height);
+
+    let bindings = DescriptorSet::default()
+        .bind_const_image(&input)
+        .bind_image(&output);
+
+    let program = Program::new(&shader, "main").add_descriptor_set(bindings);
+    Kernel::new(fw, program)?.enqueue(width.div_ceil(8), height.div_ceil(8), 1)?;
+
+    let gpu_result = output.read_vec_blocking()?;
+
+    for (pixel, gray) in pixels.chunks(4).zip(gpu_result.chunks(4)) {
+        let expected = ((pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3) as u8;
+        assert_eq!(gray, [expected, expected, expected, pixel[3]]);
+    }
+
+    Ok(())
+}
+
+const SCALE_SHADER: &str = r#"
+struct Params {
+    factor: u32,
+    offset: u32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&data)) {
+        data[i] = data[i] * params.factor + params.offset;
+    }
+}
+"#;
+
+/// `ndarray`, updating the parameters of a kernel from a [`GpuUniformBuffer`] between dispatches.
+pub fn uniform_parameters(fw: &Framework, size: u32) -> GpuResult<()> {
+    let shader = Shader::from_wgsl_source(fw, SCALE_SHADER, Some("scale"))?;
+
+    let data = (0..size).collect::<Vec<u32>>();
+
+    let params = GpuUniformBuffer::from_slice(
+        fw,
+        &[Params {
+            factor: 2,
+            offset: 1,
+            _padding: [0; 2],
+        }],
+    );
+    let gpu_data = GpuBuffer::from_slice(fw, &data);
+
+    let bindings = DescriptorSet::default()
+        .bind_uniform_buffer(&params)
+        .bind_buffer(&gpu_data, GpuBufferUsage::ReadWrite);
+
+    let program = Program::new(&shader, "main").add_descriptor_set(bindings);
+    let kernel = Kernel::new(fw, program)?;
+
+    kernel.enqueue(size.div_ceil(64), 1, 1)?;
+    params.write(&[Params {
+        factor: 3,
+        offset: 0,
+        _padding: [0; 2],
+    }])?;
+    kernel.enqueue(size.div_ceil(64), 1, 1)?;
+
+    let gpu_result = gpu_data.read_vec_blocking()?;
+
+    for (x, y) in data.into_iter().zip(gpu_result) {
+        assert_eq!((x * 2 + 1) * 3, y);
+    }
+
+    Ok(())
+}
+
+/// `async-compute`: the vector multiply without any blocking call of `gpgpu`.
+pub async fn async_readback(fw: &Framework, size: u32) -> GpuResult<()> {
+    let shader = Shader::from_wgsl_file(fw, "examples/simple-compute/shader.wgsl")?;
+
+    let data_a = (0..size).collect::<Vec<u32>>();
+    let data_b = (0..size).rev().collect::<Vec<u32>>();
+
+    let gpu_vec_a = GpuBuffer::<u32>::with_capacity(fw, size as u64);
+    let gpu_vec_b = GpuBuffer::<u32>::with_capacity(fw, size as u64);
+    let gpu_vec_c = GpuBuffer::<u32>::with_capacity(fw, size as u64);
+
+    gpu_vec_a.write_async(&data_a).await?;
+    gpu_vec_b.write_async(&data_b).await?;
+
+    let bindings = DescriptorSet::default()
+        .bind_buffer(&gpu_vec_a, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_vec_b, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_vec_c, GpuBufferUsage::ReadWrite);
+
+    let program = Program::new(&shader, "main").add_descriptor_set(bindings);
+    Kernel::new_async(fw, program)
+        .await?
+        .enqueue_async(size, 1, 1)
+        .await?;
+
+    let gpu_result = gpu_vec_c.read_vec().await?;
+
+    for (a, (b, c)) in data_a.into_iter().zip(data_b.into_iter().zip(gpu_result)) {
+        assert_eq!(a * b, c);
+    }
+
+    Ok(())
+}
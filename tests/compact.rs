@@ -0,0 +1,126 @@
+//! Stream compaction compared against the CPU, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError},
+    prelude::*,
+};
+
+#[test]
+fn compaction_keeps_the_flagged_elements_in_order() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // Longer than a block of the scan, so that its block sums are scanned too.
+    let len = 100_000;
+    let values = (0..len as u32).collect::<Vec<_>>();
+    let input = GpuBuffer::from_slice(&fw, &values);
+
+    let random_flags = common::random(len, 3)
+        .iter()
+        .map(|x| x >> 31)
+        .collect::<Vec<_>>();
+    // Any non-zero flag keeps its element.
+    let all_flags = common::random(len, 5)
+        .iter()
+        .map(|x| x | 1)
+        .collect::<Vec<_>>();
+
+    for flags in [random_flags, all_flags, vec![0; len]] {
+        let expected = values
+            .iter()
+            .zip(&flags)
+            .filter(|(_, &flag)| flag != 0)
+            .map(|(&x, _)| x)
+            .collect::<Vec<_>>();
+
+        let mut output = GpuBuffer::from_slice(&fw, &vec![u32::MAX; len + 1]);
+        let kept = ops::compact(
+            &fw,
+            &input,
+            &GpuBuffer::from_slice(&fw, &flags),
+            &mut output,
+        )?;
+
+        let output = output.read_vec_blocking()?;
+        assert_eq!(kept as usize, expected.len());
+        assert_eq!(output[..expected.len()], expected[..]);
+        assert!(output[expected.len()..].iter().all(|&x| x == u32::MAX));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn compaction_by_expression_matches_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let values = common::random(50_000, 9)
+        .iter()
+        .map(|&x| x as f32 / u32::MAX as f32)
+        .collect::<Vec<_>>();
+    let input = GpuBuffer::from_slice(&fw, &values);
+    let mut output = GpuBuffer::<f32>::with_capacity(&fw, values.len() as u64);
+
+    let kept = ops::compact_by_expr(&fw, &input, "x > 0.5", &mut output)?;
+    let expected = values
+        .iter()
+        .copied()
+        .filter(|&x| x > 0.5)
+        .collect::<Vec<_>>();
+    assert_eq!(kept as usize, expected.len());
+    assert_eq!(output.read_vec_blocking()?[..expected.len()], expected[..]);
+
+    let evens = ops::compact_by_expr(&fw, &input, "i % 2u == 0u", &mut output)?;
+    assert_eq!(evens, 25_000);
+
+    assert!(matches!(
+        ops::compact_by_expr(&fw, &input, "x * 2.0", &mut output),
+        Err(GpuError::Ops(OpsError::InvalidExpression(_)))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn small_outputs_report_the_needed_size() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let input = GpuBuffer::from_slice(&fw, &[1i32, 2, 3, 4, 5]);
+    let flags = GpuBuffer::from_slice(&fw, &[1u32, 0, 1, 1, 0]);
+
+    let mut small = GpuBuffer::from_slice(&fw, &[0i32; 2]);
+    assert!(matches!(
+        ops::compact(&fw, &input, &flags, &mut small),
+        Err(GpuError::Ops(OpsError::OutputTooSmall {
+            required: 3,
+            current: 2
+        }))
+    ));
+    assert_eq!(small.read_vec_blocking()?, [0, 0]);
+
+    let mut empty = GpuBuffer::<i32>::with_capacity(&fw, 0);
+    let none = GpuBuffer::from_slice(&fw, &[0u32; 5]);
+    assert_eq!(ops::compact(&fw, &input, &none, &mut empty)?, 0);
+
+    assert!(matches!(
+        ops::compact(
+            &fw,
+            &input,
+            &GpuBuffer::from_slice(&fw, &[1u32]),
+            &mut small
+        ),
+        Err(GpuError::Ops(OpsError::OperandMismatch { a: 5, b: 1 }))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! Multiplies complex numbers on the GPU, skipped when no adapter is available.
+
+#![cfg(feature = "num-complex")]
+
+mod common;
+
+use gpgpu::{prelude::*, GpuComplex32};
+use num_complex::Complex;
+
+const SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> a: array<vec2<f32>>;
+@group(0) @binding(1) var<storage, read> b: array<vec2<f32>>;
+@group(0) @binding(2) var<storage, read_write> product: array<vec2<f32>>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&product)) {
+        let x = a[i];
+        let y = b[i];
+        product[i] = vec2<f32>(x.x * y.x - x.y * y.y, x.x * y.y + x.y * y.x);
+    }
+}
+"#;
+
+#[test]
+fn complex_multiply() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, SHADER, Some("complex-multiply"))?;
+
+    let a = (0..100)
+        .map(|i| Complex::new(i as f32, 1.0 - i as f32))
+        .collect::<Vec<_>>();
+    let b = (0..100)
+        .map(|i| Complex::new(0.5 * i as f32, 2.0))
+        .collect::<Vec<_>>();
+
+    let gpu_a = GpuBuffer::from_complex_slice(&fw, &a);
+    let gpu_b = GpuBuffer::from_complex_slice(&fw, &b);
+    let gpu_product = GpuBuffer::<GpuComplex32>::with_capacity(&fw, 100);
+
+    let bindings = DescriptorSet::default()
+        .bind_buffer(&gpu_a, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_b, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_product, GpuBufferUsage::ReadWrite);
+
+    let program = Program::new(&shader, "main").add_descriptor_set(bindings);
+    Kernel::new(&fw, program)?.enqueue(2, 1, 1)?;
+
+    let product = gpu_product.read_complex_vec_blocking()?;
+
+    for ((x, y), z) in a.iter().zip(&b).zip(product) {
+        let expected = x * y;
+        assert_eq!(expected.re, z.re);
+        assert_eq!(expected.im, z.im);
+    }
+
+    Ok(())
+}
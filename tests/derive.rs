@@ -0,0 +1,46 @@
+//! Descriptor sets of `#[derive(GpuBindings)]` structs, skipped when no adapter is available.
+
+#![cfg(feature = "derive")]
+
+mod common;
+
+use gpgpu::{kernel::DescriptorSetError, prelude::*, primitives::pixels::Rgba8Uint, GpuBindings};
+
+#[derive(GpuBindings)]
+struct ImageBindings<'a> {
+    #[binding(0)]
+    input: &'a GpuBuffer<'a, u32>,
+    #[binding(1)]
+    output: &'a GpuImage<'a, Rgba8Uint>,
+}
+
+#[test]
+fn derived_descriptor_sets_report_binding_errors() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let input = GpuBuffer::from_slice(&fw, &[1u32, 2, 3, 4]);
+    let output = GpuImage::<Rgba8Uint>::new(&fw, 2, 2);
+    let bindings = ImageBindings {
+        input: &input,
+        output: &output,
+    };
+    assert!(bindings.descriptor_set().is_ok());
+
+    let output = GpuImage::<Rgba8Uint>::builder(&fw, 2, 2)
+        .without_usage(wgpu::TextureUsages::STORAGE_BINDING)
+        .build()?;
+    let bindings = ImageBindings {
+        input: &input,
+        output: &output,
+    };
+    assert!(matches!(
+        bindings.descriptor_set(),
+        Err(DescriptorSetError::MissingImageUsage(usage))
+            if usage == wgpu::TextureUsages::STORAGE_BINDING
+    ));
+
+    Ok(())
+}
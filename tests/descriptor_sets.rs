@@ -0,0 +1,313 @@
+//! Bindings of descriptor sets at explicit indices, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    kernel::{DescriptorSetError, KernelError},
+    prelude::*,
+};
+
+/// Writes `a + 10 * b` to `output`, its bindings declared out of their index order.
+const SUM_SHADER: &str = r#"
+@group(0) @binding(4) var<storage, read_write> output: array<u32>;
+@group(0) @binding(0) var<storage, read> a: array<u32>;
+@group(0) @binding(2) var<storage, read> b: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&output)) {
+        output[i] = a[i] + 10u * b[i];
+    }
+}
+"#;
+
+#[test]
+fn explicit_indices_collide_with_any_binding() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let buf = GpuBuffer::from_slice(&fw, &[1u32; 4]);
+    let params = GpuUniformBuffer::from_slice(&fw, &[0u32; 4]);
+
+    // With an explicit binding.
+    let result = DescriptorSet::default()
+        .bind_buffer_at(3, &buf, GpuBufferUsage::ReadOnly)?
+        .bind_uniform_buffer_at(3, &params);
+    assert!(matches!(
+        result,
+        Err(DescriptorSetError::DuplicateBinding(3))
+    ));
+
+    // With the index an implicit binding took.
+    let result = DescriptorSet::default()
+        .bind_buffer(&buf, GpuBufferUsage::ReadOnly)
+        .bind_buffer_at(0, &buf, GpuBufferUsage::ReadOnly);
+    assert!(matches!(
+        result,
+        Err(DescriptorSetError::DuplicateBinding(0))
+    ));
+
+    // Implicit bindings skip the explicit indices instead.
+    let set = DescriptorSet::default()
+        .bind_buffer_at(0, &buf, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(2, &buf, GpuBufferUsage::ReadOnly)?
+        .bind_uniform_buffer(&params)
+        .bind_uniform_buffer(&params);
+    let indices = set
+        .bindings()
+        .iter()
+        .map(|info| info.binding)
+        .collect::<Vec<_>>();
+    assert_eq!(indices, [0, 2, 1, 3]);
+
+    Ok(())
+}
+
+#[test]
+fn explicit_indices_match_the_shader_in_any_order() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, SUM_SHADER, Some("sum"))?;
+
+    let len = 100u32;
+    let a = GpuBuffer::from_slice(&fw, &(0..len).collect::<Vec<_>>());
+    let b = GpuBuffer::from_slice(&fw, &(0..len).rev().collect::<Vec<_>>());
+    let output = GpuBuffer::<u32>::with_capacity(&fw, len as u64);
+
+    let set = DescriptorSet::default()
+        .bind_buffer_at(4, &output, GpuBufferUsage::ReadWrite)?
+        .bind_buffer_at(2, &b, GpuBufferUsage::ReadOnly)?
+        .bind_buffer_at(0, &a, GpuBufferUsage::ReadOnly)?;
+    let program = Program::new(&shader, "main").add_descriptor_set(set);
+    Kernel::new(&fw, program)?.enqueue(len.div_ceil(64), 1, 1)?;
+
+    let expected = (0..len).map(|i| i + 10 * (len - 1 - i)).collect::<Vec<_>>();
+    assert_eq!(output.read_vec_blocking()?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn minimum_binding_sizes_are_checked_and_part_of_the_layout() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, SUM_SHADER, Some("sum"))?;
+
+    let len = 100u32;
+    let a = GpuBuffer::from_slice(&fw, &(0..len).collect::<Vec<_>>());
+    let b = GpuBuffer::from_slice(&fw, &vec![1u32; len as usize]);
+    let output = GpuBuffer::<u32>::with_capacity(&fw, len as u64);
+
+    let result =
+        DescriptorSet::default().bind_buffer_with_min_size_at(0, &a, GpuBufferUsage::ReadOnly, 101);
+    assert!(matches!(
+        result,
+        Err(DescriptorSetError::BufferTooSmall {
+            binding: 0,
+            capacity: 100,
+            required: 101,
+            ..
+        })
+    ));
+
+    let set = |min_elems: Option<u64>| {
+        let set = DescriptorSet::default()
+            .bind_buffer_at(4, &output, GpuBufferUsage::ReadWrite)?
+            .bind_buffer_at(2, &b, GpuBufferUsage::ReadOnly)?;
+
+        match min_elems {
+            Some(min_elems) => {
+                set.bind_buffer_with_min_size_at(0, &a, GpuBufferUsage::ReadOnly, min_elems)
+            }
+            None => set.bind_buffer_at(0, &a, GpuBufferUsage::ReadOnly),
+        }
+    };
+
+    let mut kernel = Kernel::new(
+        &fw,
+        Program::new(&shader, "main").add_descriptor_set(set(Some(len as u64))?),
+    )?;
+    kernel.enqueue(len.div_ceil(64), 1, 1)?;
+    let expected = (0..len).map(|i| i + 10).collect::<Vec<_>>();
+    assert_eq!(output.read_vec_blocking()?, expected);
+
+    // The minimum size is declared in the layout, so only sets declaring the same one replace it.
+    kernel.set_descriptor_set(0, set(Some(len as u64))?)?;
+    for min_elems in [Some(len as u64 / 2), None] {
+        assert!(matches!(
+            kernel.set_descriptor_set(0, set(min_elems)?),
+            Err(KernelError::DescriptorSetShapeMismatch(0))
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn extended_sets_follow_the_bindings_of_the_common_one() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, SUM_SHADER, Some("sum"))?;
+
+    let len = 100u32;
+    let a = GpuBuffer::from_slice(&fw, &(0..len).collect::<Vec<_>>());
+    let b = GpuBuffer::from_slice(&fw, &vec![2u32; len as usize]);
+    let c = GpuBuffer::from_slice(&fw, &vec![5u32; len as usize]);
+    let output = GpuBuffer::<u32>::with_capacity(&fw, len as u64);
+
+    // Shared by two kernels, each extending it with its own `b` and the output,
+    // renumbered from 1 and 3 to 2 and 4, keeping the gap between them.
+    let common_set = DescriptorSet::default().bind_buffer(&a, GpuBufferUsage::ReadOnly);
+    let extras = |b| -> GpuResult<DescriptorSet> {
+        Ok(DescriptorSet::default()
+            .bind_buffer_at(1, b, GpuBufferUsage::ReadOnly)?
+            .bind_buffer_at(3, &output, GpuBufferUsage::ReadWrite)?)
+    };
+
+    for (extra, factor) in [(&b, 2), (&c, 5)] {
+        let set = common_set.clone().extend(extras(extra)?);
+        let indices = set
+            .bindings()
+            .iter()
+            .map(|info| info.binding)
+            .collect::<Vec<_>>();
+        assert_eq!(indices, [0, 2, 4]);
+
+        let program = Program::new(&shader, "main").add_descriptor_set(set);
+        Kernel::new(&fw, program)?.enqueue(len.div_ceil(64), 1, 1)?;
+
+        let expected = (0..len).map(|i| i + 10 * factor).collect::<Vec<_>>();
+        assert_eq!(output.read_vec_blocking()?, expected);
+    }
+
+    // Extending an empty set keeps the indices.
+    let set = DescriptorSet::default().extend(extras(&b)?);
+    let indices = set
+        .bindings()
+        .iter()
+        .map(|info| info.binding)
+        .collect::<Vec<_>>();
+    assert_eq!(indices, [1, 3]);
+
+    Ok(())
+}
+
+/// Adds `input` to `output`, with nothing bound in group 1.
+const GROUPS_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> input: array<u32>;
+@group(2) @binding(0) var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&output)) {
+        output[i] = output[i] + input[i];
+    }
+}
+"#;
+
+#[test]
+fn skipped_groups_are_left_empty() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, GROUPS_SHADER, Some("groups"))?;
+
+    let len = 100u32;
+    let input = GpuBuffer::from_slice(&fw, &(0..len).collect::<Vec<_>>());
+    let other = GpuBuffer::from_slice(&fw, &vec![7u32; len as usize]);
+    let output = GpuBuffer::from_slice(&fw, &vec![1u32; len as usize]);
+
+    // Group 0 is replaced, and group 1 filled with an empty set.
+    let program = Program::new(&shader, "main")
+        .add_descriptor_set(DescriptorSet::default().bind_buffer(&other, GpuBufferUsage::ReadOnly))
+        .add_descriptor_set_at(
+            2,
+            DescriptorSet::default().bind_buffer(&output, GpuBufferUsage::ReadWrite),
+        )
+        .add_descriptor_set_at(
+            0,
+            DescriptorSet::default().bind_buffer(&input, GpuBufferUsage::ReadOnly),
+        );
+    Kernel::new(&fw, program)?.enqueue(len.div_ceil(64), 1, 1)?;
+
+    let expected = (0..len).map(|i| i + 1).collect::<Vec<_>>();
+    assert_eq!(output.read_vec_blocking()?, expected);
+
+    Ok(())
+}
+
+/// Reads the same parameter block through three bindings of two groups.
+const SHARED_UNIFORM_SHADER: &str = r#"
+struct Params {
+    factor: u32,
+    offset: u32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<uniform> same_params: Params;
+@group(0) @binding(2) var<storage, read_write> output: array<u32>;
+@group(1) @binding(0) var<uniform> other_params: Params;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&output)) {
+        output[i] = i * params.factor + 10u * same_params.offset + 100u * other_params.offset;
+    }
+}
+"#;
+
+#[test]
+fn a_uniform_block_is_bound_into_several_sets() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, SHARED_UNIFORM_SHADER, Some("shared uniform"))?;
+
+    let len = 100u32;
+    let params = GpuUniformBuffer::from_slice(&fw, &[3u32, 2, 0, 0]);
+    let output = GpuBuffer::<u32>::with_capacity(&fw, len as u64);
+
+    let first = DescriptorSet::default()
+        .bind_uniform_buffer(&params)
+        .bind_uniform_buffer(&params)
+        .bind_buffer(&output, GpuBufferUsage::ReadWrite);
+    let second = DescriptorSet::default().bind_uniform_buffer(&params);
+
+    let program = Program::new(&shader, "main")
+        .add_descriptor_set(first)
+        .add_descriptor_set(second);
+    let kernel = Kernel::new(&fw, program)?;
+
+    kernel.enqueue(len.div_ceil(64), 1, 1)?;
+    let expected = (0..len).map(|i| i * 3 + 220).collect::<Vec<_>>();
+    assert_eq!(output.read_vec_blocking()?, expected);
+
+    // Every binding sees the new contents.
+    params.write(&[1, 1, 0, 0])?;
+    kernel.enqueue(len.div_ceil(64), 1, 1)?;
+    let expected = (0..len).map(|i| i + 110).collect::<Vec<_>>();
+    assert_eq!(output.read_vec_blocking()?, expected);
+
+    Ok(())
+}
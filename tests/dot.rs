@@ -0,0 +1,85 @@
+//! Dot products and norms on the GPU compared against the CPU, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError},
+    prelude::*,
+};
+
+/// Multiples of 1/32 in [-1, 1).
+fn vector(len: usize, seed: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| ((i * 31 + seed * 17) % 64) as f32 / 32.0 - 1.0)
+        .collect()
+}
+
+#[test]
+fn dot_products_and_norms_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for &len in &[1, 255, 4096, 300_000] {
+        let (a, b) = (vector(len, 1), vector(len, 2));
+        let gpu_a = GpuBuffer::from_slice(&fw, &a);
+        let gpu_b = GpuBuffer::from_slice(&fw, &b);
+
+        let dot = a.iter().zip(&b).map(|(x, y)| (x * y) as f64).sum::<f64>();
+        let norm = a.iter().map(|x| (x * x) as f64).sum::<f64>().sqrt();
+
+        let gpu_dot = ops::dot(&fw, &gpu_a, &gpu_b)? as f64;
+        let gpu_norm = ops::norm2(&fw, &gpu_a)? as f64;
+        assert!(
+            (gpu_dot - dot).abs() <= 1e-5 * dot.abs().max(1.0),
+            "{} against {}",
+            gpu_dot,
+            dot
+        );
+        assert!(
+            (gpu_norm - norm).abs() <= 1e-5 * norm,
+            "{} against {}",
+            gpu_norm,
+            norm
+        );
+    }
+
+    let empty = GpuBuffer::<f32>::with_capacity(&fw, 0);
+    assert_eq!(ops::dot(&fw, &empty, &empty)?, 0.0);
+    assert_eq!(ops::norm2(&fw, &empty)?, 0.0);
+
+    Ok(())
+}
+
+#[test]
+fn results_stay_on_the_gpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let a = GpuBuffer::from_slice(&fw, &[3.0f32, 4.0]);
+    let b = GpuBuffer::from_slice(&fw, &[2.0f32, -1.0]);
+    let empty = GpuBuffer::<f32>::with_capacity(&fw, 0);
+    let mut results = GpuBuffer::from_slice(&fw, &[-1.0f32; 4]);
+
+    ops::dot_into(&fw, &a, &b, &mut results, 1)?;
+    ops::norm2_into(&fw, &a, &mut results, 2)?;
+    ops::norm2_into(&fw, &empty, &mut results, 3)?;
+    assert_eq!(results.read_vec_blocking()?, [-1.0, 2.0, 5.0, 0.0]);
+
+    assert!(matches!(
+        ops::dot_into(&fw, &a, &b, &mut results, 4),
+        Err(GpuError::Ops(OpsError::OutputTooSmall {
+            required: 5,
+            current: 4
+        }))
+    ));
+    assert!(matches!(
+        ops::dot(&fw, &a, &GpuBuffer::from_slice(&fw, &[1.0f32])),
+        Err(GpuError::Ops(OpsError::OperandMismatch { a: 2, b: 1 }))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//! Bindings with a dynamic offset given to each dispatch, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    kernel::{DescriptorSetError, KernelError},
+    prelude::*,
+};
+
+/// Adds `increments` scaled by `params.x` to the window of `values` bound at the offset
+/// of the dispatch, with the `params` of the dispatch.
+const WINDOW_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> increments: array<u32>;
+@group(0) @binding(1) var<storage, read_write> window: array<u32, 64>;
+@group(0) @binding(2) var<uniform> params: vec4<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < 64u) {
+        window[i] = window[i] + increments[i] * params.x;
+    }
+}
+"#;
+
+const WINDOW: usize = 64;
+
+#[test]
+fn dispatches_read_the_window_at_their_offsets() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let limits = fw.limits();
+    // Bytes between the windows and the parameters of each dispatch.
+    let stride = limits.min_storage_buffer_offset_alignment.max(256) as usize;
+    let uniform_stride = limits.min_uniform_buffer_offset_alignment.max(16) as usize;
+
+    let increments = GpuBuffer::from_slice(&fw, &(0..WINDOW as u32).collect::<Vec<_>>());
+    // Four windows of `stride` bytes, written so that `wgpu` does not clear the ones
+    // past the first, see `DescriptorSet::bind_buffer_dynamic`.
+    let values = GpuBuffer::from_slice(&fw, &vec![0u32; stride]);
+
+    let mut params = vec![0u32; 2 * uniform_stride / 4];
+    params[0] = 1;
+    params[uniform_stride / 4] = 5;
+    let params = GpuUniformBuffer::from_slice(&fw, &params);
+
+    let set = DescriptorSet::default()
+        .bind_buffer(&increments, GpuBufferUsage::ReadOnly)
+        .bind_buffer_dynamic(&values, GpuBufferUsage::ReadWrite, WINDOW as u64)?
+        .bind_uniform_buffer_dynamic(&params, 4)?;
+    let shader = Shader::from_wgsl_source(&fw, WINDOW_SHADER, Some("window"))?;
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    // The offsets of the group, in the order of the bindings: the window, then the parameters.
+    kernel.enqueue_with_offsets(1, 1, 1, &[&[0, 0]])?;
+    kernel.enqueue_with_offsets(1, 1, 1, &[&[2 * stride as u32, uniform_stride as u32]])?;
+
+    let mut expected = vec![0; stride];
+    for i in 0..WINDOW {
+        expected[i] = i as u32;
+        expected[2 * stride / 4 + i] = 5 * i as u32;
+    }
+    assert_eq!(values.read_vec_blocking()?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn dynamic_offsets_are_checked() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let alignment = fw.limits().min_storage_buffer_offset_alignment;
+    let increments = GpuBuffer::from_slice(&fw, &[1u32; WINDOW]);
+    let values = GpuBuffer::from_slice(&fw, &[0u32; 4 * WINDOW]);
+    let params = GpuUniformBuffer::from_slice(&fw, &[1u32, 0, 0, 0]);
+
+    let set = DescriptorSet::default()
+        .bind_buffer(&increments, GpuBufferUsage::ReadOnly)
+        .bind_buffer_dynamic(&values, GpuBufferUsage::ReadWrite, WINDOW as u64)?
+        .bind_uniform_buffer_at(2, &params)?;
+    let shader = Shader::from_wgsl_source(&fw, WINDOW_SHADER, Some("window"))?;
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    // The dispatches without offsets give none.
+    assert!(matches!(
+        kernel.enqueue(1, 1, 1),
+        Err(KernelError::DynamicOffsetCountMismatch {
+            group: 0,
+            expected: 1,
+            found: 0
+        })
+    ));
+    assert!(matches!(
+        kernel.enqueue_with_offsets(1, 1, 1, &[&[0, 0]]),
+        Err(KernelError::DynamicOffsetCountMismatch {
+            group: 0,
+            expected: 1,
+            found: 2
+        })
+    ));
+
+    let err = kernel.enqueue_with_offsets(1, 1, 1, &[&[4]]).unwrap_err();
+    assert!(err.to_string().contains("group 0 binding 1"), "{}", err);
+    assert!(matches!(
+        err,
+        KernelError::MisalignedDynamicOffset {
+            group: 0,
+            binding: 1,
+            offset: 4,
+            alignment,
+        } if alignment == fw.limits().min_storage_buffer_offset_alignment
+    ));
+
+    // The kernel is still usable after the failures.
+    kernel.enqueue_with_offsets(1, 1, 1, &[&[alignment]])?;
+    let result = values.read_vec_blocking()?;
+    let window = alignment as usize / 4;
+    assert!(result[window..window + WINDOW]
+        .iter()
+        .all(|&value| value == 1));
+
+    Ok(())
+}
+
+#[test]
+fn no_offsets_are_given_without_dynamic_bindings() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let increments = GpuBuffer::from_slice(&fw, &[2u32; WINDOW]);
+    let values = GpuBuffer::<u32>::with_capacity(&fw, WINDOW as u64);
+    let params = GpuUniformBuffer::from_slice(&fw, &[3u32, 0, 0, 0]);
+
+    let set = DescriptorSet::default()
+        .bind_buffer(&increments, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&values, GpuBufferUsage::ReadWrite)
+        .bind_uniform_buffer(&params);
+    let shader = Shader::from_wgsl_source(&fw, WINDOW_SHADER, Some("window"))?;
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    kernel.enqueue_with_offsets(1, 1, 1, &[])?;
+    kernel.enqueue_with_offsets(1, 1, 1, &[&[]])?;
+    assert_eq!(values.read_vec_blocking()?, [12; WINDOW]);
+
+    Ok(())
+}
+
+#[test]
+fn dynamic_windows_fit_in_their_buffer() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let values = GpuBuffer::<u32>::with_capacity(&fw, 16);
+    let params = GpuUniformBuffer::<u32>::with_capacity(&fw, 4);
+
+    let result =
+        DescriptorSet::default().bind_buffer_dynamic(&values, GpuBufferUsage::ReadOnly, 17);
+    assert!(matches!(
+        result,
+        Err(DescriptorSetError::BufferTooSmall {
+            binding: 0,
+            capacity: 16,
+            required: 17,
+            ..
+        })
+    ));
+
+    let result = DescriptorSet::default().bind_uniform_buffer_dynamic_at(3, &params, 0);
+    assert!(matches!(
+        result,
+        Err(DescriptorSetError::BufferTooSmall {
+            binding: 3,
+            required: 1,
+            ..
+        })
+    ));
+
+    Ok(())
+}
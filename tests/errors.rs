@@ -0,0 +1,61 @@
+//! Sorts the failures shared by all operations into the variants of `GpuError`.
+
+use gpgpu::{
+    kernel::{DescriptorSetError, KernelError, ShaderError},
+    primitives::{buffers::BufferError, images::ImageOutputError},
+    GpuError,
+};
+
+#[test]
+fn lost_devices_are_reported_as_such() {
+    assert!(matches!(
+        GpuError::from(KernelError::DispatchNotCompleted),
+        GpuError::DeviceLost
+    ));
+    assert!(matches!(
+        GpuError::from(KernelError::StatsUnavailable(wgpu::BufferAsyncError)),
+        GpuError::DeviceLost
+    ));
+    assert!(matches!(
+        GpuError::from(BufferError::AsyncMapError(wgpu::BufferAsyncError)),
+        GpuError::DeviceLost
+    ));
+    assert!(matches!(
+        GpuError::from(ImageOutputError::BufferError(BufferError::AsyncMapError(
+            wgpu::BufferAsyncError
+        ))),
+        GpuError::DeviceLost
+    ));
+}
+
+#[test]
+fn missing_features_are_missing_capabilities() {
+    let features = wgpu::Features::PIPELINE_STATISTICS_QUERY;
+
+    assert!(matches!(
+        GpuError::from(KernelError::MissingFeatures(features)),
+        GpuError::MissingCapability(missing) if missing == features
+    ));
+    assert!(matches!(
+        GpuError::from(KernelError::DescriptorSetError(
+            DescriptorSetError::MissingFeatures(features)
+        )),
+        GpuError::MissingCapability(missing) if missing == features
+    ));
+}
+
+#[test]
+fn other_errors_keep_their_variant() {
+    assert!(matches!(
+        GpuError::from(ShaderError::InvalidShader("no entry point".into())),
+        GpuError::Validation(reason) if reason == "no entry point"
+    ));
+    assert!(matches!(
+        GpuError::from(KernelError::DescriptorSetNotFound(1)),
+        GpuError::Kernel(KernelError::DescriptorSetNotFound(1))
+    ));
+    assert!(matches!(
+        GpuError::from(BufferError::CapacityOverflow(u64::MAX)),
+        GpuError::Buffer(BufferError::CapacityOverflow(u64::MAX))
+    ));
+}
@@ -0,0 +1,49 @@
+//! Runs the scenarios of the examples, skipped when no adapter is available.
+
+mod common;
+
+#[test]
+fn vector_multiply() -> gpgpu::GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    common::vector_multiply(&fw, 10000)
+}
+
+#[test]
+fn image_grayscale() -> gpgpu::GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // The GL backend of `wgpu-hal` 0.13 panics on the integer images of the scenario.
+    if fw.capabilities().backend == wgpu::Backend::Gl {
+        eprintln!("skipped: integer images are not supported by the GL backend");
+        return Ok(());
+    }
+
+    common::image_grayscale(&fw, 37, 21)
+}
+
+#[test]
+fn uniform_parameters() -> gpgpu::GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    common::uniform_parameters(&fw, 1000)
+}
+
+#[test]
+fn async_readback() -> gpgpu::GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    futures::executor::block_on(common::async_readback(&fw, 10000))
+}
@@ -0,0 +1,70 @@
+//! Fallible constructors of buffers and images, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    prelude::*,
+    primitives::{buffers::BufferError, images::ImageError, pixels::Rgba8UintNorm},
+};
+
+#[test]
+fn buffers_report_their_allocation_failures() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let result = GpuBuffer::<u32>::try_with_capacity(&fw, u64::MAX);
+    assert!(matches!(
+        result,
+        Err(BufferError::CapacityOverflow(u64::MAX))
+    ));
+
+    // One element more than the device allows.
+    let capacity = fw.limits().max_buffer_size / 4 + 1;
+    let result = GpuBuffer::<u32>::try_with_capacity(&fw, capacity);
+    assert!(matches!(result, Err(BufferError::Creation(_))));
+
+    let result = GpuUniformBuffer::<u32>::try_with_capacity(&fw, capacity);
+    assert!(matches!(result, Err(BufferError::Creation(_))));
+
+    // The framework is still usable after the failures.
+    let buf = GpuBuffer::try_from_slice(&fw, &[1u32, 2, 3, 4])?;
+    assert_eq!(buf.read_vec_blocking()?, [1, 2, 3, 4]);
+
+    let buf = GpuBuffer::<u32>::try_with_capacity(&fw, 4)?;
+    assert_eq!(buf.read_vec_blocking()?, [0; 4]);
+
+    Ok(())
+}
+
+#[test]
+fn images_report_their_allocation_failures() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let result = GpuImage::<Rgba8UintNorm>::try_new(&fw, 0, 16);
+    assert!(matches!(result, Err(ImageError::Creation(_))));
+
+    let too_wide = fw.limits().max_texture_dimension_2d + 1;
+    let result = GpuImage::<Rgba8UintNorm>::try_new(&fw, too_wide, 1);
+    assert!(matches!(result, Err(ImageError::Creation(_))));
+
+    let result = GpuConstImage::<Rgba8UintNorm>::try_from_bytes(&fw, &[0; 15], 2, 2);
+    assert!(matches!(
+        result,
+        Err(ImageError::InvalidData {
+            required: 16,
+            current: 15
+        })
+    ));
+
+    // The framework is still usable after the failures.
+    let pixels = (0..16).collect::<Vec<u8>>();
+    let image = GpuImage::<Rgba8UintNorm>::try_from_bytes(&fw, &pixels, 2, 2)?;
+    assert_eq!(image.read_vec_blocking()?, pixels);
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! Buffers round-tripped through raw pointers, as foreign bindings do.
+
+#![cfg(feature = "ffi")]
+
+mod common;
+
+use gpgpu::{features::ffi::hal, prelude::*};
+
+#[test]
+fn raw_round_trip() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let buf = GpuBuffer::<u32>::with_capacity(&fw, 64);
+    let input = (0..64u32).flat_map(u32::to_le_bytes).collect::<Vec<u8>>();
+
+    let written = unsafe { buf.write_from_raw(input.as_ptr(), input.len()) }?;
+    assert_eq!(written, input.len());
+    assert_eq!(buf.read_vec_blocking()?, (0..64).collect::<Vec<u32>>());
+
+    // One byte past the start of an allocation: not aligned for `u32`.
+    let mut output = vec![0u8; input.len() + 1];
+    let read = unsafe { buf.read_into_raw(output.as_mut_ptr().add(1), input.len()) }?;
+    assert_eq!(read, input.len());
+    assert_eq!(output[1..], input[..]);
+
+    // Empty transfers accept null pointers.
+    assert_eq!(unsafe { buf.write_from_raw(std::ptr::null(), 0) }?, 0);
+    assert_eq!(unsafe { buf.read_into_raw(std::ptr::null_mut(), 0) }?, 0);
+
+    Ok(())
+}
+
+// The GLES backend is not built on Apple platforms.
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+#[test]
+fn hal_handles() {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return,
+    };
+
+    let handles = fw.as_hal_handles();
+    assert_eq!(handles.backend, fw.capabilities().backend);
+
+    let is_gles = unsafe { handles.device_as_hal::<hal::api::Gles, _>(|device| device.is_some()) };
+    assert_eq!(is_gles, handles.backend == wgpu::Backend::Gl);
+}
@@ -0,0 +1,89 @@
+//! Float images round-tripping through `image::ImageBuffer`s and OpenEXR files bit-exactly,
+//! skipped when no adapter is available.
+
+#![cfg(feature = "integrate-image")]
+
+mod common;
+
+use gpgpu::{
+    features::integrate_image::ImageFileError, prelude::*, primitives::pixels::Rgba32Float,
+};
+use image::{ImageBuffer, Rgba};
+
+const WIDTH: u32 = 8;
+const HEIGHT: u32 = 4;
+
+/// HDR pixels, with infinities, NaNs of several payloads, subnormals and negative zero.
+fn hdr_image() -> ImageBuffer<Rgba<f32>, Vec<f32>> {
+    let specials = [
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        f32::NAN,
+        f32::from_bits(0x7fc0_1234),
+        f32::from_bits(0xffbf_ffff),
+        f32::MIN_POSITIVE / 2.0,
+        -0.0,
+        65504.0,
+    ];
+
+    ImageBuffer::from_fn(WIDTH, HEIGHT, |x, y| {
+        let i = (y * WIDTH + x) as usize;
+        Rgba([
+            specials[i % specials.len()],
+            i as f32 * 1000.5,
+            -(i as f32),
+            specials[(i + 3) % specials.len()],
+        ])
+    })
+}
+
+fn bits(img: &ImageBuffer<Rgba<f32>, Vec<f32>>) -> Vec<u32> {
+    img.iter().map(|channel| channel.to_bits()).collect()
+}
+
+#[test]
+fn float_image_buffer_round_trips_bit_exactly() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let img = hdr_image();
+
+    let gpu_img: GpuImage<Rgba32Float> = GpuImage::from_image_buffer(&fw, &img);
+    assert_eq!(bits(&gpu_img.read_to_image_buffer_blocking()?), bits(&img));
+
+    let other = GpuImage::<Rgba32Float>::new(&fw, WIDTH, HEIGHT);
+    other.write_image_buffer(&img)?;
+    assert_eq!(bits(&other.read_to_image_buffer_blocking()?), bits(&img));
+
+    Ok(())
+}
+
+#[test]
+fn exr_file_round_trips_bit_exactly() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let path = std::env::temp_dir().join(format!("gpgpu-hdr-{}.exr", std::process::id()));
+
+    let img = hdr_image();
+    let gpu_img: GpuImage<Rgba32Float> = GpuImage::from_image_buffer(&fw, &img);
+
+    match gpu_img.save_exr(&path) {
+        Err(ImageFileError::Codec(image::ImageError::Unsupported(_))) => {
+            eprintln!("skipped: the `openexr` feature of `image` is not enabled");
+            return Ok(());
+        }
+        saved => saved?,
+    }
+
+    let loaded = GpuImage::<Rgba32Float>::from_exr_file(&fw, &path);
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(bits(&loaded?.read_to_image_buffer_blocking()?), bits(&img));
+
+    Ok(())
+}
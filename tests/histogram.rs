@@ -0,0 +1,145 @@
+//! Histograms on the GPU compared against the CPU, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError, OutOfRange},
+    prelude::*,
+};
+
+/// Histogram of the integers `values` computed in `i128`, with an overflow bin if `overflow`.
+fn cpu_histogram(values: &[i128], bins: u32, (lo, hi): (i128, i128), overflow: bool) -> Vec<u32> {
+    let mut counts = vec![0; bins as usize + overflow as usize];
+    for &x in values {
+        let bin = if x >= lo && x < hi {
+            (x - lo) * bins as i128 / (hi - lo)
+        } else if overflow {
+            bins as i128
+        } else if x < lo {
+            0
+        } else {
+            bins as i128 - 1
+        };
+        counts[bin as usize] += 1;
+    }
+    counts
+}
+
+#[test]
+fn integer_histograms_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let keys = common::random(300_000, 7);
+    let unsigned = GpuBuffer::from_slice(&fw, &keys);
+    let unsigned_cpu = keys.iter().map(|&x| x as i128).collect::<Vec<_>>();
+
+    // As many bins as the workgroup memory holds, then one more with the overflow bin.
+    let shared_bins = fw.limits().max_compute_workgroup_storage_size / 4;
+
+    // The last ranges need the 64-bit products of the kernels, the last bins their global atomics.
+    for &(bins, range) in &[
+        (16, (0, 1 << 16)),
+        (7, (1000, 3_000_000_000)),
+        (1000, (0, u32::MAX)),
+        (shared_bins, (0, 1 << 20)),
+        (5000, (12345, u32::MAX - 6789)),
+    ] {
+        for &out_of_range in &[OutOfRange::Clamp, OutOfRange::Overflow] {
+            assert_eq!(
+                ops::histogram_with(&fw, &unsigned, bins, range, out_of_range)?,
+                cpu_histogram(
+                    &unsigned_cpu,
+                    bins,
+                    (range.0 as i128, range.1 as i128),
+                    out_of_range == OutOfRange::Overflow
+                ),
+                "{} bins over {:?}, {:?}",
+                bins,
+                range,
+                out_of_range
+            );
+        }
+    }
+
+    let values = keys.iter().map(|&x| x as i32 / 1000).collect::<Vec<_>>();
+    let signed = GpuBuffer::from_slice(&fw, &values);
+    let signed_cpu = values.iter().map(|&x| x as i128).collect::<Vec<_>>();
+    assert_eq!(
+        ops::histogram(&fw, &signed, 100, (-1_000_000, 2_000_000))?,
+        cpu_histogram(&signed_cpu, 100, (-1_000_000, 2_000_000), false)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn float_histograms_count_nans_as_out_of_range() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // Away from the borders of the bins of width 0.5.
+    let values = [
+        -3.0f32,
+        0.1,
+        0.3,
+        0.7,
+        1.2,
+        1.9,
+        1.9,
+        f32::NAN,
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+    ];
+    let buf = GpuBuffer::from_slice(&fw, &values);
+
+    assert_eq!(ops::histogram(&fw, &buf, 4, (0.0, 2.0))?, [4, 1, 1, 4]);
+    assert_eq!(
+        ops::histogram_with(&fw, &buf, 4, (0.0, 2.0), OutOfRange::Overflow)?,
+        [2, 1, 1, 2, 4]
+    );
+
+    let ramp = (0..100_000).map(|i| i as f32 + 0.5).collect::<Vec<_>>();
+    let counts = ops::histogram(
+        &fw,
+        &GpuBuffer::from_slice(&fw, &ramp),
+        100,
+        (0.0, 100_000.0),
+    )?;
+    assert_eq!(counts, vec![1000; 100]);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_histograms_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let buf = GpuBuffer::from_slice(&fw, &[1.0f32, 2.0]);
+
+    assert!(matches!(
+        ops::histogram(&fw, &buf, 0, (0.0, 1.0)),
+        Err(GpuError::Ops(OpsError::NoBins))
+    ));
+    for &range in &[(1.0, 1.0), (2.0, 1.0), (0.0, f32::NAN)] {
+        assert!(matches!(
+            ops::histogram(&fw, &buf, 4, range),
+            Err(GpuError::Ops(OpsError::EmptyRange))
+        ));
+    }
+
+    let empty = GpuBuffer::<u32>::with_capacity(&fw, 0);
+    assert_eq!(
+        ops::histogram_with(&fw, &empty, 3, (0, 10), OutOfRange::Overflow)?,
+        [0; 4]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//! Reloading of the shaders of kernels, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::prelude::*;
+
+/// Multiplies each element of `data` by `{{FACTOR}}`.
+const SCALE_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&data)) {
+        data[i] = data[i] * {{FACTOR}}u;
+    }
+}
+"#;
+
+/// Valid on its own, but binds a uniform block where the kernels bind a storage buffer.
+const MISMATCHED_SHADER: &str = r#"
+struct Params {
+    factor: u32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let factor = params.factor;
+}
+"#;
+
+const LEN: u32 = 200;
+
+fn scale_source(factor: u32) -> String {
+    SCALE_SHADER.replace("{{FACTOR}}", &factor.to_string())
+}
+
+/// Checks that one enqueue of `kernel` multiplies `data` by `factor`.
+fn check_factor(kernel: &Kernel, data: &GpuBuffer<u32>, factor: u32) -> GpuResult<()> {
+    data.write(&vec![1; LEN as usize])?;
+    kernel.enqueue(LEN.div_ceil(64), 1, 1)?;
+    assert_eq!(data.read_vec_blocking()?, vec![factor; LEN as usize]);
+
+    Ok(())
+}
+
+#[test]
+fn failed_reloads_keep_the_previous_pipeline() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let double = Shader::from_wgsl_source(&fw, &scale_source(2), Some("double"))?;
+    let triple = Shader::from_wgsl_source(&fw, &scale_source(3), Some("triple"))?;
+    let mismatched = Shader::from_wgsl_source(&fw, MISMATCHED_SHADER, Some("mismatched"))?;
+
+    let data = GpuBuffer::<u32>::with_capacity(&fw, LEN as u64);
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    let mut kernel = Kernel::new(&fw, Program::new(&double, "main").add_descriptor_set(set))?;
+    check_factor(&kernel, &data, 2)?;
+
+    assert!(kernel.reload_shader(&mismatched).is_err());
+    check_factor(&kernel, &data, 2)?;
+
+    kernel.reload_shader(&triple)?;
+    check_factor(&kernel, &data, 3)?;
+
+    assert!(kernel.reload_shader(&mismatched).is_err());
+    check_factor(&kernel, &data, 3)?;
+
+    Ok(())
+}
+
+#[cfg(feature = "hot-reload")]
+#[test]
+fn watchers_keep_the_previous_pipeline_of_invalid_files() -> GpuResult<()> {
+    use std::time::{Duration, SystemTime};
+
+    use gpgpu::features::hot_reload::{HotReloadError, WgslWatcher};
+
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let path = std::env::temp_dir().join(format!("gpgpu-reload-{}.wgsl", std::process::id()));
+    let start = SystemTime::now();
+
+    // Each write is dated explicitly, so that the coarse file times of some file systems
+    // do not hide a modification.
+    let write = |source: &str, seconds: u64| {
+        std::fs::write(&path, source).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(start + Duration::from_secs(seconds))
+            .unwrap();
+    };
+
+    let source = scale_source(2);
+    write(&source, 0);
+    let double = Shader::from_wgsl_source(&fw, &source, Some("double"))?;
+
+    let data = GpuBuffer::<u32>::with_capacity(&fw, LEN as u64);
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    let mut kernel = Kernel::new(&fw, Program::new(&double, "main").add_descriptor_set(set))?;
+    let mut watcher = WgslWatcher::new(&path)?;
+
+    assert!(!watcher.reload(&fw, &mut kernel)?);
+
+    // A file that does not compile.
+    write("fn main( {", 1);
+    let result = watcher.reload(&fw, &mut kernel);
+    assert!(matches!(result, Err(HotReloadError::InvalidShader { .. })));
+    check_factor(&kernel, &data, 2)?;
+
+    // It is not tried again until it is modified again.
+    assert!(!watcher.reload(&fw, &mut kernel)?);
+
+    // A file that compiles but does not match the kernel.
+    write(MISMATCHED_SHADER, 2);
+    let result = watcher.reload(&fw, &mut kernel);
+    assert!(matches!(result, Err(HotReloadError::KernelError(_))));
+    check_factor(&kernel, &data, 2)?;
+
+    write(&scale_source(3), 3);
+    assert!(watcher.reload(&fw, &mut kernel)?);
+    check_factor(&kernel, &data, 3)?;
+
+    std::fs::remove_file(&path).unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! Resources bound to kernels, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::prelude::*;
+
+/// Adds `input * params.factor` to `output`.
+const ACCUMULATE_SHADER: &str = r#"
+struct Params {
+    factor: u32,
+};
+
+@group(0) @binding(0) var<storage, read> input: array<u32>;
+@group(0) @binding(1) var<uniform> params: Params;
+@group(1) @binding(0) var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&output)) {
+        output[i] = output[i] + input[i] * params.factor;
+    }
+}
+"#;
+
+#[test]
+fn kernels_keep_working_after_their_resources_are_dropped() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, ACCUMULATE_SHADER, Some("accumulate"))?;
+
+    let len = 300u32;
+    let input = GpuBuffer::from_slice(&fw, &(0..len).collect::<Vec<_>>());
+    let params = GpuUniformBuffer::from_slice(&fw, &[3u32, 0, 0, 0]);
+    let output = GpuBuffer::from_slice(&fw, &vec![0u32; len as usize]);
+
+    let inputs = DescriptorSet::default()
+        .bind_buffer(&input, GpuBufferUsage::ReadOnly)
+        .bind_uniform_buffer(&params);
+    let outputs = DescriptorSet::default().bind_buffer(&output, GpuBufferUsage::ReadWrite);
+    let program = Program::new(&shader, "main")
+        .add_descriptor_set(inputs)
+        .add_descriptor_set(outputs);
+    let mut kernel = Kernel::new(&fw, program)?;
+
+    kernel.enqueue(len.div_ceil(64), 1, 1)?;
+
+    // Neither the dropped wrappers nor a wait in between change the dispatches.
+    drop(input);
+    drop(params);
+    fw.as_gpu_device().poll(wgpu::Maintain::Wait);
+
+    kernel.enqueue(len.div_ceil(64), 1, 1)?;
+    kernel.enqueue(len.div_ceil(64), 1, 1)?;
+
+    let expected = (0..len).map(|i| i * 9).collect::<Vec<_>>();
+    assert_eq!(output.read_vec_blocking()?, expected);
+
+    // Replacing the bind group of the dropped resources with new ones.
+    let input = GpuBuffer::from_slice(&fw, &vec![1u32; len as usize]);
+    let params = GpuUniformBuffer::from_slice(&fw, &[1u32, 0, 0, 0]);
+    let inputs = DescriptorSet::default()
+        .bind_buffer(&input, GpuBufferUsage::ReadOnly)
+        .bind_uniform_buffer(&params);
+    kernel.set_descriptor_set(0, inputs)?;
+    drop(input);
+    drop(params);
+
+    kernel.enqueue(len.div_ceil(64), 1, 1)?;
+
+    let expected = (0..len).map(|i| i * 9 + 1).collect::<Vec<_>>();
+    assert_eq!(output.read_vec_blocking()?, expected);
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Element-wise operations from `WGSL` expressions, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError},
+    prelude::*,
+};
+
+#[test]
+fn map_applies_the_expression_to_each_element() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // More elements than a dimension of workgroups dispatches on some adapters.
+    let data = (0..70_000).map(|i| i as f32).collect::<Vec<_>>();
+    let buf = GpuBuffer::from_slice(&fw, &data);
+
+    let mapped = ops::map(&fw, &buf, "x * 2.0 + 1.0")?;
+    let expected = data.iter().map(|x| x * 2.0 + 1.0).collect::<Vec<_>>();
+    assert_eq!(mapped.read_vec_blocking()?, expected);
+
+    let indices = ops::map(&fw, &GpuBuffer::from_slice(&fw, &[7u32; 5]), "x + i")?;
+    assert_eq!(indices.read_vec_blocking()?, [7, 8, 9, 10, 11]);
+
+    Ok(())
+}
+
+#[test]
+fn map_into_converts_the_elements() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let bytes = GpuBuffer::from_slice(&fw, &[0u32, 51, 255]);
+    let mut output = GpuBuffer::from_slice(&fw, &[-1.0f32; 4]);
+
+    ops::map_into(&fw, &bytes, &mut output, "f32(x) / 255.0")?;
+    assert_eq!(output.read_vec_blocking()?, [0.0, 0.2, 1.0, -1.0]);
+
+    let mut small = GpuBuffer::<f32>::with_capacity(&fw, 2);
+    assert!(matches!(
+        ops::map_into(&fw, &bytes, &mut small, "f32(x)"),
+        Err(GpuError::Ops(OpsError::OutputTooSmall {
+            required: 3,
+            current: 2
+        }))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn zip_combines_the_operands() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let a = GpuBuffer::from_slice(&fw, &[1i32, -2, 3, 4]);
+    let b = GpuBuffer::from_slice(&fw, &[10i32, 20, -30, 40]);
+    assert_eq!(
+        ops::zip(&fw, &a, &b, "a + b")?.read_vec_blocking()?,
+        [11, 18, -27, 44]
+    );
+
+    let values = GpuBuffer::from_slice(&fw, &[1.5f32, 2.5, 3.5]);
+    let mask = GpuBuffer::from_slice(&fw, &[1u32, 0, 1]);
+    let mut masked = GpuBuffer::<f32>::with_capacity(&fw, 3);
+    ops::zip_into(&fw, &values, &mask, &mut masked, "select(a, 0.0, b == 0u)")?;
+    assert_eq!(masked.read_vec_blocking()?, [1.5, 0.0, 3.5]);
+
+    let short = GpuBuffer::from_slice(&fw, &[1i32]);
+    assert!(matches!(
+        ops::zip(&fw, &a, &short, "a + b"),
+        Err(GpuError::Ops(OpsError::OperandMismatch { a: 4, b: 1 }))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn expression_errors_point_at_the_expression() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let buf = GpuBuffer::from_slice(&fw, &[1.0f32, 2.0]);
+
+    // Invalid expressions are reported even when there is nothing to compute.
+    let empty = GpuBuffer::<f32>::with_capacity(&fw, 0);
+    for (buf, expression) in [
+        (&buf, "x * unknown"),
+        (&empty, "x * unknown"),
+        (&buf, "x > 1.0"),
+    ] {
+        let diagnostic = match ops::map(&fw, buf, expression) {
+            Err(GpuError::Ops(OpsError::InvalidExpression(diagnostic))) => diagnostic,
+            other => panic!("`{}` compiled: {:?}", expression, other.map(|_| ())),
+        };
+
+        assert_eq!(diagnostic.source_line, expression);
+        assert!(diagnostic.column + diagnostic.length <= expression.len() + 1);
+
+        let displayed = diagnostic.to_string();
+        assert!(displayed.contains(expression), "{}", displayed);
+        assert!(!displayed.contains("operand_a"), "{}", displayed);
+    }
+
+    match ops::map(&fw, &buf, "x * unknown") {
+        Err(GpuError::Ops(OpsError::InvalidExpression(diagnostic))) => {
+            assert_eq!((diagnostic.column, diagnostic.length), (5, 7));
+        }
+        other => panic!("the expression compiled: {:?}", other.map(|_| ())),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cached_shaders_are_told_apart() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let unsigned = GpuBuffer::from_slice(&fw, &[1u32, 2, 3]);
+    let signed = GpuBuffer::from_slice(&fw, &[1i32, 2, 3]);
+
+    for _ in 0..2 {
+        assert_eq!(
+            ops::map(&fw, &unsigned, "x * 3u")?.read_vec_blocking()?,
+            [3, 6, 9]
+        );
+        assert_eq!(
+            ops::map(&fw, &unsigned, "x * 4u")?.read_vec_blocking()?,
+            [4, 8, 12]
+        );
+        assert_eq!(
+            ops::map(&fw, &signed, "-x")?.read_vec_blocking()?,
+            [-1, -2, -3]
+        );
+        assert_eq!(
+            ops::zip(&fw, &signed, &signed, "a * b")?.read_vec_blocking()?,
+            [1, 4, 9]
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Chunked transfers between buffers and memory regions, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{prelude::*, primitives::buffers::BufferError};
+
+#[test]
+fn mapped_bytes_round_trip() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // Spans several chunks, the last one partial.
+    let len = (64 << 20) / 4 * 2 + 1000;
+    let data = (0..len).map(|i| i as u32).collect::<Vec<u32>>();
+
+    // A region that is not aligned for `u32`, as a mapped file can be.
+    let mut region = vec![0u8; data.len() * 4 + 1];
+    region[1..].copy_from_slice(bytemuck::cast_slice(&data));
+
+    let buf = GpuBuffer::<u32>::from_mapped_bytes(&fw, &region[1..])?;
+    assert_eq!(buf.read_vec_blocking()?, data);
+
+    let mut out = vec![0u8; data.len() * 4 + 1];
+    assert_eq!(
+        buf.read_into_mapped_bytes(&mut out[1..])?,
+        data.len() as u64 * 4
+    );
+    assert_eq!(out[1..], region[1..]);
+
+    Ok(())
+}
+
+#[test]
+fn partial_elements_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let err = GpuBuffer::<[u32; 2]>::from_mapped_bytes(&fw, &[0; 12]).unwrap_err();
+    assert!(matches!(
+        err,
+        BufferError::ElementLength {
+            len: 12,
+            element_size: 8
+        }
+    ));
+
+    let buf = GpuBuffer::<u32>::from_slice(&fw, &[1, 2, 3]);
+    let err = buf.read_into_mapped_bytes(&mut [0; 6]).unwrap_err();
+    assert!(matches!(err, BufferError::ElementLength { .. }));
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! Matrix products on the GPU compared against the CPU, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, MatmulOptions, OpsError},
+    prelude::*,
+};
+
+/// `alpha * a * b + beta * c` computed in `f64`, `b` being stored transposed if `transpose_b`.
+#[allow(clippy::too_many_arguments)]
+fn cpu_matmul(
+    a: &[f32],
+    b: &[f32],
+    c: &[f32],
+    (m, n, k): (usize, usize, usize),
+    alpha: f32,
+    beta: f32,
+    transpose_b: bool,
+) -> Vec<f32> {
+    let mut out = c.to_vec();
+    for row in 0..m {
+        for col in 0..n {
+            let dot = (0..k)
+                .map(|i| {
+                    let b = if transpose_b {
+                        b[col * k + i]
+                    } else {
+                        b[i * n + col]
+                    };
+                    a[row * k + i] as f64 * b as f64
+                })
+                .sum::<f64>();
+            let scaled = if beta == 0.0 {
+                0.0
+            } else {
+                beta as f64 * c[row * n + col] as f64
+            };
+            out[row * n + col] = (alpha as f64 * dot + scaled) as f32;
+        }
+    }
+    out
+}
+
+fn assert_close(gpu: &[f32], cpu: &[f32], shape: (usize, usize, usize)) {
+    for (i, (&x, &y)) in gpu.iter().zip(cpu).enumerate() {
+        assert!(
+            (x - y).abs() <= 1e-4 * y.abs().max(1.0),
+            "{:?}: element {} is {} instead of {}",
+            shape,
+            i,
+            x,
+            y
+        );
+    }
+}
+
+/// Shapes `(m, n, k)`: square, not multiples of the tiles, and skinny.
+const SHAPES: &[(usize, usize, usize)] = &[
+    (1, 1, 1),
+    (16, 16, 16),
+    (17, 33, 5),
+    (64, 48, 80),
+    (1, 1000, 300),
+    (1000, 1, 300),
+    (513, 3, 129),
+    (7, 9, 2048),
+];
+
+#[test]
+fn products_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for &(m, n, k) in SHAPES {
+        let a = common::random_floats(m * k, 1);
+        let b = common::random_floats(k * n, 2);
+
+        let gpu_a = GpuBuffer::from_slice(&fw, &a);
+        let gpu_b = GpuBuffer::from_slice(&fw, &b);
+        let mut gpu_c = GpuBuffer::<f32>::from_slice(&fw, &vec![f32::NAN; m * n]);
+
+        ops::matmul(
+            &fw, &gpu_a, &gpu_b, &mut gpu_c, m as u32, n as u32, k as u32,
+        )?;
+
+        let cpu = cpu_matmul(&a, &b, &vec![0.0; m * n], (m, n, k), 1.0, 0.0, false);
+        assert_close(&gpu_c.read_vec_blocking()?, &cpu, (m, n, k));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn scaled_and_transposed_products_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for &(m, n, k) in SHAPES {
+        let a = common::random_floats(m * k, 3);
+        let b = common::random_floats(k * n, 4);
+        let c = common::random_floats(m * n, 5);
+
+        let gpu_a = GpuBuffer::from_slice(&fw, &a);
+        let gpu_b = GpuBuffer::from_slice(&fw, &b);
+
+        for &transpose_b in &[false, true] {
+            let mut gpu_c = GpuBuffer::from_slice(&fw, &c);
+            let options = MatmulOptions::default()
+                .alpha(-0.5)
+                .beta(2.0)
+                .transpose_b(transpose_b);
+            ops::matmul_with(
+                &fw, &gpu_a, &gpu_b, &mut gpu_c, m as u32, n as u32, k as u32, options,
+            )?;
+
+            let cpu = cpu_matmul(&a, &b, &c, (m, n, k), -0.5, 2.0, transpose_b);
+            assert_close(&gpu_c.read_vec_blocking()?, &cpu, (m, n, k));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn small_matrices_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let a = GpuBuffer::from_slice(&fw, &[1.0f32; 6]);
+    let b = GpuBuffer::from_slice(&fw, &[1.0f32; 5]);
+    let mut c = GpuBuffer::from_slice(&fw, &[0.0f32; 4]);
+
+    assert!(matches!(
+        ops::matmul(&fw, &a, &b, &mut c, 2, 2, 3),
+        Err(GpuError::Ops(OpsError::MatrixTooSmall {
+            matrix: "b",
+            required: 6,
+            current: 5
+        }))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! Reads matrices back from shaders, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::prelude::*;
+
+const SHADER: &str = r#"
+@group(0) @binding(0) var<uniform> m3: mat3x3<f32>;
+@group(0) @binding(1) var<uniform> m4: mat4x4<f32>;
+@group(0) @binding(2) var<storage, read_write> output: array<f32>;
+
+@compute @workgroup_size(1)
+fn main() {
+    for (var col = 0; col < 3; col = col + 1) {
+        for (var row = 0; row < 3; row = row + 1) {
+            output[col * 3 + row] = m3[col][row];
+        }
+    }
+
+    for (var col = 0; col < 4; col = col + 1) {
+        for (var row = 0; row < 4; row = row + 1) {
+            output[9 + col * 4 + row] = m4[col][row];
+        }
+    }
+}
+"#;
+
+#[test]
+fn uniform_matrices() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, SHADER, Some("matrices"))?;
+
+    let m3 = [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]];
+    let m4 = [
+        [10.0, 11.0, 12.0, 13.0],
+        [14.0, 15.0, 16.0, 17.0],
+        [18.0, 19.0, 20.0, 21.0],
+        [22.0, 23.0, 24.0, 25.0],
+    ];
+
+    let gpu_m3 = GpuUniformBuffer::from_mat3(&fw, &[[0.0; 3]; 3]);
+    let gpu_m4 = GpuUniformBuffer::from_mat4(&fw, &m4);
+    let output = GpuBuffer::<f32>::with_capacity(&fw, 25);
+
+    gpu_m3.write_mat3(&m3)?;
+
+    let bindings = DescriptorSet::default()
+        .bind_uniform_buffer(&gpu_m3)
+        .bind_uniform_buffer(&gpu_m4)
+        .bind_buffer(&output, GpuBufferUsage::ReadWrite);
+
+    let program = Program::new(&shader, "main").add_descriptor_set(bindings);
+    Kernel::new(&fw, program)?.enqueue(1, 1, 1)?;
+
+    let expected = (1..=25).map(|i| i as f32).collect::<Vec<_>>();
+    assert_eq!(expected, output.read_vec_blocking()?);
+
+    Ok(())
+}
+
+#[test]
+fn column_major_round_trip() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // 2 x 3 matrix [[1, 2, 3], [4, 5, 6]] in column-major order.
+    let columns = [1u32, 4, 2, 5, 3, 6];
+
+    let buf = GpuBuffer::from_column_major(&fw, &columns, 2);
+    assert_eq!(vec![1, 2, 3, 4, 5, 6], buf.read_vec_blocking()?);
+    assert_eq!(columns.to_vec(), buf.read_to_column_major_blocking(2, 3)?);
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+//! Memory statistics of the buffers and images alive, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{prelude::*, primitives::pixels::Rgba8UintNorm};
+
+const GIB: u64 = 1 << 30;
+
+#[test]
+fn dropped_buffers_are_not_counted() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let before = fw.memory_stats();
+
+    // 1 GiB in as few buffers as the device allows.
+    let chunk = fw.limits().max_buffer_size.min(GIB);
+    let count = GIB.div_ceil(chunk);
+    let buffers = (0..count)
+        .map(|_| GpuBuffer::<u8>::try_with_capacity(&fw, chunk))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let allocated = fw.memory_stats();
+    assert_eq!(allocated.buffers, before.buffers + count);
+    assert_eq!(allocated.buffer_bytes, before.buffer_bytes + count * chunk);
+
+    drop(buffers);
+    fw.as_gpu_device().poll(wgpu::Maintain::Wait);
+
+    assert_eq!(fw.memory_stats(), before);
+
+    // Destroyed buffers neither.
+    let buf = GpuBuffer::<u8>::try_with_capacity(&fw, chunk)?;
+    assert_eq!(fw.memory_stats().buffer_bytes, before.buffer_bytes + chunk);
+    buf.destroy();
+    fw.as_gpu_device().poll(wgpu::Maintain::Wait);
+
+    assert_eq!(fw.memory_stats(), before);
+
+    Ok(())
+}
+
+#[test]
+fn dropped_images_are_not_counted() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let before = fw.memory_stats();
+
+    let side = fw.limits().max_texture_dimension_2d.min(2048);
+    let image = GpuImage::<Rgba8UintNorm>::try_new(&fw, side, side)?;
+    let const_image = GpuConstImage::<Rgba8UintNorm>::try_new(&fw, side, side)?;
+
+    let allocated = fw.memory_stats();
+    assert_eq!(allocated.images, before.images + 2);
+    assert_eq!(
+        allocated.image_bytes,
+        before.image_bytes + 2 * 4 * side as u64 * side as u64
+    );
+
+    drop(image);
+    const_image.destroy();
+    fw.as_gpu_device().poll(wgpu::Maintain::Wait);
+
+    assert_eq!(fw.memory_stats(), before);
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+//! Invalid inputs returning errors rather than panicking, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{kernel::KernelError, prelude::*, primitives::buffers::BufferError};
+
+/// Increments each element of `data`.
+const INCREMENT_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&data)) {
+        data[i] = data[i] + 1u;
+    }
+}
+"#;
+
+/// Increments each element of `data`, with a two-dimensional workgroup size.
+const INCREMENT_2D_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x + global_id.y * 8u;
+
+    if (i < arrayLength(&data)) {
+        data[i] = data[i] + 1u;
+    }
+}
+"#;
+
+#[test]
+fn empty_buffers_transfer_nothing() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let buf = GpuBuffer::<u32>::with_capacity(&fw, 0);
+    assert_eq!(buf.write(&[1, 2, 3, 4])?, 0);
+    assert_eq!(buf.read_vec_blocking()?, []);
+
+    let buf = GpuBuffer::<u32>::with_capacity(&fw, 4);
+    assert_eq!(buf.write(&[])?, 0);
+    assert_eq!(buf.read_blocking(&mut [])?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn oversized_transfers_stop_at_the_capacity() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let buf = GpuBuffer::<u32>::with_capacity(&fw, 4);
+    assert_eq!(buf.write(&[1, 2, 3, 4, 5, 6])?, 16);
+
+    let mut output = [0; 6];
+    assert_eq!(buf.read_blocking(&mut output)?, 16);
+    assert_eq!(output, [1, 2, 3, 4, 0, 0]);
+
+    let uniform = GpuUniformBuffer::<u32>::with_capacity(&fw, 4);
+    assert_eq!(uniform.write(&[1, 2, 3, 4, 5, 6])?, 16);
+
+    Ok(())
+}
+
+#[test]
+fn misaligned_transfers_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let buf = GpuBuffer::<u8>::with_capacity(&fw, 8);
+    assert!(matches!(
+        buf.write(&[1, 2, 3]),
+        Err(BufferError::MisalignedTransfer(3))
+    ));
+    assert_eq!(buf.write(&[1, 2, 3, 4])?, 4);
+
+    let uniform = GpuUniformBuffer::<u8>::with_capacity(&fw, 8);
+    assert!(matches!(
+        uniform.write(&[1, 2, 3, 4, 5]),
+        Err(BufferError::MisalignedTransfer(5))
+    ));
+
+    // The last word of the buffer is only partly in it.
+    let buf = GpuBuffer::<u8>::from_slice(&fw, &[1, 2, 3, 4, 5, 6]);
+    assert!(matches!(
+        buf.read_vec_blocking(),
+        Err(BufferError::MisalignedTransfer(6))
+    ));
+
+    // Reads that end in a whole word, even partly, are not.
+    let mut output = [0; 3];
+    assert_eq!(buf.read_blocking(&mut output)?, 3);
+    assert_eq!(output, [1, 2, 3]);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_shaders_and_dispatches_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // Truncated SPIR-V.
+    assert!(Shader::from_spirv_bytes(&fw, &[0x03, 0x02, 0x23], None).is_err());
+    assert!(Shader::from_spirv_words(&fw, &[0x0723_0203, 0x0001_0000], None).is_err());
+
+    let shader = Shader::from_wgsl_source(&fw, INCREMENT_SHADER, Some("increment"))?;
+    let data = GpuBuffer::from_slice(&fw, &[0u32; 64]);
+
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    let program = Program::new(&shader, "missing").add_descriptor_set(set);
+    assert!(Kernel::new(&fw, program).is_err());
+
+    // A uniform block where the shader expects a storage buffer.
+    let uniform = GpuUniformBuffer::from_slice(&fw, &[0u32; 4]);
+    let set = DescriptorSet::default().bind_uniform_buffer(&uniform);
+    let program = Program::new(&shader, "main").add_descriptor_set(set);
+    assert!(Kernel::new(&fw, program).is_err());
+
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    let (max_x, _, _) = kernel.max_dispatch();
+    assert!(kernel.enqueue(max_x + 1, 1, 1).is_err());
+    assert!(kernel.enqueue_with_sets(1, 1, 1, &[]).is_err());
+
+    // The kernel is still usable after the failures.
+    kernel.enqueue(1, 1, 1)?;
+    assert_eq!(data.read_vec_blocking()?, [1; 64]);
+
+    Ok(())
+}
+
+#[test]
+fn element_dispatches_need_one_dimensional_workgroups() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let data = GpuBuffer::from_slice(&fw, &[0u32; 64]);
+
+    let shader = Shader::from_wgsl_source(&fw, INCREMENT_SHADER, Some("increment"))?;
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?
+        .enqueue_elements(64)?;
+    assert_eq!(data.read_vec_blocking()?, [1; 64]);
+
+    let shader = Shader::from_wgsl_source(&fw, INCREMENT_2D_SHADER, Some("increment 2d"))?;
+    let set = DescriptorSet::default().bind_buffer(&data, GpuBufferUsage::ReadWrite);
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+    assert!(matches!(
+        kernel.enqueue_elements(64),
+        Err(KernelError::MultidimensionalWorkgroupSize {
+            workgroup_size: (8, 8, 1),
+            ..
+        })
+    ));
+
+    Ok(())
+}
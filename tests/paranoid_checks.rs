@@ -0,0 +1,59 @@
+//! Catches buffers bound with another element type than the shader declares,
+//! skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{kernel::KernelError, prelude::*};
+
+const SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    data[global_id.x] = data[global_id.x] * 2.0;
+}
+"#;
+
+fn create_kernel<T: bytemuck::Pod>(fw: &Framework, buf: &GpuBuffer<T>) -> GpuResult<()> {
+    let shader = Shader::from_wgsl_source(fw, SHADER, Some("paranoid"))?;
+    let bindings = DescriptorSet::default().bind_buffer(buf, GpuBufferUsage::ReadWrite);
+
+    Kernel::new(
+        fw,
+        Program::new(&shader, "main").add_descriptor_set(bindings),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn stale_element_type() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    fw.set_paranoid_checks(true);
+
+    let floats = GpuBuffer::<f32>::with_capacity(&fw, 16);
+    create_kernel(&fw, &floats)?;
+
+    let words = GpuBuffer::<u32>::with_capacity(&fw, 16);
+    let err = create_kernel(&fw, &words).unwrap_err();
+    assert!(matches!(
+        err,
+        GpuError::Kernel(KernelError::ElementTypeMismatch { stride: 4, .. })
+    ));
+
+    let pairs = GpuBuffer::<[f32; 2]>::with_capacity(&fw, 16);
+    let err = create_kernel(&fw, &pairs).unwrap_err();
+    assert!(matches!(
+        err,
+        GpuError::Kernel(KernelError::ElementTypeMismatch { stride: 4, .. })
+    ));
+
+    fw.set_paranoid_checks(false);
+    create_kernel(&fw, &words)?;
+
+    Ok(())
+}
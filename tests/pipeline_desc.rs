@@ -0,0 +1,122 @@
+//! Instantiates described pipelines, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    framework::{
+        BindingDesc, BufferDesc, ElementDesc, KernelDesc, PipelineDesc, PipelineDescError,
+        ShaderDesc,
+    },
+    GpuError,
+};
+
+const SHADER: &str = r#"
+@group(0) @binding(0) var<uniform> factor: f32;
+@group(0) @binding(1) var<storage, read_write> data: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if (global_id.x < arrayLength(&data)) {
+        data[global_id.x] = data[global_id.x] * factor;
+    }
+}
+"#;
+
+fn scale_desc() -> PipelineDesc {
+    PipelineDesc {
+        shaders: vec![ShaderDesc {
+            name: "scale".to_string(),
+            path: None,
+            source: Some(SHADER.to_string()),
+        }],
+        buffers: vec![
+            BufferDesc {
+                name: "factor".to_string(),
+                element: ElementDesc::F32,
+                len: 1,
+                uniform: true,
+                init: None,
+            },
+            BufferDesc {
+                name: "data".to_string(),
+                element: ElementDesc::F32,
+                len: 256,
+                uniform: false,
+                init: None,
+            },
+        ],
+        kernels: vec![KernelDesc {
+            name: "scale".to_string(),
+            shader: "scale".to_string(),
+            entry_point: "main".to_string(),
+            bindings: vec![
+                BindingDesc {
+                    buffer: "factor".to_string(),
+                    binding: None,
+                    read_only: true,
+                },
+                BindingDesc {
+                    buffer: "data".to_string(),
+                    binding: None,
+                    read_only: false,
+                },
+            ],
+            dispatch: [4, 1, 1],
+        }],
+    }
+}
+
+#[test]
+fn instantiate_and_run() -> gpgpu::GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let pipeline = fw.instantiate(&scale_desc())?;
+
+    let data = (0..256).map(|i| i as f32).collect::<Vec<_>>();
+    pipeline.write("factor", &[3.0f32])?;
+    pipeline.write("data", &data)?;
+    pipeline.run()?;
+
+    let scaled = pipeline.read::<f32>("data")?;
+    assert_eq!(data.iter().map(|x| x * 3.0).collect::<Vec<_>>(), scaled);
+    assert!(pipeline.kernel("scale").is_some());
+
+    Ok(())
+}
+
+#[test]
+fn bad_references() {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return,
+    };
+
+    let mut desc = scale_desc();
+    desc.kernels[0].bindings[1].buffer = "missing".to_string();
+
+    match fw.instantiate(&desc) {
+        Err(GpuError::PipelineDesc(err @ PipelineDescError::UnknownReference { .. })) => {
+            assert_eq!(
+                err.to_string(),
+                "kernels[0].bindings[1].buffer: no buffer is named `missing`."
+            )
+        }
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+
+    let mut desc = scale_desc();
+    desc.buffers[1].name = "factor".to_string();
+
+    match fw.instantiate(&desc) {
+        Err(GpuError::PipelineDesc(err @ PipelineDescError::DuplicateName { .. })) => {
+            assert_eq!(
+                err.to_string(),
+                "buffers[1].name: the name `factor` is already used."
+            )
+        }
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    };
+}
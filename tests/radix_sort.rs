@@ -0,0 +1,119 @@
+//! Radix sorts on the GPU compared against `slice::sort`, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError},
+    prelude::*,
+};
+
+/// Pseudo-random keys over the whole `u32` range, from a xorshift generator.
+fn random(len: usize) -> Vec<u32> {
+    let mut state = 0x1234_5678u32;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        })
+        .collect()
+}
+
+/// Inputs of `len` keys: random, constant, sorted, reverse sorted, of few distinct keys,
+/// and alternating between the smallest and largest keys.
+fn inputs(len: usize) -> Vec<(&'static str, Vec<u32>)> {
+    vec![
+        ("random", random(len)),
+        ("constant", vec![0xdead_beef; len]),
+        ("sorted", (0..len as u32).collect()),
+        ("reverse", (0..len as u32).rev().collect()),
+        ("few", random(len).into_iter().map(|key| key % 3).collect()),
+        (
+            "alternating",
+            (0..len)
+                .map(|i| if i % 2 == 0 { u32::MAX } else { 0 })
+                .collect(),
+        ),
+    ]
+}
+
+#[test]
+fn keys_sort_like_slice_sort() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for &len in &[1usize, 2, 1000, 1024, 4099, 300_007] {
+        for (name, keys) in inputs(len) {
+            let mut buf = GpuBuffer::from_slice(&fw, &keys);
+            ops::radix_sort(&fw, &mut buf)?;
+
+            let mut sorted = keys;
+            sorted.sort_unstable();
+            assert_eq!(buf.read_vec_blocking()?, sorted, "{} keys, {}", len, name);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pairs_sort_stably() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for &len in &[3usize, 1025, 100_003] {
+        for (name, keys) in inputs(len) {
+            let indices = (0..len as u32).collect::<Vec<u32>>();
+            let mut gpu_keys = GpuBuffer::from_slice(&fw, &keys);
+            let mut gpu_values = GpuBuffer::from_slice(&fw, &indices);
+            ops::radix_sort_pairs(&fw, &mut gpu_keys, &mut gpu_values)?;
+
+            let mut pairs = keys.into_iter().zip(indices).collect::<Vec<_>>();
+            pairs.sort_by_key(|&(key, _)| key);
+            let (keys, values): (Vec<u32>, Vec<u32>) = pairs.into_iter().unzip();
+
+            assert_eq!(
+                gpu_keys.read_vec_blocking()?,
+                keys,
+                "{} keys, {}",
+                len,
+                name
+            );
+            assert_eq!(
+                gpu_values.read_vec_blocking()?,
+                values,
+                "{} keys, {}",
+                len,
+                name
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn mismatched_pairs_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let mut keys = GpuBuffer::from_slice(&fw, &[3u32, 1, 2]);
+    let mut values = GpuBuffer::from_slice(&fw, &[0u32, 1]);
+
+    assert!(matches!(
+        ops::radix_sort_pairs(&fw, &mut keys, &mut values),
+        Err(GpuError::Ops(OpsError::LengthMismatch {
+            keys: 3,
+            values: 2
+        }))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! Pseudo-random fills checked against a CPU Philox and for their statistics,
+//! skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, Distribution},
+    prelude::*,
+};
+
+/// Philox4x32-10 of `counter` with `key`, as specified by Salmon et al.
+fn philox(mut x: [u32; 4], mut key: [u32; 2]) -> [u32; 4] {
+    for _ in 0..10 {
+        let p0 = 0xd251_1f53u64 * x[0] as u64;
+        let p1 = 0xcd9e_8d57u64 * x[2] as u64;
+        x = [
+            (p1 >> 32) as u32 ^ x[1] ^ key[0],
+            p1 as u32,
+            (p0 >> 32) as u32 ^ x[3] ^ key[1],
+            p0 as u32,
+        ];
+        key = [
+            key[0].wrapping_add(0x9e37_79b9),
+            key[1].wrapping_add(0xbb67_ae85),
+        ];
+    }
+    x
+}
+
+/// Mean and variance of `values`, computed in `f64`.
+fn moments(values: &[f32]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().map(|&x| x as f64).sum::<f64>() / n;
+    let variance = values
+        .iter()
+        .map(|&x| (x as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    (mean, variance)
+}
+
+#[test]
+fn words_match_philox() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // Known answer of the reference implementation for a zero counter and key.
+    assert_eq!(
+        philox([0; 4], [0; 2]),
+        [0x6627_e8d5, 0xe169_c58d, 0xbc57_ac4c, 0x9b00_dbd8]
+    );
+
+    let seed = 0x0123_4567_89ab_cdef_u64;
+    // Not a multiple of 4, so that the last block is partial.
+    let mut buf = GpuBuffer::<u32>::with_capacity(&fw, 100_003);
+    ops::fill_random_u32(&fw, &mut buf, seed)?;
+
+    let expected = (0..100_003u32.div_ceil(4))
+        .flat_map(|block| philox([block, 0, 0, 0], [seed as u32, (seed >> 32) as u32]))
+        .take(100_003)
+        .collect::<Vec<_>>();
+    assert_eq!(buf.read_vec_blocking()?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn fills_are_reproducible() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for &distribution in &[Distribution::Uniform01, Distribution::Normal] {
+        let mut first = GpuBuffer::<f32>::with_capacity(&fw, 10_001);
+        let mut second = GpuBuffer::<f32>::with_capacity(&fw, 10_001);
+        let mut other = GpuBuffer::<f32>::with_capacity(&fw, 10_001);
+        ops::fill_random(&fw, &mut first, 42, distribution)?;
+        ops::fill_random(&fw, &mut second, 42, distribution)?;
+        ops::fill_random(&fw, &mut other, 43, distribution)?;
+
+        let bits = |buf: &GpuBuffer<f32>| -> GpuResult<Vec<u32>> {
+            Ok(buf
+                .read_vec_blocking()?
+                .iter()
+                .map(|x| x.to_bits())
+                .collect())
+        };
+        assert_eq!(bits(&first)?, bits(&second)?, "{:?}", distribution);
+        assert_ne!(bits(&first)?, bits(&other)?, "{:?}", distribution);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn distributions_have_the_expected_moments() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let mut buf = GpuBuffer::<f32>::with_capacity(&fw, 10_000_000);
+
+    ops::fill_random(&fw, &mut buf, 7, Distribution::Uniform01)?;
+    let uniform = buf.read_vec_blocking()?;
+    assert!(uniform.iter().all(|x| (0.0..1.0).contains(x)));
+    let (mean, variance) = moments(&uniform);
+    assert!((mean - 0.5).abs() < 1e-3, "mean {}", mean);
+    assert!(
+        (variance - 1.0 / 12.0).abs() < 1e-3,
+        "variance {}",
+        variance
+    );
+
+    ops::fill_random(&fw, &mut buf, 7, Distribution::Normal)?;
+    let normal = buf.read_vec_blocking()?;
+    assert!(normal.iter().all(|x| x.is_finite()));
+    let (mean, variance) = moments(&normal);
+    assert!(mean.abs() < 2e-3, "mean {}", mean);
+    assert!((variance - 1.0).abs() < 3e-3, "variance {}", variance);
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! Draws the particles a kernel moved with a render pass recorded by
+//! `CommandRecorder::with_encoder`, in the same submission.
+
+mod common;
+
+use gpgpu::prelude::*;
+
+const PARTICLES: u32 = 64;
+
+const SIMULATE: &str = r#"
+@group(0) @binding(0) var<storage, read_write> particles: array<vec2<f32>>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    // Centre of the pixel `i` of a 64x1 target.
+    particles[i] = vec2<f32>((f32(i) * 2.0 + 1.0) / 64.0 - 1.0, 0.0);
+}
+"#;
+
+const DRAW: &str = r#"
+@group(0) @binding(0) var<storage, read> particles: array<vec2<f32>>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(particles[index], 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 1.0, 1.0, 1.0);
+}
+"#;
+
+#[test]
+fn compute_then_draw() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+    let device = fw.as_gpu_device();
+
+    // Every particle starts at the centre: only the kernel spreads them over the target.
+    let particles = GpuBuffer::<[f32; 2]>::from_slice(&fw, &[[0.0; 2]; PARTICLES as usize]);
+
+    let shader = Shader::from_wgsl_source(&fw, SIMULATE, Some("simulate"))?;
+    let program = Program::new(&shader, "main").add_descriptor_set(
+        DescriptorSet::default().bind_buffer(&particles, GpuBufferUsage::ReadWrite),
+    );
+    let simulate = Kernel::new(&fw, program)?;
+
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("draw"),
+        source: wgpu::ShaderSource::Wgsl(DRAW.into()),
+    });
+    let points = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("draw"),
+        layout: None,
+        vertex: wgpu::VertexState {
+            module: &module,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &module,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::TextureFormat::Rgba8Unorm.into())],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::PointList,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    });
+    let particles_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("draw"),
+        layout: &points.get_bind_group_layout(0),
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: particles.as_gpu_buffer().as_entire_binding(),
+        }],
+    });
+
+    let size = wgpu::Extent3d {
+        width: PARTICLES,
+        height: 1,
+        depth_or_array_layers: 1,
+    };
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("draw"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+    });
+    let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("draw"),
+        size: PARTICLES as u64 * 4,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut recorder = fw.create_command_recorder();
+    recorder.enqueue(&simulate, 1, 1, 1)?;
+    recorder.with_encoder(|encoder| {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("draw"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+        pass.set_pipeline(&points);
+        pass.set_bind_group(0, &particles_group, &[]);
+        pass.draw(0..PARTICLES, 0..1);
+    });
+    recorder.with_encoder(|encoder| {
+        encoder.copy_texture_to_buffer(
+            target.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &staging,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(PARTICLES * 4),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+    });
+    recorder.submit().wait();
+
+    let slice = staging.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        sender.send(result).unwrap()
+    });
+    device.poll(wgpu::Maintain::Wait);
+    receiver.recv().unwrap().unwrap();
+
+    let pixels = slice.get_mapped_range();
+    for (x, pixel) in pixels.chunks(4).enumerate() {
+        assert_eq!(pixel, [255; 4], "pixel {} was not drawn", x);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! Reductions on the GPU compared against the CPU, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError, ReduceOp},
+    prelude::*,
+};
+
+const LEN: usize = 10_000_000;
+
+#[test]
+fn float_reductions_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let data = common::random_floats(LEN, 0x2545_f491);
+    let buf = GpuBuffer::from_slice(&fw, &data);
+
+    let sum = data.iter().map(|&x| x as f64).sum::<f64>();
+    let gpu_sum = ops::reduce(&fw, &buf, ReduceOp::Sum)? as f64;
+    assert!(
+        (gpu_sum - sum).abs() < 1e-6 * LEN as f64,
+        "{} != {}",
+        gpu_sum,
+        sum
+    );
+
+    let (min_index, min) =
+        data.iter()
+            .copied()
+            .enumerate()
+            .fold(
+                (0, f32::INFINITY),
+                |acc, (i, x)| if x < acc.1 { (i, x) } else { acc },
+            );
+    let (max_index, max) =
+        data.iter()
+            .copied()
+            .enumerate()
+            .fold(
+                (0, f32::NEG_INFINITY),
+                |acc, (i, x)| if x > acc.1 { (i, x) } else { acc },
+            );
+
+    assert_eq!(ops::reduce(&fw, &buf, ReduceOp::Min)?, min);
+    assert_eq!(ops::reduce(&fw, &buf, ReduceOp::Max)?, max);
+    assert_eq!(ops::argmin(&fw, &buf)?, (min_index as u32, min));
+    assert_eq!(ops::argmax(&fw, &buf)?, (max_index as u32, max));
+
+    Ok(())
+}
+
+#[test]
+fn integer_reductions_of_any_length() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // Shorter than a workgroup, not a power of two, and longer than the partials.
+    for &len in &[1usize, 7, 255, 257, 1000, 300_001] {
+        let data = (0..len as i32)
+            .map(|i| (i % 1009) * 7919 % 1009 - 500)
+            .collect::<Vec<i32>>();
+        let buf = GpuBuffer::from_slice(&fw, &data);
+
+        let min = *data.iter().min().unwrap();
+        let max = *data.iter().max().unwrap();
+        let first = |value: i32| data.iter().position(|&x| x == value).unwrap() as u32;
+
+        assert_eq!(
+            ops::reduce(&fw, &buf, ReduceOp::Sum)?,
+            data.iter().sum::<i32>()
+        );
+        assert_eq!(ops::argmin(&fw, &buf)?, (first(min), min));
+        assert_eq!(ops::argmax(&fw, &buf)?, (first(max), max));
+
+        let unsigned = data.iter().map(|&x| x as u32).collect::<Vec<u32>>();
+        let buf = GpuBuffer::from_slice(&fw, &unsigned);
+        assert_eq!(
+            ops::reduce(&fw, &buf, ReduceOp::Sum)?,
+            unsigned.iter().fold(0u32, |acc, &x| acc.wrapping_add(x))
+        );
+        assert_eq!(
+            ops::reduce(&fw, &buf, ReduceOp::Max)?,
+            *unsigned.iter().max().unwrap()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn empty_buffers() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let buf = GpuBuffer::<f32>::from_slice(&fw, &[]);
+
+    assert_eq!(ops::reduce(&fw, &buf, ReduceOp::Sum)?, 0.0);
+    assert!(matches!(
+        ops::reduce(&fw, &buf, ReduceOp::Min),
+        Err(GpuError::Ops(OpsError::Empty))
+    ));
+    assert!(matches!(
+        ops::argmax(&fw, &buf),
+        Err(GpuError::Ops(OpsError::Empty))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Prefix sums on the GPU compared against the CPU, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError, ScanKind},
+    prelude::*,
+};
+
+/// Pseudo-random values below 1000.
+fn values(len: usize) -> Vec<u32> {
+    common::random(len, 0x9e37_79b9)
+        .into_iter()
+        .map(|x| (x >> 16) % 1000)
+        .collect()
+}
+
+fn cpu_scan(data: &[u32], kind: ScanKind) -> Vec<u32> {
+    let mut sum = 0u32;
+    data.iter()
+        .map(|&x| {
+            let before = sum;
+            sum = sum.wrapping_add(x);
+            match kind {
+                ScanKind::Inclusive => sum,
+                ScanKind::Exclusive => before,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn scans_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // 1_299_709 is prime, and needs three levels of blocks.
+    for &len in &[1usize, 1023, 1024, 1 << 20, 1_299_709] {
+        let data = values(len);
+        let input = GpuBuffer::from_slice(&fw, &data);
+
+        for &kind in &[ScanKind::Inclusive, ScanKind::Exclusive] {
+            let mut output = GpuBuffer::<u32>::with_capacity(&fw, len as u64);
+            ops::scan(&fw, &input, &mut output, kind)?;
+            assert_eq!(
+                output.read_vec_blocking()?,
+                cpu_scan(&data, kind),
+                "{} {:?}",
+                len,
+                kind
+            );
+
+            let mut buf = GpuBuffer::from_slice(&fw, &data);
+            ops::scan_in_place(&fw, &mut buf, kind)?;
+            assert_eq!(
+                buf.read_vec_blocking()?,
+                cpu_scan(&data, kind),
+                "{} {:?}",
+                len,
+                kind
+            );
+        }
+
+        // The input is left as it is.
+        assert_eq!(input.read_vec_blocking()?, data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn larger_outputs_keep_their_tail() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let input = GpuBuffer::from_slice(&fw, &[1u32, 2, 3]);
+    let mut output = GpuBuffer::from_slice(&fw, &[7u32; 5]);
+    ops::scan(&fw, &input, &mut output, ScanKind::Inclusive)?;
+    assert_eq!(output.read_vec_blocking()?, [1, 3, 6, 7, 7]);
+
+    let mut small = GpuBuffer::<u32>::with_capacity(&fw, 2);
+    assert!(matches!(
+        ops::scan(&fw, &input, &mut small, ScanKind::Exclusive),
+        Err(GpuError::Ops(OpsError::OutputTooSmall {
+            required: 3,
+            current: 2
+        }))
+    ));
+
+    Ok(())
+}
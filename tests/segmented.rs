@@ -0,0 +1,230 @@
+//! Segmented reductions on the GPU compared against the CPU, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError, ReduceOp},
+    prelude::*,
+    GpuError,
+};
+
+/// Start offsets of segments of `lengths` elements.
+fn offsets(lengths: &[u32]) -> Vec<u32> {
+    lengths
+        .iter()
+        .scan(0, |start, len| {
+            let offset = *start;
+            *start += len;
+            Some(offset)
+        })
+        .collect()
+}
+
+/// Reduction of each segment of `values` starting at `offsets`, `identity` for empty ones.
+fn cpu_reduce<T: Copy>(
+    values: &[T],
+    offsets: &[u32],
+    identity: T,
+    op: impl Fn(T, T) -> T,
+) -> Vec<T> {
+    (0..offsets.len())
+        .map(|s| {
+            let end = offsets.get(s + 1).map_or(values.len(), |&end| end as usize);
+            values[offsets[s] as usize..end]
+                .iter()
+                .fold(identity, |acc, &x| op(acc, x))
+        })
+        .collect()
+}
+
+/// Segment lengths skewed in several ways.
+fn distributions() -> Vec<(&'static str, Vec<u32>)> {
+    let noise = common::random(20_000, 7);
+
+    // A huge segment amid many tiny and empty ones.
+    let mut huge = noise.iter().map(|x| x >> 30).collect::<Vec<_>>();
+    huge[1234] = 300_000;
+
+    // Lengths from 0 to about 65536 elements, most of them short.
+    let power_law = noise[..2000]
+        .iter()
+        .map(|x| (1u32 << (x >> 28)) - 1)
+        .collect::<Vec<_>>();
+
+    // Lengths around the longest one reduced by a thread.
+    let boundary = (0..600).map(|i| 60 + i % 9).collect();
+
+    vec![
+        ("huge", huge),
+        ("power law", power_law),
+        ("boundary", boundary),
+        ("single", vec![100_000]),
+        ("empty", vec![0; 300]),
+    ]
+}
+
+#[test]
+fn segment_sums_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for (name, lengths) in distributions() {
+        let offsets = offsets(&lengths);
+        let len = lengths.iter().sum::<u32>() as usize;
+
+        // Multiples of 1/8 in [-1, 1), summed exactly in any order.
+        let values = common::random(len, 3)
+            .iter()
+            .map(|x| (x >> 28) as f32 / 8.0 - 1.0)
+            .collect::<Vec<_>>();
+        let integers = common::random(len, 5);
+
+        let gpu_offsets = GpuBuffer::from_slice(&fw, &offsets);
+        let gpu_values = GpuBuffer::from_slice(&fw, &values);
+        let gpu_integers = GpuBuffer::from_slice(&fw, &integers);
+        let mut sums = GpuBuffer::<f32>::with_capacity(&fw, offsets.len() as u64);
+        let mut integer_sums = GpuBuffer::<u32>::with_capacity(&fw, offsets.len() as u64);
+
+        ops::segmented_reduce(&fw, &gpu_values, &gpu_offsets, &mut sums, ReduceOp::Sum)?;
+        ops::segmented_reduce(
+            &fw,
+            &gpu_integers,
+            &gpu_offsets,
+            &mut integer_sums,
+            ReduceOp::Sum,
+        )?;
+
+        assert_eq!(
+            sums.read_vec_blocking()?,
+            cpu_reduce(&values, &offsets, 0.0, |a, b| a + b),
+            "{}",
+            name
+        );
+        assert_eq!(
+            integer_sums.read_vec_blocking()?,
+            cpu_reduce(&integers, &offsets, 0, u32::wrapping_add),
+            "{}",
+            name
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn segment_extrema_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for (name, lengths) in distributions() {
+        let offsets = offsets(&lengths);
+        let len = lengths.iter().sum::<u32>() as usize;
+
+        let values = common::random(len, 11)
+            .iter()
+            .map(|&x| x as i32 as f32)
+            .collect::<Vec<_>>();
+        let integers = common::random(len, 13)
+            .iter()
+            .map(|&x| x as i32)
+            .collect::<Vec<_>>();
+
+        let gpu_offsets = GpuBuffer::from_slice(&fw, &offsets);
+        let gpu_values = GpuBuffer::from_slice(&fw, &values);
+        let gpu_integers = GpuBuffer::from_slice(&fw, &integers);
+        let mut output = GpuBuffer::<f32>::with_capacity(&fw, offsets.len() as u64);
+        let mut integer_output = GpuBuffer::<i32>::with_capacity(&fw, offsets.len() as u64);
+
+        ops::segmented_reduce(&fw, &gpu_values, &gpu_offsets, &mut output, ReduceOp::Min)?;
+        assert_eq!(
+            output.read_vec_blocking()?,
+            cpu_reduce(&values, &offsets, f32::INFINITY, f32::min),
+            "{}",
+            name
+        );
+
+        ops::segmented_reduce(&fw, &gpu_values, &gpu_offsets, &mut output, ReduceOp::Max)?;
+        assert_eq!(
+            output.read_vec_blocking()?,
+            cpu_reduce(&values, &offsets, f32::NEG_INFINITY, f32::max),
+            "{}",
+            name
+        );
+
+        ops::segmented_reduce(
+            &fw,
+            &gpu_integers,
+            &gpu_offsets,
+            &mut integer_output,
+            ReduceOp::Min,
+        )?;
+        assert_eq!(
+            integer_output.read_vec_blocking()?,
+            cpu_reduce(&integers, &offsets, i32::MAX, i32::min),
+            "{}",
+            name
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn empty_segments_reduce_to_the_identity() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let values = GpuBuffer::<f32>::with_capacity(&fw, 0);
+    let offsets = GpuBuffer::from_slice(&fw, &[0u32; 3]);
+    let mut output = GpuBuffer::from_slice(&fw, &[5.0f32; 4]);
+
+    for (op, identity) in [
+        (ReduceOp::Sum, 0.0),
+        (ReduceOp::Min, f32::INFINITY),
+        (ReduceOp::Max, f32::NEG_INFINITY),
+    ] {
+        ops::segmented_reduce(&fw, &values, &offsets, &mut output, op)?;
+        // Only the elements of the segments are written.
+        assert_eq!(
+            output.read_vec_blocking()?,
+            [identity, identity, identity, 5.0]
+        );
+    }
+
+    // Offsets past the end of the values and out of order.
+    let values = GpuBuffer::from_slice(&fw, &[1u32, 2, 3, 4]);
+    let offsets = GpuBuffer::from_slice(&fw, &[0u32, 3, 1, 9]);
+    let mut output = GpuBuffer::<u32>::with_capacity(&fw, 4);
+    ops::segmented_reduce(&fw, &values, &offsets, &mut output, ReduceOp::Sum)?;
+    assert_eq!(output.read_vec_blocking()?, [6, 0, 9, 0]);
+
+    Ok(())
+}
+
+#[test]
+fn outputs_too_small_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let values = GpuBuffer::from_slice(&fw, &[1.0f32; 8]);
+    let offsets = GpuBuffer::from_slice(&fw, &[0u32, 2, 4]);
+    let mut output = GpuBuffer::from_slice(&fw, &[0.0f32; 2]);
+
+    assert!(matches!(
+        ops::segmented_reduce(&fw, &values, &offsets, &mut output, ReduceOp::Sum),
+        Err(GpuError::Ops(OpsError::OutputTooSmall {
+            required: 3,
+            current: 2
+        }))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! Shaders loaded through the on-disk cache of their SPIR-V, skipped when no adapter is available.
+
+#![cfg(feature = "shader-cache")]
+
+mod common;
+
+use gpgpu::{features::shader_cache::CachedLoader, kernel::ShaderError, prelude::*};
+
+/// Doubles `data`, in the legacy `[[attribute]]` syntax.
+const LEGACY_SHADER: &str = r#"
+[[group(0), binding(0)]] var<storage, read_write> data: array<u32>;
+
+[[stage(compute), workgroup_size(64, 1, 1)]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {
+    data[global_id.x] = data[global_id.x] * 2u;
+}
+"#;
+
+#[test]
+fn entries_depend_on_the_legacy_migration() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let dir = std::env::temp_dir().join(format!("gpgpu-shader-cache-{}", std::process::id()));
+    let mut loader = CachedLoader::new(&dir)?;
+
+    fw.set_migrate_legacy_wgsl(true);
+    loader.load_wgsl_source(&fw, LEGACY_SHADER, Some("legacy"))?;
+    loader.load_wgsl_source(&fw, LEGACY_SHADER, Some("legacy"))?;
+
+    // The entry compiled with the migration is not reused without it.
+    fw.set_migrate_legacy_wgsl(false);
+    let result = loader.load_wgsl_source(&fw, LEGACY_SHADER, Some("legacy"));
+    assert!(matches!(result, Err(ShaderError::LegacyWgsl(_))));
+
+    let stats = loader.stats();
+    assert_eq!((stats.hits, stats.misses), (1, 2));
+
+    loader.clear()?;
+    std::fs::remove_dir(&dir)?;
+
+    Ok(())
+}
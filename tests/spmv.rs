@@ -0,0 +1,158 @@
+//! Sparse matrix-vector products on the GPU compared against the CPU, skipped when no adapter
+//! is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError},
+    prelude::*,
+    GpuError,
+};
+
+/// CSR matrix of `rows` by `columns` elements.
+struct Csr {
+    row_offsets: Vec<u32>,
+    col_indices: Vec<u32>,
+    values: Vec<f32>,
+}
+
+impl Csr {
+    /// Random matrix of rows of up to `max_row` elements, a quarter of them empty.
+    fn random(rows: usize, columns: u32, max_row: u32, seed: u32) -> Self {
+        let lengths = common::random(rows, seed);
+        let mut row_offsets = vec![0];
+        for &length in &lengths {
+            let length = if length % 4 == 0 {
+                0
+            } else {
+                (length >> 8) % (max_row + 1)
+            };
+            row_offsets.push(row_offsets.last().unwrap() + length);
+        }
+
+        let nonzeros = *row_offsets.last().unwrap() as usize;
+        let col_indices = common::random(nonzeros, seed + 1)
+            .iter()
+            .map(|x| (x >> 4) % columns)
+            .collect();
+        // Multiples of 1/8 in [-1, 1), whose products by `x` are summed exactly in any order.
+        let values = common::random(nonzeros, seed + 2)
+            .iter()
+            .map(|x| (x >> 28) as f32 / 8.0 - 1.0)
+            .collect();
+
+        Self {
+            row_offsets,
+            col_indices,
+            values,
+        }
+    }
+
+    fn multiply(&self, x: &[f32]) -> Vec<f32> {
+        self.row_offsets
+            .windows(2)
+            .map(|row| {
+                (row[0] as usize..row[1] as usize)
+                    .map(|k| self.values[k] * x[self.col_indices[k] as usize])
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn products_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // Short rows for the scalar kernel and long ones for the vectorized one.
+    for &(rows, columns, max_row) in &[
+        (1, 1, 1),
+        (1000, 500, 8),
+        (20_000, 20_000, 12),
+        (300, 5000, 200),
+        (50, 100_000, 4000),
+    ] {
+        let matrix = Csr::random(rows, columns, max_row, rows as u32);
+        let x = common::random(columns as usize, 9)
+            .iter()
+            .map(|x| (x >> 29) as f32 - 4.0)
+            .collect::<Vec<_>>();
+
+        let row_offsets = GpuBuffer::from_slice(&fw, &matrix.row_offsets);
+        let col_indices = GpuBuffer::from_slice(&fw, &matrix.col_indices);
+        let values = GpuBuffer::from_slice(&fw, &matrix.values);
+        let gpu_x = GpuBuffer::from_slice(&fw, &x);
+        let mut y = GpuBuffer::from_slice(&fw, &vec![f32::NAN; rows]);
+
+        ops::spmv_csr(&fw, &row_offsets, &col_indices, &values, &gpu_x, &mut y)?;
+        assert_eq!(
+            y.read_vec_blocking()?,
+            matrix.multiply(&x),
+            "{} rows of up to {} elements",
+            rows,
+            max_row
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn empty_matrices_compute_zeros() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let row_offsets = GpuBuffer::from_slice(&fw, &[0u32; 4]);
+    let col_indices = GpuBuffer::<u32>::with_capacity(&fw, 0);
+    let values = GpuBuffer::<f32>::with_capacity(&fw, 0);
+    let x = GpuBuffer::from_slice(&fw, &[1.0f32; 3]);
+    let mut y = GpuBuffer::from_slice(&fw, &[f32::NAN; 3]);
+
+    ops::spmv_csr(&fw, &row_offsets, &col_indices, &values, &x, &mut y)?;
+    assert_eq!(y.read_vec_blocking()?, [0.0; 3]);
+
+    let row_offsets = GpuBuffer::from_slice(&fw, &[0u32]);
+    let mut y = GpuBuffer::<f32>::with_capacity(&fw, 0);
+    ops::spmv_csr(&fw, &row_offsets, &col_indices, &values, &x, &mut y)?;
+
+    Ok(())
+}
+
+#[test]
+fn inconsistent_dimensions_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let row_offsets = GpuBuffer::from_slice(&fw, &[0u32, 1, 2]);
+    let col_indices = GpuBuffer::from_slice(&fw, &[0u32, 1]);
+    let values = GpuBuffer::from_slice(&fw, &[1.0f32, 2.0]);
+    let x = GpuBuffer::from_slice(&fw, &[1.0f32; 2]);
+
+    let mut y = GpuBuffer::from_slice(&fw, &[0.0f32; 3]);
+    assert!(matches!(
+        ops::spmv_csr(&fw, &row_offsets, &col_indices, &values, &x, &mut y),
+        Err(GpuError::Ops(OpsError::RowOffsetsMismatch {
+            rows: 3,
+            offsets: 3
+        }))
+    ));
+
+    let mut y = GpuBuffer::from_slice(&fw, &[0.0f32; 2]);
+    let short_values = GpuBuffer::from_slice(&fw, &[1.0f32]);
+    assert!(matches!(
+        ops::spmv_csr(&fw, &row_offsets, &col_indices, &short_values, &x, &mut y),
+        Err(GpuError::Ops(OpsError::OperandMismatch { a: 2, b: 1 }))
+    ));
+
+    ops::spmv_csr(&fw, &row_offsets, &col_indices, &values, &x, &mut y)?;
+    assert_eq!(y.read_vec_blocking()?, [1.0, 2.0]);
+
+    Ok(())
+}
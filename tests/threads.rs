@@ -0,0 +1,178 @@
+//! Creation and enqueuing of kernels from several threads, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{kernel::KernelError, prelude::*};
+
+/// Adds `params.thread + 1` to the `params.len` elements of `values` from `params.offset`.
+const REGION_SHADER: &str = r#"
+struct Params {
+    offset: u32,
+    len: u32,
+    thread: u32,
+    _padding: u32,
+};
+
+@group(0) @binding(0) var<storage, read_write> values: array<u32>;
+@group(0) @binding(1) var<uniform> params: Params;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < params.len) {
+        let index = params.offset + i;
+        values[index] = values[index] + params.thread + 1u;
+    }
+}
+"#;
+
+/// Declares more invocations per workgroup than any device supports, so `wgpu` rejects
+/// its pipeline although its bindings are valid.
+const OVERSIZED_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read_write> values: array<u32>;
+
+@compute @workgroup_size(4096, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    values[global_id.x] = 1u;
+}
+"#;
+
+const THREADS: u32 = 8;
+const REGION_LEN: u32 = 1000;
+const ENQUEUES: u32 = 50;
+
+/// Fails to compile if `T` cannot be shared between threads. [`Kernel`] must stay so even
+/// if state updated by its enqueues is added to it, which must then be behind a mutex.
+fn assert_send_sync<T: Send + Sync>() {}
+
+/// Fails to compile if `value` cannot be sent to another thread.
+fn assert_send<T: Send>(_value: &T) {}
+
+#[test]
+fn kernels_are_shared_between_threads() {
+    assert_send_sync::<Framework>();
+    assert_send_sync::<Kernel>();
+    assert_send_sync::<GpuBuffer<u32>>();
+    assert_send_sync::<GpuUniformBuffer<u32>>();
+    assert_send_sync::<DescriptorSet>();
+}
+
+#[test]
+fn kernels_are_enqueued_from_several_threads() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, REGION_SHADER, Some("region"))?;
+
+    let values = GpuBuffer::from_slice(&fw, &vec![0u32; (THREADS * REGION_LEN) as usize]);
+    let params = (0..THREADS)
+        .map(|thread| {
+            GpuUniformBuffer::from_slice(&fw, &[thread * REGION_LEN, REGION_LEN, thread, 0])
+        })
+        .collect::<Vec<_>>();
+
+    let set = DescriptorSet::default()
+        .bind_buffer(&values, GpuBufferUsage::ReadWrite)
+        .bind_uniform_buffer(&params[0]);
+    let kernel = Kernel::new(&fw, Program::new(&shader, "main").add_descriptor_set(set))?;
+
+    // Each thread writes its own region, enqueuing the same kernel with its own parameters.
+    std::thread::scope(|scope| {
+        let threads = params
+            .iter()
+            .map(|params| {
+                let (kernel, values) = (&kernel, &values);
+
+                scope.spawn(move || {
+                    let desc = DescriptorSet::default()
+                        .bind_buffer(values, GpuBufferUsage::ReadWrite)
+                        .bind_uniform_buffer(params);
+
+                    for _ in 0..ENQUEUES {
+                        kernel.enqueue_with_sets(REGION_LEN.div_ceil(64), 1, 1, &[&desc])?;
+                    }
+
+                    GpuResult::Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        threads
+            .into_iter()
+            .try_for_each(|thread| thread.join().unwrap())
+    })?;
+
+    // The dispatches of a thread are ordered, so each region got all of them.
+    let expected = (0..THREADS)
+        .flat_map(|thread| std::iter::repeat_n((thread + 1) * ENQUEUES, REGION_LEN as usize))
+        .collect::<Vec<_>>();
+    assert_eq!(values.read_vec_blocking()?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn kernels_are_created_from_several_threads() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, REGION_SHADER, Some("region"))?;
+    let oversized = Shader::from_wgsl_source(&fw, OVERSIZED_SHADER, Some("oversized"))?;
+
+    let values = GpuBuffer::<u32>::with_capacity(&fw, 64);
+    let params = GpuUniformBuffer::from_slice(&fw, &[0u32, 64, 0, 0]);
+
+    // Each thread creates valid and invalid kernels, whose futures are polled on the thread.
+    std::thread::scope(|scope| {
+        let threads = (0..THREADS)
+            .map(|thread| {
+                let valid = Program::new(&shader, "main")
+                    .label(format!("valid {}", thread))
+                    .add_descriptor_set(
+                        DescriptorSet::default()
+                            .bind_buffer(&values, GpuBufferUsage::ReadWrite)
+                            .bind_uniform_buffer(&params),
+                    );
+                let invalid = Program::new(&oversized, "main")
+                    .label(format!("invalid {}", thread))
+                    .add_descriptor_set(
+                        DescriptorSet::default().bind_buffer(&values, GpuBufferUsage::ReadWrite),
+                    );
+
+                let valid = Kernel::new_async(&fw, valid);
+                let invalid = Kernel::new_async(&fw, invalid);
+                assert_send(&valid);
+                assert_send(&invalid);
+
+                scope.spawn(move || {
+                    let valid = futures::executor::block_on(valid);
+                    let invalid = futures::executor::block_on(invalid);
+                    (
+                        thread,
+                        valid.map(|kernel| kernel.label().to_string()),
+                        invalid,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            let (thread, valid, invalid) = thread.join().unwrap();
+
+            assert_eq!(valid.unwrap(), format!("valid {}", thread));
+            match invalid {
+                Err(KernelError::InvalidPipeline { kernel, .. }) => {
+                    assert_eq!(kernel, format!("invalid {}", thread))
+                }
+                other => panic!("expected an invalid pipeline, got {:?}", other.map(|_| ())),
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! Transposes on the GPU compared against the CPU, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{
+    ops::{self, OpsError},
+    prelude::*,
+};
+
+/// Transpose of the `rows` x `cols` matrix `matrix` stored by rows.
+fn cpu_transpose<T: Copy>(matrix: &[T], rows: usize, cols: usize) -> Vec<T> {
+    (0..cols)
+        .flat_map(|col| (0..rows).map(move |row| matrix[row * cols + col]))
+        .collect()
+}
+
+#[test]
+fn transposes_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for &(rows, cols) in &[(1, 1000), (1000, 1), (1000, 37), (37, 1000), (2048, 2048)] {
+        let matrix = (0..rows * cols).map(|i| i as u32).collect::<Vec<_>>();
+        let input = GpuBuffer::from_slice(&fw, &matrix);
+        // One more element, which is left as it is.
+        let mut output = GpuBuffer::from_slice(&fw, &vec![u32::MAX; rows * cols + 1]);
+
+        ops::transpose(&fw, &input, &mut output, rows as u32, cols as u32)?;
+
+        let mut expected = cpu_transpose(&matrix, rows, cols);
+        expected.push(u32::MAX);
+        assert_eq!(output.read_vec_blocking()?, expected, "{}x{}", rows, cols);
+    }
+
+    let floats = GpuBuffer::from_slice(&fw, &[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let mut transposed = GpuBuffer::<f32>::with_capacity(&fw, 6);
+    ops::transpose(&fw, &floats, &mut transposed, 2, 3)?;
+    assert_eq!(
+        transposed.read_vec_blocking()?,
+        [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn in_place_transposes_match_the_cpu() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    for &size in &[1, 31, 32, 100, 2048] {
+        let matrix = (0..size * size)
+            .map(|i| i as i32 - 1000)
+            .collect::<Vec<_>>();
+        let mut buf = GpuBuffer::from_slice(&fw, &matrix);
+
+        ops::transpose_in_place(&fw, &mut buf, size as u32)?;
+
+        assert_eq!(
+            buf.read_vec_blocking()?,
+            cpu_transpose(&matrix, size, size),
+            "{}x{}",
+            size,
+            size
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn small_matrices_are_rejected() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let input = GpuBuffer::from_slice(&fw, &[0u32; 12]);
+    let mut output = GpuBuffer::from_slice(&fw, &[0u32; 10]);
+
+    assert!(matches!(
+        ops::transpose(&fw, &input, &mut output, 3, 4),
+        Err(GpuError::Ops(OpsError::MatrixTooSmall {
+            matrix: "output",
+            required: 12,
+            current: 10
+        }))
+    ));
+    assert!(matches!(
+        ops::transpose_in_place(&fw, &mut output, 4),
+        Err(GpuError::Ops(OpsError::MatrixTooSmall {
+            matrix: "buf",
+            required: 16,
+            current: 10
+        }))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Uploads converting their elements with `GpuBuffer::from_slice_map`.
+
+mod common;
+
+use gpgpu::prelude::*;
+
+#[test]
+fn narrowing_upload() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // Below and above the size converted on several threads, with an odd byte size.
+    for size in [0, 3, 1000, 300_001] {
+        let input = (0..size).map(|i| i as f64 * 0.25).collect::<Vec<f64>>();
+
+        let buf = GpuBuffer::from_slice_map(&fw, &input, |&x| x as f32);
+        assert_eq!(buf.capacity(), size as u64);
+
+        let expected = input.iter().map(|&x| x as f32).collect::<Vec<f32>>();
+        assert_eq!(buf.read_vec_blocking()?, expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn gathering_upload() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    struct Particle {
+        position: [f64; 2],
+        _mass: f64,
+    }
+
+    let particles = (0..100)
+        .map(|i| Particle {
+            position: [i as f64, -i as f64],
+            _mass: 1.0,
+        })
+        .collect::<Vec<_>>();
+
+    let positions = GpuBuffer::from_slice_map(&fw, &particles, |particle| {
+        particle.position.map(|x| x as f32)
+    });
+
+    for (i, position) in positions.read_vec_blocking()?.into_iter().enumerate() {
+        assert_eq!(position, [i as f32, -(i as f32)]);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! Filters a nullable column by a threshold, skipped when no adapter is available.
+
+mod common;
+
+use gpgpu::{prelude::*, primitives::buffers::BufferError};
+
+const SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> values: array<f32>;
+@group(0) @binding(1) var<storage, read> validity: array<u32>;
+@group(0) @binding(2) var<storage, read_write> selected: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let i = global_id.x;
+
+    if (i < arrayLength(&values)) {
+        let valid = ((validity[i / 32u] >> (i % 32u)) & 1u) == 1u;
+        selected[i] = u32(valid && values[i] > 0.5);
+    }
+}
+"#;
+
+#[test]
+fn threshold_filter() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    let shader = Shader::from_wgsl_source(&fw, SHADER, Some("threshold"))?;
+
+    // Every third value is null, with a value above the threshold in its slot.
+    let len = 100;
+    let values = (0..len)
+        .map(|i| {
+            if i % 3 == 0 {
+                1.0
+            } else {
+                (i % 10) as f32 / 10.0
+            }
+        })
+        .collect::<Vec<f32>>();
+    let mut bitmap = vec![0u8; (len as usize).div_ceil(8)];
+    (0..len)
+        .filter(|i| i % 3 != 0)
+        .for_each(|i| bitmap[i as usize / 8] |= 1 << (i % 8));
+
+    let (gpu_values, gpu_validity) =
+        GpuBuffer::from_values_with_validity(&fw, &values, Some(&bitmap))?;
+    let gpu_validity = gpu_validity.unwrap();
+    let selected = GpuBuffer::<u32>::with_capacity(&fw, len);
+
+    let bindings = DescriptorSet::default()
+        .bind_buffer(&gpu_values, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&gpu_validity, GpuBufferUsage::ReadOnly)
+        .bind_buffer(&selected, GpuBufferUsage::ReadWrite);
+
+    let program = Program::new(&shader, "main").add_descriptor_set(bindings);
+    Kernel::new(&fw, program)?.enqueue((len as u32).div_ceil(64), 1, 1)?;
+
+    let expected = (0..len)
+        .map(|i| (i % 3 != 0 && values[i as usize] > 0.5) as u32)
+        .collect::<Vec<_>>();
+    assert_eq!(expected, selected.read_vec_blocking()?);
+
+    let (read_values, read_bitmap) = gpu_values.read_with_validity_blocking(Some(&gpu_validity))?;
+    assert_eq!(values, read_values);
+    assert_eq!(Some(bitmap), read_bitmap);
+
+    let err = GpuBuffer::from_values_with_validity(&fw, &values, Some(&[0xff; 4])).unwrap_err();
+    assert!(matches!(
+        err,
+        BufferError::ValidityLength {
+            values: 100,
+            bytes: 4
+        }
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! NV12 frames converted to RGBA on the GPU compared against the CPU, skipped when no adapter
+//! is available.
+
+#![cfg(feature = "video")]
+
+mod common;
+
+use gpgpu::{
+    features::video::{ColorSpace, VideoInputError},
+    prelude::*,
+    primitives::pixels::Rgba8Uint,
+};
+
+/// BT.601 conversion of the `shader` of `GpuImage::from_nv12`.
+fn bt601(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let y = y as f32 - 16.0;
+    let (u, v) = (u as f32 - 128.0, v as f32 - 128.0);
+
+    [
+        1.164 * y + 1.596 * v,
+        1.164 * y - 0.392 * u - 0.813 * v,
+        1.164 * y + 2.017 * u,
+    ]
+    .map(|c| c.round().clamp(0.0, 255.0) as u8)
+}
+
+#[test]
+fn odd_frames_use_their_trailing_chroma_samples() -> GpuResult<()> {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return Ok(()),
+    };
+
+    // The GL backend of `wgpu-hal` 0.13 panics on the integer images of the conversion.
+    if fw.capabilities().backend == wgpu::Backend::Gl {
+        eprintln!("skipped: integer images are not supported by the GL backend");
+        return Ok(());
+    }
+
+    let (width, height) = (5u32, 3u32);
+    let (chroma_width, chroma_height) = (3, 2);
+
+    let y_plane = (0..width * height)
+        .map(|i| 40 + i as u8 * 10)
+        .collect::<Vec<_>>();
+    // Samples far apart, so that using the one of a neighbor shows.
+    let uv_plane = (0..chroma_width * chroma_height)
+        .flat_map(|i| [60 + i as u8 * 25, 200 - i as u8 * 25])
+        .collect::<Vec<_>>();
+
+    let img = GpuImage::from_nv12(&fw, &y_plane, &uv_plane, width, height, ColorSpace::Bt601)?;
+    let pixels = img.read_vec_blocking()?;
+
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = (y * width + x) as usize;
+            let sample = ((y / 2) * chroma_width + x / 2) as usize;
+            let expected = bt601(
+                y_plane[pixel],
+                uv_plane[2 * sample],
+                uv_plane[2 * sample + 1],
+            );
+
+            let actual = &pixels[4 * pixel..4 * pixel + 4];
+            for c in 0..3 {
+                assert!(
+                    (actual[c] as i32 - expected[c] as i32).abs() <= 1,
+                    "pixel ({}, {}): {:?} against {:?}",
+                    x,
+                    y,
+                    actual,
+                    expected
+                );
+            }
+            assert_eq!(actual[3], 255);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn chroma_planes_of_odd_frames_are_rounded_up() {
+    let fw = match common::framework() {
+        Some(fw) => fw,
+        None => return,
+    };
+
+    // A plane of `floor(5 / 2) * floor(3 / 2)` samples is too short.
+    let result = GpuImage::<Rgba8Uint>::from_nv12(&fw, &[0; 15], &[0; 4], 5, 3, ColorSpace::Bt709);
+    assert!(matches!(
+        result,
+        Err(VideoInputError::InvalidChromaPlane {
+            required: 12,
+            current: 4
+        })
+    ));
+}
@@ -0,0 +1,225 @@
+//! Kernels running on the device and textures of another `wgpu` library.
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use gpgpu::{
+    prelude::*,
+    primitives::{
+        images::ImageError,
+        pixels::{Rgba8Uint, Rgba8UintNorm},
+    },
+};
+
+const WIDTH: u32 = 16;
+const HEIGHT: u32 = 8;
+
+/// Device and queue created outside of `gpgpu`, or `None` if there is no adapter.
+fn external_device() -> Option<(Arc<wgpu::Device>, Arc<wgpu::Queue>, wgpu::Backend)> {
+    let instance =
+        wgpu::Instance::new(wgpu::util::backend_bits_from_env().unwrap_or(wgpu::Backends::PRIMARY));
+    let adapter = futures::executor::block_on(
+        instance.request_adapter(&wgpu::RequestAdapterOptions::default()),
+    );
+    let adapter = match adapter {
+        Some(adapter) => adapter,
+        None if common::adapter_required() => {
+            panic!("no adapter available although GPGPU_REQUIRE_ADAPTER is set")
+        }
+        None => {
+            eprintln!("skipped: no adapter available");
+            return None;
+        }
+    };
+
+    let (device, queue) = futures::executor::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some("external"),
+            features: wgpu::Features::empty(),
+            limits: adapter.limits(),
+        },
+        None,
+    ))
+    .ok()?;
+
+    Some((
+        Arc::new(device),
+        Arc::new(queue),
+        adapter.get_info().backend,
+    ))
+}
+
+fn texture_desc(usage: wgpu::TextureUsages) -> wgpu::TextureDescriptor<'static> {
+    wgpu::TextureDescriptor {
+        label: Some("external"),
+        size: wgpu::Extent3d {
+            width: WIDTH,
+            height: HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage,
+    }
+}
+
+#[test]
+fn external_textures() -> GpuResult<()> {
+    let (device, queue, backend) = match external_device() {
+        Some(external) => external,
+        None => return Ok(()),
+    };
+
+    // The GL backend of `wgpu-hal` 0.13 panics on the image bindings of the kernel.
+    if backend == wgpu::Backend::Gl {
+        eprintln!("skipped: image bindings are not supported by the GL backend");
+        return Ok(());
+    }
+
+    let frame_desc =
+        texture_desc(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST);
+    let frame = device.create_texture(&frame_desc);
+    let pixels = (0..WIDTH * HEIGHT)
+        .flat_map(|i| [i as u8, 0, 255, 255])
+        .collect::<Vec<u8>>();
+    queue.write_texture(
+        frame.as_image_copy(),
+        &pixels,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(WIDTH * 4),
+            rows_per_image: None,
+        },
+        frame_desc.size,
+    );
+
+    let output_desc =
+        texture_desc(wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC);
+    let output = device.create_texture(&output_desc);
+    let display_desc = texture_desc(wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC);
+    let display = device.create_texture(&display_desc);
+
+    let fw = Framework::from_wgpu(
+        Arc::clone(&device),
+        Arc::clone(&queue),
+        backend,
+        Duration::from_millis(10),
+    );
+
+    let frame = GpuConstImage::<Rgba8UintNorm>::from_wgpu_texture(&fw, frame, &frame_desc)?;
+    let output = GpuImage::<Rgba8UintNorm>::from_wgpu_texture(&fw, output, &output_desc)?;
+
+    let shader = Shader::from_wgsl_file(&fw, "examples/wgpu-interop/shader.wgsl")?;
+    let bindings = DescriptorSet::default()
+        .bind_const_image(&frame)
+        .bind_image(&output);
+    let program = Program::new(&shader, "main").add_descriptor_set(bindings);
+    let kernel = Kernel::new(&fw, program)?;
+
+    let mut recorder = fw.create_command_recorder();
+    recorder.enqueue(&kernel, WIDTH / 8, HEIGHT / 8, 1)?;
+    recorder.with_encoder(|encoder| {
+        encoder.copy_texture_to_texture(
+            output.as_gpu_texture().as_image_copy(),
+            display.as_image_copy(),
+            display_desc.size,
+        );
+    });
+    recorder.submit().wait();
+
+    let inverted = pixels
+        .chunks(4)
+        .flat_map(|pixel| [255 - pixel[0], 255, 0, 255])
+        .collect::<Vec<u8>>();
+
+    // The output texture can be read as any image, its usages allowing it.
+    assert_eq!(output.read_vec_blocking()?, inverted);
+
+    // The textures go back to the library, and stay usable once `fw` is dropped.
+    let (output, _) = output.into_gpu_parts();
+    let (frame, _) = frame.into_gpu_parts();
+    drop(kernel);
+    drop(fw);
+
+    let fw = Framework::from_wgpu(
+        Arc::clone(&device),
+        Arc::clone(&queue),
+        backend,
+        Duration::from_millis(10),
+    );
+    let display = GpuImage::<Rgba8UintNorm>::from_wgpu_texture(&fw, display, &display_desc);
+    // No storage usage: `display` can only be used by the library itself.
+    assert!(matches!(
+        display,
+        Err(ImageError::MissingUsage(usage)) if usage == wgpu::TextureUsages::STORAGE_BINDING
+    ));
+
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.copy_texture_to_texture(
+        output.as_image_copy(),
+        frame.as_image_copy(),
+        frame_desc.size,
+    );
+    queue.submit(Some(encoder.finish()));
+    device.poll(wgpu::Maintain::Wait);
+
+    Ok(())
+}
+
+#[test]
+fn incompatible_textures() {
+    let (device, queue, backend) = match external_device() {
+        Some(external) => external,
+        None => return,
+    };
+    let fw = Framework::from_wgpu(
+        Arc::clone(&device),
+        queue,
+        backend,
+        Duration::from_millis(10),
+    );
+
+    let usage = wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING;
+
+    let desc = texture_desc(usage);
+    let result = GpuImage::<Rgba8Uint>::from_wgpu_texture(&fw, device.create_texture(&desc), &desc);
+    assert!(matches!(
+        result,
+        Err(ImageError::FormatMismatch {
+            expected: wgpu::TextureFormat::Rgba8Uint,
+            found: wgpu::TextureFormat::Rgba8Unorm,
+        })
+    ));
+
+    let desc = texture_desc(wgpu::TextureUsages::STORAGE_BINDING);
+    let result =
+        GpuConstImage::<Rgba8UintNorm>::from_wgpu_texture(&fw, device.create_texture(&desc), &desc);
+    assert!(matches!(result, Err(ImageError::MissingUsage(_))));
+
+    let mut desc = texture_desc(usage);
+    desc.size.depth_or_array_layers = 2;
+    let result =
+        GpuImage::<Rgba8UintNorm>::from_wgpu_texture(&fw, device.create_texture(&desc), &desc);
+    assert!(matches!(result, Err(ImageError::UnsupportedTexture)));
+}
+
+#[test]
+fn dropped_frameworks_release_the_device() {
+    let (device, queue, backend) = match external_device() {
+        Some(external) => external,
+        None => return,
+    };
+    let polling_time = Duration::from_millis(10);
+
+    let fw = Framework::from_wgpu(Arc::clone(&device), queue, backend, polling_time);
+    assert!(Arc::strong_count(&device) > 1);
+    drop(fw);
+
+    // The polling thread stops, and only held the device while polling it.
+    std::thread::sleep(polling_time * 5);
+    assert_eq!(Arc::strong_count(&device), 1);
+}